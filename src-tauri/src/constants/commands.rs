@@ -21,9 +21,19 @@ impl TauriCommands {
     pub const DELETE_ALL_CHATS_BY_WORKSPACE: &'static str = "delete_all_chats_by_workspace";
     pub const SEND_MESSAGE: &'static str = "send_message";
     pub const EDIT_AND_RESEND_MESSAGE: &'static str = "edit_and_resend_message";
+    pub const SEND_COMPARE_MESSAGE: &'static str = "send_compare_message";
+    pub const REGENERATE_MESSAGE: &'static str = "regenerate_message";
     pub const RESPOND_TOOL_PERMISSION: &'static str = "respond_tool_permission";
     pub const GENERATE_CHAT_TITLE: &'static str = "generate_chat_title";
 
+    // Tool Permission commands
+    pub const GET_TOOL_PERMISSION_GRANTS: &'static str = "get_tool_permission_grants";
+    pub const REVOKE_TOOL_PERMISSION_GRANT: &'static str = "revoke_tool_permission_grant";
+
+    // Tool Audit commands
+    pub const GET_TOOL_AUDIT_LOG: &'static str = "get_tool_audit_log";
+    pub const EXPORT_TOOL_AUDIT_LOG: &'static str = "export_tool_audit_log";
+
     // Message commands
     pub const CREATE_MESSAGE: &'static str = "create_message";
     pub const GET_MESSAGES: &'static str = "get_messages";
@@ -31,6 +41,19 @@ impl TauriCommands {
     pub const DELETE_MESSAGE: &'static str = "delete_message";
     pub const DELETE_MESSAGES_AFTER: &'static str = "delete_messages_after";
     pub const CANCEL_MESSAGE: &'static str = "cancel_message";
+    pub const GET_MESSAGE_BRANCHES: &'static str = "get_message_branches";
+    pub const SWITCH_MESSAGE_BRANCH: &'static str = "switch_message_branch";
+
+    // Search commands
+    pub const SEARCH_HISTORY: &'static str = "search_history";
+
+    // Export commands
+    pub const EXPORT_CHAT: &'static str = "export_chat";
+    pub const EXPORT_WORKSPACE: &'static str = "export_workspace";
+    pub const EXPORT_ALL_CHATS: &'static str = "export_all_chats";
+
+    // Import commands
+    pub const IMPORT_HISTORY: &'static str = "import_history";
 
     // MCP Server commands
     pub const CREATE_MCP_SERVER: &'static str = "create_mcp_server";
@@ -44,17 +67,27 @@ impl TauriCommands {
     pub const UPDATE_MCP_SERVER_CONNECTION: &'static str = "update_mcp_server_connection";
     pub const DELETE_MCP_SERVER_CONNECTION: &'static str = "delete_mcp_server_connection";
     pub const UPDATE_MCP_SERVER_STATUS: &'static str = "update_mcp_server_status";
+    pub const AUTHORIZE_MCP_CONNECTION: &'static str = "authorize_mcp_connection";
+    pub const GET_MCP_OAUTH_STATUS: &'static str = "get_mcp_oauth_status";
+    pub const REVOKE_MCP_AUTHORIZATION: &'static str = "revoke_mcp_authorization";
 
     // App Settings commands
     pub const SAVE_APP_SETTING: &'static str = "save_app_setting";
     pub const GET_APP_SETTING: &'static str = "get_app_setting";
     pub const GET_ALL_APP_SETTINGS: &'static str = "get_all_app_settings";
 
+    // Secrets vault commands
+    pub const GET_VAULT_STATUS: &'static str = "get_vault_status";
+    pub const UNLOCK_VAULT: &'static str = "unlock_vault";
+    pub const SET_VAULT_PASSPHRASE: &'static str = "set_vault_passphrase";
+
     // Prompt commands
     pub const CREATE_PROMPT: &'static str = "create_prompt";
     pub const GET_PROMPTS: &'static str = "get_prompts";
     pub const UPDATE_PROMPT: &'static str = "update_prompt";
     pub const DELETE_PROMPT: &'static str = "delete_prompt";
+    pub const GET_MCP_PROMPTS: &'static str = "get_mcp_prompts";
+    pub const GET_MCP_PROMPT_TEMPLATE: &'static str = "get_mcp_prompt_template";
 
     // Hub commands
     pub const FETCH_HUB_PROMPTS: &'static str = "fetch_hub_prompts";
@@ -72,6 +105,11 @@ impl TauriCommands {
     pub const GET_MCP_CLIENT: &'static str = "get_mcp_client";
     pub const CALL_MCP_TOOL: &'static str = "call_mcp_tool";
     pub const DISCONNECT_MCP_CLIENT: &'static str = "disconnect_mcp_client";
+    pub const LIST_MCP_RESOURCES: &'static str = "list_mcp_resources";
+    pub const LIST_MCP_RESOURCE_TEMPLATES: &'static str = "list_mcp_resource_templates";
+    pub const READ_MCP_RESOURCE: &'static str = "read_mcp_resource";
+    pub const RESPOND_SAMPLING_REQUEST: &'static str = "respond_sampling_request";
+    pub const RESPOND_ELICITATION_REQUEST: &'static str = "respond_elicitation_request";
 
     // Python commands
     pub const GET_PYTHON_RUNTIMES_STATUS: &'static str = "get_python_runtimes_status";
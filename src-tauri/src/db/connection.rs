@@ -29,15 +29,15 @@ pub fn get_connection(app: &tauri::AppHandle) -> Result<Connection> {
     Connection::open(db_path)
 }
 
-pub fn init_db(app: &tauri::AppHandle) -> Result<Connection> {
+pub fn init_db(app: &tauri::AppHandle) -> Result<Connection, crate::error::AppError> {
     let db_path = get_db_path(app)?;
-    let conn = Connection::open(db_path)?;
+    let conn = Connection::open(&db_path)?;
 
     // Enable foreign keys
     conn.execute("PRAGMA foreign_keys = ON", [])?;
 
     // Run migrations
-    crate::db::migrations::run_migrations(&conn)?;
+    crate::db::migrations::run_migrations(&conn, &db_path)?;
 
     Ok(conn)
 }
@@ -1,6 +1,261 @@
-use rusqlite::{Connection, Result};
+use crate::error::AppError;
+use crate::features::secrets::is_encrypted;
+use rusqlite::{params, Connection, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+// Columns holding secrets that the secrets vault encrypts at rest, as (table, key column, column)
+const SECRET_COLUMNS: &[(&str, &str, &str)] = &[
+    ("llm_connections", "id", "api_key"),
+    ("mcp_server_connections", "id", "headers"),
+    ("mcp_server_connections", "id", "env_vars"),
+    ("mcp_oauth_tokens", "connection_id", "access_token"),
+    ("mcp_oauth_tokens", "connection_id", "refresh_token"),
+    ("mcp_oauth_tokens", "connection_id", "client_secret"),
+];
+
+// Number of pre-migration backups kept next to the database
+const MAX_BACKUPS: usize = 5;
+
+/// A numbered schema change, applied once inside a transaction and recorded in `schema_migrations`
+///
+/// Migrations must only be appended. Early ones are written to be idempotent because
+/// databases created before the ledger existed may already contain part of their changes.
+struct Migration {
+    version: i64,
+    name: &'static str,
+    up: fn(&Connection) -> Result<()>,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        up: initial_schema,
+    },
+    Migration {
+        version: 2,
+        name: "mcp_tool_ids_from_connection_ids",
+        up: mcp_tool_ids_from_connection_ids,
+    },
+    Migration {
+        version: 3,
+        name: "mcp_server_resources_and_prompts",
+        up: mcp_server_resources_and_prompts,
+    },
+    Migration {
+        version: 4,
+        name: "mcp_oauth_tokens",
+        up: mcp_oauth_tokens,
+    },
+    Migration {
+        version: 5,
+        name: "secrets_vault",
+        up: secrets_vault,
+    },
+    Migration {
+        version: 6,
+        name: "full_text_search",
+        up: full_text_search,
+    },
+    Migration {
+        version: 7,
+        name: "message_branches",
+        up: message_branches,
+    },
+    Migration {
+        version: 8,
+        name: "workspace_context_config",
+        up: workspace_context_config,
+    },
+    Migration {
+        version: 9,
+        name: "usage_cache_tokens",
+        up: usage_cache_tokens,
+    },
+    Migration {
+        version: 10,
+        name: "workspace_tool_execution_config",
+        up: workspace_tool_execution_config,
+    },
+    Migration {
+        version: 11,
+        name: "tool_permission_grants",
+        up: tool_permission_grants,
+    },
+    Migration {
+        version: 12,
+        name: "tool_audit_log",
+        up: tool_audit_log,
+    },
+    Migration {
+        version: 13,
+        name: "workspace_fallback_models",
+        up: workspace_fallback_models,
+    },
+    Migration {
+        version: 14,
+        name: "llm_connection_api_mode",
+        up: llm_connection_api_mode,
+    },
+];
+
+/// Apply pending migrations, backing up the database first when it already holds data
+pub fn run_migrations(conn: &Connection, db_path: &Path) -> Result<(), AppError> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    let applied = {
+        let mut stmt = conn.prepare("SELECT version FROM schema_migrations")?;
+        let versions = stmt
+            .query_map([], |row| row.get::<_, i64>(0))?
+            .collect::<Result<HashSet<_>, _>>()?;
+        versions
+    };
+
+    let latest_known = MIGRATIONS.last().map_or(0, |m| m.version);
+    if let Some(newest) = applied.iter().max().filter(|v| **v > latest_known) {
+        tracing::warn!(
+            database_version = newest,
+            app_version = latest_known,
+            "Database was migrated by a newer version of the app"
+        );
+    }
+
+    let pending: Vec<&Migration> = MIGRATIONS
+        .iter()
+        .filter(|m| !applied.contains(&m.version))
+        .collect();
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let backup_path = if table_exists(conn, "workspaces")? {
+        let current = applied.iter().max().copied().unwrap_or(0);
+        Some(backup_database(conn, db_path, current)?)
+    } else {
+        None // Fresh install, nothing to back up
+    };
+
+    for migration in pending {
+        apply_migration(conn, migration).map_err(|e| {
+            let backup = backup_path.as_ref().map_or_else(String::new, |path| {
+                format!(
+                    ". The database was backed up to {} before migrating",
+                    path.display()
+                )
+            });
+            let message = format!(
+                "Migration {} ({}) failed: {e}{backup}",
+                migration.version, migration.name
+            );
+            crate::lib::sentry_helpers::add_breadcrumb(
+                "database",
+                message.clone(),
+                sentry::Level::Error,
+            );
+            tracing::error!("{message}");
+            AppError::Migration(message)
+        })?;
+        tracing::info!(
+            version = migration.version,
+            name = migration.name,
+            "Applied database migration"
+        );
+    }
+
+    Ok(())
+}
+
+fn apply_migration(conn: &Connection, migration: &Migration) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    (migration.up)(&tx)?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    tx.execute(
+        "INSERT INTO schema_migrations (version, name, applied_at) VALUES (?1, ?2, ?3)",
+        params![migration.version, migration.name, now],
+    )?;
+    tx.commit()
+}
+
+/// Copy the database to `backups/` (consistent snapshot via `VACUUM INTO`) and prune old copies
+fn backup_database(conn: &Connection, db_path: &Path, version: i64) -> Result<PathBuf, AppError> {
+    let backup_dir = db_path
+        .parent()
+        .map_or_else(|| PathBuf::from("backups"), |dir| dir.join("backups"));
+    std::fs::create_dir_all(&backup_dir)?;
+
+    let timestamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
+    let backup_path = backup_dir.join(format!("database-{timestamp}-v{version}.db"));
+    conn.execute(
+        "VACUUM INTO ?1",
+        params![backup_path.to_string_lossy().to_string()],
+    )
+    .map_err(|e| {
+        AppError::Migration(format!("Failed to back up database before migrating: {e}"))
+    })?;
+    tracing::info!(path = %backup_path.display(), "Backed up database before migrating");
+
+    // Names start with the timestamp, so lexical order is chronological
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(&backup_dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("database-") && name.ends_with(".db"))
+        })
+        .collect();
+    backups.sort();
+    if backups.len() > MAX_BACKUPS {
+        for old in &backups[..backups.len() - MAX_BACKUPS] {
+            if let Err(e) = std::fs::remove_file(old) {
+                tracing::warn!(path = %old.display(), error = %e, "Failed to remove old database backup");
+            }
+        }
+    }
 
-pub fn run_migrations(conn: &Connection) -> Result<()> {
+    Ok(backup_path)
+}
+
+fn table_exists(conn: &Connection, table: &str) -> Result<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        params![table],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|count| count > 0)
+}
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(columns.iter().any(|name| name == column))
+}
+
+/// `ALTER TABLE ... ADD COLUMN` unless the column is already there
+fn add_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    if !column_exists(conn, table, column)? {
+        conn.execute(
+            &format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// 1: Schema as it stood before migrations were versioned
+fn initial_schema(conn: &Connection) -> Result<()> {
     // Create workspaces table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS workspaces (
@@ -24,6 +279,8 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         )",
         [],
     )?;
+    add_column(conn, "chats", "agent_id", "TEXT")?;
+    add_column(conn, "chats", "parent_id", "TEXT")?;
 
     // Create messages table
     conn.execute(
@@ -40,33 +297,18 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         )",
         [],
     )?;
-
-    // Add assistant_message_id column if it doesn't exist (migration for existing databases)
-    conn.execute(
-        "ALTER TABLE messages ADD COLUMN assistant_message_id TEXT",
-        [],
-    )
-    .ok(); // Ignore error if column already exists
-
-    // Add tool_call_id column if it doesn't exist (migration for existing database)
-    conn.execute("ALTER TABLE messages ADD COLUMN tool_call_id TEXT", [])
-        .ok(); // Ignore error if column already exists
-
-    // Add reasoning column if it doesn't exist (migration for existing databases)
-    conn.execute("ALTER TABLE messages ADD COLUMN reasoning TEXT", [])
-        .ok(); // Ignore error if column already exists
-
-    // Add metadata column if it doesn't exist (migration for existing database)
-    conn.execute("ALTER TABLE messages ADD COLUMN metadata TEXT", [])
-        .ok(); // Ignore error if column already exists
+    add_column(conn, "messages", "assistant_message_id", "TEXT")?;
+    add_column(conn, "messages", "tool_call_id", "TEXT")?;
+    add_column(conn, "messages", "reasoning", "TEXT")?;
+    add_column(conn, "messages", "metadata", "TEXT")?;
 
     // Create workspace_settings table
+    // mcp_tool_ids is added separately, older databases have mcp_connection_ids instead
     conn.execute(
         "CREATE TABLE IF NOT EXISTS workspace_settings (
             workspace_id TEXT PRIMARY KEY,
             llm_connection_id TEXT,
             system_message TEXT,
-            mcp_tool_ids TEXT,
             stream_enabled INTEGER,
             default_model TEXT,
             created_at INTEGER NOT NULL,
@@ -75,70 +317,21 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         )",
         [],
     )?;
-
-    // Add stream_enabled column if it doesn't exist (migration for existing databases)
-    conn.execute(
-        "ALTER TABLE workspace_settings ADD COLUMN stream_enabled INTEGER",
-        [],
-    )
-    .ok(); // Ignore error if column already exists
-
-    // Add default_model column if it doesn't exist (migration for existing databases)
-    conn.execute(
-        "ALTER TABLE workspace_settings ADD COLUMN default_model TEXT",
-        [],
-    )
-    .ok(); // Ignore error if column already exists
-
-    // Add require_tool_permission column if it doesn't exist (migration for existing databases)
-    conn.execute(
-        "ALTER TABLE workspace_settings ADD COLUMN require_tool_permission INTEGER",
-        [],
-    )
-    .ok(); // Ignore error if column already exists
-
-    // Add tool_permission_config column if it doesn't exist (migration for existing databases)
-    conn.execute(
-        "ALTER TABLE workspace_settings ADD COLUMN tool_permission_config TEXT",
-        [],
-    )
-    .ok(); // Ignore error if column already exists
-
-    // Add max_agent_iterations column if it doesn't exist
-    conn.execute(
-        "ALTER TABLE workspace_settings ADD COLUMN max_agent_iterations INTEGER",
-        [],
-    )
-    .ok(); // Ignore error if column already exists
-
-    // Migrate mcp_connection_ids to mcp_tool_ids (column rename)
-    // First check if old column exists and new column doesn't
-    let has_old_column = conn
-        .prepare("SELECT mcp_connection_ids FROM workspace_settings LIMIT 1")
-        .is_ok();
-    let has_new_column = conn
-        .prepare("SELECT mcp_tool_ids FROM workspace_settings LIMIT 1")
-        .is_ok();
-
-    if has_old_column && !has_new_column {
-        // Add new column
-        conn.execute(
-            "ALTER TABLE workspace_settings ADD COLUMN mcp_tool_ids TEXT",
-            [],
-        )
-        .ok();
-        // Clear old data as format is incompatible (array -> object)
-        // Users will need to re-select their tools
-        conn.execute("UPDATE workspace_settings SET mcp_tool_ids = NULL", [])
-            .ok();
-    } else if !has_old_column && !has_new_column {
-        // Fresh install, add the new column directly
-        conn.execute(
-            "ALTER TABLE workspace_settings ADD COLUMN mcp_tool_ids TEXT",
-            [],
-        )
-        .ok();
-    }
+    add_column(conn, "workspace_settings", "stream_enabled", "INTEGER")?;
+    add_column(conn, "workspace_settings", "default_model", "TEXT")?;
+    add_column(
+        conn,
+        "workspace_settings",
+        "require_tool_permission",
+        "INTEGER",
+    )?;
+    add_column(conn, "workspace_settings", "tool_permission_config", "TEXT")?;
+    add_column(
+        conn,
+        "workspace_settings",
+        "max_agent_iterations",
+        "INTEGER",
+    )?;
 
     // Create llm_connections table
     conn.execute(
@@ -155,20 +348,8 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         )",
         [],
     )?;
-
-    // Add default_model column if it doesn't exist (migration for existing databases)
-    conn.execute(
-        "ALTER TABLE llm_connections ADD COLUMN default_model TEXT",
-        [],
-    )
-    .ok(); // Ignore error if column already exists
-
-    // Add enabled column if it doesn't exist (migration for existing databases)
-    conn.execute(
-        "ALTER TABLE llm_connections ADD COLUMN enabled INTEGER DEFAULT 1",
-        [],
-    )
-    .ok(); // Ignore error if column already exists
+    add_column(conn, "llm_connections", "default_model", "TEXT")?;
+    add_column(conn, "llm_connections", "enabled", "INTEGER DEFAULT 1")?;
 
     // Create mcp_server_connections table
     conn.execute(
@@ -186,28 +367,16 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         )",
         [],
     )?;
-
-    // Migrate existing mcp_server_connections to add status and tools_json columns if they don't exist
-    let _ = conn.execute(
-        "ALTER TABLE mcp_server_connections ADD COLUMN status TEXT DEFAULT 'disconnected'",
-        [],
-    );
-    let _ = conn.execute(
-        "ALTER TABLE mcp_server_connections ADD COLUMN tools_json TEXT",
-        [],
-    );
-    let _ = conn.execute(
-        "ALTER TABLE mcp_server_connections ADD COLUMN error_message TEXT",
-        [],
-    );
-    let _ = conn.execute(
-        "ALTER TABLE mcp_server_connections ADD COLUMN runtime_path TEXT",
-        [],
-    );
-    let _ = conn.execute(
-        "ALTER TABLE mcp_server_connections ADD COLUMN env_vars TEXT",
-        [],
-    );
+    add_column(
+        conn,
+        "mcp_server_connections",
+        "status",
+        "TEXT DEFAULT 'disconnected'",
+    )?;
+    add_column(conn, "mcp_server_connections", "tools_json", "TEXT")?;
+    add_column(conn, "mcp_server_connections", "error_message", "TEXT")?;
+    add_column(conn, "mcp_server_connections", "runtime_path", "TEXT")?;
+    add_column(conn, "mcp_server_connections", "env_vars", "TEXT")?;
 
     // Create app_settings table
     conn.execute(
@@ -231,22 +400,6 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         [],
     )?;
 
-    // Create indexes for better query performance
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_chats_workspace_id ON chats(workspace_id)",
-        [],
-    )?;
-
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_messages_chat_id ON messages(chat_id)",
-        [],
-    )?;
-
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_messages_timestamp ON messages(timestamp)",
-        [],
-    )?;
-
     // Create chat_input_settings table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS chat_input_settings (
@@ -282,7 +435,23 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         [],
     )?;
 
-    // Create indexes for usage_stats
+    // Create indexes for better query performance
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chats_workspace_id ON chats(workspace_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chats_parent_id ON chats(parent_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_messages_chat_id ON messages(chat_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_messages_timestamp ON messages(timestamp)",
+        [],
+    )?;
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_usage_stats_workspace_id ON usage_stats(workspace_id)",
         [],
@@ -292,19 +461,337 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         [],
     )?;
 
-    // Add agent_id column to chats if it doesn't exist
-    conn.execute("ALTER TABLE chats ADD COLUMN agent_id TEXT", [])
-        .ok();
+    Ok(())
+}
+
+// 2: mcp_connection_ids (array of connection IDs) became mcp_tool_ids ({ "tool_name": "connection_id" })
+// Selections are converted using each connection's cached tool list instead of being cleared
+fn mcp_tool_ids_from_connection_ids(conn: &Connection) -> Result<()> {
+    if column_exists(conn, "workspace_settings", "mcp_tool_ids")? {
+        return Ok(());
+    }
+    conn.execute(
+        "ALTER TABLE workspace_settings ADD COLUMN mcp_tool_ids TEXT",
+        [],
+    )?;
+
+    if !column_exists(conn, "workspace_settings", "mcp_connection_ids")? {
+        return Ok(());
+    }
+
+    let rows = {
+        let mut stmt = conn.prepare(
+            "SELECT workspace_id, mcp_connection_ids FROM workspace_settings WHERE mcp_connection_ids IS NOT NULL",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows
+    };
+
+    for (workspace_id, connection_ids) in rows {
+        let connection_ids: Vec<String> = serde_json::from_str(&connection_ids).unwrap_or_default();
+        let mut tool_ids = serde_json::Map::new();
+
+        for connection_id in connection_ids {
+            let tools_json = conn.query_row(
+                "SELECT tools_json FROM mcp_server_connections WHERE id = ?1",
+                params![connection_id],
+                |row| row.get::<_, Option<String>>(0),
+            );
+            let tools_json = match tools_json {
+                Ok(tools_json) => tools_json,
+                Err(rusqlite::Error::QueryReturnedNoRows) => None,
+                Err(e) => return Err(e),
+            };
+
+            let tools: Vec<serde_json::Value> = tools_json
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default();
+            for tool in tools {
+                if let Some(name) = tool.get("name").and_then(|name| name.as_str()) {
+                    tool_ids.insert(
+                        name.to_string(),
+                        serde_json::Value::String(connection_id.clone()),
+                    );
+                }
+            }
+        }
+
+        if !tool_ids.is_empty() {
+            conn.execute(
+                "UPDATE workspace_settings SET mcp_tool_ids = ?1 WHERE workspace_id = ?2",
+                params![
+                    serde_json::Value::Object(tool_ids).to_string(),
+                    workspace_id
+                ],
+            )?;
+        }
+    }
+
+    Ok(())
+}
 
-    // Add parent_id column to chats if it doesn't exist
-    conn.execute("ALTER TABLE chats ADD COLUMN parent_id TEXT", [])
-        .ok();
+// 3: Cached MCP resources and prompts
+fn mcp_server_resources_and_prompts(conn: &Connection) -> Result<()> {
+    add_column(conn, "mcp_server_connections", "resources_json", "TEXT")?;
+    add_column(conn, "mcp_server_connections", "prompts_json", "TEXT")?;
+    Ok(())
+}
 
-    // Create index for parent_id
+// 4: OAuth tokens for remote MCP connections
+fn mcp_oauth_tokens(conn: &Connection) -> Result<()> {
     conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_chats_parent_id ON chats(parent_id)",
+        "CREATE TABLE IF NOT EXISTS mcp_oauth_tokens (
+            connection_id TEXT PRIMARY KEY,
+            access_token TEXT NOT NULL,
+            refresh_token TEXT,
+            token_type TEXT NOT NULL,
+            expires_at INTEGER,
+            scope TEXT,
+            client_id TEXT NOT NULL,
+            client_secret TEXT,
+            token_endpoint TEXT NOT NULL,
+            resource TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            FOREIGN KEY (connection_id) REFERENCES mcp_server_connections(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+// 5: Secrets vault key metadata (single row)
+fn secrets_vault(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS secrets_vault (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            key_source TEXT NOT NULL,
+            kdf_salt TEXT,
+            key_check TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
         [],
     )?;
+    Ok(())
+}
+
+// 6: FTS5 indexes over message content/reasoning and chat titles, kept in sync by triggers
+// Index rows share the rowid of their source row; IDs are stored too so results never need the rowid
+fn full_text_search(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+            message_id UNINDEXED,
+            chat_id UNINDEXED,
+            content,
+            reasoning,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS chats_fts USING fts5(
+            chat_id UNINDEXED,
+            title,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS messages_fts_insert AFTER INSERT ON messages BEGIN
+            INSERT INTO messages_fts (rowid, message_id, chat_id, content, reasoning)
+            VALUES (new.rowid, new.id, new.chat_id, new.content, COALESCE(new.reasoning, ''));
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS messages_fts_update AFTER UPDATE OF content, reasoning ON messages BEGIN
+            DELETE FROM messages_fts WHERE rowid = old.rowid;
+            INSERT INTO messages_fts (rowid, message_id, chat_id, content, reasoning)
+            VALUES (new.rowid, new.id, new.chat_id, new.content, COALESCE(new.reasoning, ''));
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS messages_fts_delete AFTER DELETE ON messages BEGIN
+            DELETE FROM messages_fts WHERE rowid = old.rowid;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS chats_fts_insert AFTER INSERT ON chats BEGIN
+            INSERT INTO chats_fts (rowid, chat_id, title) VALUES (new.rowid, new.id, new.title);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS chats_fts_update AFTER UPDATE OF title ON chats BEGIN
+            DELETE FROM chats_fts WHERE rowid = old.rowid;
+            INSERT INTO chats_fts (rowid, chat_id, title) VALUES (new.rowid, new.id, new.title);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS chats_fts_delete AFTER DELETE ON chats BEGIN
+            DELETE FROM chats_fts WHERE rowid = old.rowid;
+        END;
+
+        DELETE FROM messages_fts;
+        INSERT INTO messages_fts (rowid, message_id, chat_id, content, reasoning)
+        SELECT rowid, id, chat_id, content, COALESCE(reasoning, '') FROM messages;
+
+        DELETE FROM chats_fts;
+        INSERT INTO chats_fts (rowid, chat_id, title) SELECT rowid, id, title FROM chats;",
+    )
+}
+
+// 7: Messages form a tree per chat, the chat remembers the leaf of the active branch
+// Existing chats become a single branch in timestamp order (rowid breaks ties)
+fn message_branches(conn: &Connection) -> Result<()> {
+    add_column(conn, "messages", "parent_message_id", "TEXT")?;
+    add_column(conn, "chats", "active_leaf_message_id", "TEXT")?;
+
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_messages_parent_message_id ON messages(parent_message_id);
+
+        UPDATE messages SET parent_message_id = (
+            SELECT p.id FROM messages p
+            WHERE p.chat_id = messages.chat_id
+              AND (p.timestamp < messages.timestamp
+                   OR (p.timestamp = messages.timestamp AND p.rowid < messages.rowid))
+            ORDER BY p.timestamp DESC, p.rowid DESC
+            LIMIT 1
+        )
+        WHERE parent_message_id IS NULL;
+
+        UPDATE chats SET active_leaf_message_id = (
+            SELECT m.id FROM messages m
+            WHERE m.chat_id = chats.id
+            ORDER BY m.timestamp DESC, m.rowid DESC
+            LIMIT 1
+        )
+        WHERE active_leaf_message_id IS NULL;",
+    )
+}
+
+// 8: Per-workspace context window strategy
+fn workspace_context_config(conn: &Connection) -> Result<()> {
+    add_column(conn, "workspace_settings", "context_config", "TEXT")
+}
+
+// 9: Prompt cache token counts in usage stats
+fn usage_cache_tokens(conn: &Connection) -> Result<()> {
+    add_column(
+        conn,
+        "usage_stats",
+        "cache_read_tokens",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+    add_column(
+        conn,
+        "usage_stats",
+        "cache_write_tokens",
+        "INTEGER NOT NULL DEFAULT 0",
+    )
+}
+
+// 10: Per-workspace tool call concurrency limits
+fn workspace_tool_execution_config(conn: &Connection) -> Result<()> {
+    add_column(conn, "workspace_settings", "tool_execution_config", "TEXT")
+}
+
+// 11: Remembered "always allow" tool permissions, per chat or per workspace (chat_id NULL)
+fn tool_permission_grants(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS tool_permission_grants (
+            id TEXT PRIMARY KEY,
+            workspace_id TEXT NOT NULL,
+            chat_id TEXT,
+            tool_name TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+            FOREIGN KEY (chat_id) REFERENCES chats(id) ON DELETE CASCADE
+        );
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_tool_permission_grants_scope
+            ON tool_permission_grants(workspace_id, IFNULL(chat_id, ''), tool_name);",
+    )?;
+    Ok(())
+}
 
+// 12: Record of every tool call, kept when its chat or workspace is deleted
+fn tool_audit_log(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS tool_audit_log (
+            id TEXT PRIMARY KEY,
+            workspace_id TEXT NOT NULL,
+            chat_id TEXT NOT NULL,
+            message_id TEXT NOT NULL,
+            tool_call_id TEXT NOT NULL,
+            connection_id TEXT,
+            tool_name TEXT NOT NULL,
+            arguments TEXT NOT NULL,
+            result_size INTEGER NOT NULL DEFAULT 0,
+            error TEXT,
+            duration_ms INTEGER NOT NULL DEFAULT 0,
+            permission TEXT NOT NULL,
+            timestamp INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_tool_audit_log_workspace
+            ON tool_audit_log(workspace_id, timestamp);
+        CREATE INDEX IF NOT EXISTS idx_tool_audit_log_chat ON tool_audit_log(chat_id);",
+    )?;
     Ok(())
 }
+
+// 13: Per-workspace chain of connection/model pairs tried when the chat model is unavailable
+fn workspace_fallback_models(conn: &Connection) -> Result<()> {
+    add_column(conn, "workspace_settings", "fallback_models", "TEXT")
+}
+
+// 14: API an OpenAI connection sends to, NULL keeps Chat Completions
+fn llm_connection_api_mode(conn: &Connection) -> Result<()> {
+    add_column(conn, "llm_connections", "api_mode", "TEXT")
+}
+
+/// Rewrite every non-empty secret column value
+/// `rewrite` returns `None` to keep a value unchanged
+pub fn rewrite_secret_columns(
+    conn: &Connection,
+    rewrite: &mut dyn FnMut(&str) -> Result<Option<String>, AppError>,
+) -> Result<usize, AppError> {
+    let mut updated = 0;
+
+    for (table, key, column) in SECRET_COLUMNS {
+        let rows = {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {key}, {column} FROM {table} WHERE {column} IS NOT NULL AND {column} != ''"
+            ))?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+
+        for (id, value) in rows {
+            if let Some(new_value) = rewrite(&value)? {
+                conn.execute(
+                    &format!("UPDATE {table} SET {column} = ?1 WHERE {key} = ?2"),
+                    params![new_value, id],
+                )?;
+                updated += 1;
+            }
+        }
+    }
+
+    Ok(updated)
+}
+
+/// Encrypt secrets saved in plaintext before the secrets vault existed
+/// Already encrypted values are skipped, so this is safe to run on every unlock
+pub fn migrate_plaintext_secrets(
+    conn: &Connection,
+    encrypt: &dyn Fn(&str) -> Result<String, AppError>,
+) -> Result<usize, AppError> {
+    let tx = conn.unchecked_transaction()?;
+    let migrated = rewrite_secret_columns(&tx, &mut |value| {
+        if is_encrypted(value) {
+            Ok(None)
+        } else {
+            encrypt(value).map(Some)
+        }
+    })?;
+    tx.commit()?;
+    Ok(migrated)
+}
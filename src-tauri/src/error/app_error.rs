@@ -28,6 +28,10 @@ pub enum AppError {
     #[error("[LLM] {0}")]
     Llm(String),
 
+    // Rate limited or failing after every retry, the next fallback model may answer instead
+    #[error("[LLM] {0}")]
+    LlmUnavailable(String),
+
     #[error("[Python] {0}")]
     Python(String),
 
@@ -57,6 +61,12 @@ pub enum AppError {
 
     #[error("[Agent] {0}")]
     Agent(String),
+
+    #[error("[Secrets] {0}")]
+    Secrets(String),
+
+    #[error("[Migration] {0}")]
+    Migration(String),
 }
 
 impl AppError {
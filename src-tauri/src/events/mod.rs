@@ -140,6 +140,32 @@ pub struct ToolPermissionRequestEvent {
     pub chat_id: String,
     pub message_id: String,
     pub tool_calls: Vec<ToolCall>,
+    pub timeout_secs: u64,
+}
+
+// MCP sampling request event (server asks for an LLM completion)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SamplingRequestEvent {
+    pub chat_id: String,
+    pub message_id: String,
+    pub tool_call_id: String,
+    pub request_id: String,
+    pub server_name: String,
+    pub system_prompt: Option<String>,
+    pub messages: serde_json::Value,
+    pub max_tokens: i64,
+}
+
+// MCP elicitation request event (server asks the user for input)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ElicitationRequestEvent {
+    pub chat_id: String,
+    pub message_id: String,
+    pub tool_call_id: String,
+    pub request_id: String,
+    pub server_name: String,
+    pub message: String,
+    pub requested_schema: serde_json::Value,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -1,8 +1,9 @@
 use crate::constants::TauriEvents;
 use crate::error::AppError;
 use crate::events::{
-    ToolCall, ToolCallsDetectedEvent, ToolExecutionCompletedEvent, ToolExecutionErrorEvent,
-    ToolExecutionProgressEvent, ToolExecutionStartedEvent, ToolPermissionRequestEvent,
+    ElicitationRequestEvent, SamplingRequestEvent, ToolCall, ToolCallsDetectedEvent,
+    ToolExecutionCompletedEvent, ToolExecutionErrorEvent, ToolExecutionProgressEvent,
+    ToolExecutionStartedEvent, ToolPermissionRequestEvent,
 };
 use tauri::{AppHandle, Emitter};
 
@@ -138,6 +139,7 @@ impl ToolEmitter {
         chat_id: String,
         message_id: String,
         tool_calls: Vec<ToolCall>,
+        timeout_secs: u64,
     ) -> Result<(), AppError> {
         self.app
             .emit(
@@ -146,10 +148,67 @@ impl ToolEmitter {
                     chat_id,
                     message_id,
                     tool_calls,
+                    timeout_secs,
                 },
             )
             .map_err(|e| {
                 AppError::Generic(format!("Failed to emit tool-permission-request event: {e}"))
             })
     }
+
+    pub fn emit_sampling_request(
+        &self,
+        chat_id: String,
+        message_id: String,
+        tool_call_id: String,
+        request_id: String,
+        server_name: String,
+        system_prompt: Option<String>,
+        messages: serde_json::Value,
+        max_tokens: i64,
+    ) -> Result<(), AppError> {
+        self.app
+            .emit(
+                TauriEvents::SAMPLING_REQUEST,
+                SamplingRequestEvent {
+                    chat_id,
+                    message_id,
+                    tool_call_id,
+                    request_id,
+                    server_name,
+                    system_prompt,
+                    messages,
+                    max_tokens,
+                },
+            )
+            .map_err(|e| AppError::Generic(format!("Failed to emit sampling-request event: {e}")))
+    }
+
+    pub fn emit_elicitation_request(
+        &self,
+        chat_id: String,
+        message_id: String,
+        tool_call_id: String,
+        request_id: String,
+        server_name: String,
+        message: String,
+        requested_schema: serde_json::Value,
+    ) -> Result<(), AppError> {
+        self.app
+            .emit(
+                TauriEvents::ELICITATION_REQUEST,
+                ElicitationRequestEvent {
+                    chat_id,
+                    message_id,
+                    tool_call_id,
+                    request_id,
+                    server_name,
+                    message,
+                    requested_schema,
+                },
+            )
+            .map_err(|e| {
+                AppError::Generic(format!("Failed to emit elicitation-request event: {e}"))
+            })
+    }
 }
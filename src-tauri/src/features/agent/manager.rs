@@ -379,6 +379,7 @@ impl AgentManager {
             None,
             None,
             None,
+            Some(client_key.clone()),
         )
         .await
         .map_err(|e| anyhow::anyhow!(e.to_string()))?;
@@ -447,6 +448,7 @@ impl AgentManager {
             None,
             None,
             None,
+            None,
         )
         .await
         .map_err(|e| anyhow::anyhow!("Failed to create agent client: {e}"))?;
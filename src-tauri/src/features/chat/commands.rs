@@ -1,5 +1,6 @@
-use super::models::Chat;
+use super::models::{Chat, CompareResponse, CompareTarget};
 use crate::error::AppError;
+use crate::models::llm_types::ResponseFormat;
 use crate::state::AppState;
 use tauri::{AppHandle, State};
 
@@ -83,9 +84,19 @@ pub async fn send_message(
     selected_model: Option<String>,
     reasoning_effort: Option<String>,
     llm_connection_id: Option<String>,
+    response_format: Option<ResponseFormat>,
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<SendMessageResult, AppError> {
+    // Kept in the user message's metadata so tool-call turns and regenerations reuse it
+    let metadata = match response_format {
+        Some(format) => {
+            format.validate().map_err(AppError::Validation)?;
+            Some(format.to_metadata(metadata.as_deref())?)
+        }
+        None => metadata,
+    };
+
     let result = state
         .chat_service
         .send_message(
@@ -141,12 +152,80 @@ pub async fn edit_and_resend_message(
     })
 }
 
+#[tauri::command]
+pub async fn regenerate_message(
+    chat_id: String,
+    message_id: String,
+    selected_model: Option<String>,
+    reasoning_effort: Option<String>,
+    llm_connection_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<SendMessageResult, AppError> {
+    let (assistant_message_id, _) = state
+        .chat_service
+        .regenerate_message(
+            chat_id,
+            message_id,
+            selected_model,
+            reasoning_effort,
+            llm_connection_id,
+            app,
+        )
+        .await
+        .map_err(|e| AppError::Generic(e.to_string()))?;
+
+    Ok(SendMessageResult {
+        assistant_message_id,
+    })
+}
+
+#[derive(serde::Serialize)]
+pub struct SendCompareMessageResult {
+    pub user_message_id: String,
+    pub responses: Vec<CompareResponse>,
+}
+
+/// Send one message to several connection/model pairs and keep every answer as a branch
+/// Compare turns run without tools and the chat input cannot start one yet, see `ChatService::send_compare_message`
+#[tauri::command]
+pub async fn send_compare_message(
+    chat_id: String,
+    content: String,
+    files: Option<Vec<String>>,
+    metadata: Option<String>,
+    targets: Vec<CompareTarget>,
+    reasoning_effort: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<SendCompareMessageResult, AppError> {
+    let (user_message_id, responses) = state
+        .chat_service
+        .send_compare_message(
+            chat_id,
+            content,
+            files,
+            metadata,
+            targets,
+            reasoning_effort,
+            app,
+        )
+        .await
+        .map_err(|e| AppError::Generic(e.to_string()))?;
+
+    Ok(SendCompareMessageResult {
+        user_message_id,
+        responses,
+    })
+}
+
 #[tauri::command]
 
 pub fn respond_tool_permission(
     message_id: String,
     approved: bool,
     allowed_tool_ids: Option<Vec<String>>,
+    remember: Option<crate::features::tool_permission::PermissionScope>,
     state: State<'_, AppState>,
 ) -> Result<(), AppError> {
     // Get the sender from pending permissions
@@ -162,6 +241,7 @@ pub fn respond_tool_permission(
         let decision = crate::state::PermissionDecision {
             approved,
             allowed_tool_ids: allowed_tool_ids.unwrap_or_default(),
+            remember,
         };
         sender.send(decision).map_err(|_| {
             AppError::Generic(format!(
@@ -0,0 +1,294 @@
+//! Context window management: per-model limits, token estimates and
+//! trimming the request history to fit.
+//!
+//! Estimates are deliberately rough (about four characters per token) and
+//! err on the side of sending less.
+
+use crate::features::llm_connection::models::LLMConnection;
+use crate::features::message::Message;
+use crate::features::workspace::settings::{ContextConfig, ContextStrategy};
+use crate::models::llm_types::{
+    AssistantContent, ChatCompletionTool, ChatMessage, ContentPart, LLMModel, UserContent,
+};
+
+#[cfg(test)]
+mod tests;
+
+// Metadata `type` of messages holding a summary of older turns
+pub const SUMMARY_METADATA_TYPE: &str = "context_summary";
+
+const CHARS_PER_TOKEN: usize = 4;
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+const IMAGE_TOKENS: usize = 1_000;
+const OMITTED_TOOL_OUTPUT: &str = "[Tool output omitted to fit the context window]";
+
+// Context windows by model ID prefix, the longest matching prefix wins
+const CONTEXT_LIMITS: &[(&str, usize)] = &[
+    // OpenAI
+    ("gpt-5", 400_000),
+    ("gpt-4.5", 128_000),
+    ("gpt-4.1", 1_047_576),
+    ("gpt-4o", 128_000),
+    ("chatgpt-4o", 128_000),
+    ("gpt-4-turbo", 128_000),
+    ("gpt-4-32k", 32_768),
+    ("gpt-4-0125", 128_000),
+    ("gpt-4-1106", 128_000),
+    ("gpt-4", 8_192),
+    ("gpt-3.5-turbo", 16_385),
+    ("gpt-oss", 131_072),
+    ("o1", 200_000),
+    ("o1-mini", 128_000),
+    ("o1-preview", 128_000),
+    ("o3", 200_000),
+    ("o4", 200_000),
+    // Anthropic
+    ("claude", 200_000),
+    // Google
+    ("gemini-1.5-pro", 2_097_152),
+    ("gemini", 1_048_576),
+    ("gemma", 128_000),
+    // Open models
+    ("deepseek", 128_000),
+    ("llama-3", 128_000),
+    ("llama3", 128_000),
+    ("qwen", 32_768),
+    ("mistral-large", 128_000),
+    ("mistral", 32_768),
+    ("mixtral", 32_768),
+];
+
+/// Known context window of a model in tokens, `None` for models not in the table
+/// Provider prefixes such as `openai/gpt-4o` or `models/gemini-pro` are ignored
+pub fn known_context_limit(model: &str) -> Option<usize> {
+    let model = model.to_lowercase();
+    let name = model.rsplit('/').next().unwrap_or(&model);
+
+    CONTEXT_LIMITS
+        .iter()
+        .filter(|(prefix, _)| name.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, limit)| *limit)
+}
+
+/// Context window of a model on a connection
+///
+/// Prefers the size the provider reported when the models were fetched, then the
+/// built-in table. `None` means the window is unknown and history is not trimmed.
+pub fn context_limit(connection: &LLMConnection, model: &str) -> Option<usize> {
+    connection
+        .models_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<Vec<LLMModel>>(json).ok())
+        .and_then(|models| {
+            models
+                .into_iter()
+                .find(|m| m.id == model)
+                .and_then(|m| m.context_length)
+        })
+        .or_else(|| known_context_limit(model))
+}
+
+/// Tokens available for the request, keeping room for the response
+/// The workspace setting overrides the model's window, `None` when neither is known
+pub fn input_budget(limit: Option<usize>, config: &ContextConfig) -> Option<usize> {
+    let limit = config.max_context_tokens.or(limit)?;
+    let reserved = (limit / 8).clamp(1_024, 16_384);
+    Some(limit.saturating_sub(reserved))
+}
+
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(CHARS_PER_TOKEN)
+}
+
+fn estimate_part_tokens(part: &ContentPart) -> usize {
+    match part {
+        ContentPart::Text { text } => estimate_tokens(text),
+        ContentPart::ImageUrl { .. } => IMAGE_TOKENS,
+        // Base64 payloads: 3 bytes per 4 characters, then the usual ratio
+        ContentPart::FileUrl { file_url } => file_url.url.len() * 3 / 4 / CHARS_PER_TOKEN,
+        ContentPart::InlineData { inline_data } if inline_data.mime_type.starts_with("image/") => {
+            IMAGE_TOKENS
+        }
+        ContentPart::InlineData { inline_data } => inline_data.data.len() * 3 / 4 / CHARS_PER_TOKEN,
+    }
+}
+
+pub fn estimate_message_tokens(message: &ChatMessage) -> usize {
+    let content = match message {
+        ChatMessage::System { content } => estimate_tokens(content),
+        ChatMessage::User { content } => match content {
+            UserContent::Text(text) => estimate_tokens(text),
+            UserContent::Parts(parts) => parts.iter().map(estimate_part_tokens).sum(),
+        },
+        ChatMessage::Assistant {
+            content,
+            tool_calls,
+            ..
+        } => {
+            let text = match content {
+                AssistantContent::Text(text) => estimate_tokens(text),
+                AssistantContent::Parts(parts) => parts.iter().map(estimate_part_tokens).sum(),
+            };
+            let calls: usize = tool_calls
+                .iter()
+                .flatten()
+                .map(|call| {
+                    estimate_tokens(&call.function.name) + estimate_tokens(&call.function.arguments)
+                })
+                .sum();
+            text + calls
+        }
+        ChatMessage::Tool { content, parts, .. } => {
+            estimate_tokens(content) + parts.iter().map(estimate_part_tokens).sum::<usize>()
+        }
+    };
+    content + MESSAGE_OVERHEAD_TOKENS
+}
+
+pub fn estimate_messages_tokens(messages: &[ChatMessage]) -> usize {
+    messages.iter().map(estimate_message_tokens).sum()
+}
+
+pub fn estimate_tools_tokens(tools: Option<&[ChatCompletionTool]>) -> usize {
+    tools.map_or(0, |tools| {
+        serde_json::to_string(tools).map_or(0, |json| estimate_tokens(&json))
+    })
+}
+
+/// Rough token count of stored history, files counted as images
+pub fn estimate_history_tokens(messages: &[&Message]) -> usize {
+    messages
+        .iter()
+        .filter(|m| m.role != "tool_call")
+        .map(|m| {
+            let files = m
+                .metadata
+                .as_deref()
+                .and_then(|meta| serde_json::from_str::<serde_json::Value>(meta).ok())
+                .and_then(|meta| meta.get("files").and_then(|f| f.as_array()).map(Vec::len))
+                .unwrap_or(0);
+            estimate_tokens(&m.content) + files * IMAGE_TOKENS + MESSAGE_OVERHEAD_TOKENS
+        })
+        .sum()
+}
+
+pub fn is_summary(message: &Message) -> bool {
+    message
+        .metadata
+        .as_deref()
+        .and_then(|meta| serde_json::from_str::<serde_json::Value>(meta).ok())
+        .is_some_and(|meta| meta["type"] == SUMMARY_METADATA_TYPE)
+}
+
+/// Split the active path at its latest summary
+///
+/// Returns the summary and the messages it does not cover. Summaries sit at the end of
+/// the path when they are written, their `covers_until` metadata marks the last message
+/// they replace.
+pub fn split_at_summary(messages: &[Message]) -> (Option<&Message>, Vec<&Message>) {
+    let Some(summary) = messages.iter().rev().find(|m| is_summary(m)) else {
+        return (None, messages.iter().collect());
+    };
+
+    let covers_until = summary
+        .metadata
+        .as_deref()
+        .and_then(|meta| serde_json::from_str::<serde_json::Value>(meta).ok())
+        .and_then(|meta| meta["covers_until"].as_str().map(ToString::to_string));
+    let start = covers_until
+        .and_then(|id| messages.iter().position(|m| m.id == id))
+        .map_or(0, |index| index + 1);
+
+    let remaining = messages[start..]
+        .iter()
+        .filter(|m| !is_summary(m))
+        .collect();
+    (Some(summary), remaining)
+}
+
+/// Trim request messages to `budget` tokens according to the workspace strategy
+///
+/// Summaries are written before the request is built, so at this point every strategy
+/// ends with dropping the oldest messages. The system message and the latest user turn
+/// are kept. Returns the number of messages removed or blanked.
+pub fn fit_messages(
+    messages: &mut Vec<ChatMessage>,
+    budget: usize,
+    strategy: ContextStrategy,
+) -> usize {
+    let mut total = estimate_messages_tokens(messages);
+    if total <= budget {
+        return 0;
+    }
+
+    let mut changed = 0;
+    if strategy != ContextStrategy::Truncate {
+        changed += drop_stale_tool_outputs(messages, &mut total, budget);
+    }
+    changed += truncate_oldest(messages, &mut total, budget);
+    if total > budget && strategy == ContextStrategy::Truncate {
+        // The latest turn alone is too large, its earlier tool results are the last resort
+        changed += drop_stale_tool_outputs(messages, &mut total, budget);
+    }
+    changed
+}
+
+/// Blank out tool results, oldest first, keeping the trailing ones the model has not answered yet
+fn drop_stale_tool_outputs(
+    messages: &mut [ChatMessage],
+    total: &mut usize,
+    budget: usize,
+) -> usize {
+    let unanswered = messages
+        .iter()
+        .rev()
+        .take_while(|m| matches!(m, ChatMessage::Tool { .. }))
+        .count();
+    let stale_end = messages.len() - unanswered;
+
+    let mut dropped = 0;
+    for message in &mut messages[..stale_end] {
+        if *total <= budget {
+            break;
+        }
+        if let ChatMessage::Tool { content, parts, .. } = message {
+            if content == OMITTED_TOOL_OUTPUT && parts.is_empty() {
+                continue;
+            }
+            let before = estimate_message_tokens(message);
+            if let ChatMessage::Tool { content, parts, .. } = message {
+                *content = OMITTED_TOOL_OUTPUT.to_string();
+                parts.clear();
+            }
+            *total = total.saturating_sub(before - estimate_message_tokens(message));
+            dropped += 1;
+        }
+    }
+    dropped
+}
+
+/// Remove the oldest messages after the system prompt, never past the latest user message
+fn truncate_oldest(messages: &mut Vec<ChatMessage>, total: &mut usize, budget: usize) -> usize {
+    let first = usize::from(matches!(messages.first(), Some(ChatMessage::System { .. })));
+    let Some(last_user) = messages
+        .iter()
+        .rposition(|m| matches!(m, ChatMessage::User { .. }))
+    else {
+        return 0;
+    };
+
+    let mut end = first;
+    while end < last_user && *total > budget {
+        *total = total.saturating_sub(estimate_message_tokens(&messages[end]));
+        end += 1;
+    }
+    // Never start the history with a reply or a tool result whose call was removed
+    while end < last_user && !matches!(messages[end], ChatMessage::User { .. }) {
+        *total = total.saturating_sub(estimate_message_tokens(&messages[end]));
+        end += 1;
+    }
+
+    messages.drain(first..end);
+    end - first
+}
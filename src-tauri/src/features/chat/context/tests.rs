@@ -0,0 +1,367 @@
+use super::*;
+use crate::models::llm_types::{ToolCall, ToolCallFunction};
+
+fn system() -> ChatMessage {
+    ChatMessage::System {
+        content: "s".repeat(40),
+    }
+}
+
+fn user(text: &str) -> ChatMessage {
+    ChatMessage::User {
+        content: UserContent::Text(text.to_string()),
+    }
+}
+
+fn assistant(text: &str) -> ChatMessage {
+    ChatMessage::Assistant {
+        content: AssistantContent::Text(text.to_string()),
+        tool_calls: None,
+        reasoning_items: None,
+    }
+}
+
+fn tool_call(id: &str) -> ChatMessage {
+    ChatMessage::Assistant {
+        content: AssistantContent::Text(String::new()),
+        tool_calls: Some(vec![ToolCall {
+            id: id.to_string(),
+            r#type: "function".to_string(),
+            function: ToolCallFunction {
+                name: "read_file".to_string(),
+                arguments: r#"{"path": "README.md"}"#.to_string(),
+            },
+        }]),
+        reasoning_items: None,
+    }
+}
+
+fn tool_result(id: &str, size: usize) -> ChatMessage {
+    ChatMessage::Tool {
+        content: "r".repeat(size),
+        tool_call_id: id.to_string(),
+        parts: Vec::new(),
+        is_error: false,
+    }
+}
+
+fn text_of(message: &ChatMessage) -> &str {
+    match message {
+        ChatMessage::System { content } | ChatMessage::Tool { content, .. } => content,
+        ChatMessage::User {
+            content: UserContent::Text(text),
+        }
+        | ChatMessage::Assistant {
+            content: AssistantContent::Text(text),
+            ..
+        } => text,
+        _ => "",
+    }
+}
+
+fn texts(messages: &[ChatMessage]) -> Vec<&str> {
+    messages.iter().map(text_of).collect()
+}
+
+fn connection(models_json: Option<&str>) -> LLMConnection {
+    LLMConnection {
+        id: "connection".to_string(),
+        name: "Connection".to_string(),
+        base_url: "https://example.com/v1".to_string(),
+        provider: "openai".to_string(),
+        api_key: String::new(),
+        models_json: models_json.map(ToString::to_string),
+        default_model: None,
+        api_mode: None,
+        enabled: true,
+        created_at: 0,
+        updated_at: 0,
+    }
+}
+
+fn stored(id: &str, metadata: Option<&str>) -> Message {
+    Message {
+        id: id.to_string(),
+        chat_id: "chat".to_string(),
+        role: "user".to_string(),
+        content: id.to_string(),
+        reasoning: None,
+        timestamp: 0,
+        assistant_message_id: None,
+        tool_call_id: None,
+        metadata: metadata.map(ToString::to_string),
+        parent_message_id: None,
+    }
+}
+
+#[test]
+fn matches_the_longest_model_prefix() {
+    assert_eq!(known_context_limit("gpt-4o-mini"), Some(128_000));
+    assert_eq!(known_context_limit("gpt-4-0613"), Some(8_192));
+    assert_eq!(known_context_limit("gpt-4-turbo-preview"), Some(128_000));
+    assert_eq!(known_context_limit("o1-mini-2024-09-12"), Some(128_000));
+    assert_eq!(known_context_limit("o1-2024-12-17"), Some(200_000));
+    assert_eq!(known_context_limit("mistral-large-latest"), Some(128_000));
+}
+
+#[test]
+fn ignores_case_and_provider_prefixes() {
+    assert_eq!(known_context_limit("openai/GPT-4.1"), Some(1_047_576));
+    assert_eq!(
+        known_context_limit("models/gemini-1.5-pro-latest"),
+        Some(2_097_152)
+    );
+    assert_eq!(known_context_limit("my-finetune"), None);
+    assert_eq!(known_context_limit(""), None);
+}
+
+#[test]
+fn prefers_the_context_length_reported_by_the_provider() {
+    let connection = connection(Some(
+        r#"[
+            {"id": "gpt-4o", "name": "GPT-4o", "contextLength": 64000},
+            {"id": "claude-sonnet", "name": "Claude Sonnet"},
+            {"id": "local-model", "name": "Local", "contextLength": 8192}
+        ]"#,
+    ));
+
+    assert_eq!(context_limit(&connection, "gpt-4o"), Some(64_000));
+    assert_eq!(context_limit(&connection, "claude-sonnet"), Some(200_000));
+    assert_eq!(context_limit(&connection, "local-model"), Some(8_192));
+    assert_eq!(context_limit(&connection, "unlisted-model"), None);
+}
+
+#[test]
+fn falls_back_to_the_table_without_a_model_list() {
+    assert_eq!(context_limit(&connection(None), "gpt-4o"), Some(128_000));
+    assert_eq!(
+        context_limit(&connection(Some("not json")), "gpt-4o"),
+        Some(128_000)
+    );
+    assert_eq!(context_limit(&connection(None), "unknown"), None);
+}
+
+#[test]
+fn reserves_room_for_the_response() {
+    let config = ContextConfig::default();
+
+    assert_eq!(input_budget(None, &config), None);
+    assert_eq!(input_budget(Some(4_096), &config), Some(3_072));
+    assert_eq!(input_budget(Some(64_000), &config), Some(56_000));
+    assert_eq!(input_budget(Some(200_000), &config), Some(183_616));
+    assert_eq!(input_budget(Some(512), &config), Some(0));
+
+    let config = ContextConfig {
+        max_context_tokens: Some(32_000),
+        ..ContextConfig::default()
+    };
+    assert_eq!(input_budget(None, &config), Some(28_000));
+    assert_eq!(input_budget(Some(200_000), &config), Some(28_000));
+}
+
+#[test]
+fn leaves_messages_within_the_budget_alone() {
+    let mut messages = vec![system(), user("hello"), assistant("hi"), user("again")];
+    let total = estimate_messages_tokens(&messages);
+
+    assert_eq!(
+        fit_messages(&mut messages, total, ContextStrategy::Truncate),
+        0
+    );
+    assert_eq!(messages.len(), 4);
+}
+
+#[test]
+fn returns_early_when_nothing_can_be_trimmed() {
+    let mut messages = vec![system(), user(&"x".repeat(4_000))];
+
+    for strategy in [
+        ContextStrategy::Truncate,
+        ContextStrategy::Summarize,
+        ContextStrategy::DropToolOutputs,
+    ] {
+        assert_eq!(fit_messages(&mut messages, 10, strategy), 0);
+    }
+    assert_eq!(texts(&messages), vec!["s".repeat(40), "x".repeat(4_000)]);
+}
+
+#[test]
+fn drops_the_oldest_turns_but_keeps_the_system_prompt() {
+    let mut messages = vec![
+        system(),
+        user("first question"),
+        assistant("first answer"),
+        user("second question"),
+        assistant("second answer"),
+        user("third question"),
+    ];
+    let budget = estimate_messages_tokens(&messages) - 1;
+
+    let removed = fit_messages(&mut messages, budget, ContextStrategy::Truncate);
+
+    assert_eq!(removed, 2);
+    assert_eq!(
+        texts(&messages),
+        vec![
+            "s".repeat(40).as_str(),
+            "second question",
+            "second answer",
+            "third question"
+        ]
+    );
+}
+
+#[test]
+fn never_starts_the_history_with_a_reply_or_a_tool_result() {
+    let mut messages = vec![
+        system(),
+        user("first question"),
+        tool_call("call-1"),
+        tool_result("call-1", 40),
+        assistant("first answer"),
+        user("second question"),
+    ];
+    let mut total = estimate_messages_tokens(&messages);
+    let budget = total - 1;
+
+    let removed = truncate_oldest(&mut messages, &mut total, budget);
+
+    assert_eq!(removed, 4);
+    assert_eq!(
+        texts(&messages),
+        vec!["s".repeat(40).as_str(), "second question"]
+    );
+}
+
+#[test]
+fn counts_the_tokens_left_after_truncating() {
+    let mut messages = vec![
+        system(),
+        user(&"a".repeat(400)),
+        assistant(&"b".repeat(400)),
+        user("latest"),
+    ];
+    let mut total = estimate_messages_tokens(&messages);
+    let budget = total - 150;
+
+    truncate_oldest(&mut messages, &mut total, budget);
+
+    assert_eq!(total, estimate_messages_tokens(&messages));
+    assert!(total <= budget);
+}
+
+#[test]
+fn blanks_answered_tool_outputs_before_dropping_turns() {
+    let mut messages = vec![
+        system(),
+        user("question"),
+        tool_call("call-1"),
+        tool_result("call-1", 4_000),
+        tool_call("call-2"),
+        tool_result("call-2", 4_000),
+    ];
+    let budget = estimate_messages_tokens(&messages) - 10;
+
+    let changed = fit_messages(&mut messages, budget, ContextStrategy::DropToolOutputs);
+
+    assert_eq!(changed, 1);
+    assert_eq!(messages.len(), 6);
+    assert_eq!(text_of(&messages[3]), OMITTED_TOOL_OUTPUT);
+    assert_eq!(text_of(&messages[5]), "r".repeat(4_000));
+}
+
+#[test]
+fn blanks_only_as_many_tool_outputs_as_needed() {
+    let mut messages = vec![
+        user("question"),
+        tool_call("call-1"),
+        tool_result("call-1", 400),
+        tool_call("call-2"),
+        tool_result("call-2", 400),
+        assistant("answer"),
+        user("follow-up"),
+    ];
+    let mut total = estimate_messages_tokens(&messages);
+    let budget = total - 50;
+
+    let dropped = drop_stale_tool_outputs(&mut messages, &mut total, budget);
+
+    assert_eq!(dropped, 1);
+    assert_eq!(total, estimate_messages_tokens(&messages));
+    assert_eq!(text_of(&messages[2]), OMITTED_TOOL_OUTPUT);
+    assert_eq!(text_of(&messages[4]), "r".repeat(400));
+
+    // Already blanked outputs are skipped instead of counted again
+    let dropped = drop_stale_tool_outputs(&mut messages, &mut total, 0);
+    assert_eq!(dropped, 1);
+}
+
+#[test]
+fn blanks_tool_outputs_of_the_latest_turn_as_a_last_resort() {
+    let mut messages = vec![
+        system(),
+        user("question"),
+        tool_call("call-1"),
+        tool_result("call-1", 4_000),
+        tool_call("call-2"),
+        tool_result("call-2", 4_000),
+    ];
+    let budget = estimate_messages_tokens(&messages) - 10;
+
+    let changed = fit_messages(&mut messages, budget, ContextStrategy::Truncate);
+
+    assert_eq!(changed, 1);
+    assert_eq!(text_of(&messages[1]), "question");
+    assert_eq!(text_of(&messages[3]), OMITTED_TOOL_OUTPUT);
+    assert_eq!(text_of(&messages[5]), "r".repeat(4_000));
+}
+
+#[test]
+fn splits_the_history_at_the_latest_summary() {
+    let messages = vec![
+        stored("m1", None),
+        stored("m2", None),
+        stored(
+            "old-summary",
+            Some(r#"{"type": "context_summary", "covers_until": "m1"}"#),
+        ),
+        stored("m3", None),
+        stored(
+            "summary",
+            Some(r#"{"type": "context_summary", "covers_until": "m2"}"#),
+        ),
+        stored("m4", Some(r#"{"files": []}"#)),
+    ];
+
+    let (summary, remaining) = split_at_summary(&messages);
+
+    assert_eq!(summary.map(|m| m.id.as_str()), Some("summary"));
+    assert_eq!(
+        remaining.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(),
+        vec!["m3", "m4"]
+    );
+}
+
+#[test]
+fn keeps_the_whole_history_without_a_usable_summary() {
+    let messages = vec![stored("m1", None), stored("m2", Some("not json"))];
+    let (summary, remaining) = split_at_summary(&messages);
+    assert!(summary.is_none());
+    assert_eq!(remaining.len(), 2);
+
+    // A summary whose covered message is gone replaces nothing
+    let messages = vec![
+        stored("m1", None),
+        stored(
+            "summary",
+            Some(r#"{"type": "context_summary", "covers_until": "deleted"}"#),
+        ),
+        stored("m2", None),
+    ];
+    let (summary, remaining) = split_at_summary(&messages);
+    assert!(summary.is_some());
+    assert_eq!(
+        remaining.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(),
+        vec!["m1", "m2"]
+    );
+}
@@ -1,8 +1,10 @@
 pub mod commands;
+pub mod context;
 pub mod emitter;
 pub mod input_settings;
 pub mod models;
 pub mod repository;
+pub mod resource_cache;
 pub mod service;
 
 pub use emitter::ChatEmitter;
@@ -11,3 +11,21 @@ pub struct Chat {
     pub agent_id: Option<String>,
     pub parent_id: Option<String>,
 }
+
+/// A connection and model answering in compare mode
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompareTarget {
+    pub llm_connection_id: String,
+    pub model: String,
+}
+
+/// One answer of a compare turn, `error` is set when that model failed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompareResponse {
+    pub assistant_message_id: String,
+    pub llm_connection_id: String,
+    pub model: String,
+    pub content: String,
+    pub error: Option<String>,
+    pub latency_ms: u64,
+}
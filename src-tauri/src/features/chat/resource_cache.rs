@@ -0,0 +1,101 @@
+//! Contents of the MCP resources attached in each chat
+//!
+//! Every send includes the resources attached anywhere in the history, so their contents are
+//! kept for a while instead of being read from the server again on each turn.
+
+use crate::features::tool::models::MCPResourceRef;
+use crate::models::llm_types::ContentPart;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod tests;
+
+/// How long read contents are reused, the same interval as the MCP catalog refresh
+const RESOURCE_TTL: Duration = Duration::from_mins(5);
+
+struct CachedResource {
+    parts: Vec<ContentPart>,
+    read_at: Instant,
+}
+
+/// Resource contents by `chat_id`; expired entries are dropped whenever the cache is used
+#[derive(Default)]
+pub struct ResourceCache {
+    chats: Mutex<HashMap<String, HashMap<MCPResourceRef, CachedResource>>>,
+}
+
+impl ResourceCache {
+    /// Contents read for a chat that have not expired yet
+    pub fn get(&self, chat_id: &str) -> HashMap<MCPResourceRef, Vec<ContentPart>> {
+        self.get_at(chat_id, Instant::now())
+    }
+
+    pub fn insert(&self, chat_id: &str, resources: HashMap<MCPResourceRef, Vec<ContentPart>>) {
+        self.insert_at(chat_id, resources, Instant::now());
+    }
+
+    /// Forget a deleted chat
+    pub fn remove_chat(&self, chat_id: &str) {
+        self.lock().remove(chat_id);
+    }
+
+    /// Forget everything read through a connection that was disconnected or deleted
+    pub fn remove_connection(&self, connection_id: &str) {
+        let mut chats = self.lock();
+        for resources in chats.values_mut() {
+            resources.retain(|resource_ref, _| resource_ref.connection_id != connection_id);
+        }
+        chats.retain(|_, resources| !resources.is_empty());
+    }
+
+    fn get_at(&self, chat_id: &str, now: Instant) -> HashMap<MCPResourceRef, Vec<ContentPart>> {
+        let mut chats = self.lock();
+        Self::prune(&mut chats, now);
+        chats
+            .get(chat_id)
+            .map(|resources| {
+                resources
+                    .iter()
+                    .map(|(resource_ref, cached)| (resource_ref.clone(), cached.parts.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn insert_at(
+        &self,
+        chat_id: &str,
+        resources: HashMap<MCPResourceRef, Vec<ContentPart>>,
+        now: Instant,
+    ) {
+        let mut chats = self.lock();
+        Self::prune(&mut chats, now);
+        chats
+            .entry(chat_id.to_string())
+            .or_default()
+            .extend(resources.into_iter().map(|(resource_ref, parts)| {
+                (
+                    resource_ref,
+                    CachedResource {
+                        parts,
+                        read_at: now,
+                    },
+                )
+            }));
+    }
+
+    fn prune(chats: &mut HashMap<String, HashMap<MCPResourceRef, CachedResource>>, now: Instant) {
+        for resources in chats.values_mut() {
+            resources.retain(|_, cached| now.duration_since(cached.read_at) < RESOURCE_TTL);
+        }
+        chats.retain(|_, resources| !resources.is_empty());
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, HashMap<MCPResourceRef, CachedResource>>> {
+        self.chats
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,77 @@
+use super::*;
+
+fn resource(connection_id: &str, uri: &str) -> MCPResourceRef {
+    MCPResourceRef {
+        connection_id: connection_id.to_string(),
+        uri: uri.to_string(),
+    }
+}
+
+fn contents(resource_ref: &MCPResourceRef) -> HashMap<MCPResourceRef, Vec<ContentPart>> {
+    HashMap::from([(
+        resource_ref.clone(),
+        vec![ContentPart::Text {
+            text: resource_ref.uri.clone(),
+        }],
+    )])
+}
+
+#[test]
+fn keeps_contents_per_chat() {
+    let cache = ResourceCache::default();
+    let readme = resource("github", "file:///README.md");
+    let now = Instant::now();
+    cache.insert_at("chat-1", contents(&readme), now);
+
+    assert!(cache.get_at("chat-1", now).contains_key(&readme));
+    assert!(cache.get_at("chat-2", now).is_empty());
+}
+
+#[test]
+fn expires_contents_after_the_ttl() {
+    let cache = ResourceCache::default();
+    let readme = resource("github", "file:///README.md");
+    let now = Instant::now();
+    cache.insert_at("chat-1", contents(&readme), now);
+
+    assert!(cache
+        .get_at("chat-1", now + RESOURCE_TTL / 2)
+        .contains_key(&readme));
+    assert!(cache.get_at("chat-1", now + RESOURCE_TTL).is_empty());
+    assert!(cache.lock().is_empty());
+}
+
+#[test]
+fn prunes_expired_chats_when_another_chat_is_used() {
+    let cache = ResourceCache::default();
+    let now = Instant::now();
+    cache.insert_at("old", contents(&resource("github", "a")), now);
+    cache.insert_at(
+        "new",
+        contents(&resource("github", "b")),
+        now + RESOURCE_TTL,
+    );
+
+    assert!(!cache.lock().contains_key("old"));
+    assert!(cache.lock().contains_key("new"));
+}
+
+#[test]
+fn forgets_a_chat_and_a_connection() {
+    let cache = ResourceCache::default();
+    let github = resource("github", "a");
+    let drive = resource("drive", "b");
+    cache.insert("chat-1", contents(&github));
+    cache.insert("chat-1", contents(&drive));
+    cache.insert("chat-2", contents(&github));
+
+    cache.remove_connection("github");
+    assert_eq!(
+        cache.get("chat-1").into_keys().collect::<Vec<_>>(),
+        vec![drive]
+    );
+    assert!(cache.get("chat-2").is_empty());
+
+    cache.remove_chat("chat-1");
+    assert!(cache.lock().is_empty());
+}
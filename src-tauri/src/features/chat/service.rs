@@ -1,21 +1,37 @@
-use super::models::Chat;
+use super::context;
+use super::models::{Chat, CompareResponse, CompareTarget};
 use super::repository::ChatRepository;
+use super::resource_cache::ResourceCache;
 use crate::error::AppError;
 use crate::events::{AgentEmitter, ToolEmitter};
+use crate::features::llm_connection::models::LLMConnection;
 use crate::features::llm_connection::LLMConnectionService;
 use crate::features::message::{Message, MessageEmitter, MessageService};
+use crate::features::tool::call_context::ToolCallContext;
+use crate::features::tool::mcp_client::MCPClientService;
+use crate::features::tool::models::{
+    MCPResourceContent, MCPResourceRef, MCPToolFile, MCPToolOutput,
+};
+use crate::features::tool::policy::{CircuitBreakerConfig, ToolCallPolicy};
 use crate::features::tool::service::ToolService;
+use crate::features::tool_audit::{AuditPermission, ToolAuditEntry};
+use crate::features::tool_permission::{
+    PermissionCheck, ToolPermissionConfig, ToolPermissionService,
+};
 use crate::features::usage::UsageService;
-use crate::features::workspace::settings::{WorkspaceSettings, WorkspaceSettingsService};
+use crate::features::workspace::settings::{
+    ContextConfig, ContextStrategy, WorkspaceSettings, WorkspaceSettingsService,
+};
 use crate::models::llm_types::{
     AssistantContent, ChatCompletionTool, ChatMessage, ContentPart, FileUrl, ImageUrl,
-    LLMChatRequest, LLMChatResponse, ToolChoice, UserContent,
+    LLMChatRequest, LLMChatResponse, ResponseFormat, ToolChoice, UserContent,
 };
+use crate::services::llm::{FallbackUsed, LLMTarget};
 use crate::services::LLMService;
 use base64::{engine::general_purpose, Engine as _};
 use rust_mcp_sdk::{schema::CallToolRequestParams, McpClient};
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -23,6 +39,16 @@ use tauri::AppHandle;
 use tauri::Manager;
 use tokio::sync::Mutex;
 
+/// Most connection/model pairs one compare turn fans out to
+const MAX_COMPARE_TARGETS: usize = 6;
+
+/// Permission outcome of a turn's tool calls, by tool call ID
+#[derive(Default)]
+struct TurnPermissions {
+    permissions: HashMap<String, AuditPermission>,
+    denied: HashMap<String, String>, // Reason the call may not run
+}
+
 pub struct ChatService {
     repository: Arc<dyn ChatRepository>,
     llm_service: Arc<LLMService>,
@@ -34,6 +60,7 @@ pub struct ChatService {
     agent_manager: Arc<crate::features::agent::manager::AgentManager>,
     // Cancellation channels for each chat_id
     cancellation_senders: Arc<Mutex<HashMap<String, tokio::sync::broadcast::Sender<()>>>>,
+    resource_cache: ResourceCache,
 }
 
 impl ChatService {
@@ -57,6 +84,7 @@ impl ChatService {
             usage_service,
             agent_manager,
             cancellation_senders: Arc::new(Mutex::new(HashMap::new())),
+            resource_cache: ResourceCache::default(),
         }
     }
 
@@ -195,6 +223,189 @@ impl ChatService {
         Some(description)
     }
 
+    /// Extract MCP resources attached to a message from its metadata JSON.
+    fn extract_resource_refs(&self, metadata: &str) -> Vec<MCPResourceRef> {
+        serde_json::from_str::<serde_json::Value>(metadata)
+            .ok()
+            .and_then(|meta_json| meta_json.get("resources").cloned())
+            .and_then(|resources| serde_json::from_value(resources).ok())
+            .unwrap_or_default()
+    }
+
+    /// Read all MCP resources attached to the history and the current message.
+    /// Contents are cached per chat for a while and the others are fetched concurrently.
+    /// A resource attached to the current message that cannot be read fails the send;
+    /// an unreadable one from the history is replaced by a note saying so.
+    async fn load_attached_resources(
+        &self,
+        chat_id: &str,
+        existing_messages: &[Message],
+        user_metadata: Option<&str>,
+    ) -> Result<HashMap<MCPResourceRef, Vec<ContentPart>>, AppError> {
+        let current_refs = user_metadata
+            .map(|metadata| self.extract_resource_refs(metadata))
+            .unwrap_or_default();
+        let history_refs = existing_messages
+            .iter()
+            .filter(|msg| msg.role == "user")
+            .filter_map(|msg| msg.metadata.as_deref())
+            .flat_map(|metadata| self.extract_resource_refs(metadata));
+
+        let mut resources = self.resource_cache.get(chat_id);
+        let unread: HashSet<MCPResourceRef> = history_refs
+            .chain(current_refs.iter().cloned())
+            .filter(|resource_ref| !resources.contains_key(resource_ref))
+            .collect();
+        if unread.is_empty() {
+            return Ok(resources);
+        }
+
+        let reads = unread.into_iter().map(|resource_ref| async move {
+            let result = self
+                .tool_service
+                .read_resource(&resource_ref.connection_id, &resource_ref.uri)
+                .await;
+            (resource_ref, result)
+        });
+
+        let mut read = HashMap::new();
+        let mut failures = Vec::new();
+        for (resource_ref, result) in futures::future::join_all(reads).await {
+            match result {
+                Ok(contents) => {
+                    read.insert(resource_ref, Self::resource_content_parts(contents));
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        connection_id = %resource_ref.connection_id,
+                        uri = %resource_ref.uri,
+                        error = %e,
+                        "Failed to read attached MCP resource"
+                    );
+                    if current_refs.contains(&resource_ref) {
+                        failures.push(format!("{}: {e}", resource_ref.uri));
+                    } else {
+                        // Not cached, so the next send tries again
+                        let text = format!(
+                            "\n\n[Attached Resource: {} could not be read: {e}]\n",
+                            resource_ref.uri
+                        );
+                        resources.insert(resource_ref, vec![ContentPart::Text { text }]);
+                    }
+                }
+            }
+        }
+
+        if !failures.is_empty() {
+            return Err(AppError::Mcp(format!(
+                "Failed to read attached resources: {}",
+                failures.join("; ")
+            )));
+        }
+
+        self.resource_cache.insert(chat_id, read.clone());
+        resources.extend(read);
+        Ok(resources)
+    }
+
+    /// Convert the contents of an MCP resource into message parts.
+    fn resource_content_parts(contents: Vec<MCPResourceContent>) -> Vec<ContentPart> {
+        contents
+            .into_iter()
+            .filter_map(|content| {
+                let mime_type = content
+                    .mime_type
+                    .unwrap_or_else(|| "application/octet-stream".to_string());
+                if let Some(text) = content.text {
+                    Some(ContentPart::Text {
+                        text: format!(
+                            "\n\n[Attached Resource: {}]\n{}\n[End Resource]\n",
+                            content.uri, text
+                        ),
+                    })
+                } else if let Some(blob) = content.blob {
+                    let url = format!("data:{mime_type};base64,{blob}");
+                    if mime_type.starts_with("image/") {
+                        Some(ContentPart::ImageUrl {
+                            image_url: ImageUrl { url },
+                        })
+                    } else {
+                        Some(ContentPart::FileUrl {
+                            file_url: FileUrl { url, mime_type },
+                        })
+                    }
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Append the contents of attached MCP resources to a user message.
+    fn attach_resource_parts(
+        &self,
+        content: UserContent,
+        metadata: Option<&str>,
+        resources: &HashMap<MCPResourceRef, Vec<ContentPart>>,
+    ) -> UserContent {
+        let resource_parts: Vec<ContentPart> = metadata
+            .map(|metadata| self.extract_resource_refs(metadata))
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|resource_ref| resources.get(resource_ref))
+            .flatten()
+            .cloned()
+            .collect();
+
+        if resource_parts.is_empty() {
+            return content;
+        }
+
+        let mut parts = match content {
+            UserContent::Text(text) if text.is_empty() => Vec::new(),
+            UserContent::Text(text) => vec![ContentPart::Text { text }],
+            UserContent::Parts(parts) => parts,
+        };
+        parts.extend(resource_parts);
+        UserContent::Parts(parts)
+    }
+
+    /// Save binary tool output to disk and return the file paths.
+    fn save_tool_files(&self, app: &AppHandle, files: &[MCPToolFile]) -> Vec<String> {
+        files
+            .iter()
+            .filter_map(|file| {
+                let data_url = format!("data:{};base64,{}", file.mime_type, file.data);
+                match self.save_file_to_disk(app, &data_url) {
+                    Ok(path) => Some(path),
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Failed to save tool output file");
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Load saved files as content parts (images as image URLs, others as file URLs).
+    fn load_file_parts(&self, files: &[String]) -> Vec<ContentPart> {
+        files
+            .iter()
+            .filter_map(|file_path| {
+                let (url, mime_type) = self.load_file_content(file_path).ok()?;
+                Some(if mime_type.starts_with("image/") {
+                    ContentPart::ImageUrl {
+                        image_url: ImageUrl { url },
+                    }
+                } else {
+                    ContentPart::FileUrl {
+                        file_url: FileUrl { url, mime_type },
+                    }
+                })
+            })
+            .collect()
+    }
+
     /// Load a file from a path and convert to base64 data URL with mime type.
     fn load_file_content(&self, path_or_data: &str) -> Result<(String, String), AppError> {
         if path_or_data.starts_with("data:") {
@@ -251,6 +462,22 @@ impl ChatService {
         }
     }
 
+    /// Merge incoming user message metadata with the paths of its saved files
+    fn user_message_metadata(metadata: Option<&str>, files: Option<&[String]>) -> Option<String> {
+        let files = files.filter(|f| !f.is_empty());
+        match metadata {
+            Some(meta_str) => {
+                let mut meta_obj: serde_json::Value =
+                    serde_json::from_str(meta_str).unwrap_or(serde_json::json!({}));
+                if let Some(file_list) = files {
+                    meta_obj["files"] = serde_json::json!(file_list);
+                }
+                Some(meta_obj.to_string())
+            }
+            None => files.map(|file_list| serde_json::json!({ "files": file_list }).to_string()),
+        }
+    }
+
     /// Cancel an ongoing message stream for a chat
     pub fn cancel_message(&self, chat_id: &str) -> Result<(), AppError> {
         // Try to send cancellation signal
@@ -362,13 +589,22 @@ impl ChatService {
     }
 
     pub fn delete(&self, id: String) -> Result<(), AppError> {
+        self.resource_cache.remove_chat(&id);
         self.repository.delete(&id)
     }
 
     pub fn delete_by_workspace_id(&self, workspace_id: String) -> Result<(), AppError> {
+        for chat in self.repository.get_by_workspace_id(&workspace_id)? {
+            self.resource_cache.remove_chat(&chat.id);
+        }
         self.repository.delete_by_workspace_id(&workspace_id)
     }
 
+    /// Drop cached resource contents read through a connection that went away
+    pub fn forget_connection_resources(&self, connection_id: &str) {
+        self.resource_cache.remove_connection(connection_id);
+    }
+
     /// Process an agent request in a separate task context
     /// Process an agent request in a separate task context
     pub fn process_agent_request(
@@ -405,6 +641,34 @@ impl ChatService {
         reasoning_effort: Option<String>,
         llm_connection_id_override: Option<String>,
         app: AppHandle,
+    ) -> Result<(String, String), AppError> {
+        self.send_message_internal(
+            chat_id,
+            content,
+            files,
+            metadata,
+            selected_model,
+            reasoning_effort,
+            llm_connection_id_override,
+            None,
+            app,
+        )
+        .await
+    }
+
+    /// Send a message, or with `regenerate_from` answer an existing user message again
+    /// A regenerated reply becomes a sibling branch of the previous replies
+    async fn send_message_internal(
+        &self,
+        chat_id: String,
+        content: String,
+        files: Option<Vec<String>>,
+        metadata: Option<String>,
+        selected_model: Option<String>,
+        reasoning_effort: Option<String>,
+        llm_connection_id_override: Option<String>,
+        regenerate_from: Option<Message>,
+        app: AppHandle,
     ) -> Result<(String, String), AppError> {
         // Track chat message operation
         crate::lib::sentry_helpers::add_breadcrumb(
@@ -414,7 +678,15 @@ impl ChatService {
         );
 
         // Process files: Save incoming base64 files to disk and get paths with mime types
-        let processed_files = self.process_incoming_files(&app, files.clone())?;
+        // Regenerated messages reuse the files already saved with the user message
+        let processed_files = match &regenerate_from {
+            Some(user_message) => user_message
+                .metadata
+                .as_deref()
+                .and_then(|m| serde_json::from_str::<serde_json::Value>(m).ok())
+                .and_then(|m| serde_json::from_value::<Vec<String>>(m["files"].clone()).ok()),
+            None => self.process_incoming_files(&app, files.clone())?,
+        };
 
         // 1. Get chat to find workspace_id
         let chat = self
@@ -454,46 +726,77 @@ impl ChatService {
             .or(llm_connection.default_model.clone())
             .ok_or_else(|| AppError::Validation("No model selected".to_string()))?;
 
-        // 5. Get conversation history
-        let existing_messages = self.message_service.get_by_chat_id(&chat_id)?;
+        // 5. Get conversation history (active branch only)
+        if let Some(user_message) = &regenerate_from {
+            self.message_service
+                .branch_from(&chat_id, user_message.parent_message_id.as_deref())?;
+        }
+        let mut existing_messages = self.message_service.get_active_path(&chat_id)?;
+
+        // 5.5 Summarize older turns when the history outgrows the context window
+        // Regenerated replies reuse the existing user message, so no summary can be inserted before it
+        let context_config = workspace_settings.context_config();
+        if context_config.strategy == ContextStrategy::Summarize && regenerate_from.is_none() {
+            match self
+                .summarize_history_if_needed(
+                    &app,
+                    &workspace_id,
+                    &chat_id,
+                    &existing_messages,
+                    &content,
+                    &model,
+                    &llm_connection,
+                    &context_config,
+                )
+                .await
+            {
+                Ok(Some(summary)) => existing_messages.push(summary),
+                Ok(None) => {}
+                Err(e) => {
+                    // Oversized history is still truncated when the request is built
+                    tracing::warn!(chat_id = %chat_id, error = ?e, "Failed to summarize chat history");
+                }
+            }
+        }
+
+        // 5.6 Read attached MCP resources before anything is written
+        let attached_resources = self
+            .load_attached_resources(&chat_id, &existing_messages, metadata.as_deref())
+            .await?;
 
         // 6. Create user message
         let user_timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs() as i64;
-        let user_message_id = uuid::Uuid::new_v4().to_string();
+        let user_message_id = regenerate_from
+            .as_ref()
+            .map_or_else(|| uuid::Uuid::new_v4().to_string(), |m| m.id.clone());
 
         // Merge incoming metadata with processed files
-        let final_metadata = if let Some(meta_str) = &metadata {
-            let mut meta_obj: serde_json::Value =
-                serde_json::from_str(meta_str).unwrap_or(serde_json::json!({}));
-            if let Some(file_list) = &processed_files {
-                if !file_list.is_empty() {
-                    meta_obj["files"] = serde_json::json!(file_list);
-                }
-            }
-            Some(meta_obj.to_string())
-        } else if let Some(file_list) = &processed_files {
-            if file_list.is_empty() {
-                None
-            } else {
-                Some(serde_json::json!({ "files": file_list }).to_string())
-            }
-        } else {
+        let final_metadata = if regenerate_from.is_some() {
             None
+        } else {
+            Self::user_message_metadata(metadata.as_deref(), processed_files.as_deref())
         };
 
-        self.message_service.create(
-            user_message_id.clone(),
-            chat_id.clone(),
-            "user".to_string(),
-            content.clone(),
-            Some(user_timestamp),
-            None,
-            None,
-            final_metadata,
-        )?;
+        if regenerate_from.is_some() {
+            // Replies to the existing user message become siblings of the old ones
+            self.message_service
+                .branch_from(&chat_id, Some(&user_message_id))?;
+        } else {
+            self.message_service.create(
+                user_message_id.clone(),
+                chat_id.clone(),
+                existing_messages.last().map(|m| m.id.clone()),
+                "user".to_string(),
+                content.clone(),
+                Some(user_timestamp),
+                None,
+                None,
+                final_metadata,
+            )?;
+        }
 
         // 6.5 Check for Agent Mention (Routing)
         let agent_regex = regex::Regex::new(r"^@([a-zA-Z0-9\.\-_]+)\s+(.*)").unwrap();
@@ -526,6 +829,7 @@ impl ChatService {
                 self.message_service.create(
                     assistant_message_id.clone(),
                     chat_id.clone(),
+                    Some(user_message_id.clone()),
                     "assistant".to_string(),
                     "Agent Task Started".to_string(),
                     Some(assistant_timestamp),
@@ -614,6 +918,7 @@ impl ChatService {
         self.message_service.create(
             assistant_message_id.clone(),
             chat_id.clone(),
+            Some(user_message_id.clone()),
             "assistant".to_string(),
             String::new(),
             Some(assistant_timestamp),
@@ -692,8 +997,17 @@ impl ChatService {
             &content,
             processed_files.as_deref(),
             metadata.as_deref(),
+            &attached_resources,
             system_prompt_override.clone(),
         )?;
+        let api_messages = Self::fit_to_context(
+            &chat_id,
+            api_messages,
+            &model,
+            context::context_limit(&llm_connection, &model),
+            tools.as_deref(),
+            &context_config,
+        );
 
         // 10. Determine if streaming is enabled
         let stream_enabled = workspace_settings.stream_enabled.is_none_or(|v| v == 1); // Default to true
@@ -717,32 +1031,34 @@ impl ChatService {
             })),
             response_modalities: None, // Provider-specific, will be set by provider if needed
             image_config: None,        // Provider-specific, will be set by provider if needed
+            response_format: ResponseFormat::from_metadata(metadata.as_deref()),
         };
 
         // 12. Get cancellation receiver for this chat
         let cancellation_rx = self.get_cancellation_receiver(&chat_id).await;
 
-        // 13. Call LLM service
+        // 13. Call LLM service, falling back to the workspace's fallback models
+        let targets = self.llm_targets(&llm_connection, &model, &workspace_settings);
         let start_time = std::time::Instant::now();
-        let llm_response = self
+        let (llm_response, fallback) = self
             .llm_service
-            .chat(
-                &llm_connection.base_url,
-                Some(&llm_connection.api_key),
+            .chat_with_fallback(
+                &targets,
                 llm_request,
                 chat_id.clone(),
                 assistant_message_id.clone(),
                 app.clone(),
                 Some(cancellation_rx),
-                &llm_connection.provider,
             )
             .await?;
         let latency = start_time.elapsed().as_millis() as u64;
 
         // Track LLM call performance
         crate::lib::sentry_helpers::track_llm_call(
-            &llm_connection.provider,
-            &model,
+            fallback
+                .as_ref()
+                .map_or(&llm_connection.provider, |f| &f.provider),
+            fallback.as_ref().map_or(&model, |f| &f.model),
             "chat_completion",
             latency,
             &Ok::<(), Box<dyn std::error::Error>>(()),
@@ -753,10 +1069,15 @@ impl ChatService {
         let r_workspace_id = workspace_id.clone();
         let r_chat_id = chat_id.clone();
         let r_message_id = assistant_message_id.clone();
-        let r_provider = llm_connection.provider.clone();
-        let r_model = model_for_usage;
+        let r_provider = fallback
+            .as_ref()
+            .map_or_else(|| llm_connection.provider.clone(), |f| f.provider.clone());
+        let r_model = fallback
+            .as_ref()
+            .map_or(model_for_usage, |f| f.model.clone());
         let r_usage = llm_response.usage.clone();
         let r_is_stream = stream_enabled;
+        let r_status = Self::usage_status(fallback.as_ref());
 
         tokio::task::spawn_blocking(move || {
             if let Err(e) = usage_service.record_usage(
@@ -768,7 +1089,7 @@ impl ChatService {
                 r_usage,
                 latency,
                 r_is_stream,
-                "success".to_string(),
+                r_status,
             ) {
                 tracing::error!(error = ?e, "Failed to record usage");
             }
@@ -788,6 +1109,9 @@ impl ChatService {
         if let Some(usage) = &llm_response.usage {
             metadata_obj["tokenUsage"] = serde_json::json!(usage);
         }
+        if let Some(fallback) = &fallback {
+            metadata_obj["fallback"] = serde_json::json!(fallback);
+        }
 
         // Add generated images to metadata if present
         if let Some(images) = &llm_response.images {
@@ -879,7 +1203,7 @@ impl ChatService {
         // Auto-generate chat title if this is the first message
         // existing_messages was fetched at line 458, before we created the user message
         // So if existing_messages.len() == 0, this is the first message
-        if existing_messages.is_empty() {
+        if existing_messages.is_empty() && regenerate_from.is_none() {
             let title_chat_id = chat_id.clone();
             let title_content = content.clone();
             let title_model = selected_model.or(workspace_settings.default_model.clone());
@@ -902,7 +1226,8 @@ impl ChatService {
         Ok((assistant_message_id, llm_response.content))
     }
 
-    /// Edit a message and resend - deletes the message and subsequent messages, then sends new message
+    /// Edit a message and resend - the edited message becomes a new sibling branch,
+    /// the original message and its replies stay available
     pub async fn edit_and_resend_message(
         &self,
         chat_id: String,
@@ -915,48 +1240,21 @@ impl ChatService {
         llm_connection_id: Option<String>,
         app: AppHandle,
     ) -> Result<(String, String), AppError> {
-        // Process new files
-        let processed_new_files = self.process_incoming_files(&app, new_files)?;
-
-        // 1. Get the message to find its timestamp before deleting
-        let message_timestamp =
-            if let Some(message) = self.message_service.get_by_id(&message_id)? {
-                message.timestamp
-            } else {
-                // Message not found, just delete messages after it and proceed
-                self.message_service
-                    .delete_messages_after(chat_id.clone(), message_id.clone())?;
-                // Send message with new content (this will create a new user message and trigger agent loop with tool calls)
-                return self
-                    .send_message(
-                        chat_id,
-                        new_content,
-                        processed_new_files,
-                        metadata,
-                        selected_model,
-                        reasoning_effort,
-                        llm_connection_id,
-                        app,
-                    )
-                    .await;
-            };
-
-        // 2. Delete the message being edited (we'll create a new one)
-        self.message_service.delete(message_id.clone())?;
-
-        // 3. Delete all messages after this timestamp (these were after the deleted message)
-        let all_messages = self.message_service.get_by_chat_id(&chat_id)?;
-        for msg in all_messages {
-            if msg.timestamp > message_timestamp {
-                self.message_service.delete(msg.id)?;
-            }
+        // Branch from the edited message's parent (unknown messages just append to the active branch)
+        if let Some(message) = self
+            .message_service
+            .get_by_id(&message_id)?
+            .filter(|m| m.chat_id == chat_id)
+        {
+            self.message_service
+                .branch_from(&chat_id, message.parent_message_id.as_deref())?;
         }
 
-        // 4. Send message with new content (this will create a new user message and trigger agent loop with tool calls)
+        // Send message with new content (this will create a new user message and trigger agent loop with tool calls)
         self.send_message(
             chat_id,
             new_content,
-            processed_new_files,
+            new_files,
             metadata,
             selected_model,
             reasoning_effort,
@@ -966,58 +1264,445 @@ impl ChatService {
         .await
     }
 
-    /// Send message with agent loop - handles tool calls and continues conversation
-    async fn send_message_with_agent_loop(
+    /// Regenerate the reply to a user message as a new sibling branch
+    /// `message_id` may be the user message or any message of the reply to regenerate
+    pub async fn regenerate_message(
         &self,
         chat_id: String,
-        user_content: String,
-        user_message_id: String,
-        user_metadata: Option<String>,
+        message_id: String,
         selected_model: Option<String>,
         reasoning_effort: Option<String>,
-        initial_assistant_message_id: String,
-        mut initial_llm_response: Option<LLMChatResponse>,
+        llm_connection_id: Option<String>,
         app: AppHandle,
-        active_tools: Option<Vec<ChatCompletionTool>>,
-        system_prompt_override: Option<String>,
-        llm_connection_id_override: Option<String>,
     ) -> Result<(String, String), AppError> {
-        // Get workspace settings
+        // Walk up to the user message that started this turn
+        let mut current = self.message_service.get_by_id(&message_id)?;
+        let user_message = loop {
+            match current {
+                Some(message) if message.chat_id != chat_id => current = None,
+                Some(message) if message.role == "user" => break message,
+                Some(message) => {
+                    current = match message.parent_message_id {
+                        Some(parent_id) => self.message_service.get_by_id(&parent_id)?,
+                        None => None,
+                    };
+                }
+                None => {
+                    return Err(AppError::NotFound(format!(
+                        "No user message to regenerate from: {message_id}"
+                    )))
+                }
+            }
+        };
+
+        self.send_message_internal(
+            chat_id,
+            user_message.content.clone(),
+            None,
+            user_message.metadata.clone(),
+            selected_model,
+            reasoning_effort,
+            llm_connection_id,
+            Some(user_message),
+            app,
+        )
+        .await
+    }
+
+    /// Send one message to several connection/model pairs in parallel (compare mode)
+    ///
+    /// Every answer streams into its own assistant message, a sibling under the user message
+    /// tagged with its model, and gets its own usage and latency. The first answer is active,
+    /// the sibling switcher under a message picks another one to continue the thread with.
+    ///
+    /// Limitations: tools are not offered, so every answer is a single model turn that stays
+    /// side by side with the others, and the chat input has no model picker to start a compare
+    /// turn yet. It is only reachable through the `send_compare_message` command.
+    pub async fn send_compare_message(
+        &self,
+        chat_id: String,
+        content: String,
+        files: Option<Vec<String>>,
+        metadata: Option<String>,
+        targets: Vec<CompareTarget>,
+        reasoning_effort: Option<String>,
+        app: AppHandle,
+    ) -> Result<(String, Vec<CompareResponse>), AppError> {
+        if targets.is_empty() {
+            return Err(AppError::Validation(
+                "Select at least one model to compare".to_string(),
+            ));
+        }
+        if targets.len() > MAX_COMPARE_TARGETS {
+            return Err(AppError::Validation(format!(
+                "Compare mode supports up to {MAX_COMPARE_TARGETS} models"
+            )));
+        }
+
+        crate::lib::sentry_helpers::add_breadcrumb(
+            "chat",
+            format!("Comparing {} models in chat {chat_id}", targets.len()),
+            sentry::Level::Info,
+        );
+
         let chat = self
             .repository
             .get_by_id(&chat_id)?
             .ok_or_else(|| AppError::NotFound(format!("Chat not found: {chat_id}")))?;
+        if chat.agent_id.is_some() {
+            return Err(AppError::Validation(
+                "Compare mode is not available in agent sessions".to_string(),
+            ));
+        }
         let workspace_id = chat.workspace_id;
+        crate::lib::sentry_helpers::track_workspace_operation(
+            &workspace_id,
+            "send_compare_message",
+        );
 
         let workspace_settings = self
             .workspace_settings_service
             .get_by_workspace_id(&workspace_id)?
             .ok_or_else(|| AppError::Validation("Workspace settings not found".to_string()))?;
 
-        let max_iterations = workspace_settings.max_agent_iterations.unwrap_or(25) as usize;
+        // Resolve every connection before anything is written
+        let mut connections = Vec::with_capacity(targets.len());
+        for target in &targets {
+            let connection = self
+                .llm_connection_service
+                .get_by_id(&target.llm_connection_id)?
+                .ok_or_else(|| {
+                    AppError::NotFound(format!(
+                        "LLM connection not found: {}",
+                        target.llm_connection_id
+                    ))
+                })?;
+            connections.push(connection);
+        }
 
-        let llm_connection_id = llm_connection_id_override
-            .or(workspace_settings.llm_connection_id.clone())
-            .ok_or_else(|| {
-                AppError::Validation("LLM connection not configured for workspace".to_string())
-            })?;
+        let processed_files = self.process_incoming_files(&app, files)?;
+        let existing_messages = self.message_service.get_active_path(&chat_id)?;
+        let attached_resources = self
+            .load_attached_resources(&chat_id, &existing_messages, metadata.as_deref())
+            .await?;
 
-        let llm_connection = self
-            .llm_connection_service
-            .get_by_id(&llm_connection_id)?
-            .ok_or_else(|| {
-                AppError::NotFound(format!("LLM connection not found: {llm_connection_id}"))
-            })?;
+        // Create the user message
+        let user_timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let user_message_id = uuid::Uuid::new_v4().to_string();
+        self.message_service.create(
+            user_message_id.clone(),
+            chat_id.clone(),
+            existing_messages.last().map(|m| m.id.clone()),
+            "user".to_string(),
+            content.clone(),
+            Some(user_timestamp),
+            None,
+            None,
+            Self::user_message_metadata(metadata.as_deref(), processed_files.as_deref()),
+        )?;
 
-        let model = selected_model
-            .or(workspace_settings.default_model.clone())
-            .or(llm_connection.default_model.clone())
-            .ok_or_else(|| AppError::Validation("No model selected".to_string()))?;
+        // One assistant placeholder per target, all children of the user message.
+        // Only the first one moves the active leaf, the later ones are inactive siblings.
+        let compare_metadata = targets
+            .iter()
+            .zip(&connections)
+            .map(|(target, connection)| {
+                serde_json::json!({
+                    "compare": {
+                        "group": user_message_id,
+                        "llmConnectionId": target.llm_connection_id,
+                        "provider": connection.provider,
+                        "model": target.model,
+                    }
+                })
+                .to_string()
+            })
+            .collect();
+        let assistant_message_ids: Vec<String> = self
+            .message_service
+            .create_sibling_replies(
+                &chat_id,
+                &user_message_id,
+                user_timestamp + 1,
+                compare_metadata,
+            )?
+            .into_iter()
+            .map(|message| message.id)
+            .collect();
+        let message_emitter = MessageEmitter::new(app.clone());
+        for assistant_message_id in &assistant_message_ids {
+            message_emitter.emit_message_started(
+                chat_id.clone(),
+                user_message_id.clone(),
+                assistant_message_id.clone(),
+            )?;
+        }
 
+        // Same history for every model, trimmed to each model's context window
+        let api_messages = self.prepare_messages(
+            &existing_messages,
+            &workspace_settings,
+            &content,
+            processed_files.as_deref(),
+            metadata.as_deref(),
+            &attached_resources,
+            None,
+        )?;
+        let context_config = workspace_settings.context_config();
         let stream_enabled = workspace_settings.stream_enabled.is_none_or(|v| v == 1);
 
-        // Get tools
-        // Get tools if not provided
+        let runs = targets
+            .iter()
+            .zip(&connections)
+            .zip(&assistant_message_ids)
+            .map(|((target, connection), assistant_message_id)| {
+                let request = LLMChatRequest {
+                    model: target.model.clone(),
+                    messages: Self::fit_to_context(
+                        &chat_id,
+                        api_messages.clone(),
+                        &target.model,
+                        context::context_limit(connection, &target.model),
+                        None,
+                        &context_config,
+                    ),
+                    temperature: Some(0.7),
+                    max_tokens: None,
+                    stream: stream_enabled,
+                    tools: None,
+                    tool_choice: None,
+                    reasoning_effort: reasoning_effort.clone(),
+                    stream_options: Some(serde_json::json!({
+                        "include_usage": true
+                    })),
+                    response_modalities: None,
+                    image_config: None,
+                    response_format: ResponseFormat::from_metadata(metadata.as_deref()),
+                };
+                self.run_compare_target(
+                    &app,
+                    &workspace_id,
+                    &chat_id,
+                    &user_message_id,
+                    assistant_message_id,
+                    target,
+                    connection,
+                    request,
+                )
+            });
+        let responses = futures::future::join_all(runs).await;
+
+        // The first answer that succeeded stands in for the turn in the chat list
+        if let Some(response) = responses.iter().find(|r| r.error.is_none()) {
+            let last_message = if response.content.len() > 100 {
+                response.content.chars().take(100).collect::<String>() + "..."
+            } else {
+                response.content.clone()
+            };
+            self.repository
+                .update(&chat_id, None, Some(&last_message))?;
+        }
+
+        if existing_messages.is_empty() {
+            let title_app = app.clone();
+            let title_chat_id = chat_id.clone();
+            let title_model = Some(targets[0].model.clone());
+            let title_llm_connection_id = Some(targets[0].llm_connection_id.clone());
+            tokio::spawn(async move {
+                generate_chat_title_internal(
+                    title_app,
+                    title_chat_id,
+                    content,
+                    title_model,
+                    title_llm_connection_id,
+                )
+                .await;
+            });
+        }
+
+        Ok((user_message_id, responses))
+    }
+
+    /// Run one model of a compare turn, failures are kept on its message instead of failing the turn
+    #[allow(clippy::too_many_arguments)]
+    async fn run_compare_target(
+        &self,
+        app: &AppHandle,
+        workspace_id: &str,
+        chat_id: &str,
+        user_message_id: &str,
+        assistant_message_id: &str,
+        target: &CompareTarget,
+        connection: &LLMConnection,
+        request: LLMChatRequest,
+    ) -> CompareResponse {
+        let stream = request.stream;
+        let cancellation_rx = self.get_cancellation_receiver(chat_id).await;
+        let start_time = std::time::Instant::now();
+        let result = self
+            .llm_service
+            .chat(
+                &connection.base_url,
+                Some(&connection.api_key),
+                request,
+                chat_id.to_string(),
+                assistant_message_id.to_string(),
+                app.clone(),
+                Some(cancellation_rx),
+                &connection.provider,
+                connection.api_mode.as_deref(),
+            )
+            .await;
+        let latency = start_time.elapsed().as_millis() as u64;
+
+        crate::lib::sentry_helpers::track_llm_call(
+            &connection.provider,
+            &target.model,
+            "compare",
+            latency,
+            &result
+                .as_ref()
+                .map(|_| ())
+                .map_err(|e| Box::<dyn std::error::Error>::from(e.to_string())),
+        );
+
+        let (usage, status) = match &result {
+            Ok(response) => (response.usage.clone(), "success"),
+            Err(_) => (None, "error"),
+        };
+        if let Err(e) = self.usage_service.record_usage(
+            workspace_id.to_string(),
+            chat_id.to_string(),
+            assistant_message_id.to_string(),
+            connection.provider.clone(),
+            target.model.clone(),
+            usage,
+            latency,
+            stream,
+            status.to_string(),
+        ) {
+            tracing::error!(error = ?e, "Failed to record usage");
+        }
+
+        let mut metadata = serde_json::json!({
+            "compare": {
+                "group": user_message_id,
+                "llmConnectionId": target.llm_connection_id,
+                "provider": connection.provider,
+                "model": target.model,
+            },
+            "latencyMs": latency,
+        });
+
+        let (content, error) = match result {
+            Ok(response) => {
+                if let Some(usage) = &response.usage {
+                    metadata["tokenUsage"] = serde_json::json!(usage);
+                }
+                if let Some(images) = response.images.as_ref().filter(|i| !i.is_empty()) {
+                    let image_urls: Vec<String> = images
+                        .iter()
+                        .map(|img| format!("data:{};base64,{}", img.mime_type, img.data))
+                        .collect();
+                    metadata["images"] = serde_json::json!(image_urls);
+                }
+                if let Err(e) = self.message_service.update(
+                    assistant_message_id.to_string(),
+                    response.content.clone(),
+                    response.reasoning.clone(),
+                    None,
+                ) {
+                    tracing::error!(error = ?e, "Failed to save compare answer");
+                }
+                (response.content, None)
+            }
+            Err(e) => {
+                tracing::warn!(model = %target.model, error = ?e, "Compare model failed");
+                metadata["error"] = serde_json::json!(e.to_string());
+                (String::new(), Some(e.to_string()))
+            }
+        };
+
+        if let Err(e) = self
+            .message_service
+            .update_metadata(assistant_message_id.to_string(), Some(metadata.to_string()))
+        {
+            tracing::error!(error = ?e, "Failed to save compare metadata");
+        }
+        if let Err(e) = MessageEmitter::new(app.clone())
+            .emit_message_metadata_updated(chat_id.to_string(), assistant_message_id.to_string())
+        {
+            tracing::error!(error = ?e, "Failed to emit metadata-updated event");
+        }
+
+        CompareResponse {
+            assistant_message_id: assistant_message_id.to_string(),
+            llm_connection_id: target.llm_connection_id.clone(),
+            model: target.model.clone(),
+            content,
+            error,
+            latency_ms: latency,
+        }
+    }
+
+    /// Send message with agent loop - handles tool calls and continues conversation
+    async fn send_message_with_agent_loop(
+        &self,
+        chat_id: String,
+        user_content: String,
+        user_message_id: String,
+        user_metadata: Option<String>,
+        selected_model: Option<String>,
+        reasoning_effort: Option<String>,
+        initial_assistant_message_id: String,
+        mut initial_llm_response: Option<LLMChatResponse>,
+        app: AppHandle,
+        active_tools: Option<Vec<ChatCompletionTool>>,
+        system_prompt_override: Option<String>,
+        llm_connection_id_override: Option<String>,
+    ) -> Result<(String, String), AppError> {
+        // Get workspace settings
+        let chat = self
+            .repository
+            .get_by_id(&chat_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Chat not found: {chat_id}")))?;
+        let workspace_id = chat.workspace_id;
+
+        let workspace_settings = self
+            .workspace_settings_service
+            .get_by_workspace_id(&workspace_id)?
+            .ok_or_else(|| AppError::Validation("Workspace settings not found".to_string()))?;
+
+        let max_iterations = workspace_settings.max_agent_iterations.unwrap_or(25) as usize;
+
+        let llm_connection_id = llm_connection_id_override
+            .or(workspace_settings.llm_connection_id.clone())
+            .ok_or_else(|| {
+                AppError::Validation("LLM connection not configured for workspace".to_string())
+            })?;
+
+        let llm_connection = self
+            .llm_connection_service
+            .get_by_id(&llm_connection_id)?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("LLM connection not found: {llm_connection_id}"))
+            })?;
+
+        let model = selected_model
+            .or(workspace_settings.default_model.clone())
+            .or(llm_connection.default_model.clone())
+            .ok_or_else(|| AppError::Validation("No model selected".to_string()))?;
+
+        let stream_enabled = workspace_settings.stream_enabled.is_none_or(|v| v == 1);
+        let context_config = workspace_settings.context_config();
+        let context_limit = context::context_limit(&llm_connection, &model);
+        let targets = self.llm_targets(&llm_connection, &model, &workspace_settings);
+
+        // Get tools
+        // Get tools if not provided
         let tools = if active_tools.is_some() {
             active_tools
         } else {
@@ -1030,13 +1715,17 @@ impl ChatService {
         };
 
         let mut assistant_message_id = initial_assistant_message_id;
-        let mut current_messages = self.prepare_messages_for_agent_loop(
-            &chat_id,
-            &workspace_settings,
-            &user_content,
-            user_metadata.as_deref(),
-            system_prompt_override.clone(),
-        )?;
+        // Last message of this turn, parent of the next one
+        let mut leaf_message_id = assistant_message_id.clone();
+        let mut current_messages = self
+            .prepare_messages_for_agent_loop(
+                &chat_id,
+                &workspace_settings,
+                &user_content,
+                user_metadata.as_deref(),
+                system_prompt_override.clone(),
+            )
+            .await?;
 
         // Create emitters once for agent loop
         let agent_emitter = AgentEmitter::new(app.clone());
@@ -1068,6 +1757,7 @@ impl ChatService {
                 self.message_service.create(
                     new_assistant_message_id.clone(),
                     chat_id.clone(),
+                    Some(leaf_message_id.clone()),
                     "assistant".to_string(),
                     String::new(),
                     Some(timestamp),
@@ -1077,6 +1767,7 @@ impl ChatService {
                 )?;
 
                 assistant_message_id = new_assistant_message_id;
+                leaf_message_id.clone_from(&assistant_message_id);
 
                 // Notify frontend of new message so it can display it/start streaming
                 message_emitter.emit_message_started(
@@ -1097,6 +1788,16 @@ impl ChatService {
                     tools.clone()
                 };
 
+                // Tool results pile up across iterations, trim them before every call
+                current_messages = Self::fit_to_context(
+                    &chat_id,
+                    current_messages,
+                    &model,
+                    context_limit,
+                    llm_tools.as_deref(),
+                    &context_config,
+                );
+
                 // Call LLM
                 let model_for_usage = model.clone();
 
@@ -1114,20 +1815,19 @@ impl ChatService {
                     })),
                     response_modalities: None, // Provider-specific, will be set by provider if needed
                     image_config: None, // Provider-specific, will be set by provider if needed
+                    response_format: ResponseFormat::from_metadata(user_metadata.as_deref()),
                 };
 
                 let start_time = std::time::Instant::now();
-                let resp = self
+                let (resp, fallback) = self
                     .llm_service
-                    .chat(
-                        &llm_connection.base_url,
-                        Some(&llm_connection.api_key),
+                    .chat_with_fallback(
+                        &targets,
                         llm_request,
                         chat_id.clone(),
                         assistant_message_id.clone(),
                         app.clone(),
                         Some(cancellation_rx.resubscribe()),
-                        &llm_connection.provider,
                     )
                     .await?;
                 let latency = start_time.elapsed().as_millis() as u64;
@@ -1137,10 +1837,15 @@ impl ChatService {
                 let r_workspace_id = workspace_id.clone();
                 let r_chat_id = chat_id.clone();
                 let r_message_id = assistant_message_id.clone();
-                let r_provider = llm_connection.provider.clone();
-                let r_model = model_for_usage;
+                let r_provider = fallback
+                    .as_ref()
+                    .map_or_else(|| llm_connection.provider.clone(), |f| f.provider.clone());
+                let r_model = fallback
+                    .as_ref()
+                    .map_or(model_for_usage, |f| f.model.clone());
                 let r_usage = resp.usage.clone();
                 let r_is_stream = stream_enabled;
+                let r_status = Self::usage_status(fallback.as_ref());
 
                 tokio::task::spawn_blocking(move || {
                     if let Err(e) = usage_service.record_usage(
@@ -1152,7 +1857,7 @@ impl ChatService {
                         r_usage,
                         latency,
                         r_is_stream,
-                        "success".to_string(),
+                        r_status,
                     ) {
                         tracing::error!(error = ?e, "Failed to record usage");
                     }
@@ -1165,6 +1870,16 @@ impl ChatService {
                     resp.reasoning.clone(),
                     None,
                 )?;
+                if let Some(fallback) = &fallback {
+                    self.message_service.update_metadata(
+                        assistant_message_id.clone(),
+                        Some(serde_json::json!({ "fallback": fallback }).to_string()),
+                    )?;
+                    message_emitter.emit_message_metadata_updated(
+                        chat_id.clone(),
+                        assistant_message_id.clone(),
+                    )?;
+                }
 
                 resp
             };
@@ -1181,12 +1896,12 @@ impl ChatService {
                     )?;
 
                     // Check permissions
-                    let allowed_tools = self
+                    let permissions = self
                         .check_and_filter_tool_permissions(
                             &app,
                             &chat_id,
                             &assistant_message_id,
-                            tool_calls.clone(),
+                            tool_calls,
                             &workspace_settings,
                         )
                         .await?;
@@ -1197,7 +1912,9 @@ impl ChatService {
                         .handle_tool_calls(
                             &chat_id,
                             &assistant_message_id,
-                            &allowed_tools,
+                            &mut leaf_message_id,
+                            tool_calls,
+                            &permissions,
                             &app,
                             &mut cancellation_rx,
                         )
@@ -1229,6 +1946,7 @@ impl ChatService {
                     let assistant_msg_with_tools = ChatMessage::Assistant {
                         content: AssistantContent::Text(llm_response.content.clone()),
                         tool_calls: Some(tool_calls.clone()),
+                        reasoning_items: llm_response.reasoning_items.clone(),
                     };
                     current_messages.push(assistant_msg_with_tools);
 
@@ -1285,7 +2003,7 @@ impl ChatService {
 
             // Check if this is the first message in the chat
             // Only generate title if message_count == 1 (the user message just sent)
-            let message_count = match chat_service.message_service.get_by_chat_id(&chat_id) {
+            let message_count = match chat_service.message_service.get_active_path(&chat_id) {
                 Ok(messages) => messages.len(),
                 Err(_) => {
                     tracing::error!(chat_id = %chat_id, "Failed to get messages for title generation");
@@ -1309,121 +2027,260 @@ impl ChatService {
         });
     }
 
-    /// Check tool permissions and filter allowed tools
+    /// Check tool permissions, returning the calls that must not run and why
+    ///
+    /// Rules in `tool_permission_config` decide each call from its tool and arguments.
+    /// Calls that need approval are sent to the user together, unless they are always
+    /// allowed in this chat or workspace, and approvals can be remembered for later turns.
     async fn check_and_filter_tool_permissions(
         &self,
         app: &AppHandle,
         chat_id: &str,
         assistant_message_id: &str,
-        tool_calls: Vec<crate::models::llm_types::ToolCall>,
+        tool_calls: &[crate::models::llm_types::ToolCall],
         workspace_settings: &WorkspaceSettings,
-    ) -> Result<Vec<crate::models::llm_types::ToolCall>, AppError> {
+    ) -> Result<TurnPermissions, AppError> {
         let tool_emitter = ToolEmitter::new(app.clone());
+        let app_state = app.state::<crate::state::AppState>();
+        let permission_service = &app_state.tool_permission_service;
+
+        // Invalid settings are rejected on save, older ones fail closed and ask before every call
+        let config = match workspace_settings
+            .tool_permission_config
+            .as_deref()
+            .map(ToolPermissionService::parse_config)
+        {
+            None => ToolPermissionConfig::default(),
+            Some(Ok(config)) => config,
+            Some(Err(e)) => {
+                tracing::error!(
+                    error = ?e,
+                    workspace_id = %workspace_settings.workspace_id,
+                    "Unreadable tool permission settings, every tool call requires approval"
+                );
+                ToolPermissionConfig::require_all(
+                    tool_calls.iter().map(|tc| tc.function.name.as_str()),
+                )
+            }
+        };
+        let checks = permission_service.check(
+            &config,
+            &workspace_settings.workspace_id,
+            chat_id,
+            tool_calls,
+        )?;
 
-        // Parse tool permission config
-        let tool_permission_config: HashMap<String, String> =
-            if let Some(config_str) = &workspace_settings.tool_permission_config {
-                serde_json::from_str(config_str).unwrap_or_default()
-            } else {
-                HashMap::new()
+        let mut turn = TurnPermissions::default();
+        for (tc, check) in tool_calls.iter().zip(&checks) {
+            let permission = match check {
+                PermissionCheck::Auto => AuditPermission::Auto,
+                PermissionCheck::Remembered | PermissionCheck::Ask => AuditPermission::Approved,
+                PermissionCheck::Denied => AuditPermission::Denied,
             };
+            turn.permissions.insert(tc.id.clone(), permission);
+        }
+        turn.denied = tool_calls
+            .iter()
+            .zip(&checks)
+            .filter(|(_, check)| **check == PermissionCheck::Denied)
+            .map(|(tc, _)| {
+                (
+                    tc.id.clone(),
+                    format!(
+                        "Tool {} is not allowed with these arguments by the workspace's permission rules",
+                        tc.function.name
+                    ),
+                )
+            })
+            .collect();
 
-        // Check if ANY tool requires permission
-        let require_permission = tool_calls.iter().any(|tc| {
-            match tool_permission_config
-                .get(&tc.function.name)
-                .map(std::string::String::as_str)
-            {
-                Some("require") => true,
-                _ => false,
-            }
-        });
+        let ask: Vec<&crate::models::llm_types::ToolCall> = tool_calls
+            .iter()
+            .zip(&checks)
+            .filter(|(_, check)| **check == PermissionCheck::Ask)
+            .map(|(tc, _)| tc)
+            .collect();
+        if ask.is_empty() {
+            return Ok(turn);
+        }
 
-        if require_permission {
-            // Create oneshot channel for approval response
-            let (tx, rx) = tokio::sync::oneshot::channel::<crate::state::PermissionDecision>();
+        // Create oneshot channel for approval response
+        let (tx, rx) = tokio::sync::oneshot::channel::<crate::state::PermissionDecision>();
 
-            // Store sender in AppState
-            {
-                use tauri::Manager;
-                let app_state: tauri::State<crate::state::AppState> = app.state();
+        // Store sender in AppState
+        {
+            let mut pending = app_state.pending_tool_permissions.lock().map_err(|e| {
+                AppError::Generic(format!("Failed to lock pending_tool_permissions: {e}"))
+            })?;
+            pending.insert(assistant_message_id.to_string(), tx);
+        }
+
+        // Emit tool permission request event for the calls that need a decision
+        let permission_tool_calls: Vec<crate::events::ToolCall> = ask
+            .iter()
+            .map(|tc| crate::events::ToolCall {
+                id: tc.id.clone(),
+                name: tc.function.name.clone(),
+                arguments: serde_json::from_str(tc.function.arguments.trim())
+                    .unwrap_or_else(|_| serde_json::json!({})),
+            })
+            .collect();
+
+        let timeout = workspace_settings
+            .tool_execution_config()
+            .permission_timeout();
+        tool_emitter.emit_tool_permission_request(
+            chat_id.to_string(),
+            assistant_message_id.to_string(),
+            permission_tool_calls,
+            timeout.as_secs(),
+        )?;
+
+        // Wait for user approval with timeout
+        let decision = match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(decision)) => decision,
+            Ok(Err(_)) => {
+                let error = "Tool permission request cancelled".to_string();
+                Self::audit_unrun_turn(
+                    app,
+                    workspace_settings,
+                    chat_id,
+                    assistant_message_id,
+                    tool_calls,
+                    &turn,
+                    &error,
+                );
+                return Err(AppError::Generic(error));
+            }
+            Err(_) => {
+                // Timeout cleanup
                 let mut pending = app_state.pending_tool_permissions.lock().map_err(|e| {
                     AppError::Generic(format!("Failed to lock pending_tool_permissions: {e}"))
                 })?;
-                pending.insert(assistant_message_id.to_string(), tx);
-            }
-
-            // Emit tool permission request event
-            let permission_tool_calls: Vec<crate::events::ToolCall> = tool_calls
-                .iter()
-                .map(|tc| crate::events::ToolCall {
-                    id: tc.id.clone(),
-                    name: tc.function.name.clone(),
-                    arguments: serde_json::from_str(tc.function.arguments.trim())
-                        .unwrap_or_else(|_| serde_json::json!({})),
-                })
-                .collect();
-
-            tool_emitter.emit_tool_permission_request(
-                chat_id.to_string(),
-                assistant_message_id.to_string(),
-                permission_tool_calls,
-            )?;
+                pending.remove(assistant_message_id);
 
-            // Wait for user approval with timeout (60 seconds)
-            let decision = match tokio::time::timeout(tokio::time::Duration::from_secs(60), rx)
-                .await
-            {
-                Ok(Ok(decision)) => decision,
-                Ok(Err(_)) => {
-                    return Err(AppError::Generic(
-                        "Tool permission request cancelled".to_string(),
-                    ));
-                }
-                Err(_) => {
-                    // Timeout cleanup
-                    use tauri::Manager;
-                    let app_state: tauri::State<crate::state::AppState> = app.state();
-                    let mut pending = app_state.pending_tool_permissions.lock().map_err(|e| {
-                        AppError::Generic(format!("Failed to lock pending_tool_permissions: {e}"))
-                    })?;
-                    pending.remove(assistant_message_id);
-
-                    return Err(AppError::Generic(
-                        "Tool permission request timed out (60s)".to_string(),
-                    ));
+                for tc in &ask {
+                    turn.permissions
+                        .insert(tc.id.clone(), AuditPermission::TimedOut);
                 }
-            };
+                let error = format!("Tool permission request timed out ({}s)", timeout.as_secs());
+                Self::audit_unrun_turn(
+                    app,
+                    workspace_settings,
+                    chat_id,
+                    assistant_message_id,
+                    tool_calls,
+                    &turn,
+                    &error,
+                );
+                return Err(AppError::Generic(error));
+            }
+        };
 
-            // If not approved, return error
-            if !decision.approved {
-                return Err(AppError::Generic(
-                    "Tool execution denied by user".to_string(),
-                ));
+        // If not approved, return error
+        if !decision.approved {
+            for tc in &ask {
+                turn.permissions
+                    .insert(tc.id.clone(), AuditPermission::Denied);
             }
+            let error = "Tool execution denied by user".to_string();
+            Self::audit_unrun_turn(
+                app,
+                workspace_settings,
+                chat_id,
+                assistant_message_id,
+                tool_calls,
+                &turn,
+                &error,
+            );
+            return Err(AppError::Generic(error));
+        }
 
-            // Filter tool calls based on allowed_tool_ids
-            let filtered_calls: Vec<_> = tool_calls
-                .into_iter()
-                .filter(|tc| decision.allowed_tool_ids.contains(&tc.id))
-                .collect();
+        // Calls the user did not allow are answered with an error
+        let (approved, rejected): (Vec<_>, Vec<_>) = ask
+            .into_iter()
+            .partition(|tc| decision.allowed_tool_ids.contains(&tc.id));
+        for tc in &rejected {
+            turn.permissions
+                .insert(tc.id.clone(), AuditPermission::Denied);
+            turn.denied
+                .insert(tc.id.clone(), "Tool execution denied by user".to_string());
+        }
+        if approved.is_empty() && turn.denied.len() == tool_calls.len() {
+            let error = "No tools allowed to run".to_string();
+            Self::audit_unrun_turn(
+                app,
+                workspace_settings,
+                chat_id,
+                assistant_message_id,
+                tool_calls,
+                &turn,
+                &error,
+            );
+            return Err(AppError::Generic(error));
+        }
 
-            if filtered_calls.is_empty() {
-                return Err(AppError::Generic("No tools allowed to run".to_string()));
+        if let Some(scope) = decision.remember {
+            for tc in approved {
+                permission_service.remember(
+                    &workspace_settings.workspace_id,
+                    chat_id,
+                    &tc.function.name,
+                    scope,
+                )?;
             }
-
-            return Ok(filtered_calls);
         }
 
-        Ok(tool_calls)
+        Ok(turn)
+    }
+
+    /// Audit the calls of a turn that ends before running any of them
+    fn audit_unrun_turn(
+        app: &AppHandle,
+        workspace_settings: &WorkspaceSettings,
+        chat_id: &str,
+        assistant_message_id: &str,
+        tool_calls: &[crate::models::llm_types::ToolCall],
+        turn: &TurnPermissions,
+        error: &str,
+    ) {
+        let audit = &app.state::<crate::state::AppState>().tool_audit_service;
+        for tc in tool_calls {
+            let permission = turn
+                .permissions
+                .get(&tc.id)
+                .copied()
+                .unwrap_or(AuditPermission::Auto);
+            let mut entry = ToolAuditEntry::new(
+                &workspace_settings.workspace_id,
+                chat_id,
+                assistant_message_id,
+                tc,
+                permission,
+            );
+            entry.error = Some(
+                turn.denied
+                    .get(&tc.id)
+                    .cloned()
+                    .unwrap_or_else(|| error.to_string()),
+            );
+            audit.record(&entry);
+        }
     }
 
     /// Handle tool calls - execute tools and return results
+    ///
+    /// Calls run concurrently within the workspace's limits. Messages are stored and
+    /// results returned in the order the model made the calls. Denied calls do not run,
+    /// the model gets the reason as their result.
+    #[allow(clippy::too_many_arguments)]
     async fn handle_tool_calls(
         &self,
         chat_id: &str,
         assistant_message_id: &str,
+        leaf_message_id: &mut String,
         tool_calls: &[crate::models::llm_types::ToolCall],
+        permissions: &TurnPermissions,
         app: &AppHandle,
         cancellation_rx: &mut tokio::sync::broadcast::Receiver<()>,
     ) -> Result<Vec<ChatMessage>, AppError> {
@@ -1466,24 +2323,29 @@ impl ChatService {
             (map, None)
         };
 
-        // Execute each tool call
-        for tool_call in tool_calls {
-            // Create tool_call message in database
-            let tool_call_message_id = format!("tool_call_{}", tool_call.id);
-            let tool_call_timestamp = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs() as i64;
+        let execution_config = self
+            .workspace_settings_service
+            .get_by_workspace_id(&workspace_id)?
+            .map(|settings| settings.tool_execution_config())
+            .unwrap_or_default();
 
+        // Create every tool_call message before running any, so they keep the model's order
+        let tool_call_timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        for tool_call in tool_calls {
             let tool_call_data = serde_json::json!({
                 "name": tool_call.function.name,
                 "arguments": tool_call.function.arguments,
                 "status": "executing"
             });
 
+            let tool_call_message_id = format!("tool_call_{}", tool_call.id);
             self.message_service.create(
                 tool_call_message_id.clone(),
                 chat_id.to_string(),
+                Some(leaf_message_id.clone()),
                 "tool_call".to_string(),
                 serde_json::to_string(&tool_call_data)?,
                 Some(tool_call_timestamp),
@@ -1491,6 +2353,7 @@ impl ChatService {
                 None,
                 None,
             )?;
+            *leaf_message_id = tool_call_message_id;
 
             // Emit progress event immediately after creating tool_call message (before execution)
             // This allows UI to display the tool_call message right away
@@ -1503,173 +2366,126 @@ impl ChatService {
                 None,
                 None,
             )?;
+        }
 
-            // Log tool execution start for debugging
-            tracing::debug!(
-                tool = %tool_call.function.name,
-                chat_id = %chat_id,
-                tool_call_id = %tool_call.id,
-                "Starting tool execution"
-            );
+        let breaker = CircuitBreakerConfig::from_config(&execution_config);
 
-            // Find connection for this tool
-            // Execute tool logic
-            let execution_result = if let Some(client) = &agent_client {
-                // Agent Execution
-                let arguments_str = tool_call.function.arguments.trim();
-                let arguments_map = if arguments_str.is_empty() {
-                    Some(serde_json::Map::new())
-                } else {
-                    match serde_json::from_str::<serde_json::Value>(arguments_str) {
-                        Ok(serde_json::Value::Object(map)) => Some(map),
-                        Ok(_) => Some(serde_json::Map::new()),
-                        Err(e) => {
-                            return Err(AppError::Validation(format!("Invalid arguments: {e}")));
-                        }
-                    }
+        // A running call holds a slot of the turn and one of its server
+        let turn_slots = tokio::sync::Semaphore::new(execution_config.concurrency_limit());
+        let mut server_slots: HashMap<String, Arc<tokio::sync::Semaphore>> = HashMap::new();
+        let executions: Vec<_> = tool_calls
+            .iter()
+            .map(|tool_call| {
+                let client_key = match &agent_id {
+                    Some(aid) => format!("agent:{aid}"),
+                    None => tool_to_connection
+                        .get(&tool_call.function.name)
+                        .cloned()
+                        .unwrap_or_default(),
                 };
-
-                let params = CallToolRequestParams {
-                    name: tool_call.function.name.clone(),
-                    arguments: arguments_map,
+                let server = server_slots
+                    .entry(client_key.clone())
+                    .or_insert_with(|| {
+                        Arc::new(tokio::sync::Semaphore::new(
+                            execution_config.server_limit(&client_key),
+                        ))
+                    })
+                    .clone();
+                let context = ToolCallContext {
+                    workspace_id: workspace_id.clone(),
+                    chat_id: chat_id.to_string(),
+                    message_id: assistant_message_id.to_string(),
+                    tool_call_id: tool_call.id.clone(),
+                    tool_name: tool_call.function.name.clone(),
                 };
-
-                // Call tool on agent client with timeout (60 seconds) and cancellation support
-                let tool_call_future = client.call_tool(params);
-
-                // Use tokio::select to handle both timeout and cancellation
-                tokio::select! {
-                    result = tokio::time::timeout(tokio::time::Duration::from_secs(60), tool_call_future) => {
-                        match result {
-                            Ok(Ok(res)) => {
-                                // Serialize content to match expected generic JSON
-                                match serde_json::to_value(&res.content) {
-                                    Ok(value) => {
-                                        // Try to extract text content to simplify response for LLM
-                                        let extracted_text = if let serde_json::Value::Array(items) = &value
-                                        {
-                                            let mut texts = Vec::new();
-                                            let mut has_text = false;
-
-                                            for item in items {
-                                                if let Some(type_str) =
-                                                    item.get("type").and_then(|t| t.as_str())
-                                                {
-                                                    if type_str == "text" {
-                                                        if let Some(text) =
-                                                            item.get("text").and_then(|t| t.as_str())
-                                                        {
-                                                            texts.push(text);
-                                                            has_text = true;
-                                                        }
-                                                    } else if type_str == "image" {
-                                                        // Used by some tools to return screenshots
-                                                        texts.push("[Image Content]");
-                                                    }
-                                                }
-                                            }
-
-                                            if has_text {
-                                                Some(texts.join("\n\n"))
-                                            } else {
-                                                None
-                                            }
-                                        } else {
-                                            None
-                                        };
-
-                                        if let Some(text) = extracted_text {
-                                            Ok(serde_json::Value::String(text))
-                                        } else {
-                                            Ok(value)
-                                        }
-                                    }
-                                    Err(e) => Err(AppError::Generic(format!(
-                                        "Failed to serialize tool response: {e}"
-                                    ))),
-                                }
-                            }
-                            Ok(Err(e)) => Err(AppError::Generic(format!("Tool execution failed: {e}"))),
-                            Err(_) => Err(AppError::Generic(
-                                "Tool execution timed out after 60 seconds".to_string(),
-                            )),
-                        }
-                    }
-                    _ = cancellation_rx.recv() => {
-                        // Cancellation received
-                        Err(AppError::Cancelled)
+                let policy = ToolCallPolicy::resolve(
+                    &execution_config,
+                    &client_key,
+                    &tool_call.function.name,
+                );
+                let slots = (&turn_slots, server);
+                let call_cancellation_rx = cancellation_rx.resubscribe();
+                let agent_client = agent_client.as_ref();
+                let denied_reason = permissions.denied.get(&tool_call.id).cloned();
+                async move {
+                    if let Some(reason) = denied_reason {
+                        return (
+                            Err(AppError::Validation(reason)),
+                            std::time::Duration::ZERO,
+                            client_key,
+                        );
                     }
+                    let (result, duration) = self
+                        .execute_tool_call(
+                            app,
+                            tool_call,
+                            context,
+                            &client_key,
+                            agent_client,
+                            (&policy, breaker),
+                            slots,
+                            call_cancellation_rx,
+                        )
+                        .await;
+                    (result, duration, client_key)
                 }
-            } else {
-                // Standard Execution
-                let connection_id = match tool_to_connection.get(&tool_call.function.name) {
-                    Some(id) => id,
-                    None => {
-                        // Return error instead of using ?, so it gets handled in match below
-                        &String::new() // Dummy value, will be caught below
-                    }
-                };
+            })
+            .collect();
+        // join_all yields the results in call order, whichever call finishes first
+        let execution_results = futures::future::join_all(executions).await;
 
-                // Validate connection exists first
-                if connection_id.is_empty() {
-                    Err(AppError::Validation(format!(
-                        "Tool {} not found in any MCP connection",
-                        tool_call.function.name
-                    )))
-                } else {
-                    let arguments_str = tool_call.function.arguments.trim();
+        // Consume the cancellation like a sequential run would, so later turns are not cancelled
+        if execution_results
+            .iter()
+            .any(|(result, _, _)| matches!(result, Err(AppError::Cancelled)))
+        {
+            let _ = cancellation_rx.try_recv();
+        }
 
-                    // Parse arguments - return error instead of propagating with ?
-                    let arguments_result: Result<serde_json::Value, AppError> =
-                        if arguments_str.is_empty() {
-                            Ok(serde_json::json!({}))
-                        } else {
-                            serde_json::from_str(arguments_str).map_err(|e| {
-                                AppError::Validation(format!(
-                                    "Failed to parse tool arguments for '{}': {} (arguments: '{}')",
-                                    tool_call.function.name, e, arguments_str
-                                ))
-                            })
-                        };
-
-                    // Only proceed with execution if arguments parsed successfully
-                    match arguments_result {
-                        Ok(arguments) => {
-                            // Execute with timeout and cancellation support
-                            let tool_exec_future = self.tool_service.execute_tool(
-                                connection_id,
-                                &tool_call.function.name,
-                                arguments,
-                            );
+        let audit = &app.state::<crate::state::AppState>().tool_audit_service;
+        for (tool_call, (execution_result, duration, client_key)) in
+            tool_calls.iter().zip(execution_results)
+        {
+            let tool_call_message_id = format!("tool_call_{}", tool_call.id);
+            let mut audit_entry = ToolAuditEntry::new(
+                &workspace_id,
+                chat_id,
+                assistant_message_id,
+                tool_call,
+                permissions
+                    .permissions
+                    .get(&tool_call.id)
+                    .copied()
+                    .unwrap_or(AuditPermission::Auto),
+            );
+            audit_entry.connection_id = Some(client_key).filter(|key| !key.is_empty());
+            audit_entry.duration_ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
 
-                            tokio::select! {
-                                result = tokio::time::timeout(tokio::time::Duration::from_secs(60), tool_exec_future) => {
-                                    match result {
-                                        Ok(r) => r,
-                                        Err(_) => Err(AppError::Generic(
-                                            "Tool execution timed out after 60 seconds".to_string(),
-                                        )),
-                                    }
-                                }
-                                _ = cancellation_rx.recv() => {
-                                    Err(AppError::Cancelled)
-                                }
-                            }
-                        }
-                        Err(e) => Err(e), // Return parse error to be handled below
-                    }
+            // Tools report their own failures through isError
+            let execution_result = execution_result.and_then(|output| {
+                if output.is_error {
+                    Err(AppError::Mcp(output.text))
+                } else {
+                    Ok(output)
                 }
-            };
+            });
 
-            let result = match execution_result {
-                Ok(result) => {
+            let (result, result_files) = match execution_result {
+                Ok(output) => {
                     successful_count += 1;
+                    audit_entry.result_size = output.text.len() as u64;
+
+                    // Persist images/audio returned by the tool like uploaded files
+                    let files = self.save_tool_files(app, &output.files);
+                    let result = serde_json::Value::String(output.text);
 
                     // Update tool_call message with success
                     let completed_data = serde_json::json!({
                         "name": tool_call.function.name,
                         "arguments": tool_call.function.arguments,
                         "result": result,
+                        "structured_content": output.structured_content,
+                        "files": files,
                         "status": "completed"
                     });
                     self.message_service.update(
@@ -1698,11 +2514,12 @@ impl ChatService {
                         "Tool execution completed"
                     );
 
-                    result
+                    (result, files)
                 }
                 Err(e) => {
                     failed_count += 1;
                     let error_msg = e.to_string();
+                    audit_entry.error = Some(error_msg.clone());
 
                     // Log error for debugging
                     tracing::error!(
@@ -1746,29 +2563,41 @@ impl ChatService {
                         Some(error_msg.clone()),
                     )?;
 
-                    serde_json::json!({ "error": error_msg })
+                    (serde_json::json!({ "error": error_msg }), Vec::new())
                 }
             };
+            let is_error = result.get("error").is_some();
+            audit.record(&audit_entry);
 
             // Create tool result message
             let tool_result_message_id = format!("tool_result_{}", tool_call.id);
             let tool_result_timestamp = tool_call_timestamp + 1;
 
+            let tool_result_metadata = if result_files.is_empty() {
+                None
+            } else {
+                Some(serde_json::json!({ "files": result_files }).to_string())
+            };
+
             self.message_service.create(
-                tool_result_message_id,
+                tool_result_message_id.clone(),
                 chat_id.to_string(),
+                Some(leaf_message_id.clone()),
                 "tool".to_string(),
                 serde_json::to_string(&result)?,
                 Some(tool_result_timestamp),
                 None,
                 Some(tool_call.id.clone()),
-                None,
+                tool_result_metadata,
             )?;
+            *leaf_message_id = tool_result_message_id;
 
             // Add tool result to conversation
             tool_results.push(ChatMessage::Tool {
                 content: serde_json::to_string(&result)?,
                 tool_call_id: tool_call.id.clone(),
+                parts: self.load_file_parts(&result_files),
+                is_error,
             });
         }
 
@@ -1784,8 +2613,434 @@ impl ChatService {
         Ok(tool_results)
     }
 
+    /// Run one tool call once its turn and its server have a free slot, returns how long it ran
+    #[allow(clippy::too_many_arguments)]
+    async fn execute_tool_call(
+        &self,
+        app: &AppHandle,
+        tool_call: &crate::models::llm_types::ToolCall,
+        context: ToolCallContext,
+        client_key: &str,
+        agent_client: Option<&Arc<rust_mcp_sdk::mcp_client::ClientRuntime>>,
+        (policy, breaker): (&ToolCallPolicy, CircuitBreakerConfig),
+        (turn_slots, server_slots): (&tokio::sync::Semaphore, Arc<tokio::sync::Semaphore>),
+        mut cancellation_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> (Result<MCPToolOutput, AppError>, std::time::Duration) {
+        // Wait for the server first, so calls queued on a busy server leave turn slots free
+        let acquire = async {
+            let server = server_slots.acquire().await;
+            let turn = turn_slots.acquire().await;
+            (server, turn)
+        };
+        let _slots = tokio::select! {
+            slots = acquire => slots,
+            _ = cancellation_rx.recv() => {
+                return (Err(AppError::Cancelled), std::time::Duration::ZERO);
+            }
+        };
+        // The reported duration leaves out the wait for a slot
+        let started = std::time::Instant::now();
+
+        // Log tool execution start for debugging
+        tracing::debug!(
+            tool = %tool_call.function.name,
+            chat_id = %context.chat_id,
+            tool_call_id = %tool_call.id,
+            "Starting tool execution"
+        );
+
+        // Server requests and progress sent while a tool runs are attributed to its tool call
+        let tool_call_contexts = app
+            .state::<crate::state::AppState>()
+            .tool_call_contexts
+            .clone();
+        tool_call_contexts.bind(client_key, context);
+
+        // Execute tool logic
+        let execution_result = if let Some(client) = agent_client {
+            // Agent Execution
+            let arguments_str = tool_call.function.arguments.trim();
+            let arguments_map = if arguments_str.is_empty() {
+                Ok(serde_json::Map::new())
+            } else {
+                match serde_json::from_str::<serde_json::Value>(arguments_str) {
+                    Ok(serde_json::Value::Object(map)) => Ok(map),
+                    Ok(_) => Ok(serde_json::Map::new()),
+                    Err(e) => Err(AppError::Validation(format!("Invalid arguments: {e}"))),
+                }
+            };
+
+            match arguments_map {
+                Ok(arguments_map) => {
+                    let params = CallToolRequestParams {
+                        name: tool_call.function.name.clone(),
+                        arguments: Some(arguments_map),
+                    };
+
+                    // Call tool on agent client with timeout and cancellation support
+                    let tool_call_future = MCPClientService::call_tool_with_progress(
+                        client,
+                        params,
+                        Some(&tool_call.id),
+                    );
+
+                    // Use tokio::select to handle both timeout and cancellation
+                    tokio::select! {
+                        result = tokio::time::timeout(policy.timeout, tool_call_future) => {
+                            match result {
+                                Ok(Ok(res)) => Ok(MCPClientService::to_tool_output(res)),
+                                Ok(Err(e)) => Err(AppError::Generic(format!("Tool execution failed: {e}"))),
+                                Err(_) => Err(AppError::Generic(format!(
+                                    "Tool execution timed out after {} seconds",
+                                    policy.timeout.as_secs()
+                                ))),
+                            }
+                        }
+                        _ = cancellation_rx.recv() => {
+                            // Cancellation received
+                            Err(AppError::Cancelled)
+                        }
+                    }
+                }
+                Err(e) => Err(e),
+            }
+        } else if client_key.is_empty() {
+            // Validate connection exists first
+            Err(AppError::Validation(format!(
+                "Tool {} not found in any MCP connection",
+                tool_call.function.name
+            )))
+        } else {
+            // Standard Execution
+            let arguments_str = tool_call.function.arguments.trim();
+
+            // Parse arguments - return error instead of propagating with ?
+            let arguments_result: Result<serde_json::Value, AppError> = if arguments_str.is_empty()
+            {
+                Ok(serde_json::json!({}))
+            } else {
+                serde_json::from_str(arguments_str).map_err(|e| {
+                    AppError::Validation(format!(
+                        "Failed to parse tool arguments for '{}': {} (arguments: '{}')",
+                        tool_call.function.name, e, arguments_str
+                    ))
+                })
+            };
+
+            // Only proceed with execution if arguments parsed successfully
+            match arguments_result {
+                Ok(arguments) => {
+                    // Timeouts and retries follow the policy, cancellation stops them all
+                    let tool_exec_future = self.tool_service.execute_tool(
+                        client_key,
+                        &tool_call.function.name,
+                        arguments,
+                        Some(&tool_call.id),
+                        policy,
+                        breaker,
+                    );
+
+                    tokio::select! {
+                        result = tool_exec_future => result,
+                        _ = cancellation_rx.recv() => {
+                            Err(AppError::Cancelled)
+                        }
+                    }
+                }
+                Err(e) => Err(e), // Return parse error to be handled by the caller
+            }
+        };
+
+        tool_call_contexts.unbind(client_key, &tool_call.id);
+        (execution_result, started.elapsed())
+    }
+
+    /// The chat's connection and model, followed by the workspace's fallback models
+    fn llm_targets(
+        &self,
+        llm_connection: &LLMConnection,
+        model: &str,
+        workspace_settings: &WorkspaceSettings,
+    ) -> Vec<LLMTarget> {
+        let mut targets = vec![LLMTarget {
+            connection_id: llm_connection.id.clone(),
+            provider: llm_connection.provider.clone(),
+            base_url: llm_connection.base_url.clone(),
+            api_key: Some(llm_connection.api_key.clone()),
+            api_mode: llm_connection.api_mode.clone(),
+            model: model.to_string(),
+        }];
+
+        for fallback in workspace_settings.fallback_models() {
+            if fallback.connection_id == llm_connection.id && fallback.model == model {
+                continue;
+            }
+            match self
+                .llm_connection_service
+                .get_by_id(&fallback.connection_id)
+            {
+                Ok(Some(connection)) => targets.push(LLMTarget {
+                    connection_id: connection.id,
+                    provider: connection.provider,
+                    base_url: connection.base_url,
+                    api_key: Some(connection.api_key),
+                    api_mode: connection.api_mode,
+                    model: fallback.model,
+                }),
+                Ok(None) => tracing::warn!(
+                    connection_id = %fallback.connection_id,
+                    "Fallback model connection not found, skipping it"
+                ),
+                Err(e) => tracing::warn!(
+                    connection_id = %fallback.connection_id,
+                    error = ?e,
+                    "Failed to load fallback model connection, skipping it"
+                ),
+            }
+        }
+        targets
+    }
+
+    /// `usage_stats.status` of a reply, "fallback" when a fallback model answered
+    fn usage_status(fallback: Option<&FallbackUsed>) -> String {
+        if fallback.is_some() {
+            "fallback"
+        } else {
+            "success"
+        }
+        .to_string()
+    }
+
+    /// Trim request messages to the model's context window
+    /// Messages are sent as they are when the window is unknown
+    fn fit_to_context(
+        chat_id: &str,
+        mut messages: Vec<ChatMessage>,
+        model: &str,
+        limit: Option<usize>,
+        tools: Option<&[ChatCompletionTool]>,
+        config: &ContextConfig,
+    ) -> Vec<ChatMessage> {
+        let Some(budget) = context::input_budget(limit, config) else {
+            tracing::warn!(
+                chat_id = %chat_id,
+                model = %model,
+                "Context window of the model is unknown, sending the history untrimmed"
+            );
+            return messages;
+        };
+        let budget = budget.saturating_sub(context::estimate_tools_tokens(tools));
+        let trimmed = context::fit_messages(&mut messages, budget, config.strategy);
+        if trimmed > 0 {
+            tracing::info!(
+                chat_id = %chat_id,
+                model = %model,
+                budget,
+                trimmed,
+                "Trimmed chat history to fit the context window"
+            );
+        }
+        messages
+    }
+
+    /// Summarize older turns once the history outgrows the model's context window
+    ///
+    /// The summary is saved as an assistant message at the end of the active branch, so it
+    /// is visible in the chat and later requests reuse it until the history outgrows it again.
+    #[allow(clippy::too_many_arguments)]
+    async fn summarize_history_if_needed(
+        &self,
+        app: &AppHandle,
+        workspace_id: &str,
+        chat_id: &str,
+        existing_messages: &[Message],
+        pending_content: &str,
+        model: &str,
+        llm_connection: &LLMConnection,
+        config: &ContextConfig,
+    ) -> Result<Option<Message>, AppError> {
+        const MAX_TRANSCRIPT_ENTRY_CHARS: usize = 4_000;
+
+        let Some(budget) =
+            context::input_budget(context::context_limit(llm_connection, model), config)
+        else {
+            tracing::warn!(
+                chat_id = %chat_id,
+                model = %model,
+                "Context window of the model is unknown, not summarizing the history"
+            );
+            return Ok(None);
+        };
+        let (previous, remaining) = context::split_at_summary(existing_messages);
+        let used = previous.map_or(0, |summary| context::estimate_tokens(&summary.content))
+            + context::estimate_history_tokens(&remaining)
+            + context::estimate_tokens(pending_content);
+        if used <= budget {
+            return Ok(None);
+        }
+
+        // Keep the latest turns verbatim, up to half of the budget
+        let mut split = remaining.len();
+        let mut kept = 0;
+        while split > 0 {
+            let tokens = context::estimate_history_tokens(&remaining[split - 1..split]);
+            if kept + tokens > budget / 2 {
+                break;
+            }
+            kept += tokens;
+            split -= 1;
+        }
+        // Start the verbatim part at a user message so replies keep their question
+        while split < remaining.len() && remaining[split].role != "user" {
+            split += 1;
+        }
+        let covered = &remaining[..split];
+        let Some(last_covered) = covered.last() else {
+            return Ok(None);
+        };
+
+        let summary_connection = match &config.summary_connection_id {
+            Some(id) => self
+                .llm_connection_service
+                .get_by_id(id)?
+                .ok_or_else(|| AppError::NotFound(format!("LLM connection not found: {id}")))?,
+            None => llm_connection.clone(),
+        };
+        let summary_model = config
+            .summary_model
+            .clone()
+            .unwrap_or_else(|| model.to_string());
+
+        let mut transcript = String::new();
+        if let Some(previous) = previous {
+            transcript.push_str(&format!(
+                "Summary of the conversation so far:\n{}\n\n",
+                previous.content
+            ));
+        }
+        for message in covered {
+            let label = match message.role.as_str() {
+                "user" => "User",
+                "assistant" => "Assistant",
+                "tool_call" => "Tool call",
+                "tool" => "Tool result",
+                _ => continue,
+            };
+            let text: String = message
+                .content
+                .chars()
+                .take(MAX_TRANSCRIPT_ENTRY_CHARS)
+                .collect();
+            transcript.push_str(&format!("{label}: {text}\n\n"));
+        }
+        // The transcript itself must fit the summary model, keep its most recent part
+        let max_chars = context::input_budget(
+            context::context_limit(&summary_connection, &summary_model),
+            config,
+        )
+        .map_or(usize::MAX, |budget| budget * 3);
+        let transcript_chars = transcript.chars().count();
+        if transcript_chars > max_chars {
+            transcript = transcript
+                .chars()
+                .skip(transcript_chars - max_chars)
+                .collect();
+        }
+
+        let request = LLMChatRequest {
+            model: summary_model.clone(),
+            messages: vec![
+                ChatMessage::System {
+                    content: "You summarize conversations so they can continue with less context. Write a concise summary of the transcript that keeps facts, decisions, user preferences, names, code identifiers, open questions and unfinished tasks. Output only the summary.".to_string(),
+                },
+                ChatMessage::User {
+                    content: UserContent::Text(transcript),
+                },
+            ],
+            temperature: Some(0.2),
+            max_tokens: Some(1_024),
+            stream: false,
+            tools: None,
+            tool_choice: None,
+            reasoning_effort: None,
+            stream_options: None,
+            response_modalities: None,
+            image_config: None,
+            response_format: None,
+        };
+
+        let summary_message_id = uuid::Uuid::new_v4().to_string();
+        let start_time = std::time::Instant::now();
+        // Use a dummy chat ID so the request does not stream into the chat UI
+        let response = self
+            .llm_service
+            .chat(
+                &summary_connection.base_url,
+                Some(&summary_connection.api_key),
+                request,
+                "system_context_summary".to_string(),
+                summary_message_id.clone(),
+                app.clone(),
+                None,
+                &summary_connection.provider,
+                summary_connection.api_mode.as_deref(),
+            )
+            .await?;
+        let latency = start_time.elapsed().as_millis() as u64;
+
+        let content = response.content.trim().to_string();
+        if content.is_empty() {
+            return Err(AppError::Llm(
+                "Summary model returned no content".to_string(),
+            ));
+        }
+
+        if let Err(e) = self.usage_service.record_usage(
+            workspace_id.to_string(),
+            chat_id.to_string(),
+            summary_message_id.clone(),
+            summary_connection.provider.clone(),
+            summary_model.clone(),
+            response.usage.clone(),
+            latency,
+            false,
+            "success".to_string(),
+        ) {
+            tracing::error!(error = ?e, "Failed to record usage");
+        }
+
+        let mut metadata = serde_json::json!({
+            "type": context::SUMMARY_METADATA_TYPE,
+            "covers_until": last_covered.id,
+            "summarized_messages": covered.len(),
+            "model": summary_model,
+        });
+        if let Some(usage) = &response.usage {
+            metadata["tokenUsage"] = serde_json::json!(usage);
+        }
+
+        let message = self.message_service.create(
+            summary_message_id,
+            chat_id.to_string(),
+            existing_messages.last().map(|m| m.id.clone()),
+            "assistant".to_string(),
+            content,
+            None,
+            None,
+            None,
+            Some(metadata.to_string()),
+        )?;
+
+        tracing::info!(
+            chat_id = %chat_id,
+            summarized = covered.len(),
+            "Summarized older chat history"
+        );
+        Ok(Some(message))
+    }
+
     /// Prepare messages for agent loop - includes tool calls and tool results
-    fn prepare_messages_for_agent_loop(
+    async fn prepare_messages_for_agent_loop(
         &self,
         chat_id: &str,
         workspace_settings: &WorkspaceSettings,
@@ -1793,13 +3048,17 @@ impl ChatService {
         user_metadata: Option<&str>,
         system_prompt_override: Option<String>,
     ) -> Result<Vec<ChatMessage>, AppError> {
-        let existing_messages = self.message_service.get_by_chat_id(chat_id)?;
+        let existing_messages = self.message_service.get_active_path(chat_id)?;
+        let attached_resources = self
+            .load_attached_resources(chat_id, &existing_messages, user_metadata)
+            .await?;
         self.prepare_messages(
             &existing_messages,
             workspace_settings,
             user_content,
             None,
             user_metadata,
+            &attached_resources,
             system_prompt_override,
         )
     }
@@ -1811,6 +3070,7 @@ impl ChatService {
         user_content: &str,
         user_files: Option<&[String]>,
         user_metadata: Option<&str>,
+        attached_resources: &HashMap<MCPResourceRef, Vec<ContentPart>>,
         system_prompt_override: Option<String>,
     ) -> Result<Vec<ChatMessage>, AppError> {
         let mut api_messages: Vec<ChatMessage> = Vec::new();
@@ -1819,6 +3079,22 @@ impl ChatService {
         let system_message =
             system_prompt_override.or_else(|| workspace_settings.system_message.clone());
 
+        // Turns covered by a summary are replaced by the summary, appended to the system prompt
+        let (summary, history) = context::split_at_summary(existing_messages);
+        let system_message = match (system_message, summary) {
+            (Some(system_message), Some(summary)) if !system_message.trim().is_empty() => {
+                Some(format!(
+                    "{system_message}\n\nSummary of the earlier conversation:\n{}",
+                    summary.content
+                ))
+            }
+            (_, Some(summary)) => Some(format!(
+                "Summary of the earlier conversation:\n{}",
+                summary.content
+            )),
+            (system_message, None) => system_message,
+        };
+
         if let Some(system_message) = system_message {
             if !system_message.trim().is_empty() {
                 api_messages.push(ChatMessage::System {
@@ -1828,7 +3104,7 @@ impl ChatService {
         }
 
         // Add conversation history (filter out tool_call messages for API)
-        for msg in existing_messages {
+        for msg in history {
             if msg.role == "tool_call" {
                 // Skip tool_call messages - they're UI-only
                 continue;
@@ -1915,6 +3191,13 @@ impl ChatService {
                         UserContent::Text(effective_content)
                     };
 
+                    // 3. Append attached MCP resources
+                    let content = self.attach_resource_parts(
+                        content,
+                        msg.metadata.as_deref(),
+                        attached_resources,
+                    );
+
                     ChatMessage::User { content }
                 }
                 "assistant" => {
@@ -1924,14 +3207,33 @@ impl ChatService {
                     ChatMessage::Assistant {
                         content: AssistantContent::Text(msg.content.clone()),
                         tool_calls: None,
+                        reasoning_items: None,
                     }
                 }
                 "tool" => {
                     // Tool result messages need tool_call_id
                     if let Some(tool_call_id) = &msg.tool_call_id {
+                        let files = msg
+                            .metadata
+                            .as_deref()
+                            .and_then(|m| serde_json::from_str::<serde_json::Value>(m).ok())
+                            .and_then(|m| {
+                                m.get("files").and_then(|f| f.as_array()).map(|files| {
+                                    files
+                                        .iter()
+                                        .filter_map(|f| f.as_str().map(ToString::to_string))
+                                        .collect::<Vec<String>>()
+                                })
+                            })
+                            .unwrap_or_default();
+                        let is_error = serde_json::from_str::<serde_json::Value>(&msg.content)
+                            .is_ok_and(|content| content.get("error").is_some());
+
                         ChatMessage::Tool {
                             content: msg.content.clone(),
                             tool_call_id: tool_call_id.clone(),
+                            parts: self.load_file_parts(&files),
+                            is_error,
                         }
                     } else {
                         // Skip if no tool_call_id
@@ -1989,6 +3291,7 @@ impl ChatService {
         } else {
             UserContent::Text(effective_user_content)
         };
+        let content = self.attach_resource_parts(content, user_metadata, attached_resources);
 
         api_messages.push(ChatMessage::User { content });
 
@@ -2090,6 +3393,7 @@ async fn generate_chat_title_internal(
         stream_options: None,
         response_modalities: None,
         image_config: None,
+        response_format: None,
     };
 
     // 3. Call LLM
@@ -2105,6 +3409,7 @@ async fn generate_chat_title_internal(
             app.clone(),
             None,
             &llm_connection.provider,
+            llm_connection.api_mode.as_deref(),
         )
         .await;
 
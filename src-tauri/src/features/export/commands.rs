@@ -0,0 +1,66 @@
+use super::models::{ExportFormat, ExportResult, ExportScope};
+use crate::error::AppError;
+use crate::state::AppState;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, State};
+
+fn files_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("files"))
+        .map_err(|e| AppError::Generic(e.to_string()))
+}
+
+fn export(
+    scope: &ExportScope,
+    format: ExportFormat,
+    path: Option<String>,
+    app: &AppHandle,
+    state: &State<'_, AppState>,
+) -> Result<ExportResult, AppError> {
+    state.export_service.export(
+        scope,
+        format,
+        &files_dir(app)?,
+        path.as_deref().map(std::path::Path::new),
+    )
+}
+
+/// Export one chat, to `path` when given, otherwise the content is returned
+#[tauri::command]
+pub fn export_chat(
+    chat_id: String,
+    format: ExportFormat,
+    path: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ExportResult, AppError> {
+    export(&ExportScope::Chat(chat_id), format, path, &app, &state)
+}
+
+#[tauri::command]
+pub fn export_workspace(
+    workspace_id: String,
+    format: ExportFormat,
+    path: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ExportResult, AppError> {
+    export(
+        &ExportScope::Workspace(workspace_id),
+        format,
+        path,
+        &app,
+        &state,
+    )
+}
+
+#[tauri::command]
+pub fn export_all_chats(
+    format: ExportFormat,
+    path: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ExportResult, AppError> {
+    export(&ExportScope::All, format, path, &app, &state)
+}
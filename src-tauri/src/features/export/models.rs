@@ -0,0 +1,72 @@
+use crate::features::message::Message;
+use serde::{Deserialize, Serialize};
+
+// Identifies the lossless JSON document, bumped when its shape changes
+pub const EXPORT_FORMAT_NAME: &str = "nexo.chat-export";
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown, // Active branch, reasoning and tool calls folded
+    Json,     // Every branch, every column
+    Html,     // Active branch as a single file with images embedded
+}
+
+impl ExportFormat {
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "json",
+            Self::Html => "html",
+        }
+    }
+}
+
+/// What to export
+#[derive(Debug, Clone)]
+pub enum ExportScope {
+    Chat(String),
+    Workspace(String),
+    All,
+}
+
+/// Lossless export document, the shape of the JSON format
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatExport {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: i64,
+    pub workspaces: Vec<ExportedWorkspace>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportedWorkspace {
+    pub id: String,
+    pub name: String,
+    pub created_at: i64,
+    pub chats: Vec<ExportedChat>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportedChat {
+    pub id: String,
+    pub workspace_id: String,
+    pub title: String,
+    pub last_message: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub agent_id: Option<String>,
+    pub parent_id: Option<String>,
+    pub active_leaf_message_id: Option<String>,
+    pub messages: Vec<Message>, // All branches, including tool_call and tool rows
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportResult {
+    pub format: ExportFormat,
+    pub path: Option<String>,    // Set when the export was written to a file
+    pub content: Option<String>, // Set otherwise, e.g. for copying to the clipboard
+    pub chat_count: usize,
+    pub message_count: usize,
+}
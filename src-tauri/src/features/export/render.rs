@@ -0,0 +1,508 @@
+//! Markdown and HTML renderings of an export
+//!
+//! Both show the active branch of each chat. Reasoning, tool calls and context
+//! summaries are folded into `<details>` blocks so transcripts stay readable when
+//! pasted into documents.
+
+use super::models::{ChatExport, ExportedChat};
+use crate::features::chat::context::SUMMARY_METADATA_TYPE;
+use crate::features::message::Message;
+use base64::{engine::general_purpose, Engine as _};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// A message as it is shown in a transcript
+enum Entry<'a> {
+    Text {
+        role: &'a str,
+        content: &'a str,
+        reasoning: Option<&'a str>,
+        files: Vec<String>,
+    },
+    Summary(&'a str),
+    ToolCall(ToolCallRecord),
+    ToolResult {
+        content: &'a str,
+        files: Vec<String>,
+    },
+}
+
+/// Parsed content of a `tool_call` message
+struct ToolCallRecord {
+    name: String,
+    arguments: String,
+    status: String,
+    result: Option<String>,
+    error: Option<String>,
+    files: Vec<String>,
+}
+
+fn parse_json(value: &str) -> Option<serde_json::Value> {
+    serde_json::from_str(value).ok()
+}
+
+fn string_array(value: &serde_json::Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(ToString::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Files attached to a message, `images` is the format used before `files`
+fn message_files(message: &Message) -> Vec<String> {
+    message
+        .metadata
+        .as_deref()
+        .and_then(parse_json)
+        .map(|meta| {
+            if meta.get("files").is_some() {
+                string_array(&meta["files"])
+            } else {
+                string_array(&meta["images"])
+            }
+        })
+        .unwrap_or_default()
+}
+
+/// Pretty-print JSON text, other text is returned unchanged
+fn pretty(text: &str) -> String {
+    parse_json(text)
+        .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        .unwrap_or_else(|| text.to_string())
+}
+
+fn value_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(text) => Some(text.clone()),
+        other => serde_json::to_string_pretty(other).ok(),
+    }
+}
+
+fn tool_call_record(message: &Message) -> ToolCallRecord {
+    let data = parse_json(&message.content).unwrap_or_default();
+    ToolCallRecord {
+        name: data["name"].as_str().unwrap_or("tool").to_string(),
+        arguments: data["arguments"]
+            .as_str()
+            .map_or_else(|| data["arguments"].to_string(), pretty),
+        status: data["status"].as_str().unwrap_or("unknown").to_string(),
+        result: value_text(&data["result"]),
+        error: value_text(&data["error"]),
+        files: string_array(&data["files"]),
+    }
+}
+
+/// Messages on the chat's active branch, root first
+/// Falls back to every message in order when the branch cannot be followed
+fn active_path(chat: &ExportedChat) -> Vec<&Message> {
+    let by_id: HashMap<&str, &Message> = chat.messages.iter().map(|m| (m.id.as_str(), m)).collect();
+
+    let mut path = Vec::new();
+    let mut current = chat.active_leaf_message_id.as_deref();
+    while let Some(message) = current.and_then(|id| by_id.get(id)) {
+        if path.len() > by_id.len() {
+            break; // Cycle in corrupted data
+        }
+        path.push(*message);
+        current = message.parent_message_id.as_deref();
+    }
+
+    if path.is_empty() {
+        return chat.messages.iter().collect();
+    }
+    path.reverse();
+    path
+}
+
+fn entries(chat: &ExportedChat) -> Vec<Entry<'_>> {
+    let path = active_path(chat);
+    // Tool results are shown with their call, only results without a recorded call get their own entry
+    let recorded_calls: HashSet<&str> = path
+        .iter()
+        .filter(|m| m.role == "tool_call")
+        .filter_map(|m| m.id.strip_prefix("tool_call_"))
+        .collect();
+
+    path.into_iter()
+        .filter_map(|message| {
+            let is_summary = message
+                .metadata
+                .as_deref()
+                .and_then(parse_json)
+                .is_some_and(|meta| meta["type"] == SUMMARY_METADATA_TYPE);
+
+            match message.role.as_str() {
+                _ if is_summary => Some(Entry::Summary(&message.content)),
+                "tool_call" => Some(Entry::ToolCall(tool_call_record(message))),
+                "tool" => {
+                    let recorded = message
+                        .tool_call_id
+                        .as_deref()
+                        .is_some_and(|id| recorded_calls.contains(id));
+                    (!recorded).then(|| Entry::ToolResult {
+                        content: &message.content,
+                        files: message_files(message),
+                    })
+                }
+                role => {
+                    let files = message_files(message);
+                    if message.content.trim().is_empty()
+                        && message.reasoning.is_none()
+                        && files.is_empty()
+                    {
+                        return None; // Placeholder of a reply that only called tools
+                    }
+                    Some(Entry::Text {
+                        role,
+                        content: &message.content,
+                        reasoning: message
+                            .reasoning
+                            .as_deref()
+                            .filter(|r| !r.trim().is_empty()),
+                        files,
+                    })
+                }
+            }
+        })
+        .collect()
+}
+
+fn role_label(role: &str) -> String {
+    match role {
+        "user" => "User".to_string(),
+        "assistant" => "Assistant".to_string(),
+        "system" => "System".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0).map_or_else(String::new, |dt| {
+        dt.format("%Y-%m-%d %H:%M UTC").to_string()
+    })
+}
+
+fn file_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(path)
+}
+
+fn chat_count(export: &ChatExport) -> usize {
+    export.workspaces.iter().map(|w| w.chats.len()).sum()
+}
+
+/// Code block with a fence longer than any backtick run in the text
+fn fenced(text: &str, language: &str) -> String {
+    let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{fence}{language}\n{text}\n{fence}")
+}
+
+fn md_details(out: &mut String, summary: &str, body: &str) {
+    let _ = write!(
+        out,
+        "<details>\n<summary>{}</summary>\n\n{}\n\n</details>\n\n",
+        escape_html(summary),
+        body.trim_end()
+    );
+}
+
+fn md_tool_call(out: &mut String, call: &ToolCallRecord) {
+    let mut body = format!("**Arguments**\n\n{}", fenced(&call.arguments, "json"));
+    if let Some(result) = &call.result {
+        let language = if parse_json(result).is_some() {
+            "json"
+        } else {
+            ""
+        };
+        let _ = write!(
+            body,
+            "\n\n**Result**\n\n{}",
+            fenced(&pretty(result), language)
+        );
+    }
+    if let Some(error) = &call.error {
+        let _ = write!(body, "\n\n**Error**\n\n{}", fenced(error, ""));
+    }
+    for file in &call.files {
+        let _ = write!(body, "\n\n*Attachment: {}*", file_name(file));
+    }
+    md_details(
+        out,
+        &format!("Tool call: {} ({})", call.name, call.status),
+        &body,
+    );
+}
+
+fn markdown_chat(out: &mut String, chat: &ExportedChat, level: usize) {
+    let heading = "#".repeat(level);
+    let _ = writeln!(out, "{heading} {}\n", chat.title);
+    let _ = writeln!(out, "*Created {}*\n", format_timestamp(chat.created_at));
+
+    for entry in entries(chat) {
+        match entry {
+            Entry::Text {
+                role,
+                content,
+                reasoning,
+                files,
+            } => {
+                let _ = writeln!(out, "{heading}# {}\n", role_label(role));
+                if let Some(reasoning) = reasoning {
+                    md_details(out, "Reasoning", reasoning);
+                }
+                if !content.trim().is_empty() {
+                    let _ = writeln!(out, "{}\n", content.trim_end());
+                }
+                for file in files {
+                    let _ = writeln!(out, "*Attachment: {}*\n", file_name(&file));
+                }
+            }
+            Entry::Summary(content) => md_details(out, "Summary of earlier messages", content),
+            Entry::ToolCall(call) => md_tool_call(out, &call),
+            Entry::ToolResult { content, files } => {
+                let mut body = fenced(&pretty(content), "");
+                for file in files {
+                    let _ = write!(body, "\n\n*Attachment: {}*", file_name(&file));
+                }
+                md_details(out, "Tool result", &body);
+            }
+        }
+    }
+}
+
+pub fn markdown(export: &ChatExport) -> String {
+    let mut out = String::new();
+
+    // A single chat is a document of its own, otherwise chats are grouped by workspace
+    if chat_count(export) == 1 {
+        if let Some(chat) = export.workspaces.iter().flat_map(|w| &w.chats).next() {
+            markdown_chat(&mut out, chat, 1);
+        }
+        return out;
+    }
+
+    for workspace in &export.workspaces {
+        let _ = writeln!(out, "# {}\n", workspace.name);
+        for chat in &workspace.chats {
+            markdown_chat(&mut out, chat, 2);
+        }
+    }
+    out
+}
+
+const HTML_STYLE: &str = "
+body { margin: 0; background: #f7f7f8; color: #1f2328; font: 15px/1.6 -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; }
+main { max-width: 860px; margin: 0 auto; padding: 32px 20px; }
+h1, h2 { line-height: 1.3; }
+.meta { color: #6e7781; font-size: 13px; margin-top: -8px; }
+.message { background: #fff; border: 1px solid #e5e7eb; border-radius: 10px; padding: 12px 16px; margin: 12px 0; }
+.message.user { background: #eef4ff; border-color: #d4e2ff; }
+.role { font-size: 12px; font-weight: 600; text-transform: uppercase; letter-spacing: .04em; color: #6e7781; margin-bottom: 6px; }
+.text { white-space: pre-wrap; overflow-wrap: anywhere; }
+pre { background: #f3f4f6; border-radius: 6px; padding: 10px 12px; overflow-x: auto; font: 13px/1.5 ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; }
+details { border: 1px solid #e5e7eb; border-radius: 8px; padding: 6px 12px; margin: 8px 0; background: #fafafa; }
+summary { cursor: pointer; color: #57606a; font-size: 13px; }
+img { max-width: 100%; border-radius: 6px; margin: 6px 0; display: block; }
+.attachment { display: inline-block; font-size: 12px; background: #f3f4f6; border-radius: 4px; padding: 2px 8px; margin: 4px 4px 0 0; }
+.error { color: #cf222e; }
+";
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Message text with fenced code blocks as `<pre>`, everything else kept verbatim
+fn html_text(text: &str) -> String {
+    let mut out = String::new();
+    let mut in_code = false;
+    let mut block = String::new();
+
+    let flush = |out: &mut String, block: &mut String, code: bool| {
+        let content = block.trim_matches('\n');
+        if !content.is_empty() {
+            let tag = if code { "pre" } else { "div class=\"text\"" };
+            let close = if code { "pre" } else { "div" };
+            let _ = write!(out, "<{tag}>{}</{close}>", escape_html(content));
+        }
+        block.clear();
+    };
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            flush(&mut out, &mut block, in_code);
+            in_code = !in_code;
+            continue;
+        }
+        block.push_str(line);
+        block.push('\n');
+    }
+    flush(&mut out, &mut block, in_code);
+    out
+}
+
+fn image_mime(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    match extension.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        "bmp" => Some("image/bmp"),
+        "svg" => Some("image/svg+xml"),
+        _ => None,
+    }
+}
+
+/// Inline an image from the app's `files` directory, other files become a name chip
+/// Paths outside that directory are never read
+fn html_file(path: &str, files_dir: &Path) -> String {
+    let name = escape_html(file_name(path));
+    let source = Path::new(path);
+
+    let inside_files_dir = match (source.canonicalize(), files_dir.canonicalize()) {
+        (Ok(source), Ok(dir)) => source.starts_with(dir),
+        _ => false,
+    };
+    let embedded = image_mime(source)
+        .filter(|_| inside_files_dir)
+        .and_then(|mime| std::fs::read(source).ok().map(|bytes| (mime, bytes)));
+
+    match embedded {
+        Some((mime, bytes)) => format!(
+            "<img src=\"data:{mime};base64,{}\" alt=\"{name}\">",
+            general_purpose::STANDARD.encode(bytes)
+        ),
+        None => format!("<span class=\"attachment\">{name}</span>"),
+    }
+}
+
+fn html_details(out: &mut String, summary: &str, body: &str) {
+    let _ = write!(
+        out,
+        "<details><summary>{}</summary>{body}</details>",
+        escape_html(summary)
+    );
+}
+
+fn html_chat(out: &mut String, chat: &ExportedChat, files_dir: &Path, heading: &str) {
+    let _ = write!(
+        out,
+        "<section><{heading}>{}</{heading}><p class=\"meta\">Created {}</p>",
+        escape_html(&chat.title),
+        format_timestamp(chat.created_at)
+    );
+
+    for entry in entries(chat) {
+        match entry {
+            Entry::Text {
+                role,
+                content,
+                reasoning,
+                files,
+            } => {
+                let _ = write!(
+                    out,
+                    "<article class=\"message {}\"><div class=\"role\">{}</div>",
+                    escape_html(role),
+                    escape_html(&role_label(role))
+                );
+                if let Some(reasoning) = reasoning {
+                    html_details(out, "Reasoning", &html_text(reasoning));
+                }
+                out.push_str(&html_text(content));
+                for file in files {
+                    out.push_str(&html_file(&file, files_dir));
+                }
+                out.push_str("</article>");
+            }
+            Entry::Summary(content) => {
+                html_details(out, "Summary of earlier messages", &html_text(content));
+            }
+            Entry::ToolCall(call) => {
+                let mut body = format!(
+                    "<div class=\"role\">Arguments</div><pre>{}</pre>",
+                    escape_html(&call.arguments)
+                );
+                if let Some(result) = &call.result {
+                    let _ = write!(
+                        body,
+                        "<div class=\"role\">Result</div><pre>{}</pre>",
+                        escape_html(&pretty(result))
+                    );
+                }
+                if let Some(error) = &call.error {
+                    let _ = write!(
+                        body,
+                        "<div class=\"role error\">Error</div><pre>{}</pre>",
+                        escape_html(error)
+                    );
+                }
+                for file in &call.files {
+                    body.push_str(&html_file(file, files_dir));
+                }
+                html_details(
+                    out,
+                    &format!("Tool call: {} ({})", call.name, call.status),
+                    &body,
+                );
+            }
+            Entry::ToolResult { content, files } => {
+                let mut body = format!("<pre>{}</pre>", escape_html(&pretty(content)));
+                for file in files {
+                    body.push_str(&html_file(&file, files_dir));
+                }
+                html_details(out, "Tool result", &body);
+            }
+        }
+    }
+
+    out.push_str("</section>");
+}
+
+/// A self-contained page, images from `files_dir` are embedded as data URLs
+pub fn html(export: &ChatExport, files_dir: &Path) -> String {
+    let single_chat = if chat_count(export) == 1 {
+        export.workspaces.iter().flat_map(|w| &w.chats).next()
+    } else {
+        None
+    };
+    let title = single_chat.map_or_else(|| "Chat export".to_string(), |chat| chat.title.clone());
+
+    let mut body = String::new();
+    if let Some(chat) = single_chat {
+        html_chat(&mut body, chat, files_dir, "h1");
+    } else {
+        for workspace in &export.workspaces {
+            let _ = write!(body, "<h1>{}</h1>", escape_html(&workspace.name));
+            for chat in &workspace.chats {
+                html_chat(&mut body, chat, files_dir, "h2");
+            }
+        }
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n<main>{body}</main>\n</body>\n</html>\n",
+        escape_html(&title)
+    )
+}
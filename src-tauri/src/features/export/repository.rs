@@ -0,0 +1,98 @@
+use super::models::{ExportedChat, ExportedWorkspace};
+use crate::error::AppError;
+use rusqlite::params;
+use std::sync::Arc;
+use tauri::AppHandle;
+
+const CHAT_COLUMNS: &str = "id, workspace_id, title, last_message, created_at, updated_at, agent_id, parent_id, active_leaf_message_id";
+
+pub trait ExportRepository: Send + Sync {
+    /// Workspaces without their chats, all of them when `workspace_id` is `None`
+    fn get_workspaces(
+        &self,
+        workspace_id: Option<&str>,
+    ) -> Result<Vec<ExportedWorkspace>, AppError>;
+    /// Chats of a workspace without their messages, oldest first
+    fn get_chats(&self, workspace_id: &str) -> Result<Vec<ExportedChat>, AppError>;
+    fn get_chat(&self, chat_id: &str) -> Result<Option<ExportedChat>, AppError>;
+}
+
+pub struct SqliteExportRepository {
+    app: Arc<AppHandle>,
+}
+
+impl SqliteExportRepository {
+    pub const fn new(app: Arc<AppHandle>) -> Self {
+        Self { app }
+    }
+
+    fn map_chat(row: &rusqlite::Row<'_>) -> rusqlite::Result<ExportedChat> {
+        Ok(ExportedChat {
+            id: row.get(0)?,
+            workspace_id: row.get(1)?,
+            title: row.get(2)?,
+            last_message: row.get(3)?,
+            created_at: row.get(4)?,
+            updated_at: row.get(5)?,
+            agent_id: row.get(6)?,
+            parent_id: row.get(7)?,
+            active_leaf_message_id: row.get(8)?,
+            messages: Vec::new(),
+        })
+    }
+}
+
+impl ExportRepository for SqliteExportRepository {
+    fn get_workspaces(
+        &self,
+        workspace_id: Option<&str>,
+    ) -> Result<Vec<ExportedWorkspace>, AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        let mut stmt = conn.prepare(
+            "SELECT id, name, created_at FROM workspaces
+             WHERE ?1 IS NULL OR id = ?1
+             ORDER BY created_at ASC",
+        )?;
+
+        let workspaces = stmt
+            .query_map(params![workspace_id], |row| {
+                Ok(ExportedWorkspace {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    created_at: row.get(2)?,
+                    chats: Vec::new(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(workspaces)
+    }
+
+    fn get_chats(&self, workspace_id: &str) -> Result<Vec<ExportedChat>, AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {CHAT_COLUMNS} FROM chats WHERE workspace_id = ?1 ORDER BY created_at ASC"
+        ))?;
+
+        let chats = stmt
+            .query_map(params![workspace_id], Self::map_chat)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(chats)
+    }
+
+    fn get_chat(&self, chat_id: &str) -> Result<Option<ExportedChat>, AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        let result = conn.query_row(
+            &format!("SELECT {CHAT_COLUMNS} FROM chats WHERE id = ?1"),
+            params![chat_id],
+            Self::map_chat,
+        );
+
+        match result {
+            Ok(chat) => Ok(Some(chat)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
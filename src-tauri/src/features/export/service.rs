@@ -0,0 +1,125 @@
+use super::models::{
+    ChatExport, ExportFormat, ExportResult, ExportScope, EXPORT_FORMAT_NAME, EXPORT_FORMAT_VERSION,
+};
+use super::render;
+use super::repository::ExportRepository;
+use crate::error::AppError;
+use crate::features::message::MessageService;
+use std::path::Path;
+use std::sync::Arc;
+
+pub struct ExportService {
+    repository: Arc<dyn ExportRepository>,
+    message_service: Arc<MessageService>,
+}
+
+impl ExportService {
+    pub fn new(
+        repository: Arc<dyn ExportRepository>,
+        message_service: Arc<MessageService>,
+    ) -> Self {
+        Self {
+            repository,
+            message_service,
+        }
+    }
+
+    /// Load workspaces, chats and every message of every branch for `scope`
+    pub fn collect(&self, scope: &ExportScope) -> Result<ChatExport, AppError> {
+        let mut workspaces = match scope {
+            ExportScope::Chat(chat_id) => {
+                let chat = self
+                    .repository
+                    .get_chat(chat_id)?
+                    .ok_or_else(|| AppError::NotFound(format!("Chat not found: {chat_id}")))?;
+                let mut workspaces = self.repository.get_workspaces(Some(&chat.workspace_id))?;
+                if let Some(workspace) = workspaces.first_mut() {
+                    workspace.chats.push(chat);
+                }
+                workspaces
+            }
+            ExportScope::Workspace(workspace_id) => {
+                let workspaces = self.repository.get_workspaces(Some(workspace_id))?;
+                if workspaces.is_empty() {
+                    return Err(AppError::NotFound(format!(
+                        "Workspace not found: {workspace_id}"
+                    )));
+                }
+                workspaces
+            }
+            ExportScope::All => self.repository.get_workspaces(None)?,
+        };
+
+        for workspace in &mut workspaces {
+            if !matches!(scope, ExportScope::Chat(_)) {
+                workspace.chats = self.repository.get_chats(&workspace.id)?;
+            }
+            for chat in &mut workspace.chats {
+                chat.messages = self.message_service.get_by_chat_id(&chat.id)?;
+            }
+        }
+
+        let exported_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        Ok(ChatExport {
+            format: EXPORT_FORMAT_NAME.to_string(),
+            version: EXPORT_FORMAT_VERSION,
+            exported_at,
+            workspaces,
+        })
+    }
+
+    /// Render an export and write it to `path`, or return it when no path is given
+    /// `files_dir` is the app's uploaded files directory, the only place HTML exports embed images from
+    pub fn export(
+        &self,
+        scope: &ExportScope,
+        format: ExportFormat,
+        files_dir: &Path,
+        path: Option<&Path>,
+    ) -> Result<ExportResult, AppError> {
+        let export = self.collect(scope)?;
+        let chat_count = export.workspaces.iter().map(|w| w.chats.len()).sum();
+        let message_count = export
+            .workspaces
+            .iter()
+            .flat_map(|w| &w.chats)
+            .map(|c| c.messages.len())
+            .sum();
+
+        let content = match format {
+            ExportFormat::Markdown => render::markdown(&export),
+            ExportFormat::Json => serde_json::to_string_pretty(&export)?,
+            ExportFormat::Html => render::html(&export, files_dir),
+        };
+
+        crate::lib::sentry_helpers::add_breadcrumb(
+            "export",
+            format!("Exported {chat_count} chats as {}", format.extension()),
+            sentry::Level::Info,
+        );
+
+        match path {
+            Some(path) => {
+                std::fs::write(path, content)?;
+                Ok(ExportResult {
+                    format,
+                    path: Some(path.to_string_lossy().to_string()),
+                    content: None,
+                    chat_count,
+                    message_count,
+                })
+            }
+            None => Ok(ExportResult {
+                format,
+                path: None,
+                content: Some(content),
+                chat_count,
+                message_count,
+            }),
+        }
+    }
+}
@@ -134,6 +134,8 @@ pub async fn install_mcp_server_from_hub(
         runtime_path,
         status: "disconnected".to_string(),
         tools_json: None,
+        resources_json: None,
+        prompts_json: None,
         error_message: None,
         created_at: now,
         updated_at: now,
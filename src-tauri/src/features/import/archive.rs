@@ -0,0 +1,139 @@
+use crate::error::AppError;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const CONVERSATIONS_FILE: &str = "conversations.json";
+
+enum ArchiveKind {
+    Directory(PathBuf),
+    Zip(zip::ZipArchive<File>),
+}
+
+/// An unpacked or zipped data export: `conversations.json` plus attachment files
+pub struct ImportArchive {
+    kind: ArchiveKind,
+    entries: Vec<String>, // Relative paths with `/` separators
+    conversations: String,
+}
+
+impl ImportArchive {
+    /// Open a `conversations.json` file, the export directory holding it, or the export zip
+    pub fn open(path: &Path) -> Result<Self, AppError> {
+        if !path.exists() {
+            return Err(AppError::NotFound(format!(
+                "Import file not found: {}",
+                path.display()
+            )));
+        }
+
+        if path.is_dir() {
+            return Self::open_directory(path.to_path_buf(), CONVERSATIONS_FILE);
+        }
+
+        let is_zip = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+        if is_zip {
+            return Self::open_zip(path);
+        }
+
+        let directory = path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .to_path_buf();
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| AppError::Validation("Invalid import file name".to_string()))?
+            .to_string();
+        Self::open_directory(directory, &file_name)
+    }
+
+    fn open_directory(directory: PathBuf, conversations: &str) -> Result<Self, AppError> {
+        let mut entries = Vec::new();
+        let mut pending = vec![directory.clone()];
+        while let Some(dir) = pending.pop() {
+            for entry in std::fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    pending.push(path);
+                } else if let Ok(relative) = path.strip_prefix(&directory) {
+                    entries.push(relative.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+
+        if !entries.iter().any(|entry| entry == conversations) {
+            return Err(AppError::Validation(format!(
+                "{CONVERSATIONS_FILE} not found in {}",
+                directory.display()
+            )));
+        }
+
+        Ok(Self {
+            kind: ArchiveKind::Directory(directory),
+            entries,
+            conversations: conversations.to_string(),
+        })
+    }
+
+    fn open_zip(path: &Path) -> Result<Self, AppError> {
+        let archive = zip::ZipArchive::new(File::open(path)?)?;
+        let entries: Vec<String> = archive
+            .file_names()
+            .filter(|name| !name.ends_with('/'))
+            .map(ToString::to_string)
+            .collect();
+
+        // Exports are sometimes re-zipped with an extra top-level folder
+        let conversations = entries
+            .iter()
+            .filter(|name| {
+                *name == CONVERSATIONS_FILE || name.ends_with(&format!("/{CONVERSATIONS_FILE}"))
+            })
+            .min_by_key(|name| name.len())
+            .cloned()
+            .ok_or_else(|| {
+                AppError::Validation(format!("{CONVERSATIONS_FILE} not found in the archive"))
+            })?;
+
+        Ok(Self {
+            kind: ArchiveKind::Zip(archive),
+            entries,
+            conversations,
+        })
+    }
+
+    pub fn read_conversations(&mut self) -> Result<Vec<u8>, AppError> {
+        let name = self.conversations.clone();
+        self.read(&name)
+    }
+
+    pub fn read(&mut self, entry: &str) -> Result<Vec<u8>, AppError> {
+        match &mut self.kind {
+            ArchiveKind::Directory(directory) => Ok(std::fs::read(directory.join(entry))?),
+            ArchiveKind::Zip(archive) => {
+                let mut file = archive.by_name(entry)?;
+                let mut bytes = Vec::new();
+                file.read_to_end(&mut bytes)?;
+                Ok(bytes)
+            }
+        }
+    }
+
+    /// Entry of an uploaded file, exports name them `<file id>-<original name>`
+    pub fn find_file(&self, file_id: &str) -> Option<&str> {
+        if file_id.is_empty() {
+            return None;
+        }
+
+        self.entries
+            .iter()
+            .find(|entry| {
+                let name = entry.rsplit('/').next().unwrap_or(entry);
+                name.starts_with(file_id) && *entry != &self.conversations
+            })
+            .map(String::as_str)
+    }
+}
@@ -0,0 +1,159 @@
+use crate::features::export::models::ExportedChat;
+use crate::features::message::Message;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Where the bytes of an imported attachment come from
+pub enum AttachmentData {
+    Archive(String), // Entry in the export
+    Inline(Vec<u8>), // Text extracted by the source app
+}
+
+/// Attachment copied into the app's `files` directory once the import is confirmed
+pub struct PendingAttachment {
+    pub data: AttachmentData,
+    pub destination: PathBuf,
+}
+
+/// A chat ready to be written, with the files its messages reference
+pub struct ParsedChat {
+    pub chat: ExportedChat,
+    pub attachments: Vec<PendingAttachment>,
+}
+
+/// Builds a message tree from source messages that point to their parent by source ID
+///
+/// Source messages that are not imported map to their closest imported ancestor,
+/// so their children stay attached to the right branch.
+pub struct ChatBuilder<'a> {
+    chat: ExportedChat,
+    files_dir: &'a Path,
+    source_ids: HashMap<String, Option<String>>,
+    attachments: Vec<PendingAttachment>,
+}
+
+impl<'a> ChatBuilder<'a> {
+    pub fn new(
+        chat_id: String,
+        workspace_id: &str,
+        title: String,
+        created_at: i64,
+        updated_at: i64,
+        files_dir: &'a Path,
+    ) -> Self {
+        Self {
+            chat: ExportedChat {
+                id: chat_id,
+                workspace_id: workspace_id.to_string(),
+                title,
+                last_message: None,
+                created_at,
+                updated_at,
+                agent_id: None,
+                parent_id: None,
+                active_leaf_message_id: None,
+                messages: Vec::new(),
+            },
+            files_dir,
+            source_ids: HashMap::new(),
+            attachments: Vec::new(),
+        }
+    }
+
+    /// Message standing in for a source message, `None` for roots and unknown IDs
+    pub fn resolve(&self, source_id: Option<&str>) -> Option<String> {
+        source_id.and_then(|id| self.source_ids.get(id).cloned().flatten())
+    }
+
+    /// Map a source message to an imported message (or to the root)
+    pub fn link(&mut self, source_id: &str, message_id: Option<String>) {
+        self.source_ids.insert(source_id.to_string(), message_id);
+    }
+
+    pub fn get(&self, message_id: &str) -> Option<&Message> {
+        self.chat.messages.iter().find(|m| m.id == message_id)
+    }
+
+    pub fn get_mut(&mut self, message_id: &str) -> Option<&mut Message> {
+        self.chat.messages.iter_mut().find(|m| m.id == message_id)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn push(
+        &mut self,
+        id: String,
+        parent_message_id: Option<String>,
+        role: &str,
+        content: String,
+        reasoning: Option<String>,
+        timestamp: i64,
+        assistant_message_id: Option<String>,
+        metadata: Option<serde_json::Value>,
+    ) -> String {
+        self.chat.messages.push(Message {
+            id: id.clone(),
+            chat_id: self.chat.id.clone(),
+            role: role.to_string(),
+            content,
+            reasoning,
+            timestamp,
+            assistant_message_id,
+            tool_call_id: None,
+            metadata: metadata.map(|m| m.to_string()),
+            parent_message_id,
+        });
+        id
+    }
+
+    /// Reserve a path in the `files` directory for an attachment
+    pub fn attach(&mut self, data: AttachmentData, file_name: &str) -> String {
+        let extension = Path::new(file_name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("bin")
+            .to_lowercase();
+        let destination = self
+            .files_dir
+            .join(format!("{}.{extension}", uuid::Uuid::new_v4()));
+        let path = destination.to_string_lossy().to_string();
+        self.attachments
+            .push(PendingAttachment { data, destination });
+        path
+    }
+
+    /// Finish the chat, `leaf_source_id` selects the active branch (the newest message otherwise)
+    pub fn finish(mut self, leaf_source_id: Option<&str>) -> ParsedChat {
+        let newest = self
+            .chat
+            .messages
+            .iter()
+            .max_by_key(|m| m.timestamp)
+            .map(|m| m.id.clone());
+        let leaf = self.resolve(leaf_source_id).or(newest);
+
+        self.chat.last_message = leaf
+            .as_deref()
+            .and_then(|id| self.get(id))
+            .map(|m| m.content.chars().take(100).collect());
+        self.chat.active_leaf_message_id = leaf;
+        if let Some(updated_at) = self.chat.messages.iter().map(|m| m.timestamp).max() {
+            self.chat.updated_at = self.chat.updated_at.max(updated_at);
+        }
+
+        ParsedChat {
+            chat: self.chat,
+            attachments: self.attachments,
+        }
+    }
+}
+
+/// Whether a chat has more than one branch
+pub fn is_branched(chat: &ExportedChat) -> bool {
+    let mut children: HashMap<Option<&str>, usize> = HashMap::new();
+    for message in &chat.messages {
+        *children
+            .entry(message.parent_message_id.as_deref())
+            .or_default() += 1;
+    }
+    children.values().any(|count| *count > 1)
+}
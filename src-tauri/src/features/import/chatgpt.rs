@@ -0,0 +1,386 @@
+//! ChatGPT data export: `conversations.json` holds every conversation as a
+//! tree (`mapping`) of nodes, `current_node` is the leaf shown in the app.
+//! Uploaded and generated images sit next to it, named after their file ID.
+
+use super::archive::ImportArchive;
+use super::builder::{AttachmentData, ChatBuilder, ParsedChat};
+use super::models::ImportReport;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Deserialize)]
+struct Conversation {
+    id: Option<String>,
+    conversation_id: Option<String>,
+    title: Option<String>,
+    create_time: Option<f64>,
+    update_time: Option<f64>,
+    #[serde(default)]
+    mapping: HashMap<String, Node>,
+    current_node: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Node {
+    message: Option<NodeMessage>,
+    parent: Option<String>,
+    #[serde(default)]
+    children: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct NodeMessage {
+    author: Author,
+    create_time: Option<f64>,
+    #[serde(default)]
+    content: Value,
+    #[serde(default)]
+    metadata: Value,
+    recipient: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Author {
+    role: String,
+}
+
+/// Plain text of a message, whatever its content type
+fn content_text(content: &Value) -> String {
+    if let Some(text) = content["text"].as_str() {
+        return text.to_string();
+    }
+    if let Some(parts) = content["parts"].as_array() {
+        return parts
+            .iter()
+            .filter_map(|part| part.as_str().or_else(|| part["text"].as_str()))
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+    content["result"].as_str().unwrap_or_default().to_string()
+}
+
+fn thoughts_text(content: &Value) -> String {
+    content["thoughts"]
+        .as_array()
+        .map(|thoughts| {
+            thoughts
+                .iter()
+                .map(|thought| {
+                    [thought["summary"].as_str(), thought["content"].as_str()]
+                        .into_iter()
+                        .flatten()
+                        .filter(|text| !text.is_empty())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        })
+        .unwrap_or_default()
+}
+
+/// `file-service://file-abc` and `sediment://file_abc` both point to `file-abc*` / `file_abc*`
+fn asset_file_id(pointer: &str) -> &str {
+    pointer.rsplit("://").next().unwrap_or(pointer)
+}
+
+fn seconds(time: Option<f64>) -> Option<i64> {
+    time.map(|t| t as i64)
+}
+
+struct ConversationParser<'a> {
+    title: String,
+    builder: ChatBuilder<'a>,
+    archive: &'a ImportArchive,
+    report: &'a mut ImportReport,
+    reasoning: HashMap<String, String>, // Thoughts by node ID, attached to the reply below them
+}
+
+impl ConversationParser<'_> {
+    fn skip(&mut self, kind: &str, reason: impl Into<String>) {
+        self.report.skip(Some(&self.title), kind, reason);
+    }
+
+    /// Copy uploaded files and images referenced by a message
+    fn collect_files(&mut self, message: &NodeMessage) -> Vec<String> {
+        let mut files = Vec::new();
+
+        let pointers: Vec<String> = message.content["parts"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|part| part["content_type"] == "image_asset_pointer")
+            .filter_map(|part| part["asset_pointer"].as_str())
+            .map(|pointer| asset_file_id(pointer).to_string())
+            .collect();
+        for file_id in pointers {
+            match self.archive.find_file(&file_id) {
+                Some(entry) => {
+                    let entry = entry.to_string();
+                    files.push(
+                        self.builder
+                            .attach(AttachmentData::Archive(entry.clone()), &entry),
+                    );
+                }
+                None => self.skip("attachment", "Image not included in the export"),
+            }
+        }
+
+        let attachments: Vec<(String, String)> = message.metadata["attachments"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|attachment| {
+                Some((
+                    attachment["id"].as_str()?.to_string(),
+                    attachment["name"].as_str().unwrap_or_default().to_string(),
+                ))
+            })
+            .collect();
+        for (file_id, name) in attachments {
+            match self.archive.find_file(&file_id) {
+                Some(entry) => {
+                    let file_name = if name.is_empty() { entry } else { &name };
+                    let file_name = file_name.to_string();
+                    files.push(
+                        self.builder
+                            .attach(AttachmentData::Archive(entry.to_string()), &file_name),
+                    );
+                }
+                None => self.skip("attachment", "File not included in the export"),
+            }
+        }
+
+        files
+    }
+
+    fn parse_node(&mut self, node_id: &str, node: &Node, fallback_time: i64) {
+        let parent = self.builder.resolve(node.parent.as_deref());
+        let Some(message) = &node.message else {
+            self.builder.link(node_id, parent);
+            return;
+        };
+
+        let parent_time = parent
+            .as_deref()
+            .and_then(|id| self.builder.get(id))
+            .map(|m| m.timestamp);
+        let timestamp = seconds(message.create_time)
+            .or(parent_time)
+            .unwrap_or(fallback_time);
+        let content_type = message.content["content_type"].as_str().unwrap_or_default();
+        let role = message.author.role.as_str();
+        let hidden = message.metadata["is_visually_hidden_from_conversation"] == true;
+        let recipient = message.recipient.as_deref().unwrap_or("all");
+        let text = content_text(&message.content);
+
+        // Thoughts become the reasoning of the reply that follows them
+        if content_type == "thoughts" {
+            let thoughts = thoughts_text(&message.content);
+            if !thoughts.is_empty() {
+                self.reasoning.insert(node_id.to_string(), thoughts);
+            }
+            self.builder.link(node_id, parent);
+            return;
+        }
+
+        match role {
+            _ if content_type == "user_editable_context" => {
+                self.skip("content", "Custom instructions are not imported");
+            }
+            _ if hidden || content_type == "reasoning_recap" => {}
+            "system" => {
+                if !text.trim().is_empty() {
+                    self.skip("message", "System messages are not imported");
+                }
+            }
+            "user" | "assistant" if recipient == "all" => {
+                if !matches!(content_type, "text" | "multimodal_text" | "code") {
+                    self.skip(
+                        "content",
+                        format!("Unsupported content type: {content_type}"),
+                    );
+                    self.builder.link(node_id, parent);
+                    return;
+                }
+
+                let files = self.collect_files(message);
+                let reasoning = node
+                    .parent
+                    .as_deref()
+                    .and_then(|id| self.reasoning.remove(id));
+                if text.trim().is_empty() && files.is_empty() && reasoning.is_none() {
+                    self.builder.link(node_id, parent);
+                    return;
+                }
+
+                let mut metadata = serde_json::json!({
+                    "import": { "source": "chatgpt", "id": node_id }
+                });
+                if let Some(model) = message.metadata["model_slug"].as_str() {
+                    metadata["import"]["model"] = Value::from(model);
+                }
+                if !files.is_empty() {
+                    metadata["files"] = serde_json::json!(files);
+                }
+
+                let id = self.builder.push(
+                    uuid::Uuid::new_v4().to_string(),
+                    parent,
+                    role,
+                    text,
+                    reasoning,
+                    timestamp,
+                    None,
+                    Some(metadata),
+                );
+                self.builder.link(node_id, Some(id));
+                return;
+            }
+            // Messages addressed to a tool (python, browser, dalle...) are tool calls
+            "assistant" => {
+                let assistant_message_id = parent
+                    .clone()
+                    .filter(|id| self.builder.get(id).is_some_and(|m| m.role == "assistant"));
+                let call = serde_json::json!({
+                    "name": recipient,
+                    "arguments": text,
+                    "status": "completed"
+                });
+                let id = self.builder.push(
+                    format!("tool_call_{}", uuid::Uuid::new_v4()),
+                    parent,
+                    "tool_call",
+                    call.to_string(),
+                    None,
+                    timestamp,
+                    assistant_message_id,
+                    None,
+                );
+                self.builder.link(node_id, Some(id));
+                return;
+            }
+            // Tool output is stored with the call it answers
+            "tool" => {
+                let files = self.collect_files(message);
+                let call = parent
+                    .as_deref()
+                    .filter(|id| self.builder.get(id).is_some_and(|m| m.role == "tool_call"))
+                    .map(ToString::to_string);
+                match call.and_then(|id| self.builder.get_mut(&id)) {
+                    Some(call) => {
+                        let mut data: Value =
+                            serde_json::from_str(&call.content).unwrap_or_default();
+                        data["result"] = Value::from(text);
+                        if !files.is_empty() {
+                            data["files"] = serde_json::json!(files);
+                        }
+                        call.content = data.to_string();
+                    }
+                    None => self.skip("message", "Tool output without its tool call"),
+                }
+            }
+            other => self.skip("message", format!("Unsupported role: {other}")),
+        }
+
+        self.builder.link(node_id, parent);
+    }
+}
+
+fn parse_conversation(
+    conversation: Conversation,
+    workspace_id: &str,
+    archive: &ImportArchive,
+    files_dir: &Path,
+    report: &mut ImportReport,
+) -> ParsedChat {
+    let title = conversation
+        .title
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| "Untitled".to_string());
+    let chat_id = format!(
+        "chatgpt-{}",
+        conversation
+            .conversation_id
+            .or(conversation.id)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+    );
+    let created_at = seconds(conversation.create_time).unwrap_or(0);
+    let updated_at = seconds(conversation.update_time).unwrap_or(created_at);
+
+    let mut parser = ConversationParser {
+        title: title.clone(),
+        builder: ChatBuilder::new(
+            chat_id,
+            workspace_id,
+            title,
+            created_at,
+            updated_at,
+            files_dir,
+        ),
+        archive,
+        report,
+        reasoning: HashMap::new(),
+    };
+
+    // Walk the tree from its roots so parents are always imported before their children
+    let mapping = &conversation.mapping;
+    let mut roots: Vec<&String> = mapping
+        .iter()
+        .filter(|(_, node)| {
+            node.parent
+                .as_ref()
+                .is_none_or(|parent| !mapping.contains_key(parent))
+        })
+        .map(|(id, _)| id)
+        .collect();
+    roots.sort();
+
+    let mut stack: Vec<&String> = roots.into_iter().rev().collect();
+    let mut visited = 0;
+    while let Some(node_id) = stack.pop() {
+        visited += 1;
+        if visited > mapping.len() {
+            break; // Cycle in corrupted data
+        }
+        let Some(node) = mapping.get(node_id) else {
+            continue;
+        };
+        parser.parse_node(node_id, node, created_at);
+        stack.extend(node.children.iter().rev());
+    }
+
+    parser.builder.finish(conversation.current_node.as_deref())
+}
+
+/// Parse ChatGPT conversations, unreadable ones are reported and skipped
+pub fn parse(
+    conversations: Vec<Value>,
+    workspace_id: &str,
+    archive: &ImportArchive,
+    files_dir: &Path,
+    report: &mut ImportReport,
+) -> Vec<ParsedChat> {
+    let mut chats = Vec::new();
+    for value in conversations {
+        let title = value["title"].as_str().map(ToString::to_string);
+        match serde_json::from_value::<Conversation>(value) {
+            Ok(conversation) => chats.push(parse_conversation(
+                conversation,
+                workspace_id,
+                archive,
+                files_dir,
+                report,
+            )),
+            Err(e) => report.skip(
+                title.as_deref(),
+                "conversation",
+                format!("Unreadable conversation: {e}"),
+            ),
+        }
+    }
+    chats
+}
@@ -0,0 +1,314 @@
+//! Claude data export: `conversations.json` holds every conversation with its
+//! messages in order. Newer exports link messages to their parent (edits and
+//! retries become branches); older ones are a single linear thread.
+//! Uploaded files are not part of the export, only the text extracted from documents.
+
+use super::builder::{AttachmentData, ChatBuilder, ParsedChat};
+use super::models::ImportReport;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Parent of the first message of a conversation
+const ROOT_MESSAGE_UUID: &str = "00000000-0000-4000-8000-000000000000";
+
+#[derive(Deserialize)]
+struct Conversation {
+    uuid: String,
+    #[serde(default)]
+    name: String,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+    #[serde(default)]
+    chat_messages: Vec<ClaudeMessage>,
+    current_leaf_message_uuid: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ClaudeMessage {
+    uuid: String,
+    #[serde(default)]
+    text: String,
+    sender: String,
+    created_at: Option<String>,
+    #[serde(default)]
+    content: Vec<Value>,
+    #[serde(default)]
+    attachments: Vec<Attachment>,
+    #[serde(default)]
+    files: Vec<Value>,
+    parent_message_uuid: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Attachment {
+    #[serde(default)]
+    file_name: String,
+    #[serde(default)]
+    extracted_content: String,
+}
+
+fn timestamp(value: Option<&str>) -> Option<i64> {
+    value
+        .and_then(|v| chrono::DateTime::parse_from_rfc3339(v).ok())
+        .map(|t| t.timestamp())
+}
+
+/// Text of a `tool_result` block, a string or a list of content blocks
+fn tool_result_text(block: &Value) -> String {
+    match &block["content"] {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| part["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+struct ConversationParser<'a> {
+    title: String,
+    builder: ChatBuilder<'a>,
+    report: &'a mut ImportReport,
+    tool_calls: HashMap<String, String>, // tool_use ID -> tool_call message ID
+}
+
+impl ConversationParser<'_> {
+    fn skip(&mut self, kind: &str, reason: impl Into<String>) {
+        self.report.skip(Some(&self.title), kind, reason);
+    }
+
+    /// Documents whose text Claude extracted are imported as text files
+    fn collect_files(&mut self, message: &ClaudeMessage) -> Vec<String> {
+        let mut files = Vec::new();
+        for attachment in &message.attachments {
+            if attachment.extracted_content.is_empty() {
+                self.skip(
+                    "attachment",
+                    "Attachment content not included in the export",
+                );
+                continue;
+            }
+            let stem = Path::new(&attachment.file_name)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("attachment");
+            files.push(self.builder.attach(
+                AttachmentData::Inline(attachment.extracted_content.clone().into_bytes()),
+                &format!("{stem}.txt"),
+            ));
+        }
+        for _ in &message.files {
+            self.skip("attachment", "File not included in the export");
+        }
+        files
+    }
+
+    fn record_result(&mut self, block: &Value) {
+        let Some(call_id) = block["tool_use_id"]
+            .as_str()
+            .and_then(|id| self.tool_calls.get(id))
+            .cloned()
+        else {
+            self.skip("content", "Tool result without its tool call");
+            return;
+        };
+        let Some(call) = self.builder.get_mut(&call_id) else {
+            return;
+        };
+
+        let mut data: Value = serde_json::from_str(&call.content).unwrap_or_default();
+        let text = tool_result_text(block);
+        if block["is_error"] == true {
+            data["status"] = Value::from("error");
+            data["error"] = Value::from(text);
+        } else {
+            data["result"] = Value::from(text);
+        }
+        call.content = data.to_string();
+    }
+
+    fn parse_message(
+        &mut self,
+        message: &ClaudeMessage,
+        parent: Option<String>,
+        fallback_time: i64,
+    ) {
+        let role = match message.sender.as_str() {
+            "human" => "user",
+            "assistant" => "assistant",
+            other => {
+                self.skip("message", format!("Unsupported sender: {other}"));
+                self.builder.link(&message.uuid, parent);
+                return;
+            }
+        };
+        let timestamp = timestamp(message.created_at.as_deref()).unwrap_or(fallback_time);
+
+        let mut text = Vec::new();
+        let mut reasoning = Vec::new();
+        let mut tool_uses = Vec::new();
+        let mut tool_results = Vec::new();
+        if message.content.is_empty() {
+            text.push(message.text.clone());
+        }
+        for block in &message.content {
+            match block["type"].as_str().unwrap_or_default() {
+                "text" => text.push(block["text"].as_str().unwrap_or_default().to_string()),
+                "thinking" => {
+                    reasoning.push(block["thinking"].as_str().unwrap_or_default().to_string());
+                }
+                "tool_use" => tool_uses.push(block),
+                "tool_result" => tool_results.push(block),
+                other => self.skip("content", format!("Unsupported content type: {other}")),
+            }
+        }
+
+        let text = text
+            .into_iter()
+            .filter(|t| !t.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let reasoning = Some(
+            reasoning
+                .into_iter()
+                .filter(|t| !t.trim().is_empty())
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        )
+        .filter(|r| !r.is_empty());
+        let files = self.collect_files(message);
+
+        let mut last = parent;
+        let mut message_id = None;
+        if !text.is_empty() || !files.is_empty() || reasoning.is_some() || !tool_uses.is_empty() {
+            let mut metadata = serde_json::json!({
+                "import": { "source": "claude", "id": message.uuid }
+            });
+            if !files.is_empty() {
+                metadata["files"] = serde_json::json!(files);
+            }
+            let id = self.builder.push(
+                uuid::Uuid::new_v4().to_string(),
+                last,
+                role,
+                text,
+                reasoning,
+                timestamp,
+                None,
+                Some(metadata),
+            );
+            message_id = Some(id.clone());
+            last = Some(id);
+        }
+
+        for block in tool_uses {
+            let call = serde_json::json!({
+                "name": block["name"].as_str().unwrap_or("tool"),
+                "arguments": block["input"].to_string(),
+                "status": "completed"
+            });
+            let id = self.builder.push(
+                format!("tool_call_{}", uuid::Uuid::new_v4()),
+                last,
+                "tool_call",
+                call.to_string(),
+                None,
+                timestamp,
+                message_id.clone(),
+                None,
+            );
+            if let Some(tool_use_id) = block["id"].as_str() {
+                self.tool_calls.insert(tool_use_id.to_string(), id.clone());
+            }
+            last = Some(id);
+        }
+
+        for block in tool_results {
+            self.record_result(block);
+        }
+
+        self.builder.link(&message.uuid, last);
+    }
+}
+
+fn parse_conversation(
+    conversation: Conversation,
+    workspace_id: &str,
+    files_dir: &Path,
+    report: &mut ImportReport,
+) -> ParsedChat {
+    let title = Some(conversation.name)
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| "Untitled".to_string());
+    let created_at = timestamp(conversation.created_at.as_deref()).unwrap_or(0);
+    let updated_at = timestamp(conversation.updated_at.as_deref()).unwrap_or(created_at);
+
+    let mut parser = ConversationParser {
+        title: title.clone(),
+        builder: ChatBuilder::new(
+            format!("claude-{}", conversation.uuid),
+            workspace_id,
+            title,
+            created_at,
+            updated_at,
+            files_dir,
+        ),
+        report,
+        tool_calls: HashMap::new(),
+    };
+
+    let mut messages = conversation.chat_messages;
+    messages.sort_by_key(|m| timestamp(m.created_at.as_deref()).unwrap_or(created_at));
+    let linked = messages.iter().any(|m| m.parent_message_uuid.is_some());
+
+    let mut previous: Option<String> = None;
+    for message in &messages {
+        let parent = if linked {
+            message
+                .parent_message_uuid
+                .as_deref()
+                .filter(|id| *id != ROOT_MESSAGE_UUID)
+        } else {
+            previous.as_deref()
+        };
+        let parent = parser.builder.resolve(parent);
+        parser.parse_message(message, parent, created_at);
+        previous = Some(message.uuid.clone());
+    }
+
+    parser
+        .builder
+        .finish(conversation.current_leaf_message_uuid.as_deref())
+}
+
+/// Parse Claude conversations, unreadable ones are reported and skipped
+pub fn parse(
+    conversations: Vec<Value>,
+    workspace_id: &str,
+    files_dir: &Path,
+    report: &mut ImportReport,
+) -> Vec<ParsedChat> {
+    let mut chats = Vec::new();
+    for value in conversations {
+        let title = value["name"].as_str().map(ToString::to_string);
+        match serde_json::from_value::<Conversation>(value) {
+            Ok(conversation) => {
+                chats.push(parse_conversation(
+                    conversation,
+                    workspace_id,
+                    files_dir,
+                    report,
+                ));
+            }
+            Err(e) => report.skip(
+                title.as_deref(),
+                "conversation",
+                format!("Unreadable conversation: {e}"),
+            ),
+        }
+    }
+    chats
+}
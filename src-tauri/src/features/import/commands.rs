@@ -0,0 +1,29 @@
+use super::models::ImportReport;
+use crate::error::AppError;
+use crate::state::AppState;
+use std::path::Path;
+use tauri::{AppHandle, Manager, State};
+
+/// Import a ChatGPT or Claude data export (zip, directory or `conversations.json`) into a new workspace
+#[tauri::command]
+pub async fn import_history(
+    path: String,
+    dry_run: bool,
+    workspace_name: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ImportReport, AppError> {
+    let files_dir = app
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join("files"))
+        .map_err(|e| AppError::Generic(e.to_string()))?;
+    let service = state.import_service.clone();
+
+    // Large exports take a while to parse and copy
+    tokio::task::spawn_blocking(move || {
+        service.import(Path::new(&path), dry_run, workspace_name, &files_dir)
+    })
+    .await
+    .map_err(|e| AppError::Generic(e.to_string()))?
+}
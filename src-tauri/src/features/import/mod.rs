@@ -0,0 +1,13 @@
+pub mod archive;
+pub mod builder;
+pub mod chatgpt;
+pub mod claude;
+pub mod commands;
+pub mod models;
+pub mod repository;
+pub mod service;
+#[cfg(test)]
+mod tests;
+
+pub use repository::*;
+pub use service::*;
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+/// Assistant whose data export is being imported
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportSource {
+    ChatGpt,
+    Claude,
+}
+
+impl ImportSource {
+    pub const fn display_name(self) -> &'static str {
+        match self {
+            Self::ChatGpt => "ChatGPT",
+            Self::Claude => "Claude",
+        }
+    }
+}
+
+/// Something in the export that was not imported, repeated items are counted
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SkippedItem {
+    pub conversation: Option<String>, // Conversation title, None for the whole export
+    pub kind: String,                 // "conversation" | "message" | "content" | "attachment"
+    pub reason: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportReport {
+    pub source: ImportSource,
+    pub dry_run: bool,
+    pub workspace_id: Option<String>, // Created workspace, None for dry runs or when nothing was imported
+    pub conversations: usize,         // Conversations found in the export
+    pub chats: usize,                 // Chats created (or that would be created)
+    pub messages: usize,
+    pub attachments: usize,
+    pub branched_chats: usize, // Chats with more than one branch
+    pub skipped: Vec<SkippedItem>,
+}
+
+impl ImportReport {
+    pub const fn new(source: ImportSource, dry_run: bool) -> Self {
+        Self {
+            source,
+            dry_run,
+            workspace_id: None,
+            conversations: 0,
+            chats: 0,
+            messages: 0,
+            attachments: 0,
+            branched_chats: 0,
+            skipped: Vec::new(),
+        }
+    }
+
+    pub fn skip(&mut self, conversation: Option<&str>, kind: &str, reason: impl Into<String>) {
+        let reason = reason.into();
+        if let Some(item) = self.skipped.iter_mut().find(|item| {
+            item.conversation.as_deref() == conversation
+                && item.kind == kind
+                && item.reason == reason
+        }) {
+            item.count += 1;
+            return;
+        }
+
+        self.skipped.push(SkippedItem {
+            conversation: conversation.map(ToString::to_string),
+            kind: kind.to_string(),
+            reason,
+            count: 1,
+        });
+    }
+}
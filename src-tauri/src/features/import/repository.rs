@@ -0,0 +1,91 @@
+use crate::error::AppError;
+use crate::features::export::models::ExportedWorkspace;
+use rusqlite::params;
+use std::sync::Arc;
+use tauri::AppHandle;
+
+pub trait ImportRepository: Send + Sync {
+    /// IDs among `chat_ids` that already exist, i.e. conversations imported before
+    fn existing_chat_ids(&self, chat_ids: &[String]) -> Result<Vec<String>, AppError>;
+    /// Insert a workspace with its chats and messages in one transaction
+    fn insert(&self, workspace: &ExportedWorkspace) -> Result<(), AppError>;
+}
+
+pub struct SqliteImportRepository {
+    app: Arc<AppHandle>,
+}
+
+impl SqliteImportRepository {
+    pub const fn new(app: Arc<AppHandle>) -> Self {
+        Self { app }
+    }
+}
+
+impl ImportRepository for SqliteImportRepository {
+    fn existing_chat_ids(&self, chat_ids: &[String]) -> Result<Vec<String>, AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        let mut stmt = conn.prepare("SELECT 1 FROM chats WHERE id = ?1")?;
+
+        let mut existing = Vec::new();
+        for chat_id in chat_ids {
+            if stmt.exists(params![chat_id])? {
+                existing.push(chat_id.clone());
+            }
+        }
+
+        Ok(existing)
+    }
+
+    fn insert(&self, workspace: &ExportedWorkspace) -> Result<(), AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        let tx = conn.unchecked_transaction()?;
+
+        tx.execute(
+            "INSERT INTO workspaces (id, name, created_at) VALUES (?1, ?2, ?3)",
+            params![workspace.id, workspace.name, workspace.created_at],
+        )?;
+
+        {
+            let mut chat_stmt = tx.prepare(
+                "INSERT INTO chats (id, workspace_id, title, last_message, created_at, updated_at, agent_id, parent_id, active_leaf_message_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            let mut message_stmt = tx.prepare(
+                "INSERT INTO messages (id, chat_id, role, content, reasoning, timestamp, assistant_message_id, tool_call_id, metadata, parent_message_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            )?;
+
+            for chat in &workspace.chats {
+                chat_stmt.execute(params![
+                    chat.id,
+                    workspace.id,
+                    chat.title,
+                    chat.last_message,
+                    chat.created_at,
+                    chat.updated_at,
+                    chat.agent_id,
+                    chat.parent_id,
+                    chat.active_leaf_message_id,
+                ])?;
+
+                for message in &chat.messages {
+                    message_stmt.execute(params![
+                        message.id,
+                        chat.id,
+                        message.role,
+                        message.content,
+                        message.reasoning,
+                        message.timestamp,
+                        message.assistant_message_id,
+                        message.tool_call_id,
+                        message.metadata,
+                        message.parent_message_id,
+                    ])?;
+                }
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use super::archive::ImportArchive;
+use super::builder::{is_branched, AttachmentData, ParsedChat};
+use super::models::{ImportReport, ImportSource};
+use super::repository::ImportRepository;
+use super::{chatgpt, claude};
+use crate::error::AppError;
+use crate::features::export::models::ExportedWorkspace;
+use crate::features::workspace::settings::WorkspaceSettingsService;
+use serde_json::Value;
+use std::path::Path;
+use std::sync::Arc;
+
+pub struct ImportService {
+    repository: Arc<dyn ImportRepository>,
+    workspace_settings_service: Arc<WorkspaceSettingsService>,
+}
+
+impl ImportService {
+    pub fn new(
+        repository: Arc<dyn ImportRepository>,
+        workspace_settings_service: Arc<WorkspaceSettingsService>,
+    ) -> Self {
+        Self {
+            repository,
+            workspace_settings_service,
+        }
+    }
+
+    /// Tell the exports apart by the shape of their conversations
+    fn detect_source(conversations: &[Value]) -> Result<ImportSource, AppError> {
+        let first = conversations.first().ok_or_else(|| {
+            AppError::Validation("The export contains no conversations".to_string())
+        })?;
+
+        if first.get("mapping").is_some() {
+            Ok(ImportSource::ChatGpt)
+        } else if first.get("chat_messages").is_some() {
+            Ok(ImportSource::Claude)
+        } else {
+            Err(AppError::Validation(
+                "Unrecognized export, expected a ChatGPT or Claude data export".to_string(),
+            ))
+        }
+    }
+
+    /// Import a ChatGPT or Claude data export into a new workspace
+    ///
+    /// Conversations imported before are skipped. With `dry_run` nothing is written,
+    /// the report tells what would be imported and what would be left out.
+    pub fn import(
+        &self,
+        path: &Path,
+        dry_run: bool,
+        workspace_name: Option<String>,
+        files_dir: &Path,
+    ) -> Result<ImportReport, AppError> {
+        let mut archive = ImportArchive::open(path)?;
+        let conversations: Vec<Value> = serde_json::from_slice(&archive.read_conversations()?)
+            .map_err(|e| AppError::Validation(format!("Invalid conversations.json: {e}")))?;
+
+        let source = Self::detect_source(&conversations)?;
+        let mut report = ImportReport::new(source, dry_run);
+        report.conversations = conversations.len();
+
+        let workspace_id = uuid::Uuid::new_v4().to_string();
+        let mut chats = match source {
+            ImportSource::ChatGpt => chatgpt::parse(
+                conversations,
+                &workspace_id,
+                &archive,
+                files_dir,
+                &mut report,
+            ),
+            ImportSource::Claude => {
+                claude::parse(conversations, &workspace_id, files_dir, &mut report)
+            }
+        };
+
+        let chat_ids: Vec<String> = chats.iter().map(|c| c.chat.id.clone()).collect();
+        let existing = self.repository.existing_chat_ids(&chat_ids)?;
+        chats.retain(|parsed| {
+            let reason = if existing.contains(&parsed.chat.id) {
+                "Already imported"
+            } else if parsed.chat.messages.is_empty() {
+                "No messages to import"
+            } else {
+                return true;
+            };
+            report.skip(Some(&parsed.chat.title), "conversation", reason);
+            false
+        });
+
+        report.chats = chats.len();
+        report.messages = chats.iter().map(|c| c.chat.messages.len()).sum();
+        report.attachments = chats.iter().map(|c| c.attachments.len()).sum();
+        report.branched_chats = chats.iter().filter(|c| is_branched(&c.chat)).count();
+
+        if dry_run || chats.is_empty() {
+            return Ok(report);
+        }
+
+        Self::copy_attachments(&mut archive, &chats, &mut report)?;
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let workspace = ExportedWorkspace {
+            id: workspace_id.clone(),
+            name: workspace_name
+                .filter(|name| !name.trim().is_empty())
+                .unwrap_or_else(|| format!("{} import", source.display_name())),
+            created_at: now,
+            chats: chats.into_iter().map(|parsed| parsed.chat).collect(),
+        };
+        self.repository.insert(&workspace)?;
+        self.workspace_settings_service.save(
+            workspace_id.clone(),
+            None,
+            None,
+            None,
+            Some(true),
+            None,
+            None,
+            Some(10),
+            None,
+            None,
+            None,
+        )?;
+        report.workspace_id = Some(workspace_id);
+
+        crate::lib::sentry_helpers::add_breadcrumb(
+            "import",
+            format!(
+                "Imported {} chats ({} messages) from {}",
+                report.chats,
+                report.messages,
+                source.display_name()
+            ),
+            sentry::Level::Info,
+        );
+
+        Ok(report)
+    }
+
+    /// Copy attachments into the files directory, a file that cannot be read is reported
+    fn copy_attachments(
+        archive: &mut ImportArchive,
+        chats: &[ParsedChat],
+        report: &mut ImportReport,
+    ) -> Result<(), AppError> {
+        for parsed in chats {
+            for attachment in &parsed.attachments {
+                if let Some(dir) = attachment.destination.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                let bytes = match &attachment.data {
+                    AttachmentData::Archive(entry) => match archive.read(entry) {
+                        Ok(bytes) => bytes,
+                        Err(e) => {
+                            report.attachments -= 1;
+                            report.skip(
+                                Some(&parsed.chat.title),
+                                "attachment",
+                                format!("Could not read {entry}: {e}"),
+                            );
+                            continue;
+                        }
+                    },
+                    AttachmentData::Inline(bytes) => bytes.clone(),
+                };
+                std::fs::write(&attachment.destination, bytes)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,237 @@
+use super::archive::ImportArchive;
+use super::builder::{is_branched, ParsedChat};
+use super::models::{ImportReport, ImportSource};
+use super::{chatgpt, claude};
+use crate::features::message::Message;
+use serde_json::Value;
+
+const CHATGPT_EXPORT: &str = include_str!("fixtures/chatgpt.json");
+const CLAUDE_EXPORT: &str = include_str!("fixtures/claude.json");
+
+fn conversations(export: &str) -> Vec<Value> {
+    serde_json::from_str(export).unwrap()
+}
+
+fn parse_chatgpt(report: &mut ImportReport) -> Vec<ParsedChat> {
+    let export = tempfile::tempdir().unwrap();
+    std::fs::write(export.path().join("conversations.json"), CHATGPT_EXPORT).unwrap();
+    let archive = ImportArchive::open(export.path()).unwrap();
+    chatgpt::parse(
+        conversations(CHATGPT_EXPORT),
+        "workspace",
+        &archive,
+        export.path(),
+        report,
+    )
+}
+
+fn parse_claude(report: &mut ImportReport) -> Vec<ParsedChat> {
+    let files = tempfile::tempdir().unwrap();
+    claude::parse(
+        conversations(CLAUDE_EXPORT),
+        "workspace",
+        files.path(),
+        report,
+    )
+}
+
+fn import_id(message: &Message) -> Option<String> {
+    let metadata: Value = serde_json::from_str(message.metadata.as_deref()?).ok()?;
+    metadata["import"]["id"].as_str().map(ToString::to_string)
+}
+
+/// Message imported from a source message
+fn by_source<'a>(chat: &'a ParsedChat, source_id: &str) -> &'a Message {
+    chat.chat
+        .messages
+        .iter()
+        .find(|m| import_id(m).as_deref() == Some(source_id))
+        .unwrap()
+}
+
+fn parent<'a>(chat: &'a ParsedChat, message: &Message) -> Option<&'a Message> {
+    let parent_id = message.parent_message_id.as_deref()?;
+    chat.chat.messages.iter().find(|m| m.id == parent_id)
+}
+
+#[test]
+fn linearises_the_chatgpt_mapping_tree() {
+    let mut report = ImportReport::new(ImportSource::ChatGpt, true);
+    let chats = parse_chatgpt(&mut report);
+    let chat = &chats[0];
+
+    assert_eq!(chat.chat.id, "chatgpt-conv-1");
+    assert_eq!(
+        chat.chat
+            .messages
+            .iter()
+            .map(|m| m.role.as_str())
+            .collect::<Vec<_>>(),
+        vec!["user", "assistant", "tool_call", "assistant"]
+    );
+    // Parents always come before their children
+    for (index, message) in chat.chat.messages.iter().enumerate() {
+        if let Some(parent_id) = &message.parent_message_id {
+            let parent_index = chat.chat.messages.iter().position(|m| &m.id == parent_id);
+            assert!(parent_index.is_some_and(|parent_index| parent_index < index));
+        }
+    }
+
+    // Skipped nodes (root, hidden system prompt, thoughts) hand their place to their parent
+    let question = by_source(chat, "u1");
+    let answer = by_source(chat, "a1");
+    let retry = by_source(chat, "a1-retry");
+    assert_eq!(question.parent_message_id, None);
+    assert_eq!(question.content, "Why does this not compile?");
+    assert_eq!(
+        parent(chat, answer).map(|m| m.id.as_str()),
+        Some(question.id.as_str())
+    );
+    assert_eq!(
+        parent(chat, retry).map(|m| m.id.as_str()),
+        Some(question.id.as_str())
+    );
+    assert_eq!(
+        answer.reasoning.as_deref(),
+        Some("Lifetimes\nThe reference outlives the value")
+    );
+    assert!(is_branched(&chat.chat));
+
+    // The tool call belongs to the reply that made it and holds the tool output
+    let call = &chat.chat.messages[2];
+    assert_eq!(
+        call.assistant_message_id.as_deref(),
+        Some(answer.id.as_str())
+    );
+    let call: Value = serde_json::from_str(&call.content).unwrap();
+    assert_eq!(call["name"], "python");
+    assert_eq!(call["arguments"], "print(1)");
+    assert_eq!(call["result"], "1");
+
+    // `current_node` picks the active branch
+    assert_eq!(
+        chat.chat.active_leaf_message_id.as_deref(),
+        Some(retry.id.as_str())
+    );
+    assert_eq!(chat.chat.last_message.as_deref(), Some("Second try"));
+}
+
+#[test]
+fn truncates_chatgpt_timestamps_to_seconds() {
+    let mut report = ImportReport::new(ImportSource::ChatGpt, true);
+    let chats = parse_chatgpt(&mut report);
+    let chat = &chats[0];
+
+    assert_eq!(chat.chat.created_at, 1_700_000_000);
+    assert_eq!(chat.chat.updated_at, 1_700_000_100);
+    assert_eq!(by_source(chat, "u1").timestamp, 1_700_000_010);
+    assert_eq!(by_source(chat, "a1-retry").timestamp, 1_700_000_030);
+    assert_eq!(chat.chat.messages[2].timestamp, 1_700_000_020);
+    // Without its own time a message takes its parent's
+    assert_eq!(by_source(chat, "a1").timestamp, 1_700_000_010);
+}
+
+#[test]
+fn tolerates_missing_and_null_chatgpt_fields() {
+    let mut report = ImportReport::new(ImportSource::ChatGpt, true);
+    let chats = parse_chatgpt(&mut report);
+
+    assert_eq!(chats.len(), 2);
+    let empty = &chats[1].chat;
+    assert_eq!(empty.id, "chatgpt-conv-2");
+    assert_eq!(empty.title, "Untitled");
+    assert_eq!((empty.created_at, empty.updated_at), (0, 0));
+    assert!(empty.messages.is_empty());
+    assert_eq!(empty.active_leaf_message_id, None);
+
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].conversation.as_deref(), Some("Broken"));
+    assert_eq!(report.skipped[0].kind, "conversation");
+}
+
+#[test]
+fn links_claude_messages_by_parent() {
+    let mut report = ImportReport::new(ImportSource::Claude, true);
+    let chats = parse_claude(&mut report);
+    let chat = &chats[0];
+
+    assert_eq!(chat.chat.id, "claude-trip");
+    assert_eq!(chat.chat.created_at, 1_714_557_600);
+    assert_eq!(chat.chat.updated_at, 1_714_557_900);
+
+    let question = by_source(chat, "m1");
+    let answer = by_source(chat, "m2");
+    let edit = by_source(chat, "m3");
+    assert_eq!(question.parent_message_id, None);
+    assert_eq!(edit.parent_message_id, None);
+    assert_eq!(
+        parent(chat, answer).map(|m| m.id.as_str()),
+        Some(question.id.as_str())
+    );
+    assert_eq!(answer.content, "Go in spring");
+    assert_eq!(answer.reasoning.as_deref(), Some("Consider the season"));
+    assert_eq!(answer.timestamp, 1_714_557_605);
+    assert!(is_branched(&chat.chat));
+    assert_eq!(
+        chat.chat.active_leaf_message_id.as_deref(),
+        Some(edit.id.as_str())
+    );
+
+    // The tool result from the next message lands on the call
+    let call = chat
+        .chat
+        .messages
+        .iter()
+        .find(|m| m.role == "tool_call")
+        .unwrap();
+    assert_eq!(
+        call.assistant_message_id.as_deref(),
+        Some(answer.id.as_str())
+    );
+    let call: Value = serde_json::from_str(&call.content).unwrap();
+    assert_eq!(call["name"], "web_search");
+    assert_eq!(call["result"], "Late March");
+    assert_eq!(chat.chat.messages.len(), 4);
+
+    // Extracted document text is kept, files without content are reported
+    assert_eq!(chat.attachments.len(), 1);
+    let metadata: Value = serde_json::from_str(question.metadata.as_deref().unwrap()).unwrap();
+    assert_eq!(metadata["files"].as_array().map(Vec::len), Some(1));
+    assert!(report
+        .skipped
+        .iter()
+        .any(|item| item.kind == "attachment"
+            && item.conversation.as_deref() == Some("Trip planning")));
+}
+
+#[test]
+fn tolerates_missing_and_null_claude_fields() {
+    let mut report = ImportReport::new(ImportSource::Claude, true);
+    let chats = parse_claude(&mut report);
+
+    assert_eq!(chats.len(), 2);
+    let linear = &chats[1];
+    assert_eq!(linear.chat.title, "Untitled");
+    assert_eq!(linear.chat.created_at, 0);
+    // Without parent links the messages form one thread in export order
+    let hi = by_source(linear, "a");
+    let hello = by_source(linear, "b");
+    assert_eq!(hi.parent_message_id, None);
+    assert_eq!(
+        parent(linear, hello).map(|m| m.id.as_str()),
+        Some(hi.id.as_str())
+    );
+    assert_eq!((hi.timestamp, hello.timestamp), (0, 0));
+    assert_eq!(
+        linear.chat.active_leaf_message_id.as_deref(),
+        Some(hello.id.as_str())
+    );
+
+    assert!(
+        report
+            .skipped
+            .iter()
+            .any(|item| item.kind == "conversation"
+                && item.conversation.as_deref() == Some("No uuid"))
+    );
+}
@@ -13,6 +13,7 @@ pub fn create_llm_connection(
     api_key: String,
     models_json: Option<String>,
     default_model: Option<String>,
+    api_mode: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<LLMConnection, AppError> {
     state
@@ -25,6 +26,7 @@ pub fn create_llm_connection(
             api_key,
             models_json,
             default_model,
+            api_mode,
         )
         .map_err(|e| AppError::Generic(e.to_string()))
 }
@@ -47,6 +49,7 @@ pub fn update_llm_connection(
     api_key: Option<String>,
     models_json: Option<String>,
     default_model: Option<String>,
+    api_mode: Option<String>,
     enabled: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<(), AppError> {
@@ -60,6 +63,7 @@ pub fn update_llm_connection(
             api_key,
             models_json,
             default_model,
+            api_mode,
             enabled,
         )
         .map_err(|e| AppError::Generic(e.to_string()))
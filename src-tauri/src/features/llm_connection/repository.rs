@@ -1,5 +1,6 @@
 use super::models::LLMConnection;
 use crate::error::AppError;
+use crate::features::secrets::SecretsService;
 use rusqlite::params;
 use std::sync::Arc;
 use tauri::AppHandle;
@@ -18,6 +19,7 @@ pub trait LLMConnectionRepository: Send + Sync {
         api_key: Option<&str>,
         models_json: Option<&str>,
         default_model: Option<&str>,
+        api_mode: Option<&str>,
         enabled: Option<bool>,
     ) -> Result<(), AppError>;
     fn delete(&self, id: &str) -> Result<(), AppError>;
@@ -25,20 +27,28 @@ pub trait LLMConnectionRepository: Send + Sync {
 
 pub struct SqliteLLMConnectionRepository {
     app: Arc<AppHandle>,
+    secrets: Arc<SecretsService>,
 }
 
 impl SqliteLLMConnectionRepository {
-    pub const fn new(app: Arc<AppHandle>) -> Self {
-        Self { app }
+    pub const fn new(app: Arc<AppHandle>, secrets: Arc<SecretsService>) -> Self {
+        Self { app, secrets }
+    }
+
+    // api_key is encrypted at rest, and stays encrypted while the vault is locked
+    fn decrypt(&self, mut connection: LLMConnection) -> LLMConnection {
+        connection.api_key = self.secrets.reveal(&connection.api_key);
+        connection
     }
 }
 
 impl LLMConnectionRepository for SqliteLLMConnectionRepository {
     fn create(&self, connection: &LLMConnection) -> Result<(), AppError> {
+        let api_key = self.secrets.encrypt(&connection.api_key)?;
         let conn = crate::db::get_connection(&self.app)?;
         conn.execute(
-            "INSERT INTO llm_connections (id, name, base_url, provider, api_key, models_json, default_model, enabled, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            params![connection.id, connection.name, connection.base_url, connection.provider, connection.api_key, connection.models_json, connection.default_model, connection.enabled, connection.created_at, connection.updated_at],
+            "INSERT INTO llm_connections (id, name, base_url, provider, api_key, models_json, default_model, enabled, created_at, updated_at, api_mode) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![connection.id, connection.name, connection.base_url, connection.provider, api_key, connection.models_json, connection.default_model, connection.enabled, connection.created_at, connection.updated_at, connection.api_mode],
         )?;
         Ok(())
     }
@@ -46,7 +56,7 @@ impl LLMConnectionRepository for SqliteLLMConnectionRepository {
     fn get_all(&self) -> Result<Vec<LLMConnection>, AppError> {
         let conn = crate::db::get_connection(&self.app)?;
         let mut stmt = conn.prepare(
-            "SELECT id, name, base_url, provider, api_key, models_json, default_model, enabled, created_at, updated_at FROM llm_connections ORDER BY created_at DESC"
+            "SELECT id, name, base_url, provider, api_key, models_json, default_model, enabled, created_at, updated_at, api_mode FROM llm_connections ORDER BY created_at DESC"
         )?;
 
         let connections = stmt
@@ -59,6 +69,7 @@ impl LLMConnectionRepository for SqliteLLMConnectionRepository {
                     api_key: row.get(4)?,
                     models_json: row.get(5)?,
                     default_model: row.get(6)?,
+                    api_mode: row.get(10)?,
                     enabled: row.get::<_, i64>(7)? != 0, // Convert INTEGER to bool
                     created_at: row.get(8)?,
                     updated_at: row.get(9)?,
@@ -66,13 +77,16 @@ impl LLMConnectionRepository for SqliteLLMConnectionRepository {
             })?
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(connections)
+        Ok(connections
+            .into_iter()
+            .map(|connection| self.decrypt(connection))
+            .collect())
     }
 
     fn get_by_id(&self, id: &str) -> Result<Option<LLMConnection>, AppError> {
         let conn = crate::db::get_connection(&self.app)?;
         let result = conn.query_row(
-            "SELECT id, name, base_url, provider, api_key, models_json, default_model, enabled, created_at, updated_at FROM llm_connections WHERE id = ?1",
+            "SELECT id, name, base_url, provider, api_key, models_json, default_model, enabled, created_at, updated_at, api_mode FROM llm_connections WHERE id = ?1",
             params![id],
             |row| {
                 Ok(LLMConnection {
@@ -83,6 +97,7 @@ impl LLMConnectionRepository for SqliteLLMConnectionRepository {
                     api_key: row.get(4)?,
                     models_json: row.get(5)?,
                     default_model: row.get(6)?,
+                    api_mode: row.get(10)?,
                     enabled: row.get::<_, i64>(7)? != 0, // Convert INTEGER to bool
                     created_at: row.get(8)?,
                     updated_at: row.get(9)?,
@@ -91,7 +106,7 @@ impl LLMConnectionRepository for SqliteLLMConnectionRepository {
         );
 
         match result {
-            Ok(connection) => Ok(Some(connection)),
+            Ok(connection) => Ok(Some(self.decrypt(connection))),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
             Err(e) => Err(e.into()),
         }
@@ -106,6 +121,7 @@ impl LLMConnectionRepository for SqliteLLMConnectionRepository {
         api_key: Option<&str>,
         models_json: Option<&str>,
         default_model: Option<&str>,
+        api_mode: Option<&str>,
         enabled: Option<bool>,
     ) -> Result<(), AppError> {
         let conn = crate::db::get_connection(&self.app)?;
@@ -136,6 +152,7 @@ impl LLMConnectionRepository for SqliteLLMConnectionRepository {
         }
 
         if let Some(api_key) = api_key {
+            let api_key = self.secrets.encrypt(api_key)?;
             conn.execute(
                 "UPDATE llm_connections SET api_key = ?1, updated_at = ?2 WHERE id = ?3",
                 params![api_key, now, id],
@@ -163,6 +180,20 @@ impl LLMConnectionRepository for SqliteLLMConnectionRepository {
             }
         }
 
+        if let Some(api_mode) = api_mode {
+            if api_mode.is_empty() {
+                conn.execute(
+                    "UPDATE llm_connections SET api_mode = NULL, updated_at = ?1 WHERE id = ?2",
+                    params![now, id],
+                )?;
+            } else {
+                conn.execute(
+                    "UPDATE llm_connections SET api_mode = ?1, updated_at = ?2 WHERE id = ?3",
+                    params![api_mode, now, id],
+                )?;
+            }
+        }
+
         if let Some(enabled) = enabled {
             conn.execute(
                 "UPDATE llm_connections SET enabled = ?1, updated_at = ?2 WHERE id = ?3",
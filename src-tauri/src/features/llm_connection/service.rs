@@ -22,6 +22,7 @@ impl LLMConnectionService {
         api_key: String,
         models_json: Option<String>,
         default_model: Option<String>,
+        api_mode: Option<String>,
     ) -> Result<LLMConnection, AppError> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -36,6 +37,7 @@ impl LLMConnectionService {
             api_key,
             models_json,
             default_model,
+            api_mode,
             enabled: true, // New connections are enabled by default
             created_at: now,
             updated_at: now,
@@ -63,6 +65,7 @@ impl LLMConnectionService {
         api_key: Option<String>,
         models_json: Option<String>,
         default_model: Option<String>,
+        api_mode: Option<String>,
         enabled: Option<bool>,
     ) -> Result<(), AppError> {
         self.repository.update(
@@ -73,6 +76,7 @@ impl LLMConnectionService {
             api_key.as_deref(),
             models_json.as_deref(),
             default_model.as_deref(),
+            api_mode.as_deref(),
             enabled,
         )
     }
@@ -55,6 +55,12 @@ pub fn update_mcp_server_status(
     error_message: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<(), AppError> {
+    // Pooled clients are only kept for connected servers
+    if status != "connected" {
+        state.mcp_client_pool.evict(&id);
+        state.chat_service.forget_connection_resources(&id);
+    }
+
     state
         .mcp_connection_service
         .update_status(id, status, tools_json, error_message)
@@ -66,6 +72,9 @@ pub fn delete_mcp_server_connection(
     id: String,
     state: State<'_, AppState>,
 ) -> Result<(), AppError> {
+    state.mcp_client_pool.evict(&id);
+    state.chat_service.forget_connection_resources(&id);
+
     state
         .mcp_connection_service
         .delete(id)
@@ -1,5 +1,6 @@
 pub mod commands;
 pub mod models;
+pub mod oauth;
 pub mod repository;
 pub mod service;
 
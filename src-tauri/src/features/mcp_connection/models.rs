@@ -11,6 +11,8 @@ pub struct MCPServerConnection {
     pub runtime_path: Option<String>, // Path to the specific runtime (optional)
     pub status: String,  // "disconnected" | "connecting" | "connected"
     pub tools_json: Option<String>, // JSON string of tools array
+    pub resources_json: Option<String>, // JSON string of resources and resource templates
+    pub prompts_json: Option<String>, // JSON string of prompts array
     pub error_message: Option<String>, // Error message if connection failed
     pub created_at: i64,
     pub updated_at: i64,
@@ -0,0 +1,41 @@
+use super::models::MCPOAuthStatus;
+use crate::error::AppError;
+use crate::state::AppState;
+use tauri::State;
+
+#[tauri::command]
+pub async fn authorize_mcp_connection(
+    app: tauri::AppHandle,
+    connection_id: String,
+    state: State<'_, AppState>,
+) -> Result<MCPOAuthStatus, AppError> {
+    let connection = state
+        .mcp_connection_service
+        .get_by_id(&connection_id)?
+        .ok_or_else(|| AppError::NotFound(format!("MCP connection not found: {connection_id}")))?;
+
+    let status = state.mcp_oauth_service.authorize(&app, &connection).await?;
+
+    // Reconnect pooled clients with the new token
+    state.mcp_client_pool.evict(&connection_id);
+
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn get_mcp_oauth_status(
+    connection_id: String,
+    state: State<'_, AppState>,
+) -> Result<MCPOAuthStatus, AppError> {
+    state.mcp_oauth_service.get_status(&connection_id)
+}
+
+#[tauri::command]
+pub fn revoke_mcp_authorization(
+    connection_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    state.mcp_oauth_service.revoke(&connection_id)?;
+    state.mcp_client_pool.evict(&connection_id);
+    Ok(())
+}
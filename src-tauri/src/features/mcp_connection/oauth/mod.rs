@@ -0,0 +1,7 @@
+pub mod commands;
+pub mod models;
+pub mod repository;
+pub mod service;
+
+pub use repository::*;
+pub use service::*;
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+/// OAuth tokens and client registration for a remote MCP connection
+#[derive(Debug, Clone)]
+pub struct MCPOAuthToken {
+    pub connection_id: String,
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub token_type: String,
+    pub expires_at: Option<i64>, // Unix seconds
+    pub scope: Option<String>,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub token_endpoint: String,
+    pub resource: String, // Canonical MCP server URI the token is bound to
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Authorization state of a connection as shown in the UI (never exposes tokens)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MCPOAuthStatus {
+    pub connection_id: String,
+    pub authorized: bool,
+    pub expires_at: Option<i64>,
+    pub scope: Option<String>,
+}
+
+/// RFC 9728 protected resource metadata
+#[derive(Debug, Deserialize, Clone)]
+pub struct ProtectedResourceMetadata {
+    pub resource: Option<String>,
+    #[serde(default)]
+    pub authorization_servers: Vec<String>,
+    #[serde(default)]
+    pub scopes_supported: Vec<String>,
+}
+
+/// RFC 8414 authorization server metadata
+#[derive(Debug, Deserialize, Clone)]
+pub struct AuthorizationServerMetadata {
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub registration_endpoint: Option<String>,
+    #[serde(default)]
+    pub code_challenge_methods_supported: Vec<String>,
+}
+
+/// RFC 7591 dynamic client registration response
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClientRegistration {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+}
+
+/// OAuth token endpoint response
+#[derive(Debug, Deserialize, Clone)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: Option<String>,
+    pub expires_in: Option<i64>,
+    pub refresh_token: Option<String>,
+    pub scope: Option<String>,
+}
@@ -0,0 +1,97 @@
+use super::models::MCPOAuthToken;
+use crate::error::AppError;
+use crate::features::secrets::SecretsService;
+use rusqlite::params;
+use std::sync::Arc;
+use tauri::AppHandle;
+
+pub trait MCPOAuthRepository: Send + Sync {
+    fn save(&self, token: &MCPOAuthToken) -> Result<(), AppError>;
+    fn get_by_connection_id(&self, connection_id: &str) -> Result<Option<MCPOAuthToken>, AppError>;
+    fn delete(&self, connection_id: &str) -> Result<(), AppError>;
+}
+
+pub struct SqliteMCPOAuthRepository {
+    app: Arc<AppHandle>,
+    secrets: Arc<SecretsService>,
+}
+
+impl SqliteMCPOAuthRepository {
+    pub const fn new(app: Arc<AppHandle>, secrets: Arc<SecretsService>) -> Self {
+        Self { app, secrets }
+    }
+
+    // Tokens and the client secret are encrypted at rest, and stay encrypted while the vault is locked
+    fn decrypt(&self, mut token: MCPOAuthToken) -> MCPOAuthToken {
+        token.access_token = self.secrets.reveal(&token.access_token);
+        token.refresh_token = token
+            .refresh_token
+            .map(|refresh_token| self.secrets.reveal(&refresh_token));
+        token.client_secret = token
+            .client_secret
+            .map(|client_secret| self.secrets.reveal(&client_secret));
+        token
+    }
+}
+
+impl MCPOAuthRepository for SqliteMCPOAuthRepository {
+    fn save(&self, token: &MCPOAuthToken) -> Result<(), AppError> {
+        let access_token = self.secrets.encrypt(&token.access_token)?;
+        let refresh_token = token
+            .refresh_token
+            .as_deref()
+            .map(|refresh_token| self.secrets.encrypt(refresh_token))
+            .transpose()?;
+        let client_secret = token
+            .client_secret
+            .as_deref()
+            .map(|client_secret| self.secrets.encrypt(client_secret))
+            .transpose()?;
+        let conn = crate::db::get_connection(&self.app)?;
+        conn.execute(
+            "INSERT INTO mcp_oauth_tokens (connection_id, access_token, refresh_token, token_type, expires_at, scope, client_id, client_secret, token_endpoint, resource, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+             ON CONFLICT(connection_id) DO UPDATE SET access_token = excluded.access_token, refresh_token = excluded.refresh_token, token_type = excluded.token_type, expires_at = excluded.expires_at, scope = excluded.scope, client_id = excluded.client_id, client_secret = excluded.client_secret, token_endpoint = excluded.token_endpoint, resource = excluded.resource, updated_at = excluded.updated_at",
+            params![token.connection_id, access_token, refresh_token, token.token_type, token.expires_at, token.scope, token.client_id, client_secret, token.token_endpoint, token.resource, token.created_at, token.updated_at],
+        )?;
+        Ok(())
+    }
+
+    fn get_by_connection_id(&self, connection_id: &str) -> Result<Option<MCPOAuthToken>, AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        let result = conn.query_row(
+            "SELECT connection_id, access_token, refresh_token, token_type, expires_at, scope, client_id, client_secret, token_endpoint, resource, created_at, updated_at FROM mcp_oauth_tokens WHERE connection_id = ?1",
+            params![connection_id],
+            |row| {
+                Ok(MCPOAuthToken {
+                    connection_id: row.get(0)?,
+                    access_token: row.get(1)?,
+                    refresh_token: row.get(2)?,
+                    token_type: row.get(3)?,
+                    expires_at: row.get(4)?,
+                    scope: row.get(5)?,
+                    client_id: row.get(6)?,
+                    client_secret: row.get(7)?,
+                    token_endpoint: row.get(8)?,
+                    resource: row.get(9)?,
+                    created_at: row.get(10)?,
+                    updated_at: row.get(11)?,
+                })
+            },
+        );
+
+        match result {
+            Ok(token) => Ok(Some(self.decrypt(token))),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn delete(&self, connection_id: &str) -> Result<(), AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        conn.execute(
+            "DELETE FROM mcp_oauth_tokens WHERE connection_id = ?1",
+            params![connection_id],
+        )?;
+        Ok(())
+    }
+}
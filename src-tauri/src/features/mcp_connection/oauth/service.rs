@@ -0,0 +1,575 @@
+use super::models::{
+    AuthorizationServerMetadata, ClientRegistration, MCPOAuthStatus, MCPOAuthToken,
+    ProtectedResourceMetadata, TokenResponse,
+};
+use super::repository::MCPOAuthRepository;
+use crate::error::AppError;
+use crate::features::mcp_connection::MCPServerConnection;
+use crate::features::secrets::ensure_unlocked;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use reqwest::{Client, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+
+// How long the user has to finish signing in in the browser
+const CALLBACK_TIMEOUT_SECS: u64 = 300;
+// Tokens expiring within this window are refreshed before use
+const REFRESH_MARGIN_SECS: i64 = 60;
+
+const CALLBACK_PAGE: &str = "<!DOCTYPE html><html><body style=\"font-family: sans-serif\"><h3>Authorization complete</h3><p>You can close this window and return to nexo.</p></body></html>";
+const CALLBACK_ERROR_PAGE: &str = "<!DOCTYPE html><html><body style=\"font-family: sans-serif\"><h3>Authorization failed</h3><p>You can close this window and return to nexo to try again.</p></body></html>";
+
+/// OAuth 2.1 authorization for remote MCP servers (MCP authorization spec)
+///
+/// Discovers the authorization server from the server's protected resource
+/// metadata, registers nexo as a public client, runs the authorization code
+/// flow with PKCE on a loopback redirect and refreshes tokens before they expire.
+pub struct MCPOAuthService {
+    repository: Arc<dyn MCPOAuthRepository>,
+    client: Client,
+    // Serializes refreshes so clients starting together don't race on rotating refresh tokens
+    refresh_lock: Mutex<()>,
+}
+
+impl MCPOAuthService {
+    pub fn new(repository: Arc<dyn MCPOAuthRepository>) -> Self {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            repository,
+            client,
+            refresh_lock: Mutex::new(()),
+        }
+    }
+
+    fn now() -> i64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64
+    }
+
+    pub fn get_status(&self, connection_id: &str) -> Result<MCPOAuthStatus, AppError> {
+        let token = self.repository.get_by_connection_id(connection_id)?;
+
+        Ok(MCPOAuthStatus {
+            connection_id: connection_id.to_string(),
+            authorized: token.is_some(),
+            expires_at: token.as_ref().and_then(|t| t.expires_at),
+            scope: token.and_then(|t| t.scope),
+        })
+    }
+
+    /// Forget the tokens stored for a connection
+    pub fn revoke(&self, connection_id: &str) -> Result<(), AppError> {
+        self.repository.delete(connection_id)
+    }
+
+    /// Get a valid access token for a connection, refreshing it if it is about to expire
+    /// Returns `None` for connections that were never authorized
+    pub async fn get_access_token(&self, connection_id: &str) -> Result<Option<String>, AppError> {
+        let Some(token) = self.repository.get_by_connection_id(connection_id)? else {
+            return Ok(None);
+        };
+        Self::ensure_unlocked(&token)?;
+        if !Self::is_expiring(&token) {
+            return Ok(Some(token.access_token));
+        }
+
+        let _guard = self.refresh_lock.lock().await;
+
+        // Another client may have refreshed the token while we waited
+        let Some(token) = self.repository.get_by_connection_id(connection_id)? else {
+            return Ok(None);
+        };
+        Self::ensure_unlocked(&token)?;
+        if !Self::is_expiring(&token) {
+            return Ok(Some(token.access_token));
+        }
+
+        self.refresh(connection_id, token).await.map(Some)
+    }
+
+    /// Refresh an access token the server rejected before it was due to expire
+    /// Returns `None` for connections that were never authorized
+    pub async fn refresh_rejected_token(
+        &self,
+        connection_id: &str,
+        rejected_token: &str,
+    ) -> Result<Option<String>, AppError> {
+        let _guard = self.refresh_lock.lock().await;
+
+        let Some(token) = self.repository.get_by_connection_id(connection_id)? else {
+            return Ok(None);
+        };
+        Self::ensure_unlocked(&token)?;
+        // Another client already replaced the rejected token
+        if token.access_token != rejected_token {
+            return Ok(Some(token.access_token));
+        }
+
+        self.refresh(connection_id, token).await.map(Some)
+    }
+
+    /// Exchange the refresh token for a new access token, callers hold `refresh_lock`
+    async fn refresh(&self, connection_id: &str, token: MCPOAuthToken) -> Result<String, AppError> {
+        let Some(refresh_token) = token.refresh_token.clone() else {
+            return Err(AppError::Mcp(format!(
+                "Authorization for MCP connection {connection_id} expired, please sign in again"
+            )));
+        };
+
+        crate::lib::sentry_helpers::add_breadcrumb(
+            "mcp.oauth",
+            format!("Refreshing access token for connection {connection_id}"),
+            sentry::Level::Info,
+        );
+
+        let mut form = vec![
+            ("grant_type", "refresh_token".to_string()),
+            ("refresh_token", refresh_token),
+            ("client_id", token.client_id.clone()),
+            ("resource", token.resource.clone()),
+        ];
+        if let Some(client_secret) = &token.client_secret {
+            form.push(("client_secret", client_secret.clone()));
+        }
+
+        let response = self
+            .request_token(&token.token_endpoint, &form)
+            .await
+            .map_err(|e| {
+                AppError::Mcp(format!(
+                    "Failed to refresh authorization for MCP connection {connection_id}, please sign in again: {e}"
+                ))
+            })?;
+
+        let refreshed = MCPOAuthToken {
+            access_token: response.access_token,
+            // Servers that don't rotate refresh tokens omit them from the response
+            refresh_token: response.refresh_token.or(token.refresh_token),
+            token_type: response.token_type.unwrap_or(token.token_type),
+            expires_at: response.expires_in.map(|secs| Self::now() + secs),
+            scope: response.scope.or(token.scope),
+            updated_at: Self::now(),
+            ..token
+        };
+        self.repository.save(&refreshed)?;
+
+        Ok(refreshed.access_token)
+    }
+
+    // Tokens read while the secrets vault is locked are still encrypted
+    fn ensure_unlocked(token: &MCPOAuthToken) -> Result<(), AppError> {
+        ensure_unlocked(&token.access_token)?;
+        token
+            .refresh_token
+            .iter()
+            .chain(&token.client_secret)
+            .try_for_each(|secret| ensure_unlocked(secret))
+    }
+
+    fn is_expiring(token: &MCPOAuthToken) -> bool {
+        token
+            .expires_at
+            .is_some_and(|expires_at| expires_at - REFRESH_MARGIN_SECS <= Self::now())
+    }
+
+    /// Run the authorization flow for a connection in the user's browser and store the tokens
+    pub async fn authorize(
+        &self,
+        app: &AppHandle,
+        connection: &MCPServerConnection,
+    ) -> Result<MCPOAuthStatus, AppError> {
+        if connection.r#type == "stdio" {
+            return Err(AppError::Validation(
+                "OAuth is only available for remote MCP servers".to_string(),
+            ));
+        }
+
+        crate::lib::sentry_helpers::add_breadcrumb(
+            "mcp.oauth",
+            format!("Authorizing MCP connection {}", connection.id),
+            sentry::Level::Info,
+        );
+
+        let mut server_url = Url::parse(&connection.url)
+            .map_err(|e| AppError::Validation(format!("Invalid MCP server URL: {e}")))?;
+        server_url.set_fragment(None);
+
+        // 1. Find the authorization server protecting the MCP server
+        let resource_metadata = self.discover_protected_resource(&server_url).await;
+        let resource = resource_metadata
+            .as_ref()
+            .and_then(|m| m.resource.clone())
+            .unwrap_or_else(|| server_url.to_string());
+        let issuer = match resource_metadata
+            .as_ref()
+            .and_then(|m| m.authorization_servers.first())
+        {
+            Some(issuer) => Url::parse(issuer).map_err(|e| {
+                AppError::Mcp(format!("Invalid authorization server URL {issuer}: {e}"))
+            })?,
+            // Servers without resource metadata act as their own authorization server
+            None => Self::origin(&server_url)?,
+        };
+        let server_metadata = self.discover_authorization_server(&issuer).await?;
+
+        if !server_metadata.code_challenge_methods_supported.is_empty()
+            && !server_metadata
+                .code_challenge_methods_supported
+                .iter()
+                .any(|method| method == "S256")
+        {
+            return Err(AppError::Mcp(
+                "Authorization server does not support PKCE (S256)".to_string(),
+            ));
+        }
+
+        // 2. Listen for the redirect on a loopback port and register that redirect URI
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let redirect_uri = format!(
+            "http://127.0.0.1:{}/callback",
+            listener.local_addr()?.port()
+        );
+        let registration = self
+            .register_client(&server_metadata, &redirect_uri)
+            .await?;
+
+        // 3. Send the user to the authorization endpoint with a PKCE challenge
+        let code_verifier = format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+        let state = uuid::Uuid::new_v4().simple().to_string();
+
+        let mut authorization_url = Url::parse(&server_metadata.authorization_endpoint)
+            .map_err(|e| AppError::Mcp(format!("Invalid authorization endpoint: {e}")))?;
+        {
+            let mut query = authorization_url.query_pairs_mut();
+            query
+                .append_pair("response_type", "code")
+                .append_pair("client_id", &registration.client_id)
+                .append_pair("redirect_uri", &redirect_uri)
+                .append_pair("code_challenge", &code_challenge)
+                .append_pair("code_challenge_method", "S256")
+                .append_pair("state", &state)
+                .append_pair("resource", &resource);
+            if let Some(metadata) = &resource_metadata {
+                if !metadata.scopes_supported.is_empty() {
+                    query.append_pair("scope", &metadata.scopes_supported.join(" "));
+                }
+            }
+        }
+
+        app.opener()
+            .open_url(authorization_url.as_str(), None::<&str>)
+            .map_err(|e| AppError::Generic(format!("Failed to open browser: {e}")))?;
+
+        let code = tokio::time::timeout(
+            tokio::time::Duration::from_secs(CALLBACK_TIMEOUT_SECS),
+            Self::wait_for_callback(&listener, &state),
+        )
+        .await
+        .map_err(|_| AppError::Mcp("Authorization timed out".to_string()))??;
+
+        // 4. Exchange the authorization code for tokens
+        let mut form = vec![
+            ("grant_type", "authorization_code".to_string()),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", registration.client_id.clone()),
+            ("code_verifier", code_verifier),
+            ("resource", resource.clone()),
+        ];
+        if let Some(client_secret) = &registration.client_secret {
+            form.push(("client_secret", client_secret.clone()));
+        }
+        let response = self
+            .request_token(&server_metadata.token_endpoint, &form)
+            .await?;
+
+        let now = Self::now();
+        let token = MCPOAuthToken {
+            connection_id: connection.id.clone(),
+            access_token: response.access_token,
+            refresh_token: response.refresh_token,
+            token_type: response.token_type.unwrap_or_else(|| "Bearer".to_string()),
+            expires_at: response.expires_in.map(|secs| now + secs),
+            scope: response.scope,
+            client_id: registration.client_id,
+            client_secret: registration.client_secret,
+            token_endpoint: server_metadata.token_endpoint,
+            resource,
+            created_at: now,
+            updated_at: now,
+        };
+        self.repository.save(&token)?;
+
+        self.get_status(&connection.id)
+    }
+
+    fn origin(url: &Url) -> Result<Url, AppError> {
+        Url::parse(&url.origin().ascii_serialization())
+            .map_err(|e| AppError::Mcp(format!("Invalid MCP server URL: {e}")))
+    }
+
+    /// GET a JSON document, treating any failure as "not found"
+    async fn fetch_json<T: DeserializeOwned>(&self, url: &str) -> Option<T> {
+        let response = self
+            .client
+            .get(url)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        response.json::<T>().await.ok()
+    }
+
+    /// Find the server's protected resource metadata (RFC 9728)
+    /// Uses the `resource_metadata` hint of a 401 response, then the well-known URIs
+    async fn discover_protected_resource(
+        &self,
+        server_url: &Url,
+    ) -> Option<ProtectedResourceMetadata> {
+        let mut candidates = Vec::new();
+
+        if let Ok(response) = self
+            .client
+            .get(server_url.clone())
+            .header("Accept", "application/json, text/event-stream")
+            .send()
+            .await
+        {
+            if response.status() == StatusCode::UNAUTHORIZED {
+                if let Some(url) = response
+                    .headers()
+                    .get("www-authenticate")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(Self::parse_resource_metadata_hint)
+                {
+                    candidates.push(url);
+                }
+            }
+        }
+
+        let origin = server_url.origin().ascii_serialization();
+        let path = server_url.path().trim_end_matches('/');
+        if !path.is_empty() {
+            candidates.push(format!(
+                "{origin}/.well-known/oauth-protected-resource{path}"
+            ));
+        }
+        candidates.push(format!("{origin}/.well-known/oauth-protected-resource"));
+
+        for candidate in candidates {
+            if let Some(metadata) = self.fetch_json(&candidate).await {
+                return Some(metadata);
+            }
+        }
+        None
+    }
+
+    /// Extract `resource_metadata="..."` from a `WWW-Authenticate` header
+    fn parse_resource_metadata_hint(header: &str) -> Option<String> {
+        let start = header.find("resource_metadata=")? + "resource_metadata=".len();
+        let value = header[start..].trim_start_matches('"');
+        let end = value.find(['"', ',', ' ']).unwrap_or(value.len());
+        Some(value[..end].to_string()).filter(|v| !v.is_empty())
+    }
+
+    /// Find the authorization server metadata (RFC 8414, then OIDC discovery)
+    /// Falls back to the default endpoints of servers that publish no metadata
+    async fn discover_authorization_server(
+        &self,
+        issuer: &Url,
+    ) -> Result<AuthorizationServerMetadata, AppError> {
+        let origin = issuer.origin().ascii_serialization();
+        let path = issuer.path().trim_end_matches('/');
+
+        let candidates = if path.is_empty() {
+            vec![
+                format!("{origin}/.well-known/oauth-authorization-server"),
+                format!("{origin}/.well-known/openid-configuration"),
+            ]
+        } else {
+            vec![
+                format!("{origin}/.well-known/oauth-authorization-server{path}"),
+                format!("{origin}/.well-known/openid-configuration{path}"),
+                format!("{origin}{path}/.well-known/openid-configuration"),
+            ]
+        };
+
+        for candidate in candidates {
+            if let Some(metadata) = self.fetch_json(&candidate).await {
+                return Ok(metadata);
+            }
+        }
+
+        tracing::warn!(
+            issuer = %issuer,
+            "No authorization server metadata found, using default endpoints"
+        );
+        Ok(AuthorizationServerMetadata {
+            authorization_endpoint: format!("{origin}/authorize"),
+            token_endpoint: format!("{origin}/token"),
+            registration_endpoint: Some(format!("{origin}/register")),
+            code_challenge_methods_supported: Vec::new(),
+        })
+    }
+
+    /// Register nexo as a public client for this redirect URI (RFC 7591)
+    async fn register_client(
+        &self,
+        server_metadata: &AuthorizationServerMetadata,
+        redirect_uri: &str,
+    ) -> Result<ClientRegistration, AppError> {
+        let registration_endpoint =
+            server_metadata
+                .registration_endpoint
+                .as_ref()
+                .ok_or_else(|| {
+                    AppError::Mcp(
+                        "Authorization server does not support dynamic client registration"
+                            .to_string(),
+                    )
+                })?;
+
+        let response = self
+            .client
+            .post(registration_endpoint)
+            .json(&serde_json::json!({
+                "client_name": "nexo",
+                "redirect_uris": [redirect_uri],
+                "grant_types": ["authorization_code", "refresh_token"],
+                "response_types": ["code"],
+                "token_endpoint_auth_method": "none",
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(AppError::Mcp(format!(
+                "Client registration failed ({status}): {body}"
+            )));
+        }
+
+        Ok(response.json::<ClientRegistration>().await?)
+    }
+
+    async fn request_token(
+        &self,
+        token_endpoint: &str,
+        form: &[(&str, String)],
+    ) -> Result<TokenResponse, AppError> {
+        let response = self
+            .client
+            .post(token_endpoint)
+            .header("Accept", "application/json")
+            .form(form)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(AppError::Mcp(format!(
+                "Token request failed ({status}): {body}"
+            )));
+        }
+
+        Ok(response.json::<TokenResponse>().await?)
+    }
+
+    /// Wait for the browser to hit the loopback redirect and return the authorization code
+    async fn wait_for_callback(
+        listener: &TcpListener,
+        expected_state: &str,
+    ) -> Result<String, AppError> {
+        loop {
+            let (mut stream, _) = listener.accept().await?;
+
+            let mut buffer = vec![0u8; 8192];
+            let read = stream.read(&mut buffer).await?;
+            let request = String::from_utf8_lossy(&buffer[..read]);
+
+            // Request line: GET /callback?code=...&state=... HTTP/1.1
+            let path = request
+                .lines()
+                .next()
+                .and_then(|line| line.split_whitespace().nth(1))
+                .unwrap_or_default();
+            if !path.starts_with("/callback") {
+                // Browsers also ask for /favicon.ico
+                let _ = stream
+                    .write_all(
+                        b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    )
+                    .await;
+                continue;
+            }
+
+            let Ok(url) = Url::parse(&format!("http://127.0.0.1{path}")) else {
+                Self::respond(&mut stream, "400 Bad Request", CALLBACK_ERROR_PAGE).await;
+                continue;
+            };
+            let param = |name: &str| {
+                url.query_pairs()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.to_string())
+            };
+
+            // Not the redirect of this flow (a stale tab or another site), keep waiting
+            if param("state").as_deref() != Some(expected_state) {
+                tracing::warn!("Ignoring authorization callback with a mismatched state");
+                Self::respond(&mut stream, "400 Bad Request", CALLBACK_ERROR_PAGE).await;
+                continue;
+            }
+
+            if let Some(error) = param("error") {
+                Self::respond(&mut stream, "200 OK", CALLBACK_ERROR_PAGE).await;
+                let description = param("error_description").unwrap_or_default();
+                return Err(AppError::Mcp(format!(
+                    "Authorization was denied: {error} {description}"
+                )));
+            }
+
+            let Some(code) = param("code") else {
+                Self::respond(&mut stream, "400 Bad Request", CALLBACK_ERROR_PAGE).await;
+                return Err(AppError::Mcp(
+                    "Authorization callback is missing the code".to_string(),
+                ));
+            };
+            Self::respond(&mut stream, "200 OK", CALLBACK_PAGE).await;
+            return Ok(code);
+        }
+    }
+
+    /// Answer the browser with an HTML page, it may already have given up on the request
+    async fn respond(stream: &mut TcpStream, status: &str, page: &str) {
+        let response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{page}",
+            page.len()
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,51 @@
+use super::*;
+
+/// Send a browser request to the loopback listener and return the response
+async fn get(port: u16, path: &str) -> String {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    stream
+        .write_all(format!("GET {path} HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n").as_bytes())
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test]
+async fn keeps_waiting_after_a_mismatched_state() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let callback =
+        tokio::spawn(
+            async move { MCPOAuthService::wait_for_callback(&listener, "expected").await },
+        );
+
+    let favicon = get(port, "/favicon.ico").await;
+    assert!(favicon.starts_with("HTTP/1.1 404"));
+    let stale = get(port, "/callback?code=stale&state=other").await;
+    assert!(stale.starts_with("HTTP/1.1 400"));
+    assert!(stale.contains("Authorization failed"));
+    let valid = get(port, "/callback?code=abc&state=expected").await;
+    assert!(valid.starts_with("HTTP/1.1 200"));
+    assert!(valid.contains("Authorization complete"));
+
+    assert_eq!(callback.await.unwrap().unwrap(), "abc");
+}
+
+#[tokio::test]
+async fn reports_a_denied_authorization() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let callback =
+        tokio::spawn(
+            async move { MCPOAuthService::wait_for_callback(&listener, "expected").await },
+        );
+
+    let denied = get(port, "/callback?error=access_denied&state=expected").await;
+    assert!(denied.contains("Authorization failed"));
+    assert!(!denied.contains("Authorization complete"));
+
+    let error = callback.await.unwrap().unwrap_err().to_string();
+    assert!(error.contains("access_denied"));
+}
@@ -1,5 +1,6 @@
 use super::models::MCPServerConnection;
 use crate::error::AppError;
+use crate::features::secrets::SecretsService;
 use rusqlite::params;
 use std::sync::Arc;
 use tauri::AppHandle;
@@ -25,25 +26,43 @@ pub trait MCPConnectionRepository: Send + Sync {
         tools_json: Option<&str>,
         error_message: Option<&str>,
     ) -> Result<(), AppError>;
+    fn update_resources(&self, id: &str, resources_json: Option<&str>) -> Result<(), AppError>;
+    fn update_prompts(&self, id: &str, prompts_json: Option<&str>) -> Result<(), AppError>;
     fn delete(&self, id: &str) -> Result<(), AppError>;
 }
 
 pub struct SqliteMCPConnectionRepository {
     app: Arc<AppHandle>,
+    secrets: Arc<SecretsService>,
 }
 
 impl SqliteMCPConnectionRepository {
-    pub const fn new(app: Arc<AppHandle>) -> Self {
-        Self { app }
+    pub const fn new(app: Arc<AppHandle>, secrets: Arc<SecretsService>) -> Self {
+        Self { app, secrets }
+    }
+
+    // headers and env_vars are encrypted at rest, and stay encrypted while the vault is locked
+    fn decrypt(&self, mut connection: MCPServerConnection) -> MCPServerConnection {
+        connection.headers = self.secrets.reveal(&connection.headers);
+        connection.env_vars = connection
+            .env_vars
+            .map(|env_vars| self.secrets.reveal(&env_vars));
+        connection
     }
 }
 
 impl MCPConnectionRepository for SqliteMCPConnectionRepository {
     fn create(&self, connection: &MCPServerConnection) -> Result<(), AppError> {
+        let headers = self.secrets.encrypt(&connection.headers)?;
+        let env_vars = connection
+            .env_vars
+            .as_deref()
+            .map(|env_vars| self.secrets.encrypt(env_vars))
+            .transpose()?;
         let conn = crate::db::get_connection(&self.app)?;
         conn.execute(
-            "INSERT INTO mcp_server_connections (id, name, url, type, headers, env_vars, runtime_path, status, tools_json, error_message, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
-            params![connection.id, connection.name, connection.url, connection.r#type, connection.headers, connection.env_vars, connection.runtime_path, connection.status, connection.tools_json, connection.error_message, connection.created_at, connection.updated_at],
+            "INSERT INTO mcp_server_connections (id, name, url, type, headers, env_vars, runtime_path, status, tools_json, resources_json, prompts_json, error_message, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            params![connection.id, connection.name, connection.url, connection.r#type, headers, env_vars, connection.runtime_path, connection.status, connection.tools_json, connection.resources_json, connection.prompts_json, connection.error_message, connection.created_at, connection.updated_at],
         )?;
         Ok(())
     }
@@ -51,7 +70,7 @@ impl MCPConnectionRepository for SqliteMCPConnectionRepository {
     fn get_all(&self) -> Result<Vec<MCPServerConnection>, AppError> {
         let conn = crate::db::get_connection(&self.app)?;
         let mut stmt = conn.prepare(
-            "SELECT id, name, url, type, headers, env_vars, runtime_path, status, tools_json, error_message, created_at, updated_at FROM mcp_server_connections ORDER BY created_at DESC"
+            "SELECT id, name, url, type, headers, env_vars, runtime_path, status, tools_json, resources_json, prompts_json, error_message, created_at, updated_at FROM mcp_server_connections ORDER BY created_at DESC"
         )?;
 
         let connections = stmt
@@ -66,20 +85,25 @@ impl MCPConnectionRepository for SqliteMCPConnectionRepository {
                     runtime_path: row.get(6)?,
                     status: row.get(7)?,
                     tools_json: row.get(8)?,
-                    error_message: row.get(9)?,
-                    created_at: row.get(10)?,
-                    updated_at: row.get(11)?,
+                    resources_json: row.get(9)?,
+                    prompts_json: row.get(10)?,
+                    error_message: row.get(11)?,
+                    created_at: row.get(12)?,
+                    updated_at: row.get(13)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(connections)
+        Ok(connections
+            .into_iter()
+            .map(|connection| self.decrypt(connection))
+            .collect())
     }
 
     fn get_by_id(&self, id: &str) -> Result<Option<MCPServerConnection>, AppError> {
         let conn = crate::db::get_connection(&self.app)?;
         let result = conn.query_row(
-            "SELECT id, name, url, type, headers, env_vars, runtime_path, status, tools_json, error_message, created_at, updated_at FROM mcp_server_connections WHERE id = ?1",
+            "SELECT id, name, url, type, headers, env_vars, runtime_path, status, tools_json, resources_json, prompts_json, error_message, created_at, updated_at FROM mcp_server_connections WHERE id = ?1",
             params![id],
             |row| {
                 Ok(MCPServerConnection {
@@ -92,15 +116,17 @@ impl MCPConnectionRepository for SqliteMCPConnectionRepository {
                     runtime_path: row.get(6)?,
                     status: row.get(7)?,
                     tools_json: row.get(8)?,
-                    error_message: row.get(9)?,
-                    created_at: row.get(10)?,
-                    updated_at: row.get(11)?,
+                    resources_json: row.get(9)?,
+                    prompts_json: row.get(10)?,
+                    error_message: row.get(11)?,
+                    created_at: row.get(12)?,
+                    updated_at: row.get(13)?,
                 })
             },
         );
 
         match result {
-            Ok(connection) => Ok(Some(connection)),
+            Ok(connection) => Ok(Some(self.decrypt(connection))),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
             Err(e) => Err(e.into()),
         }
@@ -144,6 +170,7 @@ impl MCPConnectionRepository for SqliteMCPConnectionRepository {
         }
 
         if let Some(headers) = headers {
+            let headers = self.secrets.encrypt(headers)?;
             conn.execute(
                 "UPDATE mcp_server_connections SET headers = ?1, updated_at = ?2 WHERE id = ?3",
                 params![headers, now, id],
@@ -151,6 +178,7 @@ impl MCPConnectionRepository for SqliteMCPConnectionRepository {
         }
 
         if let Some(env_vars) = env_vars {
+            let env_vars = self.secrets.encrypt(env_vars)?;
             conn.execute(
                 "UPDATE mcp_server_connections SET env_vars = ?1, updated_at = ?2 WHERE id = ?3",
                 params![env_vars, now, id],
@@ -188,6 +216,36 @@ impl MCPConnectionRepository for SqliteMCPConnectionRepository {
         Ok(())
     }
 
+    fn update_resources(&self, id: &str, resources_json: Option<&str>) -> Result<(), AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        conn.execute(
+            "UPDATE mcp_server_connections SET resources_json = ?1, updated_at = ?2 WHERE id = ?3",
+            params![resources_json, now, id],
+        )?;
+
+        Ok(())
+    }
+
+    fn update_prompts(&self, id: &str, prompts_json: Option<&str>) -> Result<(), AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        conn.execute(
+            "UPDATE mcp_server_connections SET prompts_json = ?1, updated_at = ?2 WHERE id = ?3",
+            params![prompts_json, now, id],
+        )?;
+
+        Ok(())
+    }
+
     fn delete(&self, id: &str) -> Result<(), AppError> {
         let conn = crate::db::get_connection(&self.app)?;
         conn.execute(
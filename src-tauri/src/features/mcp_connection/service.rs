@@ -1,6 +1,8 @@
 use super::models::MCPServerConnection;
 use super::repository::MCPConnectionRepository;
 use crate::error::AppError;
+use crate::features::tool::mcp_refresh::save_catalog;
+use crate::features::tool::models::MCPServerCatalog;
 use std::sync::Arc;
 
 pub struct MCPConnectionService {
@@ -37,6 +39,8 @@ impl MCPConnectionService {
             runtime_path,
             status: "disconnected".to_string(),
             tools_json: None,
+            resources_json: None,
+            prompts_json: None,
             error_message: None,
             created_at: now,
             updated_at: now,
@@ -90,6 +94,15 @@ impl MCPConnectionService {
         )
     }
 
+    pub fn update_resources(&self, id: &str, resources_json: Option<&str>) -> Result<(), AppError> {
+        self.repository.update_resources(id, resources_json)
+    }
+
+    /// Cache everything a server publishes, as the background refresh does
+    pub fn save_catalog(&self, id: &str, catalog: &MCPServerCatalog) -> Result<(), AppError> {
+        save_catalog(self.repository.as_ref(), id, catalog)
+    }
+
     pub fn delete(&self, id: String) -> Result<(), AppError> {
         self.repository.delete(&id)
     }
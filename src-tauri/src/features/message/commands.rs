@@ -1,4 +1,4 @@
-use super::models::Message;
+use super::models::{Message, MessageBranch};
 use crate::error::AppError;
 use crate::state::AppState;
 use tauri::State;
@@ -7,6 +7,7 @@ use tauri::State;
 pub fn create_message(
     id: String,
     chat_id: String,
+    parent_message_id: Option<String>,
     role: String,
     content: String,
     timestamp: Option<i64>,
@@ -18,19 +19,46 @@ pub fn create_message(
     state
         .message_service
         .create(
-            id, chat_id, role, content, timestamp, reasoning, tool_calls, metadata,
+            id,
+            chat_id,
+            parent_message_id,
+            role,
+            content,
+            timestamp,
+            reasoning,
+            tool_calls,
+            metadata,
         )
         .map_err(|e| AppError::Generic(e.to_string()))
 }
 
+/// Messages on the chat's active branch
 #[tauri::command]
 pub fn get_messages(chat_id: String, state: State<'_, AppState>) -> Result<Vec<Message>, AppError> {
     state
         .message_service
-        .get_by_chat_id(&chat_id)
+        .get_active_path(&chat_id)
         .map_err(|e| AppError::Generic(e.to_string()))
 }
 
+#[tauri::command]
+pub fn get_message_branches(
+    chat_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<MessageBranch>, AppError> {
+    state.message_service.get_branches(&chat_id)
+}
+
+/// Activate the branch containing `message_id` and return the new active path
+#[tauri::command]
+pub fn switch_message_branch(
+    chat_id: String,
+    message_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<Message>, AppError> {
+    state.message_service.switch_branch(&chat_id, &message_id)
+}
+
 #[tauri::command]
 pub fn update_message(
     id: String,
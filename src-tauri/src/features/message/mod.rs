@@ -4,6 +4,9 @@ pub mod models;
 pub mod repository;
 pub mod service;
 
+#[cfg(test)]
+mod tests;
+
 pub use emitter::MessageEmitter;
 pub use models::*;
 pub use repository::*;
@@ -11,4 +11,13 @@ pub struct Message {
     pub assistant_message_id: Option<String>, // For tool_call messages: ID of the assistant message
     pub tool_call_id: Option<String>,         // For tool messages: ID of the tool call
     pub metadata: Option<String>,             // JSON metadata including agent info
+    pub parent_message_id: Option<String>,    // Previous message in the branch, None for roots
+}
+
+/// A fork on the active path: sibling messages sharing the same parent
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MessageBranch {
+    pub parent_message_id: Option<String>,
+    pub message_ids: Vec<String>,  // Siblings, oldest first
+    pub active_message_id: String, // Sibling on the active path
 }
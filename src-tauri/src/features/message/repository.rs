@@ -1,13 +1,26 @@
 use super::models::Message;
 use crate::error::AppError;
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use std::sync::Arc;
 use tauri::AppHandle;
 
+const MESSAGE_COLUMNS: &str = "m.id, m.chat_id, m.role, m.content, m.reasoning, m.timestamp, m.assistant_message_id, m.tool_call_id, m.metadata, m.parent_message_id";
+
 pub trait MessageRepository: Send + Sync {
+    /// Insert a message under its `parent_message_id`, `None` starts a new root
+    /// The active leaf moves to it only while it still points at the parent
     fn create(&self, message: &Message) -> Result<(), AppError>;
+    /// All messages of a chat across every branch
     fn get_by_chat_id(&self, chat_id: &str) -> Result<Vec<Message>, AppError>;
+    /// Messages from the root to the active leaf
+    fn get_active_path(&self, chat_id: &str) -> Result<Vec<Message>, AppError>;
     fn get_by_id(&self, id: &str) -> Result<Option<Message>, AppError>;
+    fn get_children(
+        &self,
+        chat_id: &str,
+        parent_message_id: Option<&str>,
+    ) -> Result<Vec<Message>, AppError>;
+    fn set_active_leaf(&self, chat_id: &str, message_id: Option<&str>) -> Result<(), AppError>;
     fn update(
         &self,
         id: &str,
@@ -15,7 +28,6 @@ pub trait MessageRepository: Send + Sync {
         reasoning: Option<&str>,
         timestamp: Option<i64>,
     ) -> Result<(), AppError>;
-    fn delete(&self, id: &str) -> Result<(), AppError>;
     fn delete_messages_after(&self, chat_id: &str, message_id: &str) -> Result<(), AppError>;
     fn update_metadata(&self, id: &str, metadata: Option<&str>) -> Result<(), AppError>;
 }
@@ -32,66 +44,35 @@ impl SqliteMessageRepository {
 
 impl MessageRepository for SqliteMessageRepository {
     fn create(&self, message: &Message) -> Result<(), AppError> {
-        let conn = crate::db::get_connection(&self.app)?;
-        conn.execute(
-            "INSERT INTO messages (id, chat_id, role, content, reasoning, timestamp, assistant_message_id, tool_call_id, metadata) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-            params![message.id, message.chat_id, message.role, message.content, message.reasoning, message.timestamp, message.assistant_message_id, message.tool_call_id, message.metadata],
-        )?;
-        Ok(())
+        insert_message(&crate::db::get_connection(&self.app)?, message)
     }
 
     fn get_by_chat_id(&self, chat_id: &str) -> Result<Vec<Message>, AppError> {
-        let conn = crate::db::get_connection(&self.app)?;
-        let mut stmt = conn.prepare(
-            "SELECT id, chat_id, role, content, reasoning, timestamp, assistant_message_id, tool_call_id, metadata FROM messages WHERE chat_id = ?1 ORDER BY timestamp ASC"
-        )?;
+        select_by_chat_id(&crate::db::get_connection(&self.app)?, chat_id)
+    }
 
-        let messages = stmt
-            .query_map(params![chat_id], |row| {
-                let reasoning: Option<String> = row.get(4)?;
-
-                Ok(Message {
-                    id: row.get(0)?,
-                    chat_id: row.get(1)?,
-                    role: row.get(2)?,
-                    content: row.get(3)?,
-                    reasoning,
-                    timestamp: row.get(5)?,
-                    assistant_message_id: row.get(6)?,
-                    tool_call_id: row.get(7)?,
-                    metadata: row.get(8)?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok(messages)
+    fn get_active_path(&self, chat_id: &str) -> Result<Vec<Message>, AppError> {
+        select_active_path(&crate::db::get_connection(&self.app)?, chat_id)
     }
 
     fn get_by_id(&self, id: &str) -> Result<Option<Message>, AppError> {
-        let conn = crate::db::get_connection(&self.app)?;
-        let result = conn.query_row(
-            "SELECT id, chat_id, role, content, reasoning, timestamp, assistant_message_id, tool_call_id, metadata FROM messages WHERE id = ?1",
-            params![id],
-            |row| {
-                Ok(Message {
-                    id: row.get(0)?,
-                    chat_id: row.get(1)?,
-                    role: row.get(2)?,
-                    content: row.get(3)?,
-                    reasoning: row.get(4)?,
-                    timestamp: row.get(5)?,
-                    assistant_message_id: row.get(6)?,
-                    tool_call_id: row.get(7)?,
-                    metadata: row.get(8)?,
-                })
-            },
-        );
-
-        match result {
-            Ok(message) => Ok(Some(message)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e.into()),
-        }
+        select_by_id(&crate::db::get_connection(&self.app)?, id)
+    }
+
+    fn get_children(
+        &self,
+        chat_id: &str,
+        parent_message_id: Option<&str>,
+    ) -> Result<Vec<Message>, AppError> {
+        select_children(
+            &crate::db::get_connection(&self.app)?,
+            chat_id,
+            parent_message_id,
+        )
+    }
+
+    fn set_active_leaf(&self, chat_id: &str, message_id: Option<&str>) -> Result<(), AppError> {
+        update_active_leaf(&crate::db::get_connection(&self.app)?, chat_id, message_id)
     }
 
     fn update(
@@ -101,61 +82,222 @@ impl MessageRepository for SqliteMessageRepository {
         reasoning: Option<&str>,
         timestamp: Option<i64>,
     ) -> Result<(), AppError> {
-        let conn = crate::db::get_connection(&self.app)?;
-
-        if let Some(ts) = timestamp {
-            conn.execute(
-                "UPDATE messages SET content = ?1, reasoning = ?2, timestamp = ?3 WHERE id = ?4",
-                params![content, reasoning, ts, id],
-            )?;
-        } else {
-            conn.execute(
-                "UPDATE messages SET content = ?1, reasoning = ?2 WHERE id = ?3",
-                params![content, reasoning, id],
-            )?;
-        }
-
-        Ok(())
+        update_content(
+            &crate::db::get_connection(&self.app)?,
+            id,
+            content,
+            reasoning,
+            timestamp,
+        )
     }
 
-    fn delete(&self, id: &str) -> Result<(), AppError> {
-        let conn = crate::db::get_connection(&self.app)?;
-        conn.execute("DELETE FROM messages WHERE id = ?1", params![id])?;
-        Ok(())
+    fn delete_messages_after(&self, chat_id: &str, message_id: &str) -> Result<(), AppError> {
+        delete_descendants(&crate::db::get_connection(&self.app)?, chat_id, message_id)
     }
 
-    fn delete_messages_after(&self, chat_id: &str, message_id: &str) -> Result<(), AppError> {
-        let conn = crate::db::get_connection(&self.app)?;
+    fn update_metadata(&self, id: &str, metadata: Option<&str>) -> Result<(), AppError> {
+        update_metadata(&crate::db::get_connection(&self.app)?, id, metadata)
+    }
+}
 
-        // Get all messages for this chat ordered by timestamp
-        let mut stmt = conn.prepare(
-            "SELECT id, timestamp FROM messages WHERE chat_id = ?1 ORDER BY timestamp ASC, id ASC",
-        )?;
+// Queries on an open connection, `SqliteMessageRepository` opens one per call
 
-        let messages: Vec<(String, i64)> = stmt
-            .query_map(params![chat_id], |row| Ok((row.get(0)?, row.get(1)?)))?
-            .collect::<Result<Vec<_>, _>>()?;
+fn map_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Message> {
+    Ok(Message {
+        id: row.get(0)?,
+        chat_id: row.get(1)?,
+        role: row.get(2)?,
+        content: row.get(3)?,
+        reasoning: row.get(4)?,
+        timestamp: row.get(5)?,
+        assistant_message_id: row.get(6)?,
+        tool_call_id: row.get(7)?,
+        metadata: row.get(8)?,
+        parent_message_id: row.get(9)?,
+    })
+}
+
+/// IDs of a message and all of its descendants
+fn subtree_ids(conn: &Connection, message_id: &str) -> Result<Vec<String>, AppError> {
+    let mut stmt = conn.prepare(
+        "WITH RECURSIVE subtree(id) AS (
+            SELECT ?1
+            UNION ALL
+            SELECT m.id FROM messages m JOIN subtree ON m.parent_message_id = subtree.id
+        )
+        SELECT id FROM subtree",
+    )?;
+    let ids = stmt
+        .query_map(params![message_id], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(ids)
+}
 
-        // Find the index of the message we want to keep
-        let message_index = messages
-            .iter()
-            .position(|(id, _)| id == message_id)
-            .ok_or_else(|| AppError::NotFound(format!("Message not found: {message_id}")))?;
+pub(super) fn insert_message(conn: &Connection, message: &Message) -> Result<(), AppError> {
+    let tx = conn.unchecked_transaction()?;
 
-        // Delete all messages after this one
-        for (id, _) in messages.iter().skip(message_index + 1) {
-            conn.execute("DELETE FROM messages WHERE id = ?1", params![id])?;
-        }
+    tx.execute(
+        "INSERT INTO messages (id, chat_id, role, content, reasoning, timestamp, assistant_message_id, tool_call_id, metadata, parent_message_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![message.id, message.chat_id, message.role, message.content, message.reasoning, message.timestamp, message.assistant_message_id, message.tool_call_id, message.metadata, message.parent_message_id],
+    )?;
+    // Compare-and-set: a message written to another branch leaves the active one alone
+    tx.execute(
+        "UPDATE chats SET active_leaf_message_id = ?1 WHERE id = ?2 AND active_leaf_message_id IS ?3",
+        params![message.id, message.chat_id, message.parent_message_id],
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+pub(super) fn select_by_chat_id(
+    conn: &Connection,
+    chat_id: &str,
+) -> Result<Vec<Message>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {MESSAGE_COLUMNS} FROM messages m WHERE m.chat_id = ?1 ORDER BY m.timestamp ASC"
+    ))?;
+
+    let messages = stmt
+        .query_map(params![chat_id], map_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(messages)
+}
 
-        Ok(())
+pub(super) fn select_active_path(
+    conn: &Connection,
+    chat_id: &str,
+) -> Result<Vec<Message>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "WITH RECURSIVE path(id, depth) AS (
+            SELECT active_leaf_message_id, 0 FROM chats
+            WHERE id = ?1 AND active_leaf_message_id IS NOT NULL
+            UNION ALL
+            SELECT m.parent_message_id, path.depth + 1 FROM messages m
+            JOIN path ON m.id = path.id
+            WHERE m.parent_message_id IS NOT NULL
+        )
+        SELECT {MESSAGE_COLUMNS} FROM messages m
+        JOIN path ON m.id = path.id
+        ORDER BY path.depth DESC"
+    ))?;
+
+    let messages = stmt
+        .query_map(params![chat_id], map_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(messages)
+}
+
+pub(super) fn select_by_id(conn: &Connection, id: &str) -> Result<Option<Message>, AppError> {
+    let result = conn.query_row(
+        &format!("SELECT {MESSAGE_COLUMNS} FROM messages m WHERE m.id = ?1"),
+        params![id],
+        map_row,
+    );
+
+    match result {
+        Ok(message) => Ok(Some(message)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
     }
+}
 
-    fn update_metadata(&self, id: &str, metadata: Option<&str>) -> Result<(), AppError> {
-        let conn = crate::db::get_connection(&self.app)?;
+pub(super) fn select_children(
+    conn: &Connection,
+    chat_id: &str,
+    parent_message_id: Option<&str>,
+) -> Result<Vec<Message>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {MESSAGE_COLUMNS} FROM messages m
+         WHERE m.chat_id = ?1 AND m.parent_message_id IS ?2
+         ORDER BY m.timestamp ASC, m.rowid ASC"
+    ))?;
+
+    let messages = stmt
+        .query_map(params![chat_id, parent_message_id], map_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(messages)
+}
+
+pub(super) fn update_active_leaf(
+    conn: &Connection,
+    chat_id: &str,
+    message_id: Option<&str>,
+) -> Result<(), AppError> {
+    conn.execute(
+        "UPDATE chats SET active_leaf_message_id = ?1 WHERE id = ?2",
+        params![message_id, chat_id],
+    )?;
+    Ok(())
+}
+
+pub(super) fn update_content(
+    conn: &Connection,
+    id: &str,
+    content: &str,
+    reasoning: Option<&str>,
+    timestamp: Option<i64>,
+) -> Result<(), AppError> {
+    if let Some(ts) = timestamp {
         conn.execute(
-            "UPDATE messages SET metadata = ?1 WHERE id = ?2",
-            params![metadata, id],
+            "UPDATE messages SET content = ?1, reasoning = ?2, timestamp = ?3 WHERE id = ?4",
+            params![content, reasoning, ts, id],
         )?;
-        Ok(())
+    } else {
+        conn.execute(
+            "UPDATE messages SET content = ?1, reasoning = ?2 WHERE id = ?3",
+            params![content, reasoning, id],
+        )?;
+    }
+
+    Ok(())
+}
+
+// Deletes every descendant of the message (all branches below it) and makes it the active leaf
+pub(super) fn delete_descendants(
+    conn: &Connection,
+    chat_id: &str,
+    message_id: &str,
+) -> Result<(), AppError> {
+    let tx = conn.unchecked_transaction()?;
+
+    let exists: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM messages WHERE id = ?1 AND chat_id = ?2",
+        params![message_id, chat_id],
+        |row| row.get(0),
+    )?;
+    if exists == 0 {
+        return Err(AppError::NotFound(format!(
+            "Message not found: {message_id}"
+        )));
+    }
+
+    for id in subtree_ids(&tx, message_id)?
+        .iter()
+        .filter(|id| *id != message_id)
+    {
+        tx.execute("DELETE FROM messages WHERE id = ?1", params![id])?;
     }
+    tx.execute(
+        "UPDATE chats SET active_leaf_message_id = ?1 WHERE id = ?2",
+        params![message_id, chat_id],
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+pub(super) fn update_metadata(
+    conn: &Connection,
+    id: &str,
+    metadata: Option<&str>,
+) -> Result<(), AppError> {
+    conn.execute(
+        "UPDATE messages SET metadata = ?1 WHERE id = ?2",
+        params![metadata, id],
+    )?;
+    Ok(())
 }
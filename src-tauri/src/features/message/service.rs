@@ -1,4 +1,4 @@
-use super::models::Message;
+use super::models::{Message, MessageBranch};
 use super::repository::MessageRepository;
 use crate::error::AppError;
 use std::sync::Arc;
@@ -12,11 +12,13 @@ impl MessageService {
         Self { repository }
     }
 
+    /// Add a message under `parent_message_id`, the active branch follows when it ends there
     #[allow(clippy::too_many_arguments)]
     pub fn create(
         &self,
         id: String,
         chat_id: String,
+        parent_message_id: Option<String>,
         role: String,
         content: String,
         timestamp: Option<i64>,
@@ -41,20 +43,105 @@ impl MessageService {
             tool_call_id,
             metadata,
             reasoning: None,
+            parent_message_id,
         };
 
         self.repository.create(&message)?;
         Ok(message)
     }
 
+    /// Add empty assistant replies under `parent_message_id`, one per metadata entry
+    /// The first one becomes active when the parent is the active leaf, the others wait as
+    /// inactive siblings
+    pub fn create_sibling_replies(
+        &self,
+        chat_id: &str,
+        parent_message_id: &str,
+        timestamp: i64,
+        metadata: Vec<String>,
+    ) -> Result<Vec<Message>, AppError> {
+        metadata
+            .into_iter()
+            .map(|metadata| {
+                self.create(
+                    uuid::Uuid::new_v4().to_string(),
+                    chat_id.to_string(),
+                    Some(parent_message_id.to_string()),
+                    "assistant".to_string(),
+                    String::new(),
+                    Some(timestamp),
+                    None,
+                    None,
+                    Some(metadata),
+                )
+            })
+            .collect()
+    }
+
     pub fn get_by_chat_id(&self, chat_id: &str) -> Result<Vec<Message>, AppError> {
         self.repository.get_by_chat_id(chat_id)
     }
 
+    /// Messages on the chat's active branch, root first
+    pub fn get_active_path(&self, chat_id: &str) -> Result<Vec<Message>, AppError> {
+        self.repository.get_active_path(chat_id)
+    }
+
     pub fn get_by_id(&self, id: &str) -> Result<Option<Message>, AppError> {
         self.repository.get_by_id(id)
     }
 
+    /// Forks along the active path, so the UI can offer switching between siblings
+    pub fn get_branches(&self, chat_id: &str) -> Result<Vec<MessageBranch>, AppError> {
+        let mut branches = Vec::new();
+
+        for message in self.repository.get_active_path(chat_id)? {
+            let siblings = self
+                .repository
+                .get_children(chat_id, message.parent_message_id.as_deref())?;
+            if siblings.len() > 1 {
+                branches.push(MessageBranch {
+                    parent_message_id: message.parent_message_id,
+                    message_ids: siblings.into_iter().map(|m| m.id).collect(),
+                    active_message_id: message.id,
+                });
+            }
+        }
+
+        Ok(branches)
+    }
+
+    /// Make the branch containing `message_id` active, following the newest reply at each fork
+    pub fn switch_branch(&self, chat_id: &str, message_id: &str) -> Result<Vec<Message>, AppError> {
+        let message = self
+            .repository
+            .get_by_id(message_id)?
+            .filter(|m| m.chat_id == chat_id)
+            .ok_or_else(|| AppError::NotFound(format!("Message not found: {message_id}")))?;
+
+        let mut leaf = message.id;
+        while let Some(child) = self
+            .repository
+            .get_children(chat_id, Some(&leaf))?
+            .into_iter()
+            .next_back()
+        {
+            leaf = child.id;
+        }
+
+        self.repository.set_active_leaf(chat_id, Some(&leaf))?;
+        self.repository.get_active_path(chat_id)
+    }
+
+    /// Start a new branch at `parent_message_id`, messages created under it then become active
+    pub fn branch_from(
+        &self,
+        chat_id: &str,
+        parent_message_id: Option<&str>,
+    ) -> Result<(), AppError> {
+        self.repository.set_active_leaf(chat_id, parent_message_id)
+    }
+
     pub fn update(
         &self,
         id: String,
@@ -70,10 +157,6 @@ impl MessageService {
         self.repository.update_metadata(&id, metadata.as_deref())
     }
 
-    pub fn delete(&self, id: String) -> Result<(), AppError> {
-        self.repository.delete(&id)
-    }
-
     pub fn delete_messages_after(
         &self,
         chat_id: String,
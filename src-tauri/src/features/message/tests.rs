@@ -0,0 +1,268 @@
+use super::repository::{
+    delete_descendants, insert_message, select_active_path, select_by_chat_id, select_by_id,
+    select_children, update_active_leaf, update_content, update_metadata,
+};
+use super::*;
+use crate::error::AppError;
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// The `SQLite` queries on a migrated in-memory database
+struct MemoryRepository {
+    conn: Mutex<Connection>,
+}
+
+impl MemoryRepository {
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap()
+    }
+}
+
+impl MessageRepository for MemoryRepository {
+    fn create(&self, message: &Message) -> Result<(), AppError> {
+        insert_message(&self.conn(), message)
+    }
+
+    fn get_by_chat_id(&self, chat_id: &str) -> Result<Vec<Message>, AppError> {
+        select_by_chat_id(&self.conn(), chat_id)
+    }
+
+    fn get_active_path(&self, chat_id: &str) -> Result<Vec<Message>, AppError> {
+        select_active_path(&self.conn(), chat_id)
+    }
+
+    fn get_by_id(&self, id: &str) -> Result<Option<Message>, AppError> {
+        select_by_id(&self.conn(), id)
+    }
+
+    fn get_children(
+        &self,
+        chat_id: &str,
+        parent_message_id: Option<&str>,
+    ) -> Result<Vec<Message>, AppError> {
+        select_children(&self.conn(), chat_id, parent_message_id)
+    }
+
+    fn set_active_leaf(&self, chat_id: &str, message_id: Option<&str>) -> Result<(), AppError> {
+        update_active_leaf(&self.conn(), chat_id, message_id)
+    }
+
+    fn update(
+        &self,
+        id: &str,
+        content: &str,
+        reasoning: Option<&str>,
+        timestamp: Option<i64>,
+    ) -> Result<(), AppError> {
+        update_content(&self.conn(), id, content, reasoning, timestamp)
+    }
+
+    fn delete_messages_after(&self, chat_id: &str, message_id: &str) -> Result<(), AppError> {
+        delete_descendants(&self.conn(), chat_id, message_id)
+    }
+
+    fn update_metadata(&self, id: &str, metadata: Option<&str>) -> Result<(), AppError> {
+        update_metadata(&self.conn(), id, metadata)
+    }
+}
+
+/// A service over a database holding the chats `chat` and `other`
+fn service() -> MessageService {
+    let conn = Connection::open_in_memory().unwrap();
+    crate::db::migrations::run_migrations(&conn, Path::new(":memory:")).unwrap();
+    conn.execute(
+        "INSERT INTO workspaces (id, name, created_at) VALUES ('workspace', 'Workspace', 0)",
+        [],
+    )
+    .unwrap();
+    for chat_id in ["chat", "other"] {
+        conn.execute(
+            "INSERT INTO chats (id, workspace_id, title, created_at, updated_at) VALUES (?1, 'workspace', ?1, 0, 0)",
+            params![chat_id],
+        )
+        .unwrap();
+    }
+    MessageService::new(Arc::new(MemoryRepository {
+        conn: Mutex::new(conn),
+    }))
+}
+
+fn add(service: &MessageService, id: &str, parent: Option<&str>, timestamp: i64) {
+    let role = if id.starts_with('a') {
+        "assistant"
+    } else {
+        "user"
+    };
+    service
+        .create(
+            id.to_string(),
+            "chat".to_string(),
+            parent.map(str::to_string),
+            role.to_string(),
+            id.to_string(),
+            Some(timestamp),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+}
+
+fn ids(messages: &[Message]) -> Vec<&str> {
+    messages.iter().map(|m| m.id.as_str()).collect()
+}
+
+/// u1 -> a1 -> u2 -> a2, with a2b regenerated next to a2 and u2b edited next to u2
+fn branched_chat() -> MessageService {
+    let service = service();
+    add(&service, "u1", None, 1);
+    add(&service, "a1", Some("u1"), 2);
+    add(&service, "u2", Some("a1"), 3);
+    add(&service, "a2", Some("u2"), 4);
+
+    service.branch_from("chat", Some("u2")).unwrap();
+    add(&service, "a2b", Some("u2"), 5);
+
+    service.branch_from("chat", Some("a1")).unwrap();
+    add(&service, "u2b", Some("a1"), 6);
+    add(&service, "a3", Some("u2b"), 7);
+    service
+}
+
+#[test]
+fn walks_the_active_path_from_the_root() {
+    let service = branched_chat();
+
+    let path = service.get_active_path("chat").unwrap();
+    assert_eq!(ids(&path), vec!["u1", "a1", "u2b", "a3"]);
+    assert_eq!(service.get_by_chat_id("chat").unwrap().len(), 7);
+    assert!(service.get_active_path("other").unwrap().is_empty());
+}
+
+#[test]
+fn moves_the_active_leaf_only_from_the_parent() {
+    let service = service();
+    add(&service, "u1", None, 1);
+    add(&service, "a1", Some("u1"), 2);
+    add(&service, "a1b", Some("u1"), 2);
+
+    assert_eq!(
+        ids(&service.get_active_path("chat").unwrap()),
+        vec!["u1", "a1"]
+    );
+    let branches = service.get_branches("chat").unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].parent_message_id.as_deref(), Some("u1"));
+    assert_eq!(branches[0].message_ids, vec!["a1", "a1b"]);
+    assert_eq!(branches[0].active_message_id, "a1");
+}
+
+#[test]
+fn lists_the_forks_on_the_active_path() {
+    let service = branched_chat();
+
+    let branches = service.get_branches("chat").unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].message_ids, vec!["u2", "u2b"]);
+    assert_eq!(branches[0].active_message_id, "u2b");
+
+    service.switch_branch("chat", "a2b").unwrap();
+    let branches = service.get_branches("chat").unwrap();
+    assert_eq!(branches.len(), 2);
+    assert_eq!(branches[0].active_message_id, "u2");
+    assert_eq!(branches[1].message_ids, vec!["a2", "a2b"]);
+    assert_eq!(branches[1].active_message_id, "a2b");
+}
+
+#[test]
+fn switches_to_the_newest_leaf_below_a_sibling() {
+    let service = branched_chat();
+
+    let path = service.switch_branch("chat", "u2").unwrap();
+    assert_eq!(ids(&path), vec!["u1", "a1", "u2", "a2b"]);
+
+    let path = service.switch_branch("chat", "a2").unwrap();
+    assert_eq!(ids(&path), vec!["u1", "a1", "u2", "a2"]);
+
+    assert!(matches!(
+        service.switch_branch("other", "a2"),
+        Err(AppError::NotFound(_))
+    ));
+    assert!(matches!(
+        service.switch_branch("chat", "missing"),
+        Err(AppError::NotFound(_))
+    ));
+}
+
+#[test]
+fn branches_from_a_parent_or_from_the_root() {
+    let service = branched_chat();
+
+    service.branch_from("chat", None).unwrap();
+    assert!(service.get_active_path("chat").unwrap().is_empty());
+    add(&service, "u0", None, 8);
+    assert_eq!(ids(&service.get_active_path("chat").unwrap()), vec!["u0"]);
+
+    let roots = service.get_branches("chat").unwrap();
+    assert_eq!(roots[0].parent_message_id, None);
+    assert_eq!(roots[0].message_ids, vec!["u1", "u0"]);
+}
+
+#[test]
+fn deletes_every_branch_below_a_message() {
+    let service = branched_chat();
+
+    service
+        .delete_messages_after("chat".to_string(), "a1".to_string())
+        .unwrap();
+    assert_eq!(
+        ids(&service.get_active_path("chat").unwrap()),
+        vec!["u1", "a1"]
+    );
+    assert_eq!(service.get_by_chat_id("chat").unwrap().len(), 2);
+    assert!(service.get_branches("chat").unwrap().is_empty());
+}
+
+#[test]
+fn keeps_every_compared_reply_as_a_sibling() {
+    let service = service();
+    add(&service, "u1", None, 1);
+    let models = ["gpt-4o", "claude-sonnet", "gemini-pro"];
+
+    let replies = service
+        .create_sibling_replies(
+            "chat",
+            "u1",
+            2,
+            models
+                .iter()
+                .map(|model| format!(r#"{{"compare": {{"group": "u1", "model": "{model}"}}}}"#))
+                .collect(),
+        )
+        .unwrap();
+
+    assert_eq!(replies.len(), models.len());
+    assert!(replies
+        .iter()
+        .all(|reply| reply.parent_message_id.as_deref() == Some("u1")
+            && reply.role == "assistant"
+            && reply.content.is_empty()));
+    let branches = service.get_branches("chat").unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].message_ids, ids(&replies));
+    assert_eq!(branches[0].active_message_id, replies[0].id);
+    assert_eq!(
+        ids(&service.get_active_path("chat").unwrap()),
+        vec!["u1", replies[0].id.as_str()]
+    );
+
+    let path = service.switch_branch("chat", &replies[2].id).unwrap();
+    assert_eq!(
+        path[1]
+            .metadata
+            .as_deref()
+            .map(|m| m.contains("gemini-pro")),
+        Some(true)
+    );
+}
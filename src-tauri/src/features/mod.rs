@@ -1,11 +1,17 @@
 pub mod chat;
+pub mod export;
 pub mod hub;
+pub mod import;
 pub mod llm_connection;
 pub mod mcp_connection;
 pub mod message;
 pub mod prompt;
 pub mod runtime;
+pub mod search;
+pub mod secrets;
 pub mod tool;
+pub mod tool_audit;
+pub mod tool_permission;
 pub mod usage;
 pub mod workspace;
 
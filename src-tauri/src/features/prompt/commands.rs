@@ -1,6 +1,8 @@
-use super::models::Prompt;
+use super::models::{MCPPromptEntry, ParsedPromptTemplate, Prompt};
+use super::template_service::PromptTemplateService;
 use crate::error::AppError;
 use crate::state::AppState;
+use std::collections::HashMap;
 use tauri::State;
 
 #[tauri::command]
@@ -44,3 +46,84 @@ pub fn delete_prompt(id: String, state: State<'_, AppState>) -> Result<(), AppEr
         .delete(id)
         .map_err(|e| AppError::Prompt(e.to_string()))
 }
+
+#[tauri::command]
+pub fn get_mcp_prompts(state: State<'_, AppState>) -> Result<Vec<MCPPromptEntry>, AppError> {
+    let cached_prompts = state
+        .tool_service
+        .get_cached_prompts()
+        .map_err(|e| AppError::Prompt(e.to_string()))?;
+
+    let entries = cached_prompts
+        .into_iter()
+        .flat_map(|(connection, prompts)| {
+            prompts.into_iter().map(move |prompt| MCPPromptEntry {
+                id: format!("mcp:{}:{}", connection.id, prompt.name),
+                connection_id: connection.id.clone(),
+                connection_name: connection.name.clone(),
+                required_variables: prompt
+                    .arguments
+                    .iter()
+                    .filter(|a| a.required)
+                    .map(|a| a.name.clone())
+                    .collect(),
+                variables: prompt.arguments.into_iter().map(|a| a.name).collect(),
+                name: prompt.name,
+                title: prompt.title,
+                description: prompt.description,
+            })
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+/// Render an MCP prompt with the argument values collected from the user.
+/// Empty values are left out, and a missing required argument is rejected
+/// before anything is sent to the server.
+#[tauri::command]
+pub async fn get_mcp_prompt_template(
+    connection_id: String,
+    name: String,
+    arguments: Option<HashMap<String, String>>,
+    state: State<'_, AppState>,
+) -> Result<ParsedPromptTemplate, AppError> {
+    let prompt = state
+        .tool_service
+        .get_cached_prompts()
+        .map_err(|e| AppError::Prompt(e.to_string()))?
+        .into_iter()
+        .filter(|(connection, _)| connection.id == connection_id)
+        .flat_map(|(_, prompts)| prompts)
+        .find(|prompt| prompt.name == name)
+        .ok_or_else(|| AppError::NotFound(format!("MCP prompt not found: {name}")))?;
+
+    let mut arguments = arguments.unwrap_or_default();
+    arguments.retain(|_, value| !value.trim().is_empty());
+    if let Some(missing) = prompt
+        .arguments
+        .iter()
+        .find(|argument| argument.required && !arguments.contains_key(&argument.name))
+    {
+        return Err(AppError::Validation(format!(
+            "Missing required argument for MCP prompt {name}: {}",
+            missing.name
+        )));
+    }
+
+    let content = state
+        .tool_service
+        .get_prompt(&connection_id, &name, arguments)
+        .await
+        .map_err(|e| AppError::Prompt(e.to_string()))?;
+
+    let template_service = PromptTemplateService::new();
+    let variables = template_service.extract_variables(&content);
+
+    Ok(ParsedPromptTemplate {
+        title: prompt.title.unwrap_or(prompt.name),
+        description: prompt.description.unwrap_or_default(),
+        content,
+        variables,
+    })
+}
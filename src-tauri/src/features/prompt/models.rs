@@ -9,6 +9,19 @@ pub struct Prompt {
     pub updated_at: i64,
 }
 
+/// A prompt published by a connected MCP server, listed next to local prompts
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MCPPromptEntry {
+    pub id: String, // "mcp:{connection_id}:{name}"
+    pub connection_id: String,
+    pub connection_name: String,
+    pub name: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub variables: Vec<String>,
+    pub required_variables: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParsedPromptTemplate {
     pub title: String,
@@ -155,7 +155,7 @@ impl PromptTemplateService {
     }
 
     /// Extract variables from content using regex {{`variable_name`}}
-    fn extract_variables(&self, content: &str) -> Vec<String> {
+    pub fn extract_variables(&self, content: &str) -> Vec<String> {
         let variable_regex = Regex::new(r"\{\{(\w+)\}\}").unwrap();
         let mut variables = std::collections::HashSet::new();
 
@@ -0,0 +1,12 @@
+use super::models::{SearchQuery, SearchResults};
+use crate::error::AppError;
+use crate::state::AppState;
+use tauri::State;
+
+#[tauri::command]
+pub fn search_history(
+    query: SearchQuery,
+    state: State<'_, AppState>,
+) -> Result<SearchResults, AppError> {
+    state.search_service.search(&query)
+}
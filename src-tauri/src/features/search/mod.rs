@@ -0,0 +1,9 @@
+pub mod commands;
+pub mod models;
+pub mod repository;
+pub mod service;
+#[cfg(test)]
+mod tests;
+
+pub use repository::*;
+pub use service::*;
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// Search request from the UI
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SearchQuery {
+    pub query: String,
+    pub workspace_id: Option<String>,
+    pub roles: Option<Vec<String>>, // Message roles to include, chat title hits are skipped when set
+    pub from: Option<i64>,          // Unix seconds, inclusive
+    pub to: Option<i64>,            // Unix seconds, inclusive
+    pub agent_id: Option<String>,
+    pub limit: Option<u32>,
+}
+
+/// A ranked search hit, either a message or a chat title
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchResult {
+    pub kind: String, // "message" | "chat"
+    pub chat_id: String,
+    pub chat_title: String,
+    pub workspace_id: String,
+    pub message_id: Option<String>,
+    pub role: Option<String>,
+    pub snippet: String, // Matches wrapped in <mark></mark>, the rest is raw text
+    pub timestamp: i64,
+    pub rank: f64, // bm25 score, lower is better, only comparable within one group
+}
+
+/// Search hits grouped by where they matched
+/// bm25 scores from the message and chat title indexes are on different scales, so each group
+/// is ranked on its own instead of being merged into one list
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SearchResults {
+    pub messages: Vec<SearchResult>,
+    pub chats: Vec<SearchResult>,
+}
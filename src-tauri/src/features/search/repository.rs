@@ -0,0 +1,163 @@
+use super::models::{SearchQuery, SearchResult};
+use crate::error::AppError;
+use rusqlite::{params, Connection};
+use std::sync::Arc;
+use tauri::AppHandle;
+
+pub trait SearchRepository: Send + Sync {
+    fn search_messages(
+        &self,
+        match_expr: &str,
+        query: &SearchQuery,
+        limit: u32,
+    ) -> Result<Vec<SearchResult>, AppError>;
+    fn search_chats(
+        &self,
+        match_expr: &str,
+        query: &SearchQuery,
+        limit: u32,
+    ) -> Result<Vec<SearchResult>, AppError>;
+}
+
+pub struct SqliteSearchRepository {
+    app: Arc<AppHandle>,
+}
+
+impl SqliteSearchRepository {
+    pub const fn new(app: Arc<AppHandle>) -> Self {
+        Self { app }
+    }
+}
+
+impl SearchRepository for SqliteSearchRepository {
+    fn search_messages(
+        &self,
+        match_expr: &str,
+        query: &SearchQuery,
+        limit: u32,
+    ) -> Result<Vec<SearchResult>, AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        select_messages(&conn, match_expr, query, limit)
+    }
+
+    fn search_chats(
+        &self,
+        match_expr: &str,
+        query: &SearchQuery,
+        limit: u32,
+    ) -> Result<Vec<SearchResult>, AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        select_chats(&conn, match_expr, query, limit)
+    }
+}
+
+pub(super) fn select_messages(
+    conn: &Connection,
+    match_expr: &str,
+    query: &SearchQuery,
+    limit: u32,
+) -> Result<Vec<SearchResult>, AppError> {
+    let roles = query
+        .roles
+        .as_ref()
+        .filter(|roles| !roles.is_empty())
+        .map(serde_json::to_string)
+        .transpose()?;
+
+    // Content matches weigh more than reasoning matches
+    let mut stmt = conn.prepare(
+        "SELECT m.id, m.chat_id, c.title, c.workspace_id, m.role, m.timestamp,
+                snippet(messages_fts, -1, '<mark>', '</mark>', '…', 24),
+                bm25(messages_fts, 0.0, 0.0, 1.0, 0.5) AS rank
+         FROM messages_fts
+         JOIN messages m ON m.id = messages_fts.message_id
+         JOIN chats c ON c.id = m.chat_id
+         WHERE messages_fts MATCH ?1
+           AND (?2 IS NULL OR c.workspace_id = ?2)
+           AND (?3 IS NULL OR m.role IN (SELECT value FROM json_each(?3)))
+           AND (?4 IS NULL OR m.timestamp >= ?4)
+           AND (?5 IS NULL OR m.timestamp <= ?5)
+           AND (?6 IS NULL OR c.agent_id = ?6)
+         ORDER BY rank
+         LIMIT ?7",
+    )?;
+
+    let results = stmt
+        .query_map(
+            params![
+                match_expr,
+                query.workspace_id,
+                roles,
+                query.from,
+                query.to,
+                query.agent_id,
+                limit
+            ],
+            |row| {
+                Ok(SearchResult {
+                    kind: "message".to_string(),
+                    message_id: Some(row.get(0)?),
+                    chat_id: row.get(1)?,
+                    chat_title: row.get(2)?,
+                    workspace_id: row.get(3)?,
+                    role: Some(row.get(4)?),
+                    timestamp: row.get(5)?,
+                    snippet: row.get(6)?,
+                    rank: row.get(7)?,
+                })
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(results)
+}
+
+pub(super) fn select_chats(
+    conn: &Connection,
+    match_expr: &str,
+    query: &SearchQuery,
+    limit: u32,
+) -> Result<Vec<SearchResult>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT c.id, c.title, c.workspace_id, c.updated_at,
+                highlight(chats_fts, 1, '<mark>', '</mark>'),
+                bm25(chats_fts, 0.0, 1.0) AS rank
+         FROM chats_fts
+         JOIN chats c ON c.id = chats_fts.chat_id
+         WHERE chats_fts MATCH ?1
+           AND (?2 IS NULL OR c.workspace_id = ?2)
+           AND (?3 IS NULL OR c.updated_at >= ?3)
+           AND (?4 IS NULL OR c.updated_at <= ?4)
+           AND (?5 IS NULL OR c.agent_id = ?5)
+         ORDER BY rank
+         LIMIT ?6",
+    )?;
+
+    let results = stmt
+        .query_map(
+            params![
+                match_expr,
+                query.workspace_id,
+                query.from,
+                query.to,
+                query.agent_id,
+                limit
+            ],
+            |row| {
+                Ok(SearchResult {
+                    kind: "chat".to_string(),
+                    chat_id: row.get(0)?,
+                    chat_title: row.get(1)?,
+                    workspace_id: row.get(2)?,
+                    timestamp: row.get(3)?,
+                    snippet: row.get(4)?,
+                    rank: row.get(5)?,
+                    message_id: None,
+                    role: None,
+                })
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(results)
+}
@@ -0,0 +1,52 @@
+use super::models::{SearchQuery, SearchResults};
+use super::repository::SearchRepository;
+use crate::error::AppError;
+use std::sync::Arc;
+
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_LIMIT: u32 = 200;
+
+pub struct SearchService {
+    repository: Arc<dyn SearchRepository>,
+}
+
+impl SearchService {
+    pub fn new(repository: Arc<dyn SearchRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Turn free text into an FTS5 query: every word must match, the last one as a prefix
+    /// Words are quoted so FTS5 syntax characters in user input are taken literally
+    pub(super) fn to_match_expression(query: &str) -> Option<String> {
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+            .collect();
+        if terms.is_empty() {
+            return None;
+        }
+
+        let mut expression = terms.join(" ");
+        expression.push('*');
+        Some(expression)
+    }
+
+    /// Search messages and chat titles, `limit` applies to each group
+    pub fn search(&self, query: &SearchQuery) -> Result<SearchResults, AppError> {
+        let Some(match_expr) = Self::to_match_expression(&query.query) else {
+            return Ok(SearchResults::default());
+        };
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+        let messages = self.repository.search_messages(&match_expr, query, limit)?;
+
+        // Chat titles have no role, so they only show up in unfiltered searches
+        let chats = if query.roles.as_ref().is_none_or(Vec::is_empty) {
+            self.repository.search_chats(&match_expr, query, limit)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(SearchResults { messages, chats })
+    }
+}
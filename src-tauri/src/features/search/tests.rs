@@ -0,0 +1,224 @@
+use super::models::{SearchQuery, SearchResult, SearchResults};
+use super::repository::{select_chats, select_messages};
+use super::*;
+use crate::error::AppError;
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// The `SQLite` queries on a migrated in-memory database
+struct MemoryRepository {
+    conn: Mutex<Connection>,
+}
+
+impl MemoryRepository {
+    fn execute(&self, sql: &str, values: impl rusqlite::Params) {
+        self.conn.lock().unwrap().execute(sql, values).unwrap();
+    }
+
+    fn add_chat(&self, id: &str, title: &str) {
+        self.execute(
+            "INSERT INTO chats (id, workspace_id, title, created_at, updated_at) VALUES (?1, 'workspace', ?2, 0, 0)",
+            params![id, title],
+        );
+    }
+
+    fn add_message(&self, id: &str, chat_id: &str, role: &str, content: &str) {
+        self.execute(
+            "INSERT INTO messages (id, chat_id, role, content, timestamp) VALUES (?1, ?2, ?3, ?4, 0)",
+            params![id, chat_id, role, content],
+        );
+    }
+}
+
+impl SearchRepository for MemoryRepository {
+    fn search_messages(
+        &self,
+        match_expr: &str,
+        query: &SearchQuery,
+        limit: u32,
+    ) -> Result<Vec<SearchResult>, AppError> {
+        select_messages(&self.conn.lock().unwrap(), match_expr, query, limit)
+    }
+
+    fn search_chats(
+        &self,
+        match_expr: &str,
+        query: &SearchQuery,
+        limit: u32,
+    ) -> Result<Vec<SearchResult>, AppError> {
+        select_chats(&self.conn.lock().unwrap(), match_expr, query, limit)
+    }
+}
+
+fn repository() -> Arc<MemoryRepository> {
+    let conn = Connection::open_in_memory().unwrap();
+    crate::db::migrations::run_migrations(&conn, Path::new(":memory:")).unwrap();
+    conn.execute(
+        "INSERT INTO workspaces (id, name, created_at) VALUES ('workspace', 'Workspace', 0)",
+        [],
+    )
+    .unwrap();
+    Arc::new(MemoryRepository {
+        conn: Mutex::new(conn),
+    })
+}
+
+fn search(repository: &Arc<MemoryRepository>, text: &str) -> SearchResults {
+    SearchService::new(repository.clone())
+        .search(&SearchQuery {
+            query: text.to_string(),
+            ..SearchQuery::default()
+        })
+        .unwrap()
+}
+
+fn message_ids(results: &SearchResults) -> Vec<&str> {
+    results
+        .messages
+        .iter()
+        .filter_map(|result| result.message_id.as_deref())
+        .collect()
+}
+
+fn chat_ids(results: &SearchResults) -> Vec<&str> {
+    results
+        .chats
+        .iter()
+        .map(|result| result.chat_id.as_str())
+        .collect()
+}
+
+#[test]
+fn keeps_the_message_index_in_sync() {
+    let repository = repository();
+    repository.add_chat("chat", "Untitled");
+    repository.add_message("m1", "chat", "user", "How do I configure rustfmt?");
+
+    let results = search(&repository, "rustfmt");
+    assert_eq!(message_ids(&results), vec!["m1"]);
+    assert_eq!(
+        results.messages[0].snippet,
+        "How do I configure <mark>rustfmt</mark>?"
+    );
+
+    repository.execute(
+        "UPDATE messages SET content = 'Use clippy instead' WHERE id = 'm1'",
+        [],
+    );
+    assert!(message_ids(&search(&repository, "rustfmt")).is_empty());
+    assert_eq!(message_ids(&search(&repository, "clippy")), vec!["m1"]);
+
+    repository.execute(
+        "UPDATE messages SET reasoning = 'thinking about lifetimes' WHERE id = 'm1'",
+        [],
+    );
+    assert_eq!(message_ids(&search(&repository, "lifetimes")), vec!["m1"]);
+
+    repository.execute("DELETE FROM messages WHERE id = 'm1'", []);
+    assert!(message_ids(&search(&repository, "clippy")).is_empty());
+}
+
+#[test]
+fn keeps_the_chat_title_index_in_sync() {
+    let repository = repository();
+    repository.add_chat("chat", "Tokio runtime questions");
+    repository.add_message("m1", "chat", "user", "tokio spawn_blocking");
+
+    assert_eq!(chat_ids(&search(&repository, "runtime")), vec!["chat"]);
+
+    repository.execute(
+        "UPDATE chats SET title = 'Async executors' WHERE id = 'chat'",
+        [],
+    );
+    assert!(chat_ids(&search(&repository, "runtime")).is_empty());
+    assert_eq!(chat_ids(&search(&repository, "executors")), vec!["chat"]);
+
+    // Deleting the chat cascades to its messages, and both leave the index
+    repository.execute("DELETE FROM chats WHERE id = 'chat'", []);
+    let results = search(&repository, "tokio");
+    assert!(results.messages.is_empty());
+    assert!(results.chats.is_empty());
+}
+
+#[test]
+fn ranks_messages_and_chat_titles_separately() {
+    let repository = repository();
+    repository.add_chat("title-hit", "Borrow checker");
+    repository.add_chat("other", "Untitled");
+    repository.add_message("m1", "other", "user", "borrow borrow borrow");
+    repository.add_message(
+        "m2",
+        "other",
+        "assistant",
+        "a long answer that mentions borrow once among many other words",
+    );
+
+    let results = search(&repository, "borrow");
+    assert_eq!(message_ids(&results), vec!["m1", "m2"]);
+    assert!(results
+        .messages
+        .iter()
+        .all(|result| result.kind == "message"));
+    assert_eq!(chat_ids(&results), vec!["title-hit"]);
+    assert_eq!(results.chats[0].kind, "chat");
+
+    // Chat titles have no role to filter on
+    let filtered = SearchService::new(repository)
+        .search(&SearchQuery {
+            query: "borrow".to_string(),
+            roles: Some(vec!["assistant".to_string()]),
+            limit: Some(1),
+            ..SearchQuery::default()
+        })
+        .unwrap();
+    assert_eq!(message_ids(&filtered), vec!["m2"]);
+    assert!(filtered.chats.is_empty());
+}
+
+#[test]
+fn matches_every_word_and_the_last_one_as_a_prefix() {
+    let repository = repository();
+    repository.add_chat("chat", "Untitled");
+    repository.add_message("m1", "chat", "user", "serde deserialize enums");
+    repository.add_message("m2", "chat", "user", "serde derive macros");
+
+    assert_eq!(message_ids(&search(&repository, "serde deser")), vec!["m1"]);
+    assert_eq!(message_ids(&search(&repository, "SERDE Dér")), vec!["m2"]);
+    assert!(message_ids(&search(&repository, "deser serde")).is_empty());
+    assert!(message_ids(&search(&repository, "   ")).is_empty());
+}
+
+#[test]
+fn quotes_fts_syntax_in_user_input() {
+    assert_eq!(SearchService::to_match_expression("  "), None);
+    assert_eq!(
+        SearchService::to_match_expression(r#"say "hi" NEAR(x)"#).as_deref(),
+        Some(r#""say" """hi""" "NEAR(x)"*"#)
+    );
+
+    let repository = repository();
+    repository.add_chat("chat", "Untitled");
+    repository.add_message("m1", "chat", "user", r#"she said "hi" AND left"#);
+
+    for query in [
+        r#""hi"#,
+        "AND",
+        "hi OR",
+        "NOT hi",
+        "content:hi",
+        "hi*",
+        "^hi",
+        "(hi",
+        "-hi",
+        "hi ) (",
+    ] {
+        let results = SearchService::new(repository.clone()).search(&SearchQuery {
+            query: query.to_string(),
+            ..SearchQuery::default()
+        });
+        assert!(results.is_ok(), "{query} failed: {results:?}");
+    }
+    assert_eq!(message_ids(&search(&repository, r#""hi""#)), vec!["m1"]);
+    assert_eq!(message_ids(&search(&repository, "AND")), vec!["m1"]);
+}
@@ -0,0 +1,25 @@
+use super::models::VaultStatus;
+use crate::error::AppError;
+use crate::state::AppState;
+use tauri::State;
+
+#[tauri::command]
+pub fn get_vault_status(state: State<'_, AppState>) -> Result<VaultStatus, AppError> {
+    state.secrets_service.status()
+}
+
+#[tauri::command]
+pub fn unlock_vault(
+    passphrase: String,
+    state: State<'_, AppState>,
+) -> Result<VaultStatus, AppError> {
+    state.secrets_service.unlock(&passphrase)
+}
+
+#[tauri::command]
+pub fn set_vault_passphrase(
+    passphrase: String,
+    state: State<'_, AppState>,
+) -> Result<VaultStatus, AppError> {
+    state.secrets_service.set_passphrase(&passphrase)
+}
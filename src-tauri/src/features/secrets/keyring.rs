@@ -0,0 +1,214 @@
+//! Storage for the vault key in the OS keyring
+//!
+//! macOS uses the login Keychain, Linux the Secret Service (via `secret-tool`)
+//! and Windows the Credential Locker (via `PowerShell`).
+
+use crate::error::AppError;
+
+const SERVICE: &str = "nexo";
+const ACCOUNT: &str = "secrets-vault-key";
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{ACCOUNT, SERVICE};
+    use crate::error::AppError;
+    use security_framework::passwords;
+
+    // errSecItemNotFound
+    const ITEM_NOT_FOUND: i32 = -25300;
+
+    pub fn store(secret: &str) -> Result<(), AppError> {
+        passwords::set_generic_password(SERVICE, ACCOUNT, secret.as_bytes())
+            .map_err(|e| AppError::Secrets(format!("Failed to write to Keychain: {e}")))
+    }
+
+    pub fn load() -> Result<Option<String>, AppError> {
+        match passwords::get_generic_password(SERVICE, ACCOUNT) {
+            Ok(bytes) => String::from_utf8(bytes)
+                .map(Some)
+                .map_err(|e| AppError::Secrets(format!("Invalid key in Keychain: {e}"))),
+            Err(e) if e.code() == ITEM_NOT_FOUND => Ok(None),
+            Err(e) => Err(AppError::Secrets(format!(
+                "Failed to read from Keychain: {e}"
+            ))),
+        }
+    }
+
+    pub fn delete() -> Result<(), AppError> {
+        match passwords::delete_generic_password(SERVICE, ACCOUNT) {
+            Ok(()) => Ok(()),
+            Err(e) if e.code() == ITEM_NOT_FOUND => Ok(()),
+            Err(e) => Err(AppError::Secrets(format!(
+                "Failed to delete from Keychain: {e}"
+            ))),
+        }
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::{run, ACCOUNT, SERVICE};
+    use crate::error::AppError;
+    use std::process::Command;
+
+    fn secret_tool() -> Command {
+        Command::new("secret-tool")
+    }
+
+    pub fn store(secret: &str) -> Result<(), AppError> {
+        let output = run(
+            secret_tool().args([
+                "store",
+                "--label=Nexo secrets vault key",
+                "service",
+                SERVICE,
+                "account",
+                ACCOUNT,
+            ]),
+            Some(secret),
+        )?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(AppError::Secrets(format!(
+                "secret-tool store failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )))
+        }
+    }
+
+    pub fn load() -> Result<Option<String>, AppError> {
+        let output = run(
+            secret_tool().args(["lookup", "service", SERVICE, "account", ACCOUNT]),
+            None,
+        )?;
+        let secret = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if output.status.success() && !secret.is_empty() {
+            return Ok(Some(secret));
+        }
+        // secret-tool exits with 1 and no output when the item does not exist
+        if output.stderr.is_empty() {
+            Ok(None)
+        } else {
+            Err(AppError::Secrets(format!(
+                "secret-tool lookup failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )))
+        }
+    }
+
+    pub fn delete() -> Result<(), AppError> {
+        run(
+            secret_tool().args(["clear", "service", SERVICE, "account", ACCOUNT]),
+            None,
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{run, ACCOUNT, SERVICE};
+    use crate::error::AppError;
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    const LOAD_VAULT: &str = "$ErrorActionPreference = 'Stop'; \
+        [void][Windows.Security.Credentials.PasswordVault, Windows.Security.Credentials, ContentType = WindowsRuntime]; \
+        $vault = New-Object Windows.Security.Credentials.PasswordVault;";
+
+    fn powershell(script: &str) -> Command {
+        let mut command = Command::new("powershell");
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        command.args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            &format!("{LOAD_VAULT} {script}"),
+        ]);
+        command
+    }
+
+    pub fn store(secret: &str) -> Result<(), AppError> {
+        let script = format!(
+            "$secret = [Console]::In.ReadToEnd().Trim(); \
+             $vault.Add((New-Object Windows.Security.Credentials.PasswordCredential('{SERVICE}', '{ACCOUNT}', $secret)))"
+        );
+        let output = run(&mut powershell(&script), Some(secret))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(AppError::Secrets(format!(
+                "Failed to write to Credential Locker: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )))
+        }
+    }
+
+    pub fn load() -> Result<Option<String>, AppError> {
+        let script = format!(
+            "try {{ $credential = $vault.Retrieve('{SERVICE}', '{ACCOUNT}') }} catch {{ exit 1 }}; \
+             $credential.RetrievePassword(); [Console]::Out.Write($credential.Password)"
+        );
+        let output = run(&mut powershell(&script), None)?;
+        let secret = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if output.status.success() && !secret.is_empty() {
+            Ok(Some(secret))
+        } else if output.status.code() == Some(1) {
+            Ok(None)
+        } else {
+            Err(AppError::Secrets(format!(
+                "Failed to read from Credential Locker: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )))
+        }
+    }
+
+    pub fn delete() -> Result<(), AppError> {
+        let script = format!(
+            "try {{ $vault.Remove($vault.Retrieve('{SERVICE}', '{ACCOUNT}')) }} catch {{ }}"
+        );
+        run(&mut powershell(&script), None)?;
+        Ok(())
+    }
+}
+
+/// Run a keyring helper, feeding `input` through stdin so the secret never shows up in argv
+#[cfg(not(target_os = "macos"))]
+fn run(
+    command: &mut std::process::Command,
+    input: Option<&str>,
+) -> Result<std::process::Output, AppError> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::Secrets(format!("OS keyring is not available: {e}")))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Some(input) = input {
+            stdin.write_all(input.as_bytes())?;
+        }
+    }
+
+    Ok(child.wait_with_output()?)
+}
+
+/// Save the vault key in the OS keyring
+pub fn store(secret: &str) -> Result<(), AppError> {
+    platform::store(secret)
+}
+
+/// Read the vault key from the OS keyring, `None` if it was never stored
+pub fn load() -> Result<Option<String>, AppError> {
+    platform::load()
+}
+
+/// Remove the vault key from the OS keyring
+pub fn delete() -> Result<(), AppError> {
+    platform::delete()
+}
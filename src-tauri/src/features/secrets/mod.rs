@@ -0,0 +1,9 @@
+pub mod commands;
+pub mod keyring;
+pub mod models;
+pub mod redaction;
+pub mod repository;
+pub mod service;
+
+pub use repository::*;
+pub use service::*;
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+/// Where the vault key comes from
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultKeySource {
+    Keyring,
+    Passphrase,
+}
+
+impl VaultKeySource {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Keyring => "keyring",
+            Self::Passphrase => "passphrase",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "keyring" => Some(Self::Keyring),
+            "passphrase" => Some(Self::Passphrase),
+            _ => None,
+        }
+    }
+}
+
+/// Vault state as shown in the UI
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VaultStatus {
+    /// No key has been set up yet (OS keyring unavailable and no passphrase set)
+    pub initialized: bool,
+    /// Key is loaded, secrets can be read and written
+    pub unlocked: bool,
+    pub key_source: Option<VaultKeySource>,
+}
+
+/// Persisted vault metadata (single row)
+#[derive(Debug, Clone)]
+pub struct VaultMetadata {
+    pub key_source: VaultKeySource,
+    pub kdf_salt: Option<String>, // base64, passphrase mode only
+    pub key_check: String,        // Known plaintext encrypted with the vault key
+}
@@ -0,0 +1,134 @@
+use regex::Regex;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::sync::{LazyLock, RwLock};
+
+pub const REDACTED: &str = "[REDACTED]";
+
+// Shorter values are too likely to collide with ordinary words
+const MIN_SECRET_LEN: usize = 8;
+
+// Secret values seen by the vault, masked verbatim wherever they show up
+static KNOWN_SECRETS: LazyLock<RwLock<HashSet<String>>> =
+    LazyLock::new(|| RwLock::new(HashSet::new()));
+
+// Credential shapes that are masked even when the vault has never seen the value
+static SECRET_PATTERNS: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new(|| {
+    vec![
+        (
+            Regex::new(r"(?i)(bearer\s+)[A-Za-z0-9\-._~+/]+=*").unwrap(),
+            "${1}[REDACTED]",
+        ),
+        (
+            Regex::new(r"\b(sk|pk|rk)-[A-Za-z0-9_\-]{12,}").unwrap(),
+            "[REDACTED]",
+        ),
+        (Regex::new(r"\bAIza[0-9A-Za-z_\-]{30,}").unwrap(), "[REDACTED]"),
+        (
+            Regex::new(r#"(?i)("?(?:api[_-]?key|x-api-key|authorization|access[_-]?token|refresh[_-]?token|client[_-]?secret|password|secret)"?\s*[:=]\s*"?)([^"\s,}&]+)"#)
+                .unwrap(),
+            "${1}[REDACTED]",
+        ),
+    ]
+});
+
+/// Remember a secret so it is masked in logs and Sentry reports
+/// JSON objects (headers, env vars) register each of their string values
+pub fn register(value: &str) {
+    let mut values = Vec::new();
+    match serde_json::from_str::<serde_json::Value>(value) {
+        Ok(serde_json::Value::Object(map)) => {
+            for item in map.values() {
+                if let Some(text) = item.as_str() {
+                    values.push(text.to_string());
+                    // "Bearer xyz" headers: also mask the bare token
+                    if let Some((_, token)) = text.split_once(' ') {
+                        values.push(token.to_string());
+                    }
+                }
+            }
+        }
+        _ => values.push(value.to_string()),
+    }
+
+    values.retain(|v| v.trim().len() >= MIN_SECRET_LEN);
+    if values.is_empty() {
+        return;
+    }
+
+    let mut known = KNOWN_SECRETS
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    known.extend(values.into_iter().map(|v| v.trim().to_string()));
+}
+
+/// Mask known secrets and credential-looking values in a piece of text
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let mut result = Cow::Borrowed(text);
+
+    {
+        let known = KNOWN_SECRETS
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        for secret in known.iter() {
+            if result.contains(secret.as_str()) {
+                result = Cow::Owned(result.replace(secret.as_str(), REDACTED));
+            }
+        }
+    }
+
+    for (pattern, replacement) in SECRET_PATTERNS.iter() {
+        if pattern.is_match(&result) {
+            result = Cow::Owned(pattern.replace_all(&result, *replacement).into_owned());
+        }
+    }
+
+    result
+}
+
+fn redact_string(value: &mut String) {
+    if let Cow::Owned(redacted) = redact(value) {
+        *value = redacted;
+    }
+}
+
+fn redact_value(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(text) => redact_string(text),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_value),
+        serde_json::Value::Object(map) => map.values_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+/// Sentry `before_breadcrumb` hook
+pub fn redact_breadcrumb(mut breadcrumb: sentry::Breadcrumb) -> sentry::Breadcrumb {
+    if let Some(message) = breadcrumb.message.as_mut() {
+        redact_string(message);
+    }
+    breadcrumb.data.values_mut().for_each(redact_value);
+    breadcrumb
+}
+
+/// Sentry `before_send` hook
+pub fn redact_event(
+    mut event: sentry::protocol::Event<'static>,
+) -> sentry::protocol::Event<'static> {
+    if let Some(message) = event.message.as_mut() {
+        redact_string(message);
+    }
+    if let Some(logentry) = event.logentry.as_mut() {
+        redact_string(&mut logentry.message);
+        logentry.params.iter_mut().for_each(redact_value);
+    }
+    for exception in &mut event.exception.values {
+        if let Some(value) = exception.value.as_mut() {
+            redact_string(value);
+        }
+    }
+    for breadcrumb in &mut event.breadcrumbs.values {
+        *breadcrumb = redact_breadcrumb(std::mem::take(breadcrumb));
+    }
+    event.extra.values_mut().for_each(redact_value);
+    event
+}
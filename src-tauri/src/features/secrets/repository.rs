@@ -0,0 +1,103 @@
+use super::models::{VaultKeySource, VaultMetadata};
+use crate::error::AppError;
+use rusqlite::{params, Connection};
+use std::sync::Arc;
+use tauri::AppHandle;
+
+pub trait SecretsRepository: Send + Sync {
+    fn get_metadata(&self) -> Result<Option<VaultMetadata>, AppError>;
+    fn save_metadata(&self, metadata: &VaultMetadata) -> Result<(), AppError>;
+    /// Re-encrypt every stored secret and save the new key metadata in one transaction
+    fn rotate(
+        &self,
+        metadata: &VaultMetadata,
+        rewrite: &mut dyn FnMut(&str) -> Result<Option<String>, AppError>,
+    ) -> Result<usize, AppError>;
+    /// Encrypt secrets still stored in plaintext
+    fn migrate_plaintext(
+        &self,
+        encrypt: &dyn Fn(&str) -> Result<String, AppError>,
+    ) -> Result<usize, AppError>;
+}
+
+pub struct SqliteSecretsRepository {
+    app: Arc<AppHandle>,
+}
+
+impl SqliteSecretsRepository {
+    pub const fn new(app: Arc<AppHandle>) -> Self {
+        Self { app }
+    }
+
+    fn upsert_metadata(conn: &Connection, metadata: &VaultMetadata) -> Result<(), AppError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        conn.execute(
+            "INSERT INTO secrets_vault (id, key_source, kdf_salt, key_check, created_at, updated_at) VALUES (1, ?1, ?2, ?3, ?4, ?4)
+             ON CONFLICT(id) DO UPDATE SET key_source = excluded.key_source, kdf_salt = excluded.kdf_salt, key_check = excluded.key_check, updated_at = excluded.updated_at",
+            params![metadata.key_source.as_str(), metadata.kdf_salt, metadata.key_check, now],
+        )?;
+        Ok(())
+    }
+}
+
+impl SecretsRepository for SqliteSecretsRepository {
+    fn get_metadata(&self) -> Result<Option<VaultMetadata>, AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        let result = conn.query_row(
+            "SELECT key_source, kdf_salt, key_check FROM secrets_vault WHERE id = 1",
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            },
+        );
+
+        match result {
+            Ok((key_source, kdf_salt, key_check)) => {
+                let key_source = VaultKeySource::parse(&key_source).ok_or_else(|| {
+                    AppError::Secrets(format!("Unknown vault key source: {key_source}"))
+                })?;
+                Ok(Some(VaultMetadata {
+                    key_source,
+                    kdf_salt,
+                    key_check,
+                }))
+            }
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save_metadata(&self, metadata: &VaultMetadata) -> Result<(), AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        Self::upsert_metadata(&conn, metadata)
+    }
+
+    fn rotate(
+        &self,
+        metadata: &VaultMetadata,
+        rewrite: &mut dyn FnMut(&str) -> Result<Option<String>, AppError>,
+    ) -> Result<usize, AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        let tx = conn.unchecked_transaction()?;
+        let updated = crate::db::migrations::rewrite_secret_columns(&tx, rewrite)?;
+        Self::upsert_metadata(&tx, metadata)?;
+        tx.commit()?;
+        Ok(updated)
+    }
+
+    fn migrate_plaintext(
+        &self,
+        encrypt: &dyn Fn(&str) -> Result<String, AppError>,
+    ) -> Result<usize, AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        crate::db::migrations::migrate_plaintext_secrets(&conn, encrypt)
+    }
+}
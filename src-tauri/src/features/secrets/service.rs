@@ -0,0 +1,365 @@
+use super::keyring;
+use super::models::{VaultKeySource, VaultMetadata, VaultStatus};
+use super::redaction;
+use super::repository::SecretsRepository;
+use crate::error::AppError;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::num::NonZeroU32;
+use std::sync::{Arc, RwLock};
+
+#[cfg(test)]
+mod tests;
+
+// Prefix of values encrypted by the vault, versioned so the scheme can change later
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+// Known plaintext stored encrypted to verify a key before using it
+const KEY_CHECK_PLAINTEXT: &str = "nexo-secrets-vault";
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+#[cfg(not(test))]
+const PBKDF2_ITERATIONS: u32 = 600_000;
+// Unoptimized test builds would spend seconds on every key derivation
+#[cfg(test)]
+const PBKDF2_ITERATIONS: u32 = 1_000;
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Whether a stored value was encrypted by the vault
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+fn locked_error() -> AppError {
+    AppError::Secrets("Secret vault is locked, unlock it with your passphrase".to_string())
+}
+
+/// Fail with a "vault locked" error when a secret could not be revealed
+/// Call before handing a secret read through `SecretsService::reveal` to a provider or server
+pub fn ensure_unlocked(value: &str) -> Result<(), AppError> {
+    if is_encrypted(value) {
+        Err(locked_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Encrypts secret columns at rest (AES-256-GCM)
+///
+/// The key lives in the OS keyring when available, otherwise it is derived
+/// from a user passphrase and must be unlocked after every start.
+pub struct SecretsService {
+    repository: Arc<dyn SecretsRepository>,
+    key: RwLock<Option<LessSafeKey>>,
+    rng: SystemRandom,
+}
+
+impl SecretsService {
+    pub fn new(repository: Arc<dyn SecretsRepository>) -> Self {
+        Self {
+            repository,
+            key: RwLock::new(None),
+            rng: SystemRandom::new(),
+        }
+    }
+
+    /// Load the vault key on startup, creating a keyring-backed key on first run
+    /// Failures leave the vault locked instead of failing the app
+    pub fn initialize(&self) -> Result<(), AppError> {
+        match self.repository.get_metadata()? {
+            Some(metadata) if metadata.key_source == VaultKeySource::Keyring => {
+                match keyring::load() {
+                    Ok(Some(encoded)) => {
+                        let bytes = STANDARD.decode(encoded.trim()).map_err(|e| {
+                            AppError::Secrets(format!("Invalid vault key in OS keyring: {e}"))
+                        })?;
+                        self.unlock_with(&metadata, &bytes)?;
+                    }
+                    Ok(None) => {
+                        tracing::error!("Vault key is missing from the OS keyring");
+                        return Ok(());
+                    }
+                    Err(e) => {
+                        tracing::error!(error = %e, "Failed to read vault key from the OS keyring");
+                        return Ok(());
+                    }
+                }
+            }
+            // Passphrase vaults wait for unlock_vault
+            Some(_) => return Ok(()),
+            None => {
+                let mut bytes = [0u8; KEY_LEN];
+                self.fill_random(&mut bytes)?;
+                let encoded = STANDARD.encode(bytes);
+
+                // Read the key back, some keyring daemons accept writes they never persist
+                let stored = keyring::store(&encoded)
+                    .and_then(|()| keyring::load())
+                    .map(|loaded| loaded.as_deref().map(str::trim) == Some(encoded.as_str()));
+                match stored {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        tracing::warn!(
+                            "OS keyring did not keep the vault key, a passphrase is required"
+                        );
+                        return Ok(());
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "OS keyring unavailable, a passphrase is required");
+                        return Ok(());
+                    }
+                }
+
+                let key = Self::make_key(&bytes)?;
+                let metadata = VaultMetadata {
+                    key_source: VaultKeySource::Keyring,
+                    kdf_salt: None,
+                    key_check: self.seal(&key, KEY_CHECK_PLAINTEXT)?,
+                };
+                self.repository.save_metadata(&metadata)?;
+                *self.key_mut() = Some(key);
+            }
+        }
+
+        self.migrate_plaintext()
+    }
+
+    pub fn status(&self) -> Result<VaultStatus, AppError> {
+        let metadata = self.repository.get_metadata()?;
+        Ok(VaultStatus {
+            initialized: metadata.is_some(),
+            unlocked: self.is_unlocked(),
+            key_source: metadata.map(|m| m.key_source),
+        })
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.key
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .is_some()
+    }
+
+    /// Unlock a passphrase-protected vault
+    pub fn unlock(&self, passphrase: &str) -> Result<VaultStatus, AppError> {
+        let metadata = self
+            .repository
+            .get_metadata()?
+            .ok_or_else(|| AppError::Secrets("No vault passphrase has been set".to_string()))?;
+        if metadata.key_source != VaultKeySource::Passphrase {
+            return Err(AppError::Validation(
+                "Vault key is stored in the OS keyring, no passphrase is needed".to_string(),
+            ));
+        }
+
+        let salt = metadata
+            .kdf_salt
+            .as_deref()
+            .ok_or_else(|| AppError::Secrets("Vault salt is missing".to_string()))
+            .and_then(|salt| {
+                STANDARD
+                    .decode(salt)
+                    .map_err(|e| AppError::Secrets(format!("Invalid vault salt: {e}")))
+            })?;
+        let bytes = Self::derive_key(passphrase, &salt);
+        self.unlock_with(&metadata, &bytes)?;
+        self.migrate_plaintext()?;
+        self.status()
+    }
+
+    /// Protect the vault with a passphrase instead of the OS keyring, re-encrypting all secrets
+    /// Also used to change an existing passphrase (the vault must be unlocked)
+    pub fn set_passphrase(&self, passphrase: &str) -> Result<VaultStatus, AppError> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+            return Err(AppError::Validation(format!(
+                "Passphrase must be at least {MIN_PASSPHRASE_LEN} characters"
+            )));
+        }
+
+        let previous = self.repository.get_metadata()?;
+        let mut current_key = self.key_mut();
+        if previous.is_some() && current_key.is_none() {
+            return Err(locked_error());
+        }
+
+        let mut salt = [0u8; SALT_LEN];
+        self.fill_random(&mut salt)?;
+        let new_key = Self::make_key(&Self::derive_key(passphrase, &salt))?;
+        let metadata = VaultMetadata {
+            key_source: VaultKeySource::Passphrase,
+            kdf_salt: Some(STANDARD.encode(salt)),
+            key_check: self.seal(&new_key, KEY_CHECK_PLAINTEXT)?,
+        };
+
+        let old_key = current_key.as_ref();
+        self.repository.rotate(&metadata, &mut |value| {
+            let plaintext = if is_encrypted(value) {
+                Self::open(old_key.ok_or_else(locked_error)?, value)?
+            } else {
+                value.to_string()
+            };
+            self.seal(&new_key, &plaintext).map(Some)
+        })?;
+        *current_key = Some(new_key);
+        drop(current_key);
+
+        if previous.is_some_and(|m| m.key_source == VaultKeySource::Keyring) {
+            if let Err(e) = keyring::delete() {
+                tracing::warn!(error = %e, "Failed to remove old vault key from the OS keyring");
+            }
+        }
+
+        self.status()
+    }
+
+    /// Encrypt a secret for storage, empty values are stored as is
+    /// Without a vault (no OS keyring and no passphrase yet) the save fails until a passphrase is set
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, AppError> {
+        if plaintext.is_empty() || is_encrypted(plaintext) {
+            return Ok(plaintext.to_string());
+        }
+        redaction::register(plaintext);
+
+        if let Some(key) = self
+            .key
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .as_ref()
+        {
+            return self.seal(key, plaintext);
+        }
+
+        if self.repository.get_metadata()?.is_some() {
+            return Err(locked_error());
+        }
+        Err(AppError::Secrets(
+            "The OS keyring is unavailable, set a vault passphrase in Settings before saving secrets"
+                .to_string(),
+        ))
+    }
+
+    /// Decrypt a stored secret, plaintext values from before the vault pass through
+    pub fn decrypt(&self, value: &str) -> Result<String, AppError> {
+        if !is_encrypted(value) {
+            redaction::register(value);
+            return Ok(value.to_string());
+        }
+
+        let guard = self
+            .key
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let plaintext = Self::open(guard.as_ref().ok_or_else(locked_error)?, value)?;
+        redaction::register(&plaintext);
+        Ok(plaintext)
+    }
+
+    /// Decrypt a stored secret for reading records, never failing
+    /// While the vault is locked (or the value is corrupted) the ciphertext is returned as is,
+    /// which marks the field as locked for the UI and for `ensure_unlocked`
+    pub fn reveal(&self, value: &str) -> String {
+        match self.decrypt(value) {
+            Ok(plaintext) => plaintext,
+            Err(e) => {
+                if self.is_unlocked() {
+                    tracing::error!(error = %e, "Failed to decrypt stored secret");
+                }
+                value.to_string()
+            }
+        }
+    }
+
+    fn key_mut(&self) -> std::sync::RwLockWriteGuard<'_, Option<LessSafeKey>> {
+        self.key
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn unlock_with(&self, metadata: &VaultMetadata, bytes: &[u8]) -> Result<(), AppError> {
+        let key = Self::make_key(bytes)?;
+        match Self::open(&key, &metadata.key_check) {
+            Ok(check) if check == KEY_CHECK_PLAINTEXT => {
+                *self.key_mut() = Some(key);
+                Ok(())
+            }
+            _ => Err(AppError::Validation(
+                "Incorrect vault passphrase or key".to_string(),
+            )),
+        }
+    }
+
+    fn migrate_plaintext(&self) -> Result<(), AppError> {
+        let migrated = self
+            .repository
+            .migrate_plaintext(&|value| self.encrypt(value))?;
+        if migrated > 0 {
+            tracing::info!(count = migrated, "Encrypted plaintext secrets");
+        }
+        Ok(())
+    }
+
+    fn fill_random(&self, bytes: &mut [u8]) -> Result<(), AppError> {
+        self.rng
+            .fill(bytes)
+            .map_err(|_| AppError::Secrets("Failed to generate random bytes".to_string()))
+    }
+
+    fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; KEY_LEN] {
+        let mut bytes = [0u8; KEY_LEN];
+        ring::pbkdf2::derive(
+            ring::pbkdf2::PBKDF2_HMAC_SHA256,
+            NonZeroU32::new(PBKDF2_ITERATIONS).unwrap(),
+            salt,
+            passphrase.as_bytes(),
+            &mut bytes,
+        );
+        bytes
+    }
+
+    fn make_key(bytes: &[u8]) -> Result<LessSafeKey, AppError> {
+        UnboundKey::new(&AES_256_GCM, bytes)
+            .map(LessSafeKey::new)
+            .map_err(|_| AppError::Secrets("Invalid vault key length".to_string()))
+    }
+
+    /// `enc:v1:` + base64(nonce || ciphertext || tag)
+    fn seal(&self, key: &LessSafeKey, plaintext: &str) -> Result<String, AppError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        self.fill_random(&mut nonce)?;
+
+        let mut in_out = plaintext.as_bytes().to_vec();
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut in_out,
+        )
+        .map_err(|_| AppError::Secrets("Failed to encrypt secret".to_string()))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&in_out);
+        Ok(format!("{ENCRYPTED_PREFIX}{}", STANDARD.encode(payload)))
+    }
+
+    fn open(key: &LessSafeKey, value: &str) -> Result<String, AppError> {
+        let encoded = value
+            .strip_prefix(ENCRYPTED_PREFIX)
+            .ok_or_else(|| AppError::Secrets("Value is not encrypted".to_string()))?;
+        let payload = STANDARD
+            .decode(encoded)
+            .map_err(|e| AppError::Secrets(format!("Corrupted secret: {e}")))?;
+        if payload.len() < NONCE_LEN {
+            return Err(AppError::Secrets("Corrupted secret".to_string()));
+        }
+
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| AppError::Secrets("Corrupted secret".to_string()))?;
+        let mut in_out = ciphertext.to_vec();
+        let plaintext = key
+            .open_in_place(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| AppError::Secrets("Failed to decrypt secret".to_string()))?;
+
+        String::from_utf8(plaintext.to_vec())
+            .map_err(|e| AppError::Secrets(format!("Corrupted secret: {e}")))
+    }
+}
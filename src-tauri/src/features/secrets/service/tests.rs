@@ -0,0 +1,194 @@
+use super::*;
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::sync::Mutex;
+
+const PASSPHRASE: &str = "correct horse battery";
+
+/// Vault metadata in memory, secret columns in a migrated in-memory database
+struct MemoryRepository {
+    metadata: Mutex<Option<VaultMetadata>>,
+    conn: Mutex<Connection>,
+}
+
+impl MemoryRepository {
+    fn new() -> Arc<Self> {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn, Path::new(":memory:")).unwrap();
+        Arc::new(Self {
+            metadata: Mutex::new(None),
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn insert_api_key(&self, id: &str, api_key: &str) {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO llm_connections (id, name, base_url, provider, api_key, created_at, updated_at)
+                 VALUES (?1, ?1, 'https://api.openai.com/v1', 'openai', ?2, 0, 0)",
+                params![id, api_key],
+            )
+            .unwrap();
+    }
+
+    fn api_key(&self, id: &str) -> String {
+        self.conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT api_key FROM llm_connections WHERE id = ?1",
+                [id],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+}
+
+impl SecretsRepository for MemoryRepository {
+    fn get_metadata(&self) -> Result<Option<VaultMetadata>, AppError> {
+        Ok(self.metadata.lock().unwrap().clone())
+    }
+
+    fn save_metadata(&self, metadata: &VaultMetadata) -> Result<(), AppError> {
+        *self.metadata.lock().unwrap() = Some(metadata.clone());
+        Ok(())
+    }
+
+    fn rotate(
+        &self,
+        metadata: &VaultMetadata,
+        rewrite: &mut dyn FnMut(&str) -> Result<Option<String>, AppError>,
+    ) -> Result<usize, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let updated = crate::db::migrations::rewrite_secret_columns(&tx, rewrite)?;
+        tx.commit()?;
+        drop(conn);
+        self.save_metadata(metadata)?;
+        Ok(updated)
+    }
+
+    fn migrate_plaintext(
+        &self,
+        encrypt: &dyn Fn(&str) -> Result<String, AppError>,
+    ) -> Result<usize, AppError> {
+        let conn = self.conn.lock().unwrap();
+        crate::db::migrations::migrate_plaintext_secrets(&conn, encrypt)
+    }
+}
+
+fn unlocked_vault(repository: &Arc<MemoryRepository>) -> SecretsService {
+    let service = SecretsService::new(repository.clone());
+    service.set_passphrase(PASSPHRASE).unwrap();
+    service
+}
+
+#[test]
+fn encrypts_and_decrypts_a_secret() {
+    let service = unlocked_vault(&MemoryRepository::new());
+
+    let sealed = service.encrypt("sk-round-trip-secret").unwrap();
+    assert!(is_encrypted(&sealed));
+    assert!(!sealed.contains("round-trip"));
+    assert_ne!(service.encrypt("sk-round-trip-secret").unwrap(), sealed);
+    assert_eq!(service.decrypt(&sealed).unwrap(), "sk-round-trip-secret");
+    assert_eq!(service.encrypt("").unwrap(), "");
+    assert_eq!(service.encrypt(&sealed).unwrap(), sealed);
+}
+
+#[test]
+fn refuses_to_store_secrets_without_a_vault() {
+    let service = SecretsService::new(MemoryRepository::new());
+
+    let error = service.encrypt("sk-no-vault-secret").unwrap_err();
+    assert!(matches!(error, AppError::Secrets(message) if message.contains("passphrase")));
+    assert!(!service.status().unwrap().initialized);
+}
+
+#[test]
+fn keeps_the_vault_locked_after_a_wrong_passphrase() {
+    let repository = MemoryRepository::new();
+    let sealed = unlocked_vault(&repository)
+        .encrypt("sk-wrong-passphrase")
+        .unwrap();
+
+    let restarted = SecretsService::new(repository);
+    assert!(matches!(
+        restarted.unlock("not the passphrase"),
+        Err(AppError::Validation(_))
+    ));
+    assert!(!restarted.is_unlocked());
+
+    let status = restarted.unlock(PASSPHRASE).unwrap();
+    assert!(status.unlocked);
+    assert_eq!(restarted.decrypt(&sealed).unwrap(), "sk-wrong-passphrase");
+}
+
+#[test]
+fn reports_a_locked_vault() {
+    let repository = MemoryRepository::new();
+    let sealed = unlocked_vault(&repository).encrypt("sk-locked").unwrap();
+    let locked = SecretsService::new(repository);
+
+    assert!(matches!(
+        locked.encrypt("sk-new-secret"),
+        Err(AppError::Secrets(_))
+    ));
+    assert!(matches!(locked.decrypt(&sealed), Err(AppError::Secrets(_))));
+    assert_eq!(locked.reveal(&sealed), sealed);
+    assert!(ensure_unlocked(&locked.reveal(&sealed)).is_err());
+    assert!(ensure_unlocked("plaintext from before the vault").is_ok());
+    assert!(matches!(
+        locked.set_passphrase("a new passphrase"),
+        Err(AppError::Secrets(_))
+    ));
+}
+
+#[test]
+fn re_encrypts_every_secret_when_the_passphrase_changes() {
+    let repository = MemoryRepository::new();
+    repository.insert_api_key("legacy", "sk-saved-before-the-vault");
+    let service = unlocked_vault(&repository);
+    assert!(is_encrypted(&repository.api_key("legacy")));
+
+    repository.insert_api_key("openai", &service.encrypt("sk-openai-key").unwrap());
+    let before = repository.api_key("openai");
+
+    service.set_passphrase("another passphrase").unwrap();
+    let after = repository.api_key("openai");
+    assert_ne!(after, before);
+    assert_eq!(service.decrypt(&after).unwrap(), "sk-openai-key");
+
+    let restarted = SecretsService::new(repository.clone());
+    assert!(restarted.unlock(PASSPHRASE).is_err());
+    restarted.unlock("another passphrase").unwrap();
+    assert_eq!(
+        restarted.decrypt(&repository.api_key("legacy")).unwrap(),
+        "sk-saved-before-the-vault"
+    );
+}
+
+#[test]
+fn redacts_secrets_the_vault_has_seen() {
+    let service = unlocked_vault(&MemoryRepository::new());
+    let sealed = service.encrypt("plain-registered-value").unwrap();
+    service.decrypt(&sealed).unwrap();
+    service
+        .encrypt(r#"{"Authorization": "Token header-token-value", "X-Id": "short"}"#)
+        .unwrap();
+
+    assert_eq!(
+        redaction::redact("request failed for plain-registered-value"),
+        "request failed for [REDACTED]"
+    );
+    assert_eq!(
+        redaction::redact("sent header-token-value with X-Id short"),
+        "sent [REDACTED] with X-Id short"
+    );
+    assert_eq!(
+        redaction::redact("api_key=never-registered"),
+        "api_key=[REDACTED]"
+    );
+}
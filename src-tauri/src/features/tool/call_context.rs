@@ -0,0 +1,102 @@
+use crate::error::AppError;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Tool call a server request is attributed to
+#[derive(Debug, Clone)]
+pub struct ToolCallContext {
+    pub workspace_id: String,
+    pub chat_id: String,
+    pub message_id: String,
+    pub tool_call_id: String,
+    pub tool_name: String,
+}
+
+/// Tool calls currently running, keyed by client key (connection id or `agent:{id}`)
+///
+/// Requests and notifications a server sends while one of its tools runs
+/// (sampling, elicitation, progress) are attributed to that tool call.
+/// Several calls can run on one client at once.
+#[derive(Default)]
+pub struct ToolCallContexts {
+    contexts: Mutex<HashMap<String, Vec<ToolCallContext>>>,
+}
+
+impl ToolCallContexts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attribute server requests from a client to a tool call
+    pub fn bind(&self, client_key: &str, context: ToolCallContext) {
+        self.contexts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .entry(client_key.to_string())
+            .or_default()
+            .push(context);
+    }
+
+    /// Stop attributing server requests from a client to a tool call
+    pub fn unbind(&self, client_key: &str, tool_call_id: &str) {
+        let mut contexts = self
+            .contexts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(running) = contexts.get_mut(client_key) {
+            running.retain(|context| context.tool_call_id != tool_call_id);
+            if running.is_empty() {
+                contexts.remove(client_key);
+            }
+        }
+    }
+
+    /// Get a specific tool call running on a client
+    pub fn get_call(&self, client_key: &str, tool_call_id: &str) -> Option<ToolCallContext> {
+        self.contexts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(client_key)
+            .and_then(|running| {
+                running
+                    .iter()
+                    .find(|context| context.tool_call_id == tool_call_id)
+            })
+            .cloned()
+    }
+
+    /// Find the tool call a server request belongs to
+    ///
+    /// Tool calls are sent with their id as progress token, so a request carrying that
+    /// token is matched exactly. Without one the request can only be attributed while a
+    /// single call runs on the client; with several running it is rejected, not guessed.
+    pub fn resolve(
+        &self,
+        client_key: &str,
+        progress_token: Option<&str>,
+    ) -> Result<ToolCallContext, AppError> {
+        if let Some(token) = progress_token {
+            return self.get_call(client_key, token).ok_or_else(|| {
+                AppError::Validation(format!(
+                    "No running tool call matches progress token {token}"
+                ))
+            });
+        }
+
+        let contexts = self
+            .contexts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        match contexts.get(client_key).map(Vec::as_slice) {
+            Some([context]) => Ok(context.clone()),
+            Some([_, _, ..]) => Err(AppError::Validation(
+                "Several tool calls are running on this server and the request does not say which one it belongs to"
+                    .to_string(),
+            )),
+            _ => Err(AppError::Validation(
+                "Server requests are only available while one of its tools runs for a chat"
+                    .to_string(),
+            )),
+        }
+    }
+}
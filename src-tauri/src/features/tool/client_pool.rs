@@ -0,0 +1,397 @@
+use super::mcp_client::MCPClientService;
+use super::policy::ToolCallError;
+use crate::error::AppError;
+use crate::features::mcp_connection::MCPServerConnection;
+use crate::features::workspace::settings::ToolErrorKind;
+use rust_mcp_sdk::{
+    error::McpSdkError,
+    mcp_client::ClientRuntime,
+    schema::{CallToolRequestParams, CallToolResult},
+    McpClient,
+};
+use rust_mcp_transport::error::TransportError;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+#[cfg(test)]
+mod tests;
+
+// Clients that have not been used for this long are shut down
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+// How often the background job checks pooled clients
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(60);
+// Health check timeout for pooled clients
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+// Restart backoff: 1s, 2s, 4s, ... capped at 60s
+const RESTART_BACKOFF_BASE: Duration = Duration::from_secs(1);
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+struct PooledClient {
+    client: Arc<ClientRuntime>,
+    // Connection settings the client was started with, used to detect config changes
+    fingerprint: String,
+}
+
+#[derive(Default)]
+struct ClientSlot {
+    pooled: Option<PooledClient>,
+    // Last known connection settings, used to restart crashed servers
+    connection: Option<MCPServerConnection>,
+    last_used: Option<Instant>,
+    consecutive_failures: u32,
+    retry_after: Option<Instant>,
+    restart_pending: bool,
+}
+
+impl ClientSlot {
+    /// Unused for `IDLE_TIMEOUT`, or never used at all
+    fn is_idle(&self, now: Instant) -> bool {
+        self.last_used
+            .is_none_or(|last_used| now.saturating_duration_since(last_used) >= IDLE_TIMEOUT)
+    }
+
+    /// Whether a failed start still has to wait for its backoff
+    fn backing_off(&self, now: Instant) -> Option<Duration> {
+        self.retry_after
+            .filter(|retry_after| *retry_after > now)
+            .map(|retry_after| retry_after - now)
+    }
+
+    /// A crashed client whose backoff has run out
+    fn restart_due(&self, now: Instant) -> bool {
+        self.restart_pending && self.backing_off(now).is_none()
+    }
+
+    const fn record_start(&mut self) {
+        self.consecutive_failures = 0;
+        self.retry_after = None;
+        self.restart_pending = false;
+    }
+
+    fn record_failure(&mut self, now: Instant) {
+        self.consecutive_failures += 1;
+        self.retry_after = Some(now + MCPClientPool::backoff(self.consecutive_failures));
+    }
+}
+
+/// Long-lived MCP clients keyed by `MCPServerConnection.id`
+///
+/// Clients are started on first use and reused across tool calls, so stdio
+/// servers are not respawned for every invocation. A background job shuts down
+/// idle clients and restarts crashed ones with exponential backoff.
+pub struct MCPClientPool {
+    app: AppHandle,
+    slots: Mutex<HashMap<String, Arc<tokio::sync::Mutex<ClientSlot>>>>,
+}
+
+impl MCPClientPool {
+    pub fn new(app: AppHandle) -> Self {
+        Self {
+            app,
+            slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn slot(&self, connection_id: &str) -> Arc<tokio::sync::Mutex<ClientSlot>> {
+        let mut slots = self
+            .slots
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        slots.entry(connection_id.to_string()).or_default().clone()
+    }
+
+    fn fingerprint(connection: &MCPServerConnection) -> String {
+        format!(
+            "{}|{}|{}|{}|{}",
+            connection.url,
+            connection.r#type,
+            connection.headers,
+            connection.env_vars.as_deref().unwrap_or_default(),
+            connection.runtime_path.as_deref().unwrap_or_default()
+        )
+    }
+
+    fn backoff(consecutive_failures: u32) -> Duration {
+        let exponent = consecutive_failures.saturating_sub(1).min(6);
+        (RESTART_BACKOFF_BASE * 2u32.pow(exponent)).min(RESTART_BACKOFF_MAX)
+    }
+
+    /// Return the pooled client for a slot, (re)starting it when missing, stale or dead
+    async fn ensure_client(
+        &self,
+        slot: &mut ClientSlot,
+        connection: &MCPServerConnection,
+    ) -> Result<Arc<ClientRuntime>, AppError> {
+        let fingerprint = Self::fingerprint(connection);
+        slot.connection = Some(connection.clone());
+        slot.last_used = Some(Instant::now());
+
+        if let Some(pooled) = &slot.pooled {
+            if pooled.fingerprint == fingerprint && !pooled.client.is_shut_down().await {
+                return Ok(pooled.client.clone());
+            }
+        }
+
+        // Connection settings changed or the client died - replace it
+        if let Some(stale) = slot.pooled.take() {
+            let _ = stale.client.shut_down().await;
+        }
+
+        if let Some(wait) = slot.backing_off(Instant::now()) {
+            return Err(AppError::Mcp(format!(
+                "MCP server {} failed to start, retrying in {}s",
+                connection.name,
+                wait.as_secs().max(1)
+            )));
+        }
+
+        let headers = if connection.headers.is_empty() {
+            None
+        } else {
+            Some(connection.headers.clone())
+        };
+
+        match MCPClientService::create_and_start_client(
+            &self.app,
+            connection.url.clone(),
+            connection.r#type.clone(),
+            headers,
+            connection.env_vars.clone(),
+            connection.runtime_path.clone(),
+            Some(connection.id.clone()),
+        )
+        .await
+        {
+            Ok(client) => {
+                slot.record_start();
+                slot.pooled = Some(PooledClient {
+                    client: client.clone(),
+                    fingerprint,
+                });
+                Ok(client)
+            }
+            Err(e) => {
+                slot.record_failure(Instant::now());
+                Err(e)
+            }
+        }
+    }
+
+    /// Drop the slot's client if it is still the given (failed) client
+    async fn discard(slot: &mut ClientSlot, client: &Arc<ClientRuntime>) {
+        let is_same = slot
+            .pooled
+            .as_ref()
+            .is_some_and(|pooled| Arc::ptr_eq(&pooled.client, client));
+        if is_same {
+            slot.pooled = None;
+            let _ = client.shut_down().await;
+        }
+    }
+
+    /// Get a running client for a connection, starting one if needed
+    pub async fn get_client(
+        &self,
+        connection: &MCPServerConnection,
+    ) -> Result<Arc<ClientRuntime>, AppError> {
+        let slot = self.slot(&connection.id);
+        let mut slot = slot.lock().await;
+        self.ensure_client(&mut slot, connection).await
+    }
+
+    /// Whether a call failed before the request reached the server
+    ///
+    /// Only these are safe to send again, anything later may already have run the tool
+    fn never_sent(error: &McpSdkError) -> bool {
+        let io_never_sent = |e: &std::io::Error| {
+            matches!(
+                e.kind(),
+                std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::ConnectionRefused
+                    | std::io::ErrorKind::NotConnected
+            )
+        };
+        match error {
+            McpSdkError::Io(e) | McpSdkError::Transport(TransportError::Io(e)) => io_never_sent(e),
+            McpSdkError::Transport(TransportError::HttpConnection(e)) => e.is_connect(),
+            // The server no longer knows the session and rejects the request unprocessed
+            McpSdkError::Transport(TransportError::SessionExpired) => true,
+            // Rejected credentials, the fresh client starts with a refreshed OAuth token
+            e if MCPClientService::is_unauthorized(e) => true,
+            _ => false,
+        }
+    }
+
+    /// Call a tool on the pooled client for a connection
+    ///
+    /// Failed clients are discarded. A call that never reached the server, because the
+    /// pooled process or connection was already gone, is sent once more on a fresh
+    /// client; every other failure is returned so tool calls never run twice.
+    pub async fn call_tool(
+        &self,
+        connection: &MCPServerConnection,
+        params: CallToolRequestParams,
+        progress_token: Option<&str>,
+    ) -> Result<CallToolResult, ToolCallError> {
+        let transport_error = |e: AppError| ToolCallError::new(ToolErrorKind::Transport, e);
+        let call_error = |e: McpSdkError| {
+            let kind = if matches!(e, McpSdkError::RpcError(_)) {
+                ToolErrorKind::Server
+            } else {
+                ToolErrorKind::Transport
+            };
+            ToolCallError::new(
+                kind,
+                AppError::Mcp(format!(
+                    "Failed to call tool {} on MCP server {}: {}",
+                    params.name, connection.name, e
+                )),
+            )
+        };
+
+        let slot = self.slot(&connection.id);
+        let mut retried = false;
+        loop {
+            let client = {
+                let mut slot = slot.lock().await;
+                self.ensure_client(&mut slot, connection)
+                    .await
+                    .map_err(transport_error)?
+            };
+
+            match MCPClientService::call_tool_with_progress(&client, params.clone(), progress_token)
+                .await
+            {
+                Ok(result) => return Ok(result),
+                // The server answered with an error, the connection itself is fine
+                Err(e @ McpSdkError::RpcError(_)) => return Err(call_error(e)),
+                Err(e) => {
+                    tracing::warn!(
+                        connection_id = %connection.id,
+                        tool = %params.name,
+                        error = %e,
+                        "MCP client failed, discarding it"
+                    );
+
+                    let mut slot = slot.lock().await;
+                    Self::discard(&mut slot, &client).await;
+                    if retried || !Self::never_sent(&e) {
+                        return Err(call_error(e));
+                    }
+                    retried = true;
+                }
+            }
+        }
+    }
+
+    /// Shut down and forget the client for a connection
+    pub async fn shut_down(&self, connection_id: &str) {
+        let slot = {
+            let mut slots = self
+                .slots
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            slots.remove(connection_id)
+        };
+
+        if let Some(slot) = slot {
+            let mut slot = slot.lock().await;
+            if let Some(pooled) = slot.pooled.take() {
+                let _ = pooled.client.shut_down().await;
+            }
+        }
+    }
+
+    /// Shut down the client for a connection in the background
+    pub fn evict(self: &Arc<Self>, connection_id: &str) {
+        let pool = self.clone();
+        let connection_id = connection_id.to_string();
+        tauri::async_runtime::spawn(async move {
+            pool.shut_down(&connection_id).await;
+        });
+    }
+
+    /// Start background job that shuts down idle clients and restarts crashed ones
+    pub fn start_maintenance(self: Arc<Self>) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(MAINTENANCE_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+            loop {
+                interval.tick().await;
+                self.run_maintenance().await;
+            }
+        });
+    }
+
+    async fn run_maintenance(&self) {
+        let slots: Vec<(String, Arc<tokio::sync::Mutex<ClientSlot>>)> = {
+            let slots = self
+                .slots
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            slots
+                .iter()
+                .map(|(id, slot)| (id.clone(), slot.clone()))
+                .collect()
+        };
+
+        for (connection_id, slot) in slots {
+            // Skip slots that are busy connecting
+            let Ok(mut slot) = slot.try_lock() else {
+                continue;
+            };
+
+            if slot.is_idle(Instant::now()) {
+                slot.restart_pending = false;
+                if let Some(pooled) = slot.pooled.take() {
+                    tracing::debug!(
+                        connection_id = %connection_id,
+                        "Shutting down idle MCP client"
+                    );
+                    let _ = pooled.client.shut_down().await;
+                }
+                continue;
+            }
+
+            if let Some(pooled) = &slot.pooled {
+                let client = pooled.client.clone();
+                let healthy =
+                    !client.is_shut_down().await && client.ping(Some(PING_TIMEOUT)).await.is_ok();
+                if healthy {
+                    continue;
+                }
+
+                tracing::warn!(
+                    connection_id = %connection_id,
+                    "MCP server stopped responding, restarting"
+                );
+                slot.pooled = None;
+                slot.restart_pending = true;
+                let _ = client.shut_down().await;
+            }
+
+            if !slot.restart_due(Instant::now()) {
+                continue;
+            }
+
+            let Some(connection) = slot.connection.clone() else {
+                continue;
+            };
+
+            // Restarting is not a use - keep the idle clock running
+            let last_used = slot.last_used;
+            if let Err(e) = self.ensure_client(&mut slot, &connection).await {
+                tracing::error!(
+                    connection_id = %connection_id,
+                    error = %e,
+                    "Failed to restart MCP server"
+                );
+                slot.restart_pending = true;
+            }
+            slot.last_used = last_used;
+        }
+    }
+}
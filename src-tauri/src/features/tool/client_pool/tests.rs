@@ -0,0 +1,91 @@
+use super::*;
+use rust_mcp_sdk::schema::RpcError;
+use std::io::{Error, ErrorKind};
+
+fn io(kind: ErrorKind) -> Error {
+    Error::from(kind)
+}
+
+#[test]
+fn retries_only_calls_that_never_left() {
+    for kind in [
+        ErrorKind::BrokenPipe,
+        ErrorKind::ConnectionRefused,
+        ErrorKind::NotConnected,
+    ] {
+        assert!(MCPClientPool::never_sent(&McpSdkError::Io(io(kind))));
+        assert!(MCPClientPool::never_sent(&McpSdkError::Transport(
+            TransportError::Io(io(kind))
+        )));
+    }
+    assert!(MCPClientPool::never_sent(&McpSdkError::Transport(
+        TransportError::SessionExpired
+    )));
+    assert!(MCPClientPool::never_sent(&McpSdkError::Transport(
+        TransportError::Http(reqwest::StatusCode::UNAUTHORIZED)
+    )));
+}
+
+#[test]
+fn does_not_retry_calls_the_server_may_have_run() {
+    for kind in [
+        ErrorKind::ConnectionReset,
+        ErrorKind::UnexpectedEof,
+        ErrorKind::TimedOut,
+    ] {
+        assert!(!MCPClientPool::never_sent(&McpSdkError::Io(io(kind))));
+    }
+    assert!(!MCPClientPool::never_sent(&McpSdkError::RpcError(
+        RpcError::internal_error()
+    )));
+    assert!(!MCPClientPool::never_sent(&McpSdkError::Transport(
+        TransportError::Http(reqwest::StatusCode::INTERNAL_SERVER_ERROR)
+    )));
+    assert!(!MCPClientPool::never_sent(&McpSdkError::Internal {
+        description: "request timed out".to_string(),
+    }));
+}
+
+#[test]
+fn doubles_the_restart_backoff_up_to_a_minute() {
+    let backoff: Vec<u64> = (1..=9)
+        .map(|failures| MCPClientPool::backoff(failures).as_secs())
+        .collect();
+    assert_eq!(backoff, vec![1, 2, 4, 8, 16, 32, 60, 60, 60]);
+    assert_eq!(MCPClientPool::backoff(0), RESTART_BACKOFF_BASE);
+    assert_eq!(MCPClientPool::backoff(u32::MAX), RESTART_BACKOFF_MAX);
+}
+
+#[test]
+fn shuts_down_clients_after_the_idle_timeout() {
+    let now = Instant::now();
+    let mut slot = ClientSlot::default();
+    assert!(slot.is_idle(now));
+
+    slot.last_used = Some(now);
+    assert!(!slot.is_idle(now));
+    assert!(!slot.is_idle(now + IDLE_TIMEOUT.saturating_sub(Duration::from_secs(1))));
+    assert!(slot.is_idle(now + IDLE_TIMEOUT));
+}
+
+#[test]
+fn waits_for_the_backoff_before_restarting() {
+    let now = Instant::now();
+    let mut slot = ClientSlot {
+        restart_pending: true,
+        ..ClientSlot::default()
+    };
+    assert!(slot.restart_due(now));
+
+    slot.record_failure(now);
+    slot.record_failure(now);
+    assert_eq!(slot.consecutive_failures, 2);
+    assert_eq!(slot.backing_off(now), Some(Duration::from_secs(2)));
+    assert!(!slot.restart_due(now + Duration::from_secs(1)));
+    assert!(slot.restart_due(now + Duration::from_secs(2)));
+
+    slot.record_start();
+    assert_eq!(slot.consecutive_failures, 0);
+    assert_eq!(slot.backing_off(now), None);
+    assert!(!slot.restart_due(now));
+}
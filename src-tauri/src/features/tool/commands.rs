@@ -1,7 +1,10 @@
 use super::mcp_client::MCPClientService;
-use super::models::MCPTool;
+use super::models::{
+    MCPResource, MCPResourceContent, MCPResourceTemplate, MCPServerCatalog, MCPTool,
+};
 use crate::error::AppError;
 use crate::state::mcp_client_state::MCPClientState;
+use crate::state::AppState;
 use tauri::State;
 
 #[tauri::command]
@@ -25,27 +28,39 @@ pub async fn test_mcp_connection_and_fetch_tools(
     .map_err(|e| AppError::Mcp(e.to_string()))
 }
 
+/// Connect a server and fetch its tools, resources and prompts.
+/// For a saved connection they are cached right away instead of on the next background refresh.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn connect_mcp_server_and_fetch_tools(
     app: tauri::AppHandle,
+    connection_id: Option<String>,
     url: String,
     r#type: String,
     headers: Option<String>,
     env_vars: Option<String>,
     runtime_path: Option<String>,
-) -> Result<Vec<MCPTool>, AppError> {
-    // This is the same logic as test_mcp_connection_and_fetch_tools
-    // but we'll use it for automatic connection when saving
-    MCPClientService::test_connection_and_fetch_tools(
+    state: State<'_, AppState>,
+) -> Result<MCPServerCatalog, AppError> {
+    let catalog = MCPClientService::test_connection_and_fetch_catalog(
         &app,
         url,
         r#type,
         headers,
         env_vars,
         runtime_path,
+        connection_id.clone(),
     )
     .await
-    .map_err(|e| AppError::Mcp(e.to_string()))
+    .map_err(|e| AppError::Mcp(e.to_string()))?;
+
+    if let Some(connection_id) = &connection_id {
+        state
+            .mcp_connection_service
+            .save_catalog(connection_id, &catalog)?;
+    }
+
+    Ok(catalog)
 }
 
 #[tauri::command]
@@ -110,11 +125,81 @@ pub async fn call_mcp_tool(
 pub async fn disconnect_mcp_client(
     connection_id: String,
     state: State<'_, MCPClientState>,
+    app_state: State<'_, AppState>,
 ) -> Result<(), AppError> {
     let mut connection_info = state.connection_info.lock().await;
 
     // Remove connection info
     connection_info.remove(&connection_id);
+    drop(connection_info);
+
+    // Shut down the pooled client, if any
+    app_state.mcp_client_pool.shut_down(&connection_id).await;
+    app_state
+        .chat_service
+        .forget_connection_resources(&connection_id);
 
     Ok(())
 }
+
+#[tauri::command]
+pub async fn list_mcp_resources(
+    connection_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<MCPResource>, AppError> {
+    let catalog = state
+        .tool_service
+        .refresh_resources(&connection_id)
+        .await
+        .map_err(|e| AppError::Mcp(e.to_string()))?;
+
+    Ok(catalog.resources)
+}
+
+#[tauri::command]
+pub async fn list_mcp_resource_templates(
+    connection_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<MCPResourceTemplate>, AppError> {
+    let catalog = state
+        .tool_service
+        .refresh_resources(&connection_id)
+        .await
+        .map_err(|e| AppError::Mcp(e.to_string()))?;
+
+    Ok(catalog.templates)
+}
+
+#[tauri::command]
+pub async fn read_mcp_resource(
+    connection_id: String,
+    uri: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<MCPResourceContent>, AppError> {
+    state
+        .tool_service
+        .read_resource(&connection_id, &uri)
+        .await
+        .map_err(|e| AppError::Mcp(e.to_string()))
+}
+
+#[tauri::command]
+pub fn respond_sampling_request(
+    request_id: String,
+    approved: bool,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    state.sampling_service.respond(&request_id, approved)
+}
+
+#[tauri::command]
+pub fn respond_elicitation_request(
+    request_id: String,
+    action: String,
+    content: Option<serde_json::Map<String, serde_json::Value>>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    state
+        .elicitation_service
+        .respond(&request_id, &action, content)
+}
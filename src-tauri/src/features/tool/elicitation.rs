@@ -0,0 +1,150 @@
+use super::call_context::ToolCallContexts;
+use crate::error::AppError;
+use crate::events::ToolEmitter;
+use rust_mcp_sdk::schema::{
+    ElicitRequestParams, ElicitResult, ElicitResultAction, ElicitResultContentValue,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+use tokio::sync::oneshot;
+
+/// Handles `elicitation/create` requests from MCP servers
+///
+/// The request is shown to the user in the chat whose tool call is running on
+/// the server, and the user's answer is sent back as the elicitation result.
+pub struct MCPElicitationService {
+    call_contexts: Arc<ToolCallContexts>,
+    // request_id -> oneshot sender for the user's answer
+    pending_requests: Mutex<HashMap<String, oneshot::Sender<ElicitResult>>>,
+}
+
+impl MCPElicitationService {
+    pub fn new(call_contexts: Arc<ToolCallContexts>) -> Self {
+        Self {
+            call_contexts,
+            pending_requests: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Deliver the user's answer for a pending elicitation request
+    pub fn respond(
+        &self,
+        request_id: &str,
+        action: &str,
+        content: Option<serde_json::Map<String, serde_json::Value>>,
+    ) -> Result<(), AppError> {
+        let action = match action {
+            "accept" => ElicitResultAction::Accept,
+            "decline" => ElicitResultAction::Decline,
+            "cancel" => ElicitResultAction::Cancel,
+            other => {
+                return Err(AppError::Validation(format!(
+                    "Invalid elicitation action: {other}"
+                )))
+            }
+        };
+
+        // Only accepted requests carry content
+        let content = match action {
+            ElicitResultAction::Accept => Some(
+                content
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let value = match value {
+                            serde_json::Value::Bool(b) => ElicitResultContentValue::Boolean(b),
+                            serde_json::Value::Number(n) if n.is_i64() => {
+                                ElicitResultContentValue::Integer(n.as_i64().unwrap_or_default())
+                            }
+                            serde_json::Value::String(s) => ElicitResultContentValue::String(s),
+                            other => ElicitResultContentValue::String(other.to_string()),
+                        };
+                        (key, value)
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        };
+
+        let sender = self
+            .pending_requests
+            .lock()
+            .map_err(|e| AppError::Generic(format!("Failed to lock pending_requests: {e}")))?
+            .remove(request_id);
+
+        match sender {
+            Some(sender) => sender
+                .send(ElicitResult {
+                    action,
+                    content,
+                    meta: None,
+                })
+                .map_err(|_| {
+                    AppError::Generic(format!(
+                        "Failed to send answer for elicitation request {request_id}"
+                    ))
+                }),
+            None => Err(AppError::Validation(format!(
+                "No pending elicitation request found: {request_id}"
+            ))),
+        }
+    }
+
+    /// Ask the user for the input requested by the client identified by `client_key`
+    /// Unanswered requests are cancelled after 60 seconds
+    pub async fn elicit(
+        &self,
+        app: &AppHandle,
+        client_key: Option<&str>,
+        server_name: &str,
+        params: ElicitRequestParams,
+    ) -> Result<ElicitResult, AppError> {
+        let client_key = client_key.ok_or_else(|| {
+            AppError::Validation(
+                "Elicitation is only available while a tool runs for a chat".to_string(),
+            )
+        })?;
+        // Elicitation requests carry no progress token, they resolve only while a single call runs
+        let context = self.call_contexts.resolve(client_key, None)?;
+
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let (tx, rx) = oneshot::channel::<ElicitResult>();
+        self.pending_requests
+            .lock()
+            .map_err(|e| AppError::Generic(format!("Failed to lock pending_requests: {e}")))?
+            .insert(request_id.clone(), tx);
+
+        let tool_emitter = ToolEmitter::new(app.clone());
+        tool_emitter.emit_elicitation_request(
+            context.chat_id,
+            context.message_id,
+            context.tool_call_id,
+            request_id.clone(),
+            server_name.to_string(),
+            params.message,
+            serde_json::to_value(&params.requested_schema)?,
+        )?;
+
+        match tokio::time::timeout(tokio::time::Duration::from_secs(60), rx).await {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(_)) => Err(AppError::Generic(
+                "Elicitation request cancelled".to_string(),
+            )),
+            Err(_) => {
+                self.pending_requests
+                    .lock()
+                    .map_err(|e| {
+                        AppError::Generic(format!("Failed to lock pending_requests: {e}"))
+                    })?
+                    .remove(&request_id);
+
+                Ok(ElicitResult {
+                    action: ElicitResultAction::Cancel,
+                    content: None,
+                    meta: None,
+                })
+            }
+        }
+    }
+}
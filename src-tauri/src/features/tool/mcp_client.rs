@@ -1,30 +1,133 @@
-use super::models::MCPTool;
+use super::models::{
+    MCPPrompt, MCPPromptArgument, MCPResource, MCPResourceCatalog, MCPResourceContent,
+    MCPResourceTemplate, MCPServerCatalog, MCPTool, MCPToolFile, MCPToolOutput,
+};
 use crate::error::AppError;
 use crate::features::addon::models::AddonIndex;
 use crate::features::runtime::node::service::NodeRuntime;
 use crate::features::runtime::python::service::PythonRuntime;
+use crate::features::secrets::ensure_unlocked;
+use reqwest::StatusCode;
 use rust_mcp_sdk::{
+    error::McpSdkError,
     mcp_client::{client_runtime, ClientHandler, ClientRuntime},
     schema::{
-        CallToolRequestParams, ClientCapabilities, Implementation, InitializeRequestParams,
-        LATEST_PROTOCOL_VERSION,
+        schema_utils::RequestFromClient, CallToolRequestParams, CallToolResult, ClientCapabilities,
+        ContentBlock, CreateMessageRequest, CreateMessageResult, ElicitRequest, ElicitResult,
+        EmbeddedResourceResource, GetPromptRequestParams, Implementation, InitializeRequestParams,
+        ListPromptsRequestParams, ListResourceTemplatesRequestParams, ListResourcesRequestParams,
+        ProgressNotification, ProgressToken, ReadResourceRequestParams,
+        ReadResourceResultContentsItem, RpcError, LATEST_PROTOCOL_VERSION,
     },
     McpClient,
 };
 use rust_mcp_transport::{
-    ClientSseTransport, ClientSseTransportOptions, RequestOptions, StdioTransport,
-    StreamableTransportOptions, TransportOptions,
+    error::TransportError, ClientSseTransport, ClientSseTransportOptions, RequestOptions,
+    StdioTransport, StreamableTransportOptions, TransportOptions,
 };
 use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 
-// Simple client handler - we don't need to handle any server messages for this use case
-struct SimpleClientHandler;
+// Client handler - answers requests the server sends back to nexo
+struct NexoClientHandler {
+    app: AppHandle,
+    // Key used to attribute server requests to a chat (connection id or "agent:{id}")
+    client_key: Option<String>,
+}
 
 #[async_trait::async_trait]
-impl ClientHandler for SimpleClientHandler {
-    // Empty implementation - we only need to list tools, not handle server messages
+impl ClientHandler for NexoClientHandler {
+    async fn handle_create_message_request(
+        &self,
+        request: CreateMessageRequest,
+        runtime: &dyn McpClient,
+    ) -> Result<CreateMessageResult, RpcError> {
+        let Some(state) = self.app.try_state::<crate::state::AppState>() else {
+            return Err(RpcError::internal_error().with_message("nexo is not ready".to_string()));
+        };
+        let sampling_service = state.sampling_service.clone();
+
+        let server_name = runtime
+            .server_info()
+            .map_or_else(|| "MCP server".to_string(), |info| info.server_info.name);
+
+        sampling_service
+            .create_message(
+                &self.app,
+                self.client_key.as_deref(),
+                &server_name,
+                request.params,
+            )
+            .await
+            .map_err(|e| RpcError::internal_error().with_message(e.to_string()))
+    }
+
+    async fn handle_elicit_request(
+        &self,
+        request: ElicitRequest,
+        runtime: &dyn McpClient,
+    ) -> Result<ElicitResult, RpcError> {
+        let Some(state) = self.app.try_state::<crate::state::AppState>() else {
+            return Err(RpcError::internal_error().with_message("nexo is not ready".to_string()));
+        };
+        let elicitation_service = state.elicitation_service.clone();
+
+        let server_name = runtime
+            .server_info()
+            .map_or_else(|| "MCP server".to_string(), |info| info.server_info.name);
+
+        elicitation_service
+            .elicit(
+                &self.app,
+                self.client_key.as_deref(),
+                &server_name,
+                request.params,
+            )
+            .await
+            .map_err(|e| RpcError::internal_error().with_message(e.to_string()))
+    }
+
+    async fn handle_progress_notification(
+        &self,
+        notification: ProgressNotification,
+        _runtime: &dyn McpClient,
+    ) -> Result<(), RpcError> {
+        let Some(state) = self.app.try_state::<crate::state::AppState>() else {
+            return Ok(());
+        };
+        // Progress tokens are tool call ids, ignore progress of other requests
+        let params = notification.params;
+        let ProgressToken::String(token) = &params.progress_token else {
+            return Ok(());
+        };
+        let Some(context) = self
+            .client_key
+            .as_deref()
+            .and_then(|key| state.tool_call_contexts.get_call(key, token))
+        else {
+            return Ok(());
+        };
+
+        let tool_emitter = crate::events::ToolEmitter::new(self.app.clone());
+        if let Err(e) = tool_emitter.emit_tool_execution_progress(
+            context.chat_id,
+            context.message_id,
+            context.tool_call_id,
+            context.tool_name,
+            "progress".to_string(),
+            Some(serde_json::json!({
+                "progress": params.progress,
+                "total": params.total,
+                "message": params.message,
+            })),
+            None,
+        ) {
+            tracing::warn!(error = %e, "Failed to emit MCP progress notification");
+        }
+
+        Ok(())
+    }
 }
 
 pub struct MCPClientService;
@@ -53,7 +156,13 @@ impl MCPClientService {
     /// Create client details for MCP initialization
     fn create_client_details() -> InitializeRequestParams {
         InitializeRequestParams {
-            capabilities: ClientCapabilities::default(),
+            capabilities: ClientCapabilities {
+                // Servers may request LLM completions through the workspace's connection
+                sampling: Some(serde_json::Map::new()),
+                // Servers may ask the user for input while a tool runs
+                elicitation: Some(serde_json::Map::new()),
+                ..ClientCapabilities::default()
+            },
             client_info: Implementation {
                 name: "nexo".to_string(),
                 title: None,
@@ -64,6 +173,7 @@ impl MCPClientService {
     }
 
     /// Create and start MCP client based on transport type
+    /// `client_key` is the connection id (or `agent:{id}`), used to attribute server requests and look up OAuth tokens
     pub async fn create_and_start_client(
         app: &AppHandle,
         url: String,
@@ -71,6 +181,7 @@ impl MCPClientService {
         headers: Option<String>,
         env_vars_json: Option<String>,
         runtime_path: Option<String>,
+        client_key: Option<String>,
     ) -> Result<Arc<ClientRuntime>, AppError> {
         // Validate transport type
         if r#type != "sse"
@@ -83,16 +194,108 @@ impl MCPClientService {
             )));
         }
 
-        let custom_headers = Self::parse_json_map(&headers);
-        let mut env_vars = Self::parse_json_map(&env_vars_json);
+        // Headers and env vars read while the secrets vault is locked are still encrypted
+        headers
+            .iter()
+            .chain(&env_vars_json)
+            .try_for_each(|secret| ensure_unlocked(secret))?;
+
+        let mut custom_headers = Self::parse_json_map(&headers);
+        let env_vars = Self::parse_json_map(&env_vars_json);
+
+        // Remote connections authorized through OAuth send their (refreshed) access token
+        let oauth = if r#type == "stdio" {
+            None
+        } else {
+            client_key
+                .clone()
+                .zip(app.try_state::<crate::state::AppState>())
+        };
+        let mut access_token = match &oauth {
+            Some((connection_id, state)) => {
+                state
+                    .mcp_oauth_service
+                    .get_access_token(connection_id)
+                    .await?
+            }
+            None => None,
+        };
+
+        loop {
+            if let Some(access_token) = &access_token {
+                custom_headers.get_or_insert_with(HashMap::new).insert(
+                    "Authorization".to_string(),
+                    format!("Bearer {access_token}"),
+                );
+            }
+
+            let client = Self::build_client(
+                app,
+                &url,
+                &r#type,
+                custom_headers.clone(),
+                env_vars.clone(),
+                runtime_path.clone(),
+                client_key.clone(),
+            )?;
+
+            let Err(e) = client.clone().start().await else {
+                return Ok(client);
+            };
+
+            // A token rejected before its expiry (revoked, or the clock is off) is refreshed once
+            if let (Some((connection_id, state)), Some(rejected)) = (&oauth, access_token.take()) {
+                if Self::is_unauthorized(&e) {
+                    tracing::warn!(
+                        connection_id = %connection_id,
+                        "MCP server rejected the OAuth access token, refreshing it"
+                    );
+                    access_token = state
+                        .mcp_oauth_service
+                        .refresh_rejected_token(connection_id, &rejected)
+                        .await?;
+                    if access_token.is_some() {
+                        continue;
+                    }
+                }
+            }
+
+            let err_msg = format!("Failed to start MCP client for {url}: {e}");
+            tracing::error!("{}", err_msg);
+            return Err(AppError::Generic(err_msg));
+        }
+    }
+
+    /// Whether the server turned the request away for missing or invalid credentials
+    pub(crate) fn is_unauthorized(error: &McpSdkError) -> bool {
+        matches!(
+            error,
+            McpSdkError::Transport(TransportError::Http(StatusCode::UNAUTHORIZED))
+        )
+            // Errors raised inside the client runtime only keep the message
+            || error.to_string().contains("401 Unauthorized")
+    }
 
+    /// Create the client for a transport type, without starting it
+    fn build_client(
+        app: &AppHandle,
+        url: &str,
+        r#type: &str,
+        custom_headers: Option<HashMap<String, String>>,
+        mut env_vars: Option<HashMap<String, String>>,
+        runtime_path: Option<String>,
+        client_key: Option<String>,
+    ) -> Result<Arc<ClientRuntime>, AppError> {
         // Fallback: If it's stdio and headers are provided but env_vars are not, use headers as env_vars (legacy support)
         if r#type == "stdio" && env_vars.is_none() && custom_headers.is_some() {
-            env_vars = custom_headers.clone();
+            env_vars.clone_from(&custom_headers);
         }
 
         let client_details = Self::create_client_details();
-        let handler = SimpleClientHandler {};
+        let handler = NexoClientHandler {
+            app: app.clone(),
+            client_key,
+        };
 
         let client = if r#type == "sse" {
             // Create SSE transport with custom headers if provided
@@ -100,7 +303,7 @@ impl MCPClientService {
                 custom_headers,
                 ..ClientSseTransportOptions::default()
             };
-            let transport = match ClientSseTransport::new(&url, sse_options) {
+            let transport = match ClientSseTransport::new(url, sse_options) {
                 Ok(t) => t,
                 Err(e) => {
                     let err_msg = format!("Failed to create SSE transport for {url}: {e}");
@@ -116,7 +319,7 @@ impl MCPClientService {
                 ..RequestOptions::default()
             };
             let transport_options = StreamableTransportOptions {
-                mcp_url: url.clone(),
+                mcp_url: url.to_string(),
                 request_options,
             };
             client_runtime::with_transport_options(client_details, transport_options, handler)
@@ -127,7 +330,7 @@ impl MCPClientService {
             // - "command arg1 arg2" (command with space-separated arguments)
             // - "/path/to/command" (absolute path)
             // Use shell-words to parse command and arguments, respecting quotes
-            let parts = match shell_words::split(&url) {
+            let parts = match shell_words::split(url) {
                 Ok(p) => p,
                 Err(e) => {
                     let err_msg = format!("Invalid stdio URL '{url}': parse error: {e}");
@@ -308,13 +511,6 @@ impl MCPClientService {
             client_runtime::create_client(client_details, transport, handler)
         };
 
-        // Start the client
-        if let Err(e) = client.clone().start().await {
-            let err_msg = format!("Failed to start MCP client for {url}: {e}");
-            tracing::error!("{}", err_msg);
-            return Err(AppError::Generic(err_msg));
-        }
-
         Ok(client)
     }
 
@@ -327,6 +523,31 @@ impl MCPClientService {
         env_vars_json: Option<String>,
         runtime_path: Option<String>,
     ) -> Result<Vec<MCPTool>, AppError> {
+        let catalog = Self::test_connection_and_fetch_catalog(
+            app,
+            url,
+            r#type,
+            headers,
+            env_vars_json,
+            runtime_path,
+            None,
+        )
+        .await?;
+
+        Ok(catalog.tools)
+    }
+
+    /// Test MCP connection and fetch tools and resources
+    /// `connection_id` is set for saved connections so their OAuth tokens are used
+    pub async fn test_connection_and_fetch_catalog(
+        app: &AppHandle,
+        url: String,
+        r#type: String,
+        headers: Option<String>,
+        env_vars_json: Option<String>,
+        runtime_path: Option<String>,
+        connection_id: Option<String>,
+    ) -> Result<MCPServerCatalog, AppError> {
         let client = Self::create_and_start_client(
             app,
             url.clone(),
@@ -334,6 +555,7 @@ impl MCPClientService {
             headers,
             env_vars_json,
             runtime_path,
+            connection_id,
         )
         .await?;
 
@@ -366,13 +588,283 @@ impl MCPClientService {
             })
             .collect();
 
+        // Resources are optional - don't fail the connection test over them
+        let resources = Self::fetch_resources(&client).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to list resources from MCP server {url}: {e}");
+            MCPResourceCatalog::default()
+        });
+
+        // Prompts are optional too
+        let prompts = Self::fetch_prompts(&client).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to list prompts from MCP server {url}: {e}");
+            Vec::new()
+        });
+
         // Clean up - shut down the client connection
         client
             .shut_down()
             .await
             .map_err(|e| AppError::Generic(format!("Failed to close connection: {e}")))?;
 
-        Ok(tools)
+        Ok(MCPServerCatalog {
+            tools,
+            resources,
+            prompts,
+        })
+    }
+
+    /// List resources and resource templates published by a server
+    pub async fn fetch_resources(client: &ClientRuntime) -> Result<MCPResourceCatalog, AppError> {
+        // Servers without the resources capability reject resources/* requests
+        if client.server_has_resources() == Some(false) {
+            return Ok(MCPResourceCatalog::default());
+        }
+
+        let mut catalog = MCPResourceCatalog::default();
+
+        // Follow pagination cursors until the server has no more pages
+        let mut cursor = None;
+        loop {
+            let page = client
+                .list_resources(Some(ListResourcesRequestParams { cursor }))
+                .await
+                .map_err(|e| AppError::Mcp(format!("Failed to list resources: {e}")))?;
+
+            catalog
+                .resources
+                .extend(page.resources.into_iter().map(|resource| MCPResource {
+                    uri: resource.uri,
+                    name: resource.name,
+                    description: resource.description,
+                    mime_type: resource.mime_type,
+                }));
+
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        let mut cursor = None;
+        loop {
+            // Templates are optional even for servers that publish resources
+            let page = match client
+                .list_resource_templates(Some(ListResourceTemplatesRequestParams { cursor }))
+                .await
+            {
+                Ok(page) => page,
+                Err(e) => {
+                    tracing::debug!("Server does not list resource templates: {e}");
+                    break;
+                }
+            };
+
+            catalog
+                .templates
+                .extend(
+                    page.resource_templates
+                        .into_iter()
+                        .map(|template| MCPResourceTemplate {
+                            uri_template: template.uri_template,
+                            name: template.name,
+                            description: template.description,
+                            mime_type: template.mime_type,
+                        }),
+                );
+
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        Ok(catalog)
+    }
+
+    /// Read a resource by URI
+    pub async fn read_resource(
+        client: &ClientRuntime,
+        uri: &str,
+    ) -> Result<Vec<MCPResourceContent>, AppError> {
+        let result = client
+            .read_resource(ReadResourceRequestParams {
+                uri: uri.to_string(),
+            })
+            .await
+            .map_err(|e| AppError::Mcp(format!("Failed to read resource {uri}: {e}")))?;
+
+        let contents = result
+            .contents
+            .into_iter()
+            .map(|item| match item {
+                ReadResourceResultContentsItem::TextResourceContents(text) => MCPResourceContent {
+                    uri: text.uri,
+                    mime_type: text.mime_type,
+                    text: Some(text.text),
+                    blob: None,
+                },
+                ReadResourceResultContentsItem::BlobResourceContents(blob) => MCPResourceContent {
+                    uri: blob.uri,
+                    mime_type: blob.mime_type,
+                    text: None,
+                    blob: Some(blob.blob),
+                },
+            })
+            .collect();
+
+        Ok(contents)
+    }
+
+    /// List prompts published by a server
+    pub async fn fetch_prompts(client: &ClientRuntime) -> Result<Vec<MCPPrompt>, AppError> {
+        // Servers without the prompts capability reject prompts/* requests
+        if client.server_has_prompts() == Some(false) {
+            return Ok(Vec::new());
+        }
+
+        let mut prompts = Vec::new();
+
+        // Follow pagination cursors until the server has no more pages
+        let mut cursor = None;
+        loop {
+            let page = client
+                .list_prompts(Some(ListPromptsRequestParams { cursor }))
+                .await
+                .map_err(|e| AppError::Mcp(format!("Failed to list prompts: {e}")))?;
+
+            prompts.extend(page.prompts.into_iter().map(|prompt| {
+                MCPPrompt {
+                    name: prompt.name,
+                    title: prompt.title,
+                    description: prompt.description,
+                    arguments: prompt
+                        .arguments
+                        .into_iter()
+                        .map(|argument| MCPPromptArgument {
+                            name: argument.name,
+                            description: argument.description,
+                            required: argument.required.unwrap_or(false),
+                        })
+                        .collect(),
+                }
+            }));
+
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        Ok(prompts)
+    }
+
+    /// Render a prompt with arguments and join its text messages
+    pub async fn get_prompt(
+        client: &ClientRuntime,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<String, AppError> {
+        let result = client
+            .get_prompt(GetPromptRequestParams {
+                name: name.to_string(),
+                arguments: Some(arguments),
+            })
+            .await
+            .map_err(|e| AppError::Mcp(format!("Failed to get prompt {name}: {e}")))?;
+
+        let content = result
+            .messages
+            .into_iter()
+            .filter_map(|message| match message.content {
+                ContentBlock::TextContent(text) => Some(text.text),
+                ContentBlock::EmbeddedResource(embedded) => match embedded.resource {
+                    EmbeddedResourceResource::TextResourceContents(text) => Some(text.text),
+                    EmbeddedResourceResource::BlobResourceContents(_) => None,
+                },
+                _ => None,
+            })
+            .collect::<Vec<String>>()
+            .join("\n\n");
+
+        Ok(content)
+    }
+
+    /// Call a tool, asking the server to report progress under `progress_token`
+    /// `CallToolRequestParams` has no `_meta`, so the request is sent as a raw `tools/call`
+    pub async fn call_tool_with_progress(
+        client: &ClientRuntime,
+        params: CallToolRequestParams,
+        progress_token: Option<&str>,
+    ) -> Result<CallToolResult, McpSdkError> {
+        let Some(progress_token) = progress_token else {
+            return client.call_tool(params).await;
+        };
+
+        let mut request = serde_json::to_value(&params).map_err(|e| McpSdkError::Internal {
+            description: format!("Failed to serialize tool call: {e}"),
+        })?;
+        if let Some(request) = request.as_object_mut() {
+            request.insert("method".to_string(), serde_json::json!("tools/call"));
+            request.insert(
+                "_meta".to_string(),
+                serde_json::json!({ "progressToken": progress_token }),
+            );
+        }
+
+        let response = client
+            .request(RequestFromClient::CustomRequest(request), None)
+            .await?;
+        Ok(response.try_into()?)
+    }
+
+    /// Split a tool result into text for the model and binary content
+    /// Falls back to `structuredContent` when the tool returned no text
+    pub fn to_tool_output(result: CallToolResult) -> MCPToolOutput {
+        let mut texts = Vec::new();
+        let mut files = Vec::new();
+
+        for block in result.content {
+            match block {
+                ContentBlock::TextContent(text) => texts.push(text.text),
+                ContentBlock::ImageContent(image) => files.push(MCPToolFile {
+                    mime_type: image.mime_type,
+                    data: image.data,
+                }),
+                ContentBlock::AudioContent(audio) => files.push(MCPToolFile {
+                    mime_type: audio.mime_type,
+                    data: audio.data,
+                }),
+                ContentBlock::ResourceLink(link) => {
+                    texts.push(format!("[Resource: {} ({})]", link.name, link.uri));
+                }
+                ContentBlock::EmbeddedResource(embedded) => match embedded.resource {
+                    EmbeddedResourceResource::TextResourceContents(text) => {
+                        texts.push(format!("[Resource: {}]\n{}", text.uri, text.text));
+                    }
+                    EmbeddedResourceResource::BlobResourceContents(blob) => {
+                        files.push(MCPToolFile {
+                            mime_type: blob
+                                .mime_type
+                                .unwrap_or_else(|| "application/octet-stream".to_string()),
+                            data: blob.blob,
+                        });
+                    }
+                },
+            }
+        }
+
+        let structured_content = result.structured_content.map(serde_json::Value::Object);
+        let text = match &structured_content {
+            Some(structured) if texts.is_empty() => structured.to_string(),
+            _ => texts.join("\n\n"),
+        };
+
+        MCPToolOutput {
+            text,
+            files,
+            structured_content,
+            is_error: result.is_error.unwrap_or(false),
+        }
     }
 
     /// Call a tool using MCP client
@@ -393,6 +885,7 @@ impl MCPClientService {
             headers,
             env_vars_json,
             runtime_path,
+            None,
         )
         .await?;
 
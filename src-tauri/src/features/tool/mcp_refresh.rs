@@ -1,4 +1,5 @@
 use super::mcp_client::MCPClientService;
+use super::models::MCPServerCatalog;
 use crate::error::AppError;
 use crate::features::mcp_connection::MCPConnectionRepository;
 use std::sync::Arc;
@@ -59,8 +60,8 @@ impl MCPToolRefreshService {
                 continue;
             }
 
-            // Fetch tools from MCP server
-            match MCPClientService::test_connection_and_fetch_tools(
+            // Fetch tools, resources and prompts from MCP server
+            match MCPClientService::test_connection_and_fetch_catalog(
                 &self.app,
                 connection.url.clone(),
                 connection.r#type.clone(),
@@ -71,21 +72,15 @@ impl MCPToolRefreshService {
                 },
                 connection.env_vars.clone(),
                 connection.runtime_path.clone(),
+                Some(connection.id.clone()),
             )
             .await
             {
-                Ok(tools) => {
-                    // Serialize tools to JSON
-                    let tools_json = serde_json::to_string(&tools).map_err(|e| {
-                        AppError::Generic(format!("Failed to serialize tools: {e}"))
-                    })?;
-
-                    // Update connection with new tools
-                    self.mcp_connection_repository.update_status(
+                Ok(catalog) => {
+                    save_catalog(
+                        self.mcp_connection_repository.as_ref(),
                         &connection.id,
-                        "connected",
-                        Some(&tools_json),
-                        None,
+                        &catalog,
                     )?;
                 }
                 Err(e) => {
@@ -106,3 +101,22 @@ impl MCPToolRefreshService {
         Ok(())
     }
 }
+
+/// Cache a server's tools, resources and prompts on its connection and mark it connected
+pub fn save_catalog(
+    repository: &dyn MCPConnectionRepository,
+    connection_id: &str,
+    catalog: &MCPServerCatalog,
+) -> Result<(), AppError> {
+    // Serialize tools, resources and prompts to JSON
+    let tools_json = serde_json::to_string(&catalog.tools)
+        .map_err(|e| AppError::Generic(format!("Failed to serialize tools: {e}")))?;
+    let resources_json = serde_json::to_string(&catalog.resources)
+        .map_err(|e| AppError::Generic(format!("Failed to serialize resources: {e}")))?;
+    let prompts_json = serde_json::to_string(&catalog.prompts)
+        .map_err(|e| AppError::Generic(format!("Failed to serialize prompts: {e}")))?;
+
+    repository.update_status(connection_id, "connected", Some(&tools_json), None)?;
+    repository.update_resources(connection_id, Some(&resources_json))?;
+    repository.update_prompts(connection_id, Some(&prompts_json))
+}
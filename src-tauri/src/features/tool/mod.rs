@@ -1,5 +1,10 @@
+pub mod call_context;
+pub mod client_pool;
 pub mod commands;
+pub mod elicitation;
 pub mod mcp_client;
 pub mod mcp_refresh;
 pub mod models;
+pub mod policy;
+pub mod sampling;
 pub mod service;
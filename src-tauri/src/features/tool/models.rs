@@ -7,3 +7,96 @@ pub struct MCPTool {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub input_schema: Option<String>, // JSON string
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MCPResource {
+    pub uri: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MCPResourceTemplate {
+    pub uri_template: String, // RFC 6570 URI template
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// Resources and templates published by a server (cached in `resources_json`)
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MCPResourceCatalog {
+    pub resources: Vec<MCPResource>,
+    pub templates: Vec<MCPResourceTemplate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MCPResourceContent {
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blob: Option<String>, // base64 encoded
+}
+
+/// A resource attached to a chat message (stored in message metadata under "resources")
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct MCPResourceRef {
+    pub connection_id: String,
+    pub uri: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MCPPromptArgument {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// A prompt published by a server (cached in `prompts_json`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MCPPrompt {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<MCPPromptArgument>,
+}
+
+/// Everything a server publishes, fetched in one connection
+#[derive(Debug, Serialize, Clone)]
+pub struct MCPServerCatalog {
+    pub tools: Vec<MCPTool>,
+    pub resources: MCPResourceCatalog,
+    pub prompts: Vec<MCPPrompt>,
+}
+
+/// Binary content returned by a tool (image, audio or blob resource)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MCPToolFile {
+    pub mime_type: String,
+    pub data: String, // base64 encoded
+}
+
+/// Result of a tool call, split into text for the model and binary content
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MCPToolOutput {
+    pub text: String,
+    #[serde(default)]
+    pub files: Vec<MCPToolFile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<serde_json::Value>,
+    #[serde(default)]
+    pub is_error: bool,
+}
@@ -0,0 +1,222 @@
+//! Timeouts, retries and circuit breaking for MCP tool calls
+//!
+//! Policies come from the workspace's tool execution settings: a tool's own policy
+//! wins over its connection's, which wins over the workspace default. The circuit
+//! breaker is shared by all workspaces, since they share the pooled server processes.
+
+use crate::error::AppError;
+use crate::features::workspace::settings::{ToolErrorKind, ToolExecutionConfig, ToolPolicy};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+const DEFAULT_MAX_RETRIES: u32 = 2;
+const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(30);
+
+const DEFAULT_BREAKER_THRESHOLD: u32 = 5;
+const DEFAULT_BREAKER_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// A failed tool call and how it failed
+#[derive(Debug)]
+pub struct ToolCallError {
+    pub kind: ToolErrorKind,
+    pub error: AppError,
+}
+
+impl ToolCallError {
+    pub const fn new(kind: ToolErrorKind, error: AppError) -> Self {
+        Self { kind, error }
+    }
+}
+
+/// Policy of one tool call, with every level merged in
+///
+/// Nothing is retried unless `retry_on` is configured: a failed call may still have
+/// had its effect, and only the user knows which tools are safe to run twice.
+#[derive(Debug, Clone)]
+pub struct ToolCallPolicy {
+    pub timeout: Duration,
+    pub max_retries: u32,
+    pub retry_backoff: Duration,
+    pub retry_on: Vec<ToolErrorKind>,
+}
+
+impl Default for ToolCallPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+            retry_on: Vec::new(),
+        }
+    }
+}
+
+impl ToolCallPolicy {
+    pub fn resolve(config: &ToolExecutionConfig, connection_id: &str, tool_name: &str) -> Self {
+        let levels: Vec<&ToolPolicy> = [
+            config.tool_policies.get(tool_name),
+            config.connection_policies.get(connection_id),
+            Some(&config.default_policy),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let pick = |field: fn(&ToolPolicy) -> Option<u64>| levels.iter().find_map(|p| field(p));
+
+        let defaults = Self::default();
+        Self {
+            timeout: pick(|p| p.timeout_secs)
+                .filter(|secs| *secs > 0)
+                .map_or(defaults.timeout, Duration::from_secs),
+            max_retries: levels
+                .iter()
+                .find_map(|p| p.max_retries)
+                .unwrap_or(defaults.max_retries),
+            retry_backoff: pick(|p| p.retry_backoff_ms)
+                .map_or(defaults.retry_backoff, Duration::from_millis),
+            retry_on: levels
+                .iter()
+                .find_map(|p| p.retry_on.clone())
+                .unwrap_or(defaults.retry_on),
+        }
+    }
+
+    pub fn should_retry(&self, kind: ToolErrorKind, attempt: u32) -> bool {
+        attempt < self.max_retries && self.retry_on.contains(&kind)
+    }
+
+    /// Delay before retry number `attempt` (0-based): backoff, 2x backoff, 4x backoff...
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.retry_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_RETRY_BACKOFF)
+    }
+}
+
+/// When a server is considered degraded and for how long
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitBreakerConfig {
+    pub threshold: u32,
+    pub cooldown: Duration,
+}
+
+impl CircuitBreakerConfig {
+    pub fn from_config(config: &ToolExecutionConfig) -> Self {
+        Self {
+            threshold: config
+                .circuit_breaker_threshold
+                .unwrap_or(DEFAULT_BREAKER_THRESHOLD),
+            cooldown: config
+                .circuit_breaker_cooldown_secs
+                .map_or(DEFAULT_BREAKER_COOLDOWN, Duration::from_secs),
+        }
+    }
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self::from_config(&ToolExecutionConfig::default())
+    }
+}
+
+#[derive(Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    // A single call is let through after the cooldown, until it finishes or this passes
+    probe_until: Option<Instant>,
+}
+
+/// Per-connection circuit breakers
+///
+/// A server whose calls keep timing out or failing to connect is marked degraded,
+/// and calls to it fail right away until the cooldown ends. A single probe call is
+/// then let through while the others keep failing fast: a response closes the
+/// breaker, another failure opens it again.
+#[derive(Default)]
+pub struct CircuitBreakers {
+    states: Mutex<HashMap<String, BreakerState>>,
+}
+
+impl CircuitBreakers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail fast while the connection is degraded
+    pub fn check(
+        &self,
+        connection_id: &str,
+        server_name: &str,
+        config: CircuitBreakerConfig,
+    ) -> Result<(), AppError> {
+        let mut states = self
+            .states
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let Some(state) = states.get_mut(connection_id) else {
+            return Ok(());
+        };
+        let Some(open_until) = state.open_until else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        let remaining = open_until.saturating_duration_since(now);
+        if !remaining.is_zero() {
+            return Err(AppError::Mcp(format!(
+                "MCP server {server_name} is degraded after repeated failures, \
+                 its tools are unavailable for the next {} seconds. \
+                 Continue without this tool or try again later.",
+                remaining.as_secs().max(1)
+            )));
+        }
+
+        // Half-open: one probe at a time (a probe that never reports back expires)
+        if state
+            .probe_until
+            .is_some_and(|probe_until| probe_until > now)
+        {
+            return Err(AppError::Mcp(format!(
+                "MCP server {server_name} is degraded after repeated failures \
+                 and is being checked for recovery. \
+                 Continue without this tool or try again shortly."
+            )));
+        }
+        state.probe_until = Some(now + config.cooldown.max(Duration::from_secs(1)));
+        Ok(())
+    }
+
+    /// The server responded (even with an error), it is reachable again
+    pub fn record_success(&self, connection_id: &str) {
+        self.states
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(connection_id);
+    }
+
+    /// Count a failure, returns true when it marks the server degraded
+    /// A failed probe opens the breaker again right away
+    pub fn record_failure(&self, connection_id: &str, config: CircuitBreakerConfig) -> bool {
+        if config.threshold == 0 {
+            return false;
+        }
+
+        let mut states = self
+            .states
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let state = states.entry(connection_id.to_string()).or_default();
+        state.consecutive_failures += 1;
+        if state.consecutive_failures < config.threshold {
+            return false;
+        }
+
+        state.open_until = Some(Instant::now() + config.cooldown);
+        state.probe_until = None;
+        true
+    }
+}
@@ -0,0 +1,303 @@
+use super::call_context::{ToolCallContext, ToolCallContexts};
+use crate::error::AppError;
+use crate::events::ToolEmitter;
+use crate::features::llm_connection::LLMConnectionService;
+use crate::features::usage::UsageService;
+use crate::features::workspace::settings::WorkspaceSettingsService;
+use crate::models::llm_types::{
+    AssistantContent, ChatMessage, ContentPart, ImageUrl, InlineData, LLMChatRequest, UserContent,
+};
+use crate::services::LLMService;
+use rust_mcp_sdk::schema::{
+    CreateMessageRequestParams, CreateMessageResult, Role, SamplingMessageContent, TextContent,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+use tokio::sync::oneshot;
+
+/// Handles `sampling/createMessage` requests from MCP servers
+///
+/// Servers can only sample while one of their tools runs for a chat (see
+/// `ToolCallContexts`). Completions use the workspace's LLM connection and
+/// model, need user approval and are recorded as usage of that chat.
+pub struct MCPSamplingService {
+    llm_service: Arc<LLMService>,
+    llm_connection_service: Arc<LLMConnectionService>,
+    workspace_settings_service: Arc<WorkspaceSettingsService>,
+    usage_service: Arc<UsageService>,
+    call_contexts: Arc<ToolCallContexts>,
+    // request_id -> oneshot sender for approval response
+    pending_approvals: Mutex<HashMap<String, oneshot::Sender<bool>>>,
+}
+
+impl MCPSamplingService {
+    pub fn new(
+        llm_service: Arc<LLMService>,
+        llm_connection_service: Arc<LLMConnectionService>,
+        workspace_settings_service: Arc<WorkspaceSettingsService>,
+        usage_service: Arc<UsageService>,
+        call_contexts: Arc<ToolCallContexts>,
+    ) -> Self {
+        Self {
+            llm_service,
+            llm_connection_service,
+            workspace_settings_service,
+            usage_service,
+            call_contexts,
+            pending_approvals: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Deliver the user's decision for a pending sampling request
+    pub fn respond(&self, request_id: &str, approved: bool) -> Result<(), AppError> {
+        let sender = self
+            .pending_approvals
+            .lock()
+            .map_err(|e| AppError::Generic(format!("Failed to lock pending_approvals: {e}")))?
+            .remove(request_id);
+
+        match sender {
+            Some(sender) => sender.send(approved).map_err(|_| {
+                AppError::Generic(format!(
+                    "Failed to send approval response for sampling request {request_id}"
+                ))
+            }),
+            None => Err(AppError::Validation(format!(
+                "No pending sampling request found: {request_id}"
+            ))),
+        }
+    }
+
+    /// Ask the user to approve a sampling request (60 second timeout)
+    async fn request_approval(
+        &self,
+        app: &AppHandle,
+        context: &ToolCallContext,
+        request_id: &str,
+        server_name: &str,
+        params: &CreateMessageRequestParams,
+    ) -> Result<(), AppError> {
+        let (tx, rx) = oneshot::channel::<bool>();
+        self.pending_approvals
+            .lock()
+            .map_err(|e| AppError::Generic(format!("Failed to lock pending_approvals: {e}")))?
+            .insert(request_id.to_string(), tx);
+
+        let tool_emitter = ToolEmitter::new(app.clone());
+        tool_emitter.emit_sampling_request(
+            context.chat_id.clone(),
+            context.message_id.clone(),
+            context.tool_call_id.clone(),
+            request_id.to_string(),
+            server_name.to_string(),
+            params.system_prompt.clone(),
+            serde_json::to_value(&params.messages)?,
+            params.max_tokens,
+        )?;
+
+        match tokio::time::timeout(tokio::time::Duration::from_secs(60), rx).await {
+            Ok(Ok(true)) => Ok(()),
+            Ok(Ok(false)) => Err(AppError::Generic(
+                "Sampling request denied by user".to_string(),
+            )),
+            Ok(Err(_)) => Err(AppError::Generic("Sampling request cancelled".to_string())),
+            Err(_) => {
+                self.pending_approvals
+                    .lock()
+                    .map_err(|e| {
+                        AppError::Generic(format!("Failed to lock pending_approvals: {e}"))
+                    })?
+                    .remove(request_id);
+
+                Err(AppError::Generic(
+                    "Sampling request timed out (60s)".to_string(),
+                ))
+            }
+        }
+    }
+
+    /// Convert sampling messages to chat messages
+    fn to_chat_messages(params: &CreateMessageRequestParams) -> Vec<ChatMessage> {
+        let mut messages = Vec::new();
+
+        if let Some(system_prompt) = &params.system_prompt {
+            if !system_prompt.trim().is_empty() {
+                messages.push(ChatMessage::System {
+                    content: system_prompt.clone(),
+                });
+            }
+        }
+
+        for message in &params.messages {
+            let part = match &message.content {
+                SamplingMessageContent::TextContent(text) => ContentPart::Text {
+                    text: text.text.clone(),
+                },
+                SamplingMessageContent::ImageContent(image) => ContentPart::ImageUrl {
+                    image_url: ImageUrl {
+                        url: format!("data:{};base64,{}", image.mime_type, image.data),
+                    },
+                },
+                SamplingMessageContent::AudioContent(audio) => ContentPart::InlineData {
+                    inline_data: InlineData {
+                        mime_type: audio.mime_type.clone(),
+                        data: audio.data.clone(),
+                    },
+                },
+            };
+
+            messages.push(match (&message.role, part) {
+                (Role::User, ContentPart::Text { text }) => ChatMessage::User {
+                    content: UserContent::Text(text),
+                },
+                (Role::User, part) => ChatMessage::User {
+                    content: UserContent::Parts(vec![part]),
+                },
+                (Role::Assistant, ContentPart::Text { text }) => ChatMessage::Assistant {
+                    content: AssistantContent::Text(text),
+                    tool_calls: None,
+                    reasoning_items: None,
+                },
+                (Role::Assistant, part) => ChatMessage::Assistant {
+                    content: AssistantContent::Parts(vec![part]),
+                    tool_calls: None,
+                    reasoning_items: None,
+                },
+            });
+        }
+
+        messages
+    }
+
+    /// Run a `sampling/createMessage` request from the client identified by `client_key`
+    pub async fn create_message(
+        &self,
+        app: &AppHandle,
+        client_key: Option<&str>,
+        server_name: &str,
+        params: CreateMessageRequestParams,
+    ) -> Result<CreateMessageResult, AppError> {
+        let client_key = client_key.ok_or_else(|| {
+            AppError::Validation(
+                "Sampling is only available while a tool runs for a chat".to_string(),
+            )
+        })?;
+        // The SDK drops request `_meta`, servers can echo the progress token in the metadata instead
+        let progress_token = params
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get("progressToken"))
+            .and_then(serde_json::Value::as_str);
+        let context = self.call_contexts.resolve(client_key, progress_token)?;
+
+        crate::lib::sentry_helpers::add_breadcrumb(
+            "mcp.sampling",
+            format!(
+                "Sampling request from {server_name} in chat {}",
+                context.chat_id
+            ),
+            sentry::Level::Info,
+        );
+
+        // Resolve the workspace's LLM connection and model
+        let workspace_settings = self
+            .workspace_settings_service
+            .get_by_workspace_id(&context.workspace_id)?
+            .ok_or_else(|| AppError::Validation("Workspace settings not found".to_string()))?;
+
+        let llm_connection_id = workspace_settings
+            .llm_connection_id
+            .clone()
+            .ok_or_else(|| {
+                AppError::Validation("LLM connection not configured for workspace".to_string())
+            })?;
+
+        let llm_connection = self
+            .llm_connection_service
+            .get_by_id(&llm_connection_id)?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("LLM connection not found: {llm_connection_id}"))
+            })?;
+
+        let model = workspace_settings
+            .default_model
+            .clone()
+            .or_else(|| llm_connection.default_model.clone())
+            .ok_or_else(|| AppError::Validation("No model selected".to_string()))?;
+
+        let request_id = uuid::Uuid::new_v4().to_string();
+        self.request_approval(app, &context, &request_id, server_name, &params)
+            .await?;
+
+        let request = LLMChatRequest {
+            model: model.clone(),
+            messages: Self::to_chat_messages(&params),
+            temperature: params.temperature,
+            max_tokens: u32::try_from(params.max_tokens).ok(),
+            stream: false,
+            tools: None,
+            tool_choice: None,
+            reasoning_effort: None,
+            stream_options: None,
+            response_modalities: None,
+            image_config: None,
+            response_format: None,
+        };
+
+        // Use dummy IDs to avoid interfering with current chat UI
+        let start_time = std::time::Instant::now();
+        let response = self
+            .llm_service
+            .chat(
+                &llm_connection.base_url,
+                Some(&llm_connection.api_key),
+                request,
+                "system_sampling".to_string(),
+                format!("sampling_{request_id}"),
+                app.clone(),
+                None,
+                &llm_connection.provider,
+                llm_connection.api_mode.as_deref(),
+            )
+            .await?;
+        let latency = start_time.elapsed().as_millis() as u64;
+
+        // Record usage against the chat that triggered the tool call
+        let usage_service = self.usage_service.clone();
+        let r_provider = llm_connection.provider.clone();
+        let r_model = model.clone();
+        let r_usage = response.usage.clone();
+
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = usage_service.record_usage(
+                context.workspace_id,
+                context.chat_id,
+                context.message_id,
+                r_provider,
+                r_model,
+                r_usage,
+                latency,
+                false,
+                "success".to_string(),
+            ) {
+                tracing::error!(error = ?e, "Failed to record sampling usage");
+            }
+        });
+
+        let stop_reason = response.finish_reason.map(|reason| match reason.as_str() {
+            "stop" | "end_turn" | "STOP" => "endTurn".to_string(),
+            "length" | "max_tokens" | "MAX_TOKENS" => "maxTokens".to_string(),
+            "stop_sequence" => "stopSequence".to_string(),
+            _ => reason,
+        });
+
+        Ok(CreateMessageResult {
+            content: TextContent::new(response.content, None, None).into(),
+            meta: None,
+            model,
+            role: Role::Assistant,
+            stop_reason,
+        })
+    }
+}
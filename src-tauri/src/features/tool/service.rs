@@ -1,29 +1,35 @@
+use super::client_pool::MCPClientPool;
 use super::mcp_client::MCPClientService;
+use super::policy::{CircuitBreakerConfig, CircuitBreakers, ToolCallError, ToolCallPolicy};
 use crate::error::AppError;
-use crate::features::mcp_connection::MCPConnectionService;
-use crate::features::tool::models::MCPTool;
-use crate::features::workspace::settings::WorkspaceSettingsService;
+use crate::features::mcp_connection::{MCPConnectionService, MCPServerConnection};
+use crate::features::tool::models::{
+    MCPPrompt, MCPResourceCatalog, MCPResourceContent, MCPTool, MCPToolOutput,
+};
+use crate::features::workspace::settings::{ToolErrorKind, WorkspaceSettingsService};
 use crate::models::llm_types::ChatCompletionTool;
+use rust_mcp_sdk::schema::CallToolRequestParams;
 use serde_json;
 use std::sync::Arc;
-use tauri::AppHandle;
 
 pub struct ToolService {
-    app: AppHandle,
     mcp_connection_service: Arc<MCPConnectionService>,
     workspace_settings_service: Arc<WorkspaceSettingsService>,
+    client_pool: Arc<MCPClientPool>,
+    circuit_breakers: CircuitBreakers,
 }
 
 impl ToolService {
-    pub const fn new(
-        app: AppHandle,
+    pub fn new(
         mcp_connection_service: Arc<MCPConnectionService>,
         workspace_settings_service: Arc<WorkspaceSettingsService>,
+        client_pool: Arc<MCPClientPool>,
     ) -> Self {
         Self {
-            app,
             mcp_connection_service,
             workspace_settings_service,
+            client_pool,
+            circuit_breakers: CircuitBreakers::new(),
         }
     }
 
@@ -111,12 +117,20 @@ impl ToolService {
     }
 
     /// Execute an MCP tool
+    /// Progress reported by the server is tagged with `progress_token`
+    ///
+    /// Each attempt is bounded by the policy's timeout, and failures the policy names
+    /// are retried with exponential backoff. Timeouts and transport failures count
+    /// towards the connection's circuit breaker.
     pub async fn execute_tool(
         &self,
         connection_id: &str,
         tool_name: &str,
         arguments: serde_json::Value,
-    ) -> Result<serde_json::Value, AppError> {
+        progress_token: Option<&str>,
+        policy: &ToolCallPolicy,
+        breaker: CircuitBreakerConfig,
+    ) -> Result<MCPToolOutput, AppError> {
         // Track tool execution start
         crate::lib::sentry_helpers::add_breadcrumb(
             "mcp.tool",
@@ -127,6 +141,112 @@ impl ToolService {
         let start_time = std::time::Instant::now();
 
         // Get MCP connection
+        let connection = self.get_connected_connection(connection_id)?;
+
+        // Call the tool on the pooled client (started on first use, reused afterwards)
+        let params = CallToolRequestParams {
+            name: tool_name.to_string(),
+            arguments: match arguments {
+                serde_json::Value::Object(map) => Some(map),
+                _ => None,
+            },
+        };
+
+        let mut attempt = 0;
+        let result = loop {
+            self.circuit_breakers
+                .check(connection_id, &connection.name, breaker)?;
+
+            let outcome = match tokio::time::timeout(
+                policy.timeout,
+                self.client_pool
+                    .call_tool(&connection, params.clone(), progress_token),
+            )
+            .await
+            {
+                Ok(Ok(call_result)) => {
+                    let output = MCPClientService::to_tool_output(call_result);
+                    if output.is_error && policy.should_retry(ToolErrorKind::Tool, attempt) {
+                        Err(ToolCallError::new(
+                            ToolErrorKind::Tool,
+                            AppError::Mcp(output.text),
+                        ))
+                    } else {
+                        Ok(output)
+                    }
+                }
+                Ok(Err(e)) => Err(e),
+                Err(_) => Err(ToolCallError::new(
+                    ToolErrorKind::Timeout,
+                    AppError::Generic(format!(
+                        "Tool execution timed out after {} seconds",
+                        policy.timeout.as_secs()
+                    )),
+                )),
+            };
+
+            let failure = match outcome {
+                Ok(output) => {
+                    self.circuit_breakers.record_success(connection_id);
+                    break output;
+                }
+                Err(failure) => failure,
+            };
+
+            // Only an unreachable or unresponsive server counts against it
+            if !matches!(
+                failure.kind,
+                ToolErrorKind::Timeout | ToolErrorKind::Transport
+            ) {
+                self.circuit_breakers.record_success(connection_id);
+            } else if self.circuit_breakers.record_failure(connection_id, breaker) {
+                tracing::warn!(
+                    connection_id = %connection_id,
+                    server = %connection.name,
+                    cooldown_secs = breaker.cooldown.as_secs(),
+                    "MCP server marked degraded after repeated failures"
+                );
+                crate::lib::sentry_helpers::add_breadcrumb(
+                    "mcp.tool",
+                    format!("MCP server {} marked degraded", connection.name),
+                    sentry::Level::Warning,
+                );
+            }
+
+            if !policy.should_retry(failure.kind, attempt) {
+                return Err(failure.error);
+            }
+
+            let delay = policy.backoff(attempt);
+            tracing::debug!(
+                tool = %tool_name,
+                connection_id = %connection_id,
+                attempt = attempt + 1,
+                delay_ms = delay.as_millis() as u64,
+                error = %failure.error,
+                "Retrying tool call"
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        };
+
+        // Track tool execution completion
+        let duration = start_time.elapsed().as_millis() as u64;
+        crate::lib::sentry_helpers::track_tool_execution(
+            tool_name,
+            connection_id,
+            duration,
+            &Ok::<(), Box<dyn std::error::Error>>(()),
+        );
+
+        Ok(result)
+    }
+
+    /// Get an MCP connection, failing if it is missing or not connected
+    fn get_connected_connection(
+        &self,
+        connection_id: &str,
+    ) -> Result<MCPServerConnection, AppError> {
         let connection = self
             .mcp_connection_service
             .get_by_id(connection_id)?
@@ -140,43 +260,85 @@ impl ToolService {
             )));
         }
 
-        // Parse headers
-        let headers = if connection.headers.is_empty() {
-            None
-        } else {
-            Some(connection.headers.clone())
-        };
+        Ok(connection)
+    }
 
-        // Execute tool using MCP client service
-        let result_json = MCPClientService::call_tool(
-            &self.app,
-            connection.url,
-            connection.r#type,
-            headers,
-            connection.env_vars,
-            tool_name.to_string(),
-            arguments,
-            connection.runtime_path,
-        )
-        .await
-        .map_err(|e| AppError::Generic(format!("Failed to execute tool {tool_name}: {e}")))?;
-
-        // Parse result JSON
-        let result: serde_json::Value = serde_json::from_str(&result_json)
-            .map_err(|e| AppError::Generic(format!("Failed to parse tool result: {e}")))?;
+    /// Fetch resources and resource templates from an MCP server and cache them in `resources_json`
+    pub async fn refresh_resources(
+        &self,
+        connection_id: &str,
+    ) -> Result<MCPResourceCatalog, AppError> {
+        let connection = self.get_connected_connection(connection_id)?;
+        let client = self.client_pool.get_client(&connection).await?;
+        let catalog = MCPClientService::fetch_resources(&client).await?;
 
-        // Track tool execution completion
-        let duration = start_time.elapsed().as_millis() as u64;
-        crate::lib::sentry_helpers::track_tool_execution(
-            tool_name,
-            connection_id,
-            duration,
-            &Ok::<(), Box<dyn std::error::Error>>(()),
+        let resources_json = serde_json::to_string(&catalog)
+            .map_err(|e| AppError::Generic(format!("Failed to serialize resources: {e}")))?;
+        self.mcp_connection_service
+            .update_resources(connection_id, Some(&resources_json))?;
+
+        Ok(catalog)
+    }
+
+    /// Read a resource by URI from an MCP server
+    pub async fn read_resource(
+        &self,
+        connection_id: &str,
+        uri: &str,
+    ) -> Result<Vec<MCPResourceContent>, AppError> {
+        crate::lib::sentry_helpers::add_breadcrumb(
+            "mcp.resource",
+            format!("Reading resource {uri} via connection {connection_id}"),
+            sentry::Level::Info,
         );
 
+        let connection = self.get_connected_connection(connection_id)?;
+        let client = self.client_pool.get_client(&connection).await?;
+        MCPClientService::read_resource(&client, uri).await
+    }
+
+    /// Get cached prompts of all connected MCP servers
+    pub fn get_cached_prompts(
+        &self,
+    ) -> Result<Vec<(MCPServerConnection, Vec<MCPPrompt>)>, AppError> {
+        let connections = self.mcp_connection_service.get_all()?;
+
+        let mut result = Vec::new();
+        for connection in connections {
+            if connection.status != "connected" {
+                continue;
+            }
+
+            let prompts: Vec<MCPPrompt> = match &connection.prompts_json {
+                Some(prompts_json) => serde_json::from_str(prompts_json).map_err(|e| {
+                    AppError::Generic(format!(
+                        "Failed to parse cached prompts for connection {}: {}",
+                        connection.id, e
+                    ))
+                })?,
+                None => continue,
+            };
+
+            if !prompts.is_empty() {
+                result.push((connection, prompts));
+            }
+        }
+
         Ok(result)
     }
 
+    /// Render a prompt from an MCP server with the given arguments
+    pub async fn get_prompt(
+        &self,
+        connection_id: &str,
+        name: &str,
+        arguments: std::collections::HashMap<String, String>,
+    ) -> Result<String, AppError> {
+        let connection = self.get_connected_connection(connection_id)?;
+        let client = self.client_pool.get_client(&connection).await?;
+        MCPClientService::get_prompt(&client, name, arguments).await
+    }
+
     /// Get a map of tool names to connection IDs for a workspace
     pub fn get_tool_to_connection_map(
         &self,
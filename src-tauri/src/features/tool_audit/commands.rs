@@ -0,0 +1,27 @@
+use super::models::{AuditExportFormat, AuditExportResult, ToolAuditEntry, ToolAuditFilter};
+use crate::error::AppError;
+use crate::state::AppState;
+use tauri::State;
+
+#[tauri::command]
+pub fn get_tool_audit_log(
+    filter: ToolAuditFilter,
+    page: u32,
+    limit: u32,
+    state: State<'_, AppState>,
+) -> Result<Vec<ToolAuditEntry>, AppError> {
+    state.tool_audit_service.get_entries(&filter, page, limit)
+}
+
+/// Export the matching entries, to `path` when given, otherwise the content is returned
+#[tauri::command]
+pub fn export_tool_audit_log(
+    filter: ToolAuditFilter,
+    format: AuditExportFormat,
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<AuditExportResult, AppError> {
+    state
+        .tool_audit_service
+        .export(&filter, format, path.as_deref().map(std::path::Path::new))
+}
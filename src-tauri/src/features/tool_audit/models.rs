@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+/// How a call got permission to run
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditPermission {
+    Auto,     // Allowed by the workspace settings
+    Approved, // Approved by the user, now or by a remembered decision
+    Denied,   // Denied by the user or by a permission rule
+    TimedOut, // Nobody answered the permission request in time
+}
+
+impl AuditPermission {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Approved => "approved",
+            Self::Denied => "denied",
+            Self::TimedOut => "timed_out",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "approved" => Self::Approved,
+            "denied" => Self::Denied,
+            "timed_out" => Self::TimedOut,
+            _ => Self::Auto,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ToolAuditEntry {
+    pub id: String,
+    pub workspace_id: String,
+    pub chat_id: String,
+    pub message_id: String, // Assistant message that made the call
+    pub tool_call_id: String,
+    pub connection_id: Option<String>, // MCP connection, "agent:<id>" for agent tools
+    pub tool_name: String,
+    pub arguments: String,
+    pub result_size: u64, // Bytes of text returned to the model
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub permission: AuditPermission,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ToolAuditFilter {
+    pub start_date: Option<i64>,
+    pub end_date: Option<i64>,
+    pub workspace_id: Option<String>,
+    pub chat_id: Option<String>,
+    pub tool_name: Option<String>,
+    pub permission: Option<AuditPermission>,
+    #[serde(default)]
+    pub errors_only: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditExportFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuditExportResult {
+    pub format: AuditExportFormat,
+    pub path: Option<String>,    // Set when the export was written to a file
+    pub content: Option<String>, // Set otherwise
+    pub entry_count: usize,
+}
+
+impl ToolAuditEntry {
+    /// Entry for a call that has not run yet, callers fill in its outcome
+    pub fn new(
+        workspace_id: &str,
+        chat_id: &str,
+        message_id: &str,
+        tool_call: &crate::models::llm_types::ToolCall,
+        permission: AuditPermission,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            workspace_id: workspace_id.to_string(),
+            chat_id: chat_id.to_string(),
+            message_id: message_id.to_string(),
+            tool_call_id: tool_call.id.clone(),
+            connection_id: None,
+            tool_name: tool_call.function.name.clone(),
+            arguments: tool_call.function.arguments.clone(),
+            result_size: 0,
+            error: None,
+            duration_ms: 0,
+            permission,
+            timestamp: chrono::Utc::now().timestamp(),
+        }
+    }
+}
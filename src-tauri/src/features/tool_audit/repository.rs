@@ -0,0 +1,123 @@
+use super::models::{AuditPermission, ToolAuditEntry, ToolAuditFilter};
+use crate::error::AppError;
+use rusqlite::params;
+use std::sync::Arc;
+use tauri::AppHandle;
+
+pub trait ToolAuditRepository: Send + Sync {
+    fn create(&self, entry: &ToolAuditEntry) -> Result<(), AppError>;
+    /// Matching entries, newest first, every one of them when `limit` is `None`
+    fn get(
+        &self,
+        filter: &ToolAuditFilter,
+        limit: Option<u32>,
+        offset: u32,
+    ) -> Result<Vec<ToolAuditEntry>, AppError>;
+}
+
+pub struct SqliteToolAuditRepository {
+    app: Arc<AppHandle>,
+}
+
+impl SqliteToolAuditRepository {
+    pub const fn new(app: Arc<AppHandle>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolAuditRepository for SqliteToolAuditRepository {
+    fn create(&self, entry: &ToolAuditEntry) -> Result<(), AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        conn.execute(
+            "INSERT INTO tool_audit_log (
+                id, workspace_id, chat_id, message_id, tool_call_id, connection_id, tool_name,
+                arguments, result_size, error, duration_ms, permission, timestamp
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                entry.id,
+                entry.workspace_id,
+                entry.chat_id,
+                entry.message_id,
+                entry.tool_call_id,
+                entry.connection_id,
+                entry.tool_name,
+                entry.arguments,
+                entry.result_size,
+                entry.error,
+                entry.duration_ms,
+                entry.permission.as_str(),
+                entry.timestamp
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn get(
+        &self,
+        filter: &ToolAuditFilter,
+        limit: Option<u32>,
+        offset: u32,
+    ) -> Result<Vec<ToolAuditEntry>, AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        let mut query = String::from("SELECT * FROM tool_audit_log WHERE 1=1");
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(ws_id) = &filter.workspace_id {
+            query.push_str(" AND workspace_id = ?");
+            params.push(Box::new(ws_id.clone()));
+        }
+        if let Some(chat_id) = &filter.chat_id {
+            query.push_str(" AND chat_id = ?");
+            params.push(Box::new(chat_id.clone()));
+        }
+        if let Some(tool_name) = &filter.tool_name {
+            query.push_str(" AND tool_name = ?");
+            params.push(Box::new(tool_name.clone()));
+        }
+        if let Some(permission) = filter.permission {
+            query.push_str(" AND permission = ?");
+            params.push(Box::new(permission.as_str()));
+        }
+        if filter.errors_only {
+            query.push_str(" AND error IS NOT NULL");
+        }
+        if let Some(start) = filter.start_date {
+            query.push_str(" AND timestamp >= ?");
+            params.push(Box::new(start));
+        }
+        if let Some(end) = filter.end_date {
+            query.push_str(" AND timestamp <= ?");
+            params.push(Box::new(end));
+        }
+
+        // A negative limit means no limit in SQLite
+        query.push_str(" ORDER BY timestamp DESC, rowid DESC LIMIT ? OFFSET ?");
+        params.push(Box::new(limit.map_or(-1, i64::from)));
+        params.push(Box::new(offset));
+
+        let mut stmt = conn.prepare(&query)?;
+        let params_ref: Vec<&dyn rusqlite::ToSql> =
+            params.iter().map(std::convert::AsRef::as_ref).collect();
+
+        let entries = stmt
+            .query_map(params_ref.as_slice(), |row| {
+                Ok(ToolAuditEntry {
+                    id: row.get("id")?,
+                    workspace_id: row.get("workspace_id")?,
+                    chat_id: row.get("chat_id")?,
+                    message_id: row.get("message_id")?,
+                    tool_call_id: row.get("tool_call_id")?,
+                    connection_id: row.get("connection_id")?,
+                    tool_name: row.get("tool_name")?,
+                    arguments: row.get("arguments")?,
+                    result_size: row.get("result_size")?,
+                    error: row.get("error")?,
+                    duration_ms: row.get("duration_ms")?,
+                    permission: AuditPermission::parse(&row.get::<_, String>("permission")?),
+                    timestamp: row.get("timestamp")?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+}
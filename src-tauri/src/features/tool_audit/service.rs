@@ -0,0 +1,111 @@
+use super::models::{AuditExportFormat, AuditExportResult, ToolAuditEntry, ToolAuditFilter};
+use super::repository::ToolAuditRepository;
+use crate::error::AppError;
+use std::path::Path;
+use std::sync::Arc;
+
+const CSV_COLUMNS: &str = "id,timestamp,workspace_id,chat_id,message_id,tool_call_id,\
+connection_id,tool_name,arguments,result_size,error,duration_ms,permission";
+
+pub struct ToolAuditService {
+    repository: Arc<dyn ToolAuditRepository>,
+}
+
+impl ToolAuditService {
+    pub fn new(repository: Arc<dyn ToolAuditRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Store an entry, a failure is logged so it never fails the tool call itself
+    pub fn record(&self, entry: &ToolAuditEntry) {
+        if let Err(e) = self.repository.create(entry) {
+            tracing::error!(
+                tool = %entry.tool_name,
+                tool_call_id = %entry.tool_call_id,
+                error = %e,
+                "Failed to write tool audit log entry"
+            );
+        }
+    }
+
+    pub fn get_entries(
+        &self,
+        filter: &ToolAuditFilter,
+        page: u32,
+        limit: u32,
+    ) -> Result<Vec<ToolAuditEntry>, AppError> {
+        self.repository
+            .get(filter, Some(limit), page.saturating_mul(limit))
+    }
+
+    pub fn export(
+        &self,
+        filter: &ToolAuditFilter,
+        format: AuditExportFormat,
+        path: Option<&Path>,
+    ) -> Result<AuditExportResult, AppError> {
+        let entries = self.repository.get(filter, None, 0)?;
+        let content = match format {
+            AuditExportFormat::Json => serde_json::to_string_pretty(&entries)?,
+            AuditExportFormat::Csv => Self::to_csv(&entries),
+        };
+
+        crate::lib::sentry_helpers::add_breadcrumb(
+            "tool_audit",
+            format!("Exported {} tool audit log entries", entries.len()),
+            sentry::Level::Info,
+        );
+
+        let entry_count = entries.len();
+        match path {
+            Some(path) => {
+                std::fs::write(path, content)?;
+                Ok(AuditExportResult {
+                    format,
+                    path: Some(path.to_string_lossy().to_string()),
+                    content: None,
+                    entry_count,
+                })
+            }
+            None => Ok(AuditExportResult {
+                format,
+                path: None,
+                content: Some(content),
+                entry_count,
+            }),
+        }
+    }
+
+    fn to_csv(entries: &[ToolAuditEntry]) -> String {
+        // RFC 4180: quote fields holding separators, quotes or line breaks
+        fn field(value: &str) -> String {
+            if value.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", value.replace('"', "\"\""))
+            } else {
+                value.to_string()
+            }
+        }
+
+        let mut csv = format!("{CSV_COLUMNS}\r\n");
+        for entry in entries {
+            let row = [
+                field(&entry.id),
+                entry.timestamp.to_string(),
+                field(&entry.workspace_id),
+                field(&entry.chat_id),
+                field(&entry.message_id),
+                field(&entry.tool_call_id),
+                field(entry.connection_id.as_deref().unwrap_or_default()),
+                field(&entry.tool_name),
+                field(&entry.arguments),
+                entry.result_size.to_string(),
+                field(entry.error.as_deref().unwrap_or_default()),
+                entry.duration_ms.to_string(),
+                entry.permission.as_str().to_string(),
+            ];
+            csv.push_str(&row.join(","));
+            csv.push_str("\r\n");
+        }
+        csv
+    }
+}
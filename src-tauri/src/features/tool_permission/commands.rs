@@ -0,0 +1,20 @@
+use super::models::ToolPermissionGrant;
+use crate::error::AppError;
+use crate::state::AppState;
+use tauri::State;
+
+#[tauri::command]
+pub fn get_tool_permission_grants(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<ToolPermissionGrant>, AppError> {
+    state.tool_permission_service.get_grants(&workspace_id)
+}
+
+#[tauri::command]
+pub fn revoke_tool_permission_grant(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    state.tool_permission_service.revoke(&id)
+}
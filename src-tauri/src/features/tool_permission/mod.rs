@@ -0,0 +1,10 @@
+pub mod commands;
+pub mod models;
+pub mod repository;
+pub mod service;
+#[cfg(test)]
+mod tests;
+
+pub use models::*;
+pub use repository::*;
+pub use service::*;
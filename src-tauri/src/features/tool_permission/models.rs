@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Component, Path};
+
+/// What happens to a tool call without asking, or whether to ask
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionMode {
+    /// Run without asking
+    #[default]
+    #[serde(alias = "allow")]
+    Auto,
+    /// Ask the user, unless they chose to always allow the tool
+    Require,
+    /// Never run, the model gets an error instead
+    Deny,
+}
+
+const fn require() -> PermissionMode {
+    PermissionMode::Require
+}
+
+/// Entry of `tool_permission_config` for one tool: a mode, or rules over its arguments
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum ToolPermissionEntry {
+    Mode(PermissionMode),
+    Rules(ToolPermissionRules),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ToolPermissionRules {
+    #[serde(default = "require")]
+    pub default: PermissionMode, // When no rule matches
+    #[serde(default)]
+    pub rules: Vec<ArgumentRule>, // First match wins
+}
+
+/// Matches tool calls by one of their arguments, every condition given must hold
+///
+/// `{ "action": "auto", "argument": "path", "prefix": "/home/me/project/" }`
+/// `{ "action": "auto", "argument": "sql", "not_pattern": "(?i)\\b(delete|drop)\\b" }`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArgumentRule {
+    pub action: PermissionMode,
+    pub argument: Option<String>, // Argument name or JSON pointer, all arguments when unset
+    pub prefix: Option<String>,
+    pub pattern: Option<String>,     // Regex the value must match
+    pub not_pattern: Option<String>, // Regex the value must not match
+}
+
+impl ArgumentRule {
+    /// Regexes that do not compile, reported when the settings are saved
+    pub fn invalid_patterns(&self) -> Vec<String> {
+        [&self.pattern, &self.not_pattern]
+            .into_iter()
+            .flatten()
+            .filter_map(|pattern| regex::Regex::new(pattern).err().map(|e| e.to_string()))
+            .collect()
+    }
+
+    pub fn matches(&self, arguments: &Value) -> bool {
+        let value = match self.argument.as_deref() {
+            None => Some(arguments),
+            Some(pointer) if pointer.starts_with('/') => arguments.pointer(pointer),
+            Some(name) => arguments.get(name),
+        };
+        let Some(value) = value else {
+            return false;
+        };
+        let text = value
+            .as_str()
+            .map_or_else(|| value.to_string(), ToString::to_string);
+
+        if let Some(prefix) = &self.prefix {
+            // `..` could climb out of the allowed directory, and comparing whole
+            // components keeps `/home/me/project` from matching `/home/me/project-old`
+            let path = Path::new(&text);
+            let escapes = path.components().any(|c| c == Component::ParentDir);
+            if escapes || !path.starts_with(prefix) {
+                return false;
+            }
+        }
+        let is_match =
+            |pattern: &str| regex::Regex::new(pattern).is_ok_and(|regex| regex.is_match(&text));
+        if self.pattern.as_deref().is_some_and(|p| !is_match(p)) {
+            return false;
+        }
+        // An invalid pattern must not let calls through
+        if let Some(not_pattern) = &self.not_pattern {
+            if regex::Regex::new(not_pattern).map_or(true, |regex| regex.is_match(&text)) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Parsed `tool_permission_config`: `{ "tool_name": "require" | "auto" | "deny" | { rules } }`
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(transparent)]
+pub struct ToolPermissionConfig(pub HashMap<String, ToolPermissionEntry>);
+
+impl ToolPermissionConfig {
+    /// Config that asks before running any of `tool_names`, for settings that cannot be read
+    pub fn require_all<'a>(tool_names: impl IntoIterator<Item = &'a str>) -> Self {
+        Self(
+            tool_names
+                .into_iter()
+                .map(|name| {
+                    (
+                        name.to_string(),
+                        ToolPermissionEntry::Mode(PermissionMode::Require),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Mode for a call, tools without an entry run without asking
+    ///
+    /// Also says whether an argument rule picked the mode: a remembered grant covers
+    /// the tool, not the particular arguments a rule singled out
+    pub fn mode_for(&self, tool_name: &str, arguments: &Value) -> (PermissionMode, bool) {
+        match self.0.get(tool_name) {
+            None => (PermissionMode::Auto, false),
+            Some(ToolPermissionEntry::Mode(mode)) => (*mode, false),
+            Some(ToolPermissionEntry::Rules(rules)) => rules
+                .rules
+                .iter()
+                .find(|rule| rule.matches(arguments))
+                .map_or((rules.default, false), |rule| (rule.action, true)),
+        }
+    }
+}
+
+/// Where an "always allow" applies
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionScope {
+    Chat,
+    Workspace,
+}
+
+/// A remembered "always allow" for a tool
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ToolPermissionGrant {
+    pub id: String,
+    pub workspace_id: String,
+    pub chat_id: Option<String>, // None applies to every chat of the workspace
+    pub tool_name: String,
+    pub created_at: i64,
+}
+
+/// How a tool call got, or did not get, permission to run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PermissionCheck {
+    /// Runs without asking
+    Auto,
+    /// The user always allows this tool
+    Remembered,
+    /// The user has to decide
+    Ask,
+    /// A rule forbids the call
+    Denied,
+}
@@ -0,0 +1,97 @@
+use super::models::ToolPermissionGrant;
+use crate::error::AppError;
+use rusqlite::params;
+use std::sync::Arc;
+use tauri::AppHandle;
+
+pub trait ToolPermissionRepository: Send + Sync {
+    /// Grants that apply to a chat: its own and its workspace's
+    fn get_for_chat(
+        &self,
+        workspace_id: &str,
+        chat_id: &str,
+    ) -> Result<Vec<ToolPermissionGrant>, AppError>;
+    /// Every grant of a workspace, chat grants included
+    fn get_by_workspace(&self, workspace_id: &str) -> Result<Vec<ToolPermissionGrant>, AppError>;
+    /// Save a grant, granting a tool twice in the same scope keeps the first one
+    fn create(&self, grant: &ToolPermissionGrant) -> Result<(), AppError>;
+    fn delete(&self, id: &str) -> Result<(), AppError>;
+}
+
+pub struct SqliteToolPermissionRepository {
+    app: Arc<AppHandle>,
+}
+
+impl SqliteToolPermissionRepository {
+    pub const fn new(app: Arc<AppHandle>) -> Self {
+        Self { app }
+    }
+
+    fn map_grant(row: &rusqlite::Row<'_>) -> rusqlite::Result<ToolPermissionGrant> {
+        Ok(ToolPermissionGrant {
+            id: row.get(0)?,
+            workspace_id: row.get(1)?,
+            chat_id: row.get(2)?,
+            tool_name: row.get(3)?,
+            created_at: row.get(4)?,
+        })
+    }
+}
+
+impl ToolPermissionRepository for SqliteToolPermissionRepository {
+    fn get_for_chat(
+        &self,
+        workspace_id: &str,
+        chat_id: &str,
+    ) -> Result<Vec<ToolPermissionGrant>, AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        let mut stmt = conn.prepare(
+            "SELECT id, workspace_id, chat_id, tool_name, created_at
+             FROM tool_permission_grants
+             WHERE workspace_id = ?1 AND (chat_id IS NULL OR chat_id = ?2)",
+        )?;
+        let grants = stmt
+            .query_map(params![workspace_id, chat_id], Self::map_grant)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(grants)
+    }
+
+    fn get_by_workspace(&self, workspace_id: &str) -> Result<Vec<ToolPermissionGrant>, AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        let mut stmt = conn.prepare(
+            "SELECT id, workspace_id, chat_id, tool_name, created_at
+             FROM tool_permission_grants
+             WHERE workspace_id = ?1
+             ORDER BY created_at DESC",
+        )?;
+        let grants = stmt
+            .query_map(params![workspace_id], Self::map_grant)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(grants)
+    }
+
+    fn create(&self, grant: &ToolPermissionGrant) -> Result<(), AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        conn.execute(
+            "INSERT OR IGNORE INTO tool_permission_grants (id, workspace_id, chat_id, tool_name, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                grant.id,
+                grant.workspace_id,
+                grant.chat_id,
+                grant.tool_name,
+                grant.created_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn delete(&self, id: &str) -> Result<(), AppError> {
+        let conn = crate::db::get_connection(&self.app)?;
+        conn.execute(
+            "DELETE FROM tool_permission_grants WHERE id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+}
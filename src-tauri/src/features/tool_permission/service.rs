@@ -0,0 +1,109 @@
+use super::models::{
+    PermissionCheck, PermissionMode, PermissionScope, ToolPermissionConfig, ToolPermissionEntry,
+    ToolPermissionGrant,
+};
+use super::repository::ToolPermissionRepository;
+use crate::error::AppError;
+use std::sync::Arc;
+
+pub struct ToolPermissionService {
+    repository: Arc<dyn ToolPermissionRepository>,
+}
+
+impl ToolPermissionService {
+    pub fn new(repository: Arc<dyn ToolPermissionRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Parse `tool_permission_config`, rejecting unknown modes and invalid patterns
+    pub fn parse_config(config: &str) -> Result<ToolPermissionConfig, AppError> {
+        let parsed: ToolPermissionConfig = serde_json::from_str(config)
+            .map_err(|e| AppError::Validation(format!("Invalid tool permission settings: {e}")))?;
+
+        for (tool_name, entry) in &parsed.0 {
+            let ToolPermissionEntry::Rules(rules) = entry else {
+                continue;
+            };
+            if let Some(error) = rules.rules.iter().flat_map(|r| r.invalid_patterns()).next() {
+                return Err(AppError::Validation(format!(
+                    "Invalid pattern in the permission rules of {tool_name}: {error}"
+                )));
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Decide every call of a turn, remembered grants skip the question
+    ///
+    /// Grants only apply when the tool as a whole requires approval, a call that an
+    /// argument rule marked as requiring approval is always asked about
+    pub fn check(
+        &self,
+        config: &ToolPermissionConfig,
+        workspace_id: &str,
+        chat_id: &str,
+        tool_calls: &[crate::models::llm_types::ToolCall],
+    ) -> Result<Vec<PermissionCheck>, AppError> {
+        let modes: Vec<(PermissionMode, bool)> = tool_calls
+            .iter()
+            .map(|tc| {
+                let arguments = serde_json::from_str(tc.function.arguments.trim())
+                    .unwrap_or_else(|_| serde_json::json!({}));
+                config.mode_for(&tc.function.name, &arguments)
+            })
+            .collect();
+
+        let grants = if modes.contains(&(PermissionMode::Require, false)) {
+            self.repository.get_for_chat(workspace_id, chat_id)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(tool_calls
+            .iter()
+            .zip(modes)
+            .map(|(tc, (mode, by_rule))| match mode {
+                PermissionMode::Auto => PermissionCheck::Auto,
+                PermissionMode::Deny => PermissionCheck::Denied,
+                PermissionMode::Require
+                    if !by_rule && grants.iter().any(|g| g.tool_name == tc.function.name) =>
+                {
+                    PermissionCheck::Remembered
+                }
+                PermissionMode::Require => PermissionCheck::Ask,
+            })
+            .collect())
+    }
+
+    /// Always allow a tool in a chat or in its whole workspace
+    pub fn remember(
+        &self,
+        workspace_id: &str,
+        chat_id: &str,
+        tool_name: &str,
+        scope: PermissionScope,
+    ) -> Result<(), AppError> {
+        let grant = ToolPermissionGrant {
+            id: uuid::Uuid::new_v4().to_string(),
+            workspace_id: workspace_id.to_string(),
+            chat_id: match scope {
+                PermissionScope::Chat => Some(chat_id.to_string()),
+                PermissionScope::Workspace => None,
+            },
+            tool_name: tool_name.to_string(),
+            created_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as i64,
+        };
+        self.repository.create(&grant)
+    }
+
+    pub fn get_grants(&self, workspace_id: &str) -> Result<Vec<ToolPermissionGrant>, AppError> {
+        self.repository.get_by_workspace(workspace_id)
+    }
+
+    pub fn revoke(&self, id: &str) -> Result<(), AppError> {
+        self.repository.delete(id)
+    }
+}
@@ -0,0 +1,252 @@
+use super::*;
+use crate::error::AppError;
+use crate::models::llm_types::{ToolCall, ToolCallFunction};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+/// Grants in memory, matching chats the way the `SQLite` query does
+#[derive(Default)]
+struct MemoryRepository {
+    grants: Mutex<Vec<ToolPermissionGrant>>,
+}
+
+impl ToolPermissionRepository for MemoryRepository {
+    fn get_for_chat(
+        &self,
+        workspace_id: &str,
+        chat_id: &str,
+    ) -> Result<Vec<ToolPermissionGrant>, AppError> {
+        Ok(self
+            .grants
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|g| {
+                g.workspace_id == workspace_id && g.chat_id.as_deref().is_none_or(|c| c == chat_id)
+            })
+            .cloned()
+            .collect())
+    }
+
+    fn get_by_workspace(&self, workspace_id: &str) -> Result<Vec<ToolPermissionGrant>, AppError> {
+        Ok(self
+            .grants
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|g| g.workspace_id == workspace_id)
+            .cloned()
+            .collect())
+    }
+
+    fn create(&self, grant: &ToolPermissionGrant) -> Result<(), AppError> {
+        self.grants.lock().unwrap().push(grant.clone());
+        Ok(())
+    }
+
+    fn delete(&self, id: &str) -> Result<(), AppError> {
+        self.grants.lock().unwrap().retain(|g| g.id != id);
+        Ok(())
+    }
+}
+
+fn rule(json: serde_json::Value) -> ArgumentRule {
+    serde_json::from_value(json).unwrap()
+}
+
+fn config(json: &str) -> ToolPermissionConfig {
+    ToolPermissionService::parse_config(json).unwrap()
+}
+
+fn call(name: &str, arguments: &serde_json::Value) -> ToolCall {
+    ToolCall {
+        id: format!("call-{name}"),
+        r#type: "function".to_string(),
+        function: ToolCallFunction {
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+        },
+    }
+}
+
+#[test]
+fn keeps_paths_inside_the_prefix() {
+    let inside = rule(json!({"action": "auto", "argument": "path", "prefix": "/home/me/project"}));
+
+    assert!(inside.matches(&json!({"path": "/home/me/project"})));
+    assert!(inside.matches(&json!({"path": "/home/me/project/src/main.rs"})));
+    assert!(!inside.matches(&json!({"path": "/home/me/project/../secrets.txt"})));
+    assert!(!inside.matches(&json!({"path": "/home/me/project/src/../../other"})));
+    assert!(!inside.matches(&json!({"path": "/etc/passwd"})));
+    assert!(!inside.matches(&json!({"file": "/home/me/project/a"})));
+}
+
+#[test]
+fn compares_prefixes_by_whole_components() {
+    let inside = rule(json!({"action": "auto", "argument": "path", "prefix": "/home/me/project/"}));
+
+    assert!(inside.matches(&json!({"path": "/home/me/project/README.md"})));
+    assert!(!inside.matches(&json!({"path": "/home/me/project-old/README.md"})));
+    assert!(!inside.matches(&json!({"path": "/home/me/projects"})));
+}
+
+#[test]
+fn reads_arguments_by_name_or_json_pointer() {
+    let by_pointer = rule(json!({
+        "action": "deny",
+        "argument": "/options/target",
+        "pattern": "^prod"
+    }));
+    assert!(by_pointer.matches(&json!({"options": {"target": "production"}})));
+    assert!(!by_pointer.matches(&json!({"options": {"target": "staging"}})));
+    assert!(!by_pointer.matches(&json!({"target": "production"})));
+
+    // Without an argument the whole JSON is matched
+    let anywhere = rule(json!({"action": "deny", "pattern": "rm -rf"}));
+    assert!(anywhere.matches(&json!({"command": "rm -rf /"})));
+    assert!(!anywhere.matches(&json!({"command": "ls"})));
+}
+
+#[test]
+fn rejects_values_matching_the_not_pattern() {
+    let read_only = rule(json!({
+        "action": "auto",
+        "argument": "sql",
+        "pattern": "(?i)^\\s*select\\b",
+        "not_pattern": "(?i)\\b(delete|drop|update|insert)\\b"
+    }));
+
+    assert!(read_only.matches(&json!({"sql": "SELECT * FROM users"})));
+    assert!(!read_only.matches(&json!({"sql": "select 1; DROP TABLE users"})));
+    assert!(!read_only.matches(&json!({"sql": "DELETE FROM users"})));
+}
+
+#[test]
+fn never_matches_with_an_invalid_regex() {
+    let pattern = rule(json!({"action": "auto", "argument": "sql", "pattern": "(select"}));
+    let not_pattern = rule(json!({"action": "auto", "argument": "sql", "not_pattern": "[drop"}));
+
+    assert!(!pattern.matches(&json!({"sql": "(select"})));
+    assert!(!not_pattern.matches(&json!({"sql": "select 1"})));
+    assert_eq!(pattern.invalid_patterns().len(), 1);
+    assert_eq!(not_pattern.invalid_patterns().len(), 1);
+
+    let error = ToolPermissionService::parse_config(
+        r#"{"query": {"rules": [{"action": "auto", "pattern": "(select"}]}}"#,
+    )
+    .unwrap_err();
+    assert!(matches!(error, AppError::Validation(message) if message.contains("query")));
+    assert!(ToolPermissionService::parse_config(r#"{"query": "sometimes"}"#).is_err());
+}
+
+#[test]
+fn picks_the_first_matching_rule_or_the_default() {
+    let config = config(
+        r#"{
+            "read_file": {
+                "default": "require",
+                "rules": [
+                    {"action": "deny", "argument": "path", "pattern": "\\.env$"},
+                    {"action": "auto", "argument": "path", "prefix": "/project"}
+                ]
+            },
+            "write_file": {"rules": []},
+            "shell": "deny",
+            "search": "allow"
+        }"#,
+    );
+
+    assert_eq!(
+        config.mode_for("read_file", &json!({"path": "/project/.env"})),
+        (PermissionMode::Deny, true)
+    );
+    assert_eq!(
+        config.mode_for("read_file", &json!({"path": "/project/src/lib.rs"})),
+        (PermissionMode::Auto, true)
+    );
+    assert_eq!(
+        config.mode_for("read_file", &json!({"path": "/tmp/notes"})),
+        (PermissionMode::Require, false)
+    );
+    assert_eq!(
+        config.mode_for("write_file", &json!({})),
+        (PermissionMode::Require, false)
+    );
+    assert_eq!(
+        config.mode_for("shell", &json!({})),
+        (PermissionMode::Deny, false)
+    );
+    assert_eq!(
+        config.mode_for("search", &json!({})),
+        (PermissionMode::Auto, false)
+    );
+    assert_eq!(
+        config.mode_for("unlisted", &json!({})),
+        (PermissionMode::Auto, false)
+    );
+}
+
+#[test]
+fn grants_cover_tools_but_not_rules_that_require_approval() {
+    let service = ToolPermissionService::new(Arc::new(MemoryRepository::default()));
+    let config = config(
+        r#"{
+            "run_sql": {
+                "default": "require",
+                "rules": [{"action": "require", "argument": "sql", "pattern": "(?i)drop"}]
+            },
+            "shell": "deny"
+        }"#,
+    );
+    let calls = [
+        call("run_sql", &json!({"sql": "select 1"})),
+        call("run_sql", &json!({"sql": "DROP TABLE users"})),
+        call("shell", &json!({"command": "ls"})),
+    ];
+
+    let checks = service.check(&config, "workspace", "chat", &calls).unwrap();
+    assert_eq!(
+        checks,
+        vec![
+            PermissionCheck::Ask,
+            PermissionCheck::Ask,
+            PermissionCheck::Denied
+        ]
+    );
+
+    service
+        .remember("workspace", "chat", "run_sql", PermissionScope::Chat)
+        .unwrap();
+    service
+        .remember("workspace", "chat", "shell", PermissionScope::Workspace)
+        .unwrap();
+    let checks = service.check(&config, "workspace", "chat", &calls).unwrap();
+    assert_eq!(
+        checks,
+        vec![
+            PermissionCheck::Remembered,
+            PermissionCheck::Ask,
+            PermissionCheck::Denied
+        ]
+    );
+
+    // Chat grants stay in their chat
+    let checks = service
+        .check(&config, "workspace", "other-chat", &calls[..1])
+        .unwrap();
+    assert_eq!(checks, vec![PermissionCheck::Ask]);
+}
+
+#[test]
+fn asks_before_every_tool_when_settings_cannot_be_read() {
+    let config = ToolPermissionConfig::require_all(["read_file", "shell"]);
+
+    assert_eq!(
+        config.mode_for("read_file", &json!({"path": "/project/a"})),
+        (PermissionMode::Require, false)
+    );
+    assert_eq!(
+        config.mode_for("shell", &json!({})),
+        (PermissionMode::Require, false)
+    );
+}
@@ -11,6 +11,10 @@ pub struct UsageStat {
     pub input_tokens: u32,
     pub output_tokens: u32,
     pub total_tokens: u32,
+    #[serde(default)]
+    pub cache_read_tokens: u32,
+    #[serde(default)]
+    pub cache_write_tokens: u32,
     pub latency_ms: u64,
     pub cost: f64,
     pub timestamp: i64,
@@ -31,6 +35,8 @@ pub struct UsageSummary {
     pub total_requests: u32,
     pub total_input_tokens: u32,
     pub total_output_tokens: u32,
+    pub total_cache_read_tokens: u32,
+    pub total_cache_write_tokens: u32,
     pub total_cost: f64,
     pub average_latency: f64,
 }
@@ -28,9 +28,10 @@ impl UsageRepository for SqliteUsageRepository {
         conn.execute(
             "INSERT INTO usage_stats (
                 id, workspace_id, chat_id, message_id, provider, model,
-                input_tokens, output_tokens, total_tokens, latency_ms,
-                cost, timestamp, is_stream, status, request_type
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                input_tokens, output_tokens, total_tokens, cache_read_tokens,
+                cache_write_tokens, latency_ms, cost, timestamp, is_stream, status,
+                request_type
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
             params![
                 stat.id,
                 stat.workspace_id,
@@ -41,6 +42,8 @@ impl UsageRepository for SqliteUsageRepository {
                 stat.input_tokens,
                 stat.output_tokens,
                 stat.total_tokens,
+                stat.cache_read_tokens,
+                stat.cache_write_tokens,
                 stat.latency_ms,
                 stat.cost,
                 stat.timestamp,
@@ -93,6 +96,8 @@ impl UsageRepository for SqliteUsageRepository {
                 input_tokens: row.get("input_tokens")?,
                 output_tokens: row.get("output_tokens")?,
                 total_tokens: row.get("total_tokens")?,
+                cache_read_tokens: row.get("cache_read_tokens")?,
+                cache_write_tokens: row.get("cache_write_tokens")?,
                 latency_ms: row.get("latency_ms")?,
                 cost: row.get("cost")?,
                 timestamp: row.get("timestamp")?,
@@ -118,7 +123,9 @@ impl UsageRepository for SqliteUsageRepository {
                 COALESCE(SUM(input_tokens), 0) as input,
                 COALESCE(SUM(output_tokens), 0) as output,
                 COALESCE(SUM(cost), 0.0) as cost,
-                COALESCE(AVG(latency_ms), 0.0) as latency
+                COALESCE(AVG(latency_ms), 0.0) as latency,
+                COALESCE(SUM(cache_read_tokens), 0) as cache_read,
+                COALESCE(SUM(cache_write_tokens), 0) as cache_write
              FROM usage_stats WHERE 1=1",
         );
         let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
@@ -144,6 +151,8 @@ impl UsageRepository for SqliteUsageRepository {
                 total_requests: row.get(0)?,
                 total_input_tokens: row.get(1)?,
                 total_output_tokens: row.get(2)?,
+                total_cache_read_tokens: row.get(5)?,
+                total_cache_write_tokens: row.get(6)?,
                 total_cost: row.get(3)?,
                 average_latency: row.get(4)?,
             })
@@ -14,27 +14,41 @@ impl UsageService {
         Self { repo }
     }
 
-    pub fn calculate_cost(&self, model: &str, input_tokens: u32, output_tokens: u32) -> f64 {
+    /// `input_tokens` includes the cached ones, which are billed at their own rates
+    pub fn calculate_cost(
+        &self,
+        model: &str,
+        input_tokens: u32,
+        output_tokens: u32,
+        cache_read_tokens: u32,
+        cache_write_tokens: u32,
+    ) -> f64 {
         let model_lower = model.to_lowercase();
         // Prices per 1M tokens (approximate as of 2024)
-        // Format: (input_price, output_price)
-        let price_map: Vec<(&str, f64, f64)> = vec![
-            // OpenAI
-            ("gpt-4o", 5.0, 15.0),
-            ("gpt-4o-mini", 0.15, 0.6),
-            ("gpt-4-turbo", 10.0, 30.0),
-            ("gpt-3.5-turbo", 0.5, 1.5),
+        // Format: (input_price, output_price, cache_read_price, cache_write_price)
+        let price_map: Vec<(&str, f64, f64, f64, f64)> = vec![
+            // OpenAI (cache writes are not billed separately)
+            ("gpt-4o", 5.0, 15.0, 2.5, 5.0),
+            ("gpt-4o-mini", 0.15, 0.6, 0.075, 0.15),
+            ("gpt-4-turbo", 10.0, 30.0, 10.0, 10.0),
+            ("gpt-3.5-turbo", 0.5, 1.5, 0.5, 0.5),
             // Anthropic
-            ("claude-3-5-sonnet", 3.0, 15.0),
-            ("claude-3-opus", 15.0, 75.0),
-            ("claude-3-haiku", 0.25, 1.25),
+            ("claude-3-5-sonnet", 3.0, 15.0, 0.3, 3.75),
+            ("claude-3-opus", 15.0, 75.0, 1.5, 18.75),
+            ("claude-3-haiku", 0.25, 1.25, 0.03, 0.30),
         ];
 
-        for (key, input_price, output_price) in price_map {
+        for (key, input_price, output_price, cache_read_price, cache_write_price) in price_map {
             if model_lower.contains(key) {
-                let input_cost = (f64::from(input_tokens) / 1_000_000.0) * input_price;
-                let output_cost = (f64::from(output_tokens) / 1_000_000.0) * output_price;
-                return input_cost + output_cost;
+                let uncached = input_tokens
+                    .saturating_sub(cache_read_tokens)
+                    .saturating_sub(cache_write_tokens);
+                let per_million =
+                    |tokens: u32, price: f64| (f64::from(tokens) / 1_000_000.0) * price;
+                return per_million(uncached, input_price)
+                    + per_million(cache_read_tokens, cache_read_price)
+                    + per_million(cache_write_tokens, cache_write_price)
+                    + per_million(output_tokens, output_price);
             }
         }
 
@@ -64,7 +78,22 @@ impl UsageService {
             .and_then(|u| u.total_tokens)
             .unwrap_or(input_tokens + output_tokens);
 
-        let cost = self.calculate_cost(&model, input_tokens, output_tokens);
+        let cache_read_tokens = usage
+            .as_ref()
+            .and_then(|u| u.cache_read_tokens)
+            .unwrap_or(0);
+        let cache_write_tokens = usage
+            .as_ref()
+            .and_then(|u| u.cache_write_tokens)
+            .unwrap_or(0);
+
+        let cost = self.calculate_cost(
+            &model,
+            input_tokens,
+            output_tokens,
+            cache_read_tokens,
+            cache_write_tokens,
+        );
 
         let stat = UsageStat {
             id: Uuid::new_v4().to_string(),
@@ -76,6 +105,8 @@ impl UsageService {
             input_tokens,
             output_tokens,
             total_tokens,
+            cache_read_tokens,
+            cache_write_tokens,
             latency_ms,
             cost,
             timestamp: chrono::Utc::now().timestamp(),
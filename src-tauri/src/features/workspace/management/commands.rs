@@ -25,6 +25,9 @@ pub fn create_workspace(
         None,       // default_model
         None,       // tool_permission_config
         Some(10),   // max_agent_iterations
+        None,       // context_config
+        None,       // tool_execution_config
+        None,       // fallback_models
     )?;
 
     Ok(workspace)
@@ -13,6 +13,9 @@ pub fn save_workspace_settings(
     default_model: Option<String>,
     tool_permission_config: Option<String>,
     max_agent_iterations: Option<i64>,
+    context_config: Option<String>,
+    tool_execution_config: Option<String>,
+    fallback_models: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<(), AppError> {
     state
@@ -27,6 +30,9 @@ pub fn save_workspace_settings(
             default_model,
             tool_permission_config,
             max_agent_iterations,
+            context_config,
+            tool_execution_config,
+            fallback_models,
         )
         .map_err(|e| AppError::Generic(e.to_string()))
 }
@@ -1,4 +1,11 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Tool calls of one model turn that run at the same time, unless configured
+pub const DEFAULT_MAX_CONCURRENT_TOOL_CALLS: usize = 4;
+
+/// Seconds a tool permission request waits for the user, unless configured
+pub const DEFAULT_PERMISSION_TIMEOUT_SECS: u64 = 60;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WorkspaceSettings {
@@ -11,6 +18,133 @@ pub struct WorkspaceSettings {
 
     pub tool_permission_config: Option<String>, // JSON object: { "tool_name": "require" | "auto", ... }
     pub max_agent_iterations: Option<i64>,
+    pub context_config: Option<String>, // JSON ContextConfig, NULL for defaults
+    pub tool_execution_config: Option<String>, // JSON ToolExecutionConfig, NULL for defaults
+    pub fallback_models: Option<String>, // JSON array of FallbackModel, tried in order
     pub created_at: i64,
     pub updated_at: i64,
 }
+
+impl WorkspaceSettings {
+    /// Models to try when the chat model stays rate limited or unavailable
+    pub fn fallback_models(&self) -> Vec<FallbackModel> {
+        self.fallback_models
+            .as_deref()
+            .and_then(|models| serde_json::from_str(models).ok())
+            .unwrap_or_default()
+    }
+
+    /// Parsed context window settings, invalid JSON falls back to the defaults
+    pub fn context_config(&self) -> ContextConfig {
+        self.context_config
+            .as_deref()
+            .and_then(|config| serde_json::from_str(config).ok())
+            .unwrap_or_default()
+    }
+
+    /// Parsed tool execution settings, invalid JSON falls back to the defaults
+    pub fn tool_execution_config(&self) -> ToolExecutionConfig {
+        self.tool_execution_config
+            .as_deref()
+            .and_then(|config| serde_json::from_str(config).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// What to do when the chat history no longer fits the model's context window
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextStrategy {
+    /// Drop the oldest messages
+    #[default]
+    Truncate,
+    /// Replace older turns with a summary written by `summary_model`
+    Summarize,
+    /// Blank out tool results the model has already answered, then drop the oldest messages
+    DropToolOutputs,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ContextConfig {
+    #[serde(default)]
+    pub strategy: ContextStrategy,
+    pub summary_model: Option<String>, // Defaults to the chat model
+    pub summary_connection_id: Option<String>, // Defaults to the chat connection
+    pub max_context_tokens: Option<usize>, // Overrides the built-in limit for the model
+}
+
+/// How the tool calls of one model turn are executed
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ToolExecutionConfig {
+    pub max_concurrent_calls: Option<usize>, // 1 runs the calls one after another
+    pub max_concurrent_per_server: Option<usize>, // Defaults to max_concurrent_calls
+    #[serde(default)]
+    pub server_limits: HashMap<String, usize>, // Per MCP connection ID
+    #[serde(default)]
+    pub default_policy: ToolPolicy,
+    #[serde(default)]
+    pub connection_policies: HashMap<String, ToolPolicy>, // Per MCP connection ID
+    #[serde(default)]
+    pub tool_policies: HashMap<String, ToolPolicy>, // Per tool name, over its connection's policy
+    pub circuit_breaker_threshold: Option<u32>, // Consecutive failures that mark a server degraded, 0 disables
+    pub circuit_breaker_cooldown_secs: Option<u64>, // How long a degraded server gets no calls
+    pub permission_timeout_secs: Option<u64>,   // How long to wait for the user to allow a call
+}
+
+impl ToolExecutionConfig {
+    /// Tool calls of one turn running at the same time
+    pub fn concurrency_limit(&self) -> usize {
+        self.max_concurrent_calls
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_TOOL_CALLS)
+            .max(1)
+    }
+
+    /// How long a permission request waits for the user
+    pub fn permission_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(
+            self.permission_timeout_secs
+                .filter(|secs| *secs > 0)
+                .unwrap_or(DEFAULT_PERMISSION_TIMEOUT_SECS),
+        )
+    }
+
+    /// Tool calls running at the same time on one MCP server
+    pub fn server_limit(&self, connection_id: &str) -> usize {
+        self.server_limits
+            .get(connection_id)
+            .copied()
+            .or(self.max_concurrent_per_server)
+            .unwrap_or_else(|| self.concurrency_limit())
+            .max(1)
+    }
+}
+
+/// Timeout and retries of a tool call, unset fields fall back to the less specific policy
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ToolPolicy {
+    pub timeout_secs: Option<u64>,
+    pub max_retries: Option<u32>,
+    pub retry_backoff_ms: Option<u64>, // Doubles with every retry
+    pub retry_on: Option<Vec<ToolErrorKind>>,
+}
+
+/// Ways a tool call can fail, used to choose which failures are retried
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolErrorKind {
+    /// No result within the timeout
+    Timeout,
+    /// The server could not be started or reached, or the connection broke
+    Transport,
+    /// The server answered with a JSON-RPC error
+    Server,
+    /// The tool ran and reported a failure (`isError`)
+    Tool,
+}
+
+/// A connection and model the chat falls back to
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct FallbackModel {
+    pub connection_id: String,
+    pub model: String,
+}
@@ -37,13 +37,13 @@ impl WorkspaceSettingsRepository for SqliteWorkspaceSettingsRepository {
 
         if exists {
             conn.execute(
-                "UPDATE workspace_settings SET llm_connection_id = ?1, system_message = ?2, mcp_tool_ids = ?3, stream_enabled = ?4, default_model = ?5, tool_permission_config = ?6, max_agent_iterations = ?7, updated_at = ?8 WHERE workspace_id = ?9",
-                params![settings.llm_connection_id, settings.system_message, settings.mcp_tool_ids, settings.stream_enabled, settings.default_model, settings.tool_permission_config, settings.max_agent_iterations, settings.updated_at, settings.workspace_id],
+                "UPDATE workspace_settings SET llm_connection_id = ?1, system_message = ?2, mcp_tool_ids = ?3, stream_enabled = ?4, default_model = ?5, tool_permission_config = ?6, max_agent_iterations = ?7, context_config = ?8, tool_execution_config = ?9, fallback_models = ?10, updated_at = ?11 WHERE workspace_id = ?12",
+                params![settings.llm_connection_id, settings.system_message, settings.mcp_tool_ids, settings.stream_enabled, settings.default_model, settings.tool_permission_config, settings.max_agent_iterations, settings.context_config, settings.tool_execution_config, settings.fallback_models, settings.updated_at, settings.workspace_id],
             )?;
         } else {
             conn.execute(
-                "INSERT INTO workspace_settings (workspace_id, llm_connection_id, system_message, mcp_tool_ids, stream_enabled, default_model, tool_permission_config, max_agent_iterations, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-                params![settings.workspace_id, settings.llm_connection_id, settings.system_message, settings.mcp_tool_ids, settings.stream_enabled, settings.default_model, settings.tool_permission_config, settings.max_agent_iterations, settings.created_at, settings.updated_at],
+                "INSERT INTO workspace_settings (workspace_id, llm_connection_id, system_message, mcp_tool_ids, stream_enabled, default_model, tool_permission_config, max_agent_iterations, context_config, tool_execution_config, fallback_models, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                params![settings.workspace_id, settings.llm_connection_id, settings.system_message, settings.mcp_tool_ids, settings.stream_enabled, settings.default_model, settings.tool_permission_config, settings.max_agent_iterations, settings.context_config, settings.tool_execution_config, settings.fallback_models, settings.created_at, settings.updated_at],
             )?;
         }
 
@@ -56,7 +56,7 @@ impl WorkspaceSettingsRepository for SqliteWorkspaceSettingsRepository {
     ) -> Result<Option<WorkspaceSettings>, AppError> {
         let conn = crate::db::get_connection(&self.app)?;
         let result = conn.query_row(
-            "SELECT workspace_id, llm_connection_id, system_message, mcp_tool_ids, stream_enabled, default_model, tool_permission_config, created_at, updated_at, max_agent_iterations FROM workspace_settings WHERE workspace_id = ?1",
+            "SELECT workspace_id, llm_connection_id, system_message, mcp_tool_ids, stream_enabled, default_model, tool_permission_config, created_at, updated_at, max_agent_iterations, context_config, tool_execution_config, fallback_models FROM workspace_settings WHERE workspace_id = ?1",
             params![workspace_id],
             |row| {
                 Ok(WorkspaceSettings {
@@ -70,6 +70,9 @@ impl WorkspaceSettingsRepository for SqliteWorkspaceSettingsRepository {
                     created_at: row.get(7)?,
                     updated_at: row.get(8)?,
                     max_agent_iterations: row.get(9)?,
+                    context_config: row.get(10)?,
+                    tool_execution_config: row.get(11)?,
+                    fallback_models: row.get(12)?,
                 })
             },
         );
@@ -1,4 +1,4 @@
-use super::models::WorkspaceSettings;
+use super::models::{ContextConfig, FallbackModel, ToolExecutionConfig, WorkspaceSettings};
 use super::repository::WorkspaceSettingsRepository;
 use crate::error::AppError;
 use std::sync::Arc;
@@ -12,6 +12,7 @@ impl WorkspaceSettingsService {
         Self { repository }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn save(
         &self,
         workspace_id: String,
@@ -22,7 +23,28 @@ impl WorkspaceSettingsService {
         default_model: Option<String>,
         tool_permission_config: Option<String>,
         max_agent_iterations: Option<i64>,
+        context_config: Option<String>,
+        tool_execution_config: Option<String>,
+        fallback_models: Option<String>,
     ) -> Result<(), AppError> {
+        // Reject malformed context settings instead of silently using the defaults
+        if let Some(config) = &context_config {
+            serde_json::from_str::<ContextConfig>(config)
+                .map_err(|e| AppError::Validation(format!("Invalid context settings: {e}")))?;
+        }
+        if let Some(config) = &tool_permission_config {
+            crate::features::tool_permission::ToolPermissionService::parse_config(config)?;
+        }
+        if let Some(config) = &tool_execution_config {
+            serde_json::from_str::<ToolExecutionConfig>(config).map_err(|e| {
+                AppError::Validation(format!("Invalid tool execution settings: {e}"))
+            })?;
+        }
+        if let Some(models) = &fallback_models {
+            serde_json::from_str::<Vec<FallbackModel>>(models)
+                .map_err(|e| AppError::Validation(format!("Invalid fallback models: {e}")))?;
+        }
+
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -39,6 +61,9 @@ impl WorkspaceSettingsService {
             default_model,
             tool_permission_config,
             max_agent_iterations,
+            context_config,
+            tool_execution_config,
+            fallback_models,
             created_at: now,
             updated_at: now,
         };
@@ -63,6 +88,9 @@ impl WorkspaceSettingsService {
                 None,
                 None,
                 Some(10),
+                None,
+                None,
+                None,
             )?;
             return self.repository.get_by_workspace_id(workspace_id);
         }
@@ -43,6 +43,13 @@ fn init_sentry() -> sentry::ClientInitGuard {
         sample_rate: 1.0,
         traces_sample_rate: if cfg!(debug_assertions) { 1.0 } else { 0.1 },
         attach_stacktrace: true,
+        // Keep API keys and tokens out of reports
+        before_send: Some(Arc::new(|event| {
+            Some(features::secrets::redaction::redact_event(event))
+        })),
+        before_breadcrumb: Some(Arc::new(|breadcrumb| {
+            Some(features::secrets::redaction::redact_breadcrumb(breadcrumb))
+        })),
         ..Default::default()
     });
 
@@ -80,6 +87,17 @@ pub fn run() {
                     log::LevelFilter::Info
                 })
                 .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepAll)
+                // Same layout as the default format, with secrets masked (covers `tracing` events too)
+                .format(|out, message, record| {
+                    let message = message.to_string();
+                    out.finish(format_args!(
+                        "{}[{}][{}] {}",
+                        chrono::Utc::now().format("[%Y-%m-%d][%H:%M:%S]"),
+                        record.target(),
+                        record.level(),
+                        features::secrets::redaction::redact(&message)
+                    ));
+                })
                 .build(),
         )
         .plugin(tauri_plugin_clipboard_manager::init())
@@ -168,13 +186,31 @@ pub fn run() {
             features::chat::commands::send_message,
             features::chat::commands::generate_chat_title,
             features::chat::commands::edit_and_resend_message,
+            features::chat::commands::send_compare_message,
+            features::chat::commands::regenerate_message,
             features::chat::commands::respond_tool_permission,
+            // Tool Permission commands
+            features::tool_permission::commands::get_tool_permission_grants,
+            features::tool_permission::commands::revoke_tool_permission_grant,
+            // Tool Audit commands
+            features::tool_audit::commands::get_tool_audit_log,
+            features::tool_audit::commands::export_tool_audit_log,
             // Message commands
             features::message::commands::create_message,
             features::message::commands::get_messages,
             features::message::commands::update_message,
             features::message::commands::delete_messages_after,
+            features::message::commands::get_message_branches,
+            features::message::commands::switch_message_branch,
             features::message::commands::cancel_message,
+            // Search commands
+            features::search::commands::search_history,
+            // Export commands
+            features::export::commands::export_chat,
+            features::export::commands::export_workspace,
+            features::export::commands::export_all_chats,
+            // Import commands
+            features::import::commands::import_history,
             // Chat Input Settings commands
             features::chat::input_settings::commands::get_chat_input_settings,
             features::chat::input_settings::commands::save_chat_input_settings,
@@ -193,15 +229,24 @@ pub fn run() {
             features::mcp_connection::commands::update_mcp_server_connection,
             features::mcp_connection::commands::delete_mcp_server_connection,
             features::mcp_connection::commands::update_mcp_server_status,
+            features::mcp_connection::oauth::commands::authorize_mcp_connection,
+            features::mcp_connection::oauth::commands::get_mcp_oauth_status,
+            features::mcp_connection::oauth::commands::revoke_mcp_authorization,
             // App Settings commands
             features::app_settings::commands::save_app_setting,
             features::app_settings::commands::get_app_setting,
             features::app_settings::commands::get_all_app_settings,
+            // Secrets vault commands
+            features::secrets::commands::get_vault_status,
+            features::secrets::commands::unlock_vault,
+            features::secrets::commands::set_vault_passphrase,
             // Prompt commands
             features::prompt::commands::create_prompt,
             features::prompt::commands::get_prompts,
             features::prompt::commands::update_prompt,
             features::prompt::commands::delete_prompt,
+            features::prompt::commands::get_mcp_prompts,
+            features::prompt::commands::get_mcp_prompt_template,
             // Hub commands
             features::hub::commands::fetch_hub_prompts,
             features::hub::commands::fetch_prompt_template,
@@ -217,6 +262,11 @@ pub fn run() {
             features::tool::commands::get_mcp_client,
             features::tool::commands::call_mcp_tool,
             features::tool::commands::disconnect_mcp_client,
+            features::tool::commands::list_mcp_resources,
+            features::tool::commands::list_mcp_resource_templates,
+            features::tool::commands::read_mcp_resource,
+            features::tool::commands::respond_sampling_request,
+            features::tool::commands::respond_elicitation_request,
             // Python commands
             features::runtime::python::commands::get_python_runtimes_status,
             features::runtime::python::commands::install_python_runtime,
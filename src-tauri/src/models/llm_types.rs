@@ -14,6 +14,13 @@ pub struct LLMModel {
     pub supports_thinking: bool,
     #[serde(default, rename = "supportsImageGeneration")]
     pub supports_image_generation: bool,
+    // Context window in tokens, when the provider's model list reports it
+    #[serde(
+        default,
+        rename = "contextLength",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub context_length: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -42,11 +49,21 @@ pub enum ChatMessage {
         content: AssistantContent,
         #[serde(skip_serializing_if = "Option::is_none")]
         tool_calls: Option<Vec<ToolCall>>,
+        // Reasoning items of the turn that made the tool calls, passed back as-is by the
+        // OpenAI Responses API (not part of the Chat Completions wire format)
+        #[serde(skip)]
+        reasoning_items: Option<Vec<serde_json::Value>>,
     },
     #[serde(rename = "tool")]
     Tool {
         content: String,
         tool_call_id: String,
+        // Images/audio returned by the tool, sent natively by providers that accept them
+        // in tool results (not part of the OpenAI wire format)
+        #[serde(skip)]
+        parts: Vec<ContentPart>,
+        #[serde(skip)]
+        is_error: bool,
     },
 }
 
@@ -126,7 +143,7 @@ pub struct ToolChoiceFunction {
     pub name: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LLMChatRequest {
     pub model: String,
     pub messages: Vec<ChatMessage>,
@@ -147,6 +164,59 @@ pub struct LLMChatRequest {
     pub response_modalities: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image_config: Option<ImageConfig>,
+    // Mapped by each provider to its own structured output option
+    #[serde(skip)]
+    pub response_format: Option<ResponseFormat>,
+}
+
+/// Provider-neutral structured output: the reply must be JSON matching `schema`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResponseFormat {
+    pub name: String,
+    pub schema: serde_json::Value,
+    #[serde(default)]
+    pub strict: bool,
+}
+
+impl ResponseFormat {
+    /// Structured output requested in a user message's metadata (`responseFormat`)
+    pub fn from_metadata(metadata: Option<&str>) -> Option<Self> {
+        let metadata: serde_json::Value = serde_json::from_str(metadata?).ok()?;
+        serde_json::from_value(metadata.get("responseFormat")?.clone()).ok()
+    }
+
+    /// Store the format in a user message's metadata, where the follow-up turns read it from
+    pub fn to_metadata(&self, metadata: Option<&str>) -> Result<String, serde_json::Error> {
+        let mut metadata = metadata
+            .and_then(|metadata| serde_json::from_str::<serde_json::Value>(metadata).ok())
+            .filter(serde_json::Value::is_object)
+            .unwrap_or_else(|| serde_json::json!({}));
+        metadata["responseFormat"] = serde_json::to_value(self)?;
+        Ok(metadata.to_string())
+    }
+
+    /// Reject formats no provider accepts
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Response format name is required".to_string());
+        }
+        if !self.schema.is_object() {
+            return Err("Response format schema must be a JSON object".to_string());
+        }
+        Ok(())
+    }
+
+    /// OpenAI Chat Completions `response_format`
+    pub fn to_openai(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": self.name,
+                "schema": self.schema,
+                "strict": self.strict,
+            }
+        })
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -157,14 +227,54 @@ pub struct ImageConfig {
     pub image_size: Option<String>, // "1K", "2K", "4K"
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct TokenUsage {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub prompt_tokens: Option<u32>,
+    pub prompt_tokens: Option<u32>, // All input tokens, cached ones included
     #[serde(skip_serializing_if = "Option::is_none")]
     pub completion_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub total_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_read_tokens: Option<u32>, // Input tokens served from the prompt cache
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_write_tokens: Option<u32>, // Input tokens written to the prompt cache
+}
+
+fn token_count(value: &serde_json::Value, keys: &[&str]) -> Option<u32> {
+    keys.iter()
+        .find_map(|key| value.pointer(key).and_then(serde_json::Value::as_u64))
+        .map(|v| v as u32)
+}
+
+impl TokenUsage {
+    /// `usage` of OpenAI-style APIs, Chat Completions and Responses naming alike
+    pub fn from_openai(usage: &serde_json::Value) -> Self {
+        Self {
+            prompt_tokens: token_count(usage, &["/prompt_tokens", "/input_tokens"]),
+            completion_tokens: token_count(usage, &["/completion_tokens", "/output_tokens"]),
+            total_tokens: token_count(usage, &["/total_tokens"]),
+            cache_read_tokens: token_count(
+                usage,
+                &[
+                    "/prompt_tokens_details/cached_tokens",
+                    "/input_tokens_details/cached_tokens",
+                ],
+            ),
+            cache_write_tokens: None, // OpenAI caches automatically, writes are not billed
+        }
+    }
+
+    /// `usageMetadata` of the Gemini API
+    pub fn from_gemini(usage: &serde_json::Value) -> Self {
+        Self {
+            prompt_tokens: token_count(usage, &["/promptTokenCount"]),
+            completion_tokens: token_count(usage, &["/candidatesTokenCount"]),
+            total_tokens: token_count(usage, &["/totalTokenCount"]),
+            cache_read_tokens: token_count(usage, &["/cachedContentTokenCount"]),
+            cache_write_tokens: None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -180,6 +290,9 @@ pub struct LLMChatResponse {
     pub reasoning: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub images: Option<Vec<InlineData>>,
+    /// Opaque reasoning items (OpenAI Responses API) to send back with the tool results
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_items: Option<Vec<serde_json::Value>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -189,7 +302,7 @@ pub struct SSEChunk {
     pub created: Option<u64>,
     pub model: Option<String>,
     pub choices: Option<Vec<SSEChoice>>,
-    pub usage: Option<TokenUsage>,
+    pub usage: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1,11 +1,17 @@
 pub mod providers;
+pub mod retry;
+pub mod structured;
 
 use crate::error::AppError;
+use crate::events::MessageEmitter;
+use crate::features::secrets::ensure_unlocked;
 use crate::models::llm_types::{LLMChatRequest, LLMChatResponse, LLMModel};
 use providers::{
-    AnthropicProvider, GoogleProvider, LLMProvider, OpenAICompatProvider, OpenAIProvider,
+    AnthropicProvider, AzureOpenAIProvider, BedrockProvider, GoogleProvider, LLMProvider,
+    OpenAICompatProvider, OpenAIProvider,
 };
 use reqwest::Client;
+use serde::Serialize;
 use std::sync::Arc;
 use tauri::AppHandle;
 
@@ -13,6 +19,26 @@ pub struct LLMService {
     client: Arc<Client>,
 }
 
+/// A connection and model a chat request can be sent to
+#[derive(Debug, Clone)]
+pub struct LLMTarget {
+    pub connection_id: String,
+    pub provider: String,
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub api_mode: Option<String>,
+    pub model: String,
+}
+
+/// The fallback that answered because the targets before it were unavailable
+#[derive(Debug, Clone, Serialize)]
+pub struct FallbackUsed {
+    pub connection_id: String,
+    pub provider: String,
+    pub model: String,
+    pub reason: String, // Error of the last unavailable target
+}
+
 impl LLMService {
     pub fn new() -> Self {
         let client = Client::builder()
@@ -25,11 +51,17 @@ impl LLMService {
         }
     }
 
-    fn get_provider(&self, provider: &str) -> Box<dyn LLMProvider> {
+    fn get_provider(&self, provider: &str, api_mode: Option<&str>) -> Box<dyn LLMProvider> {
         match provider.to_lowercase().as_str() {
-            "openai" => Box::new(OpenAIProvider::new(self.client.clone())),
+            "openai" => Box::new(OpenAIProvider::new(self.client.clone(), api_mode)),
             "google" | "gemini" => Box::new(GoogleProvider::new(self.client.clone())),
             "anthropic" | "claude" => Box::new(AnthropicProvider::new(self.client.clone())),
+            "azure" | "azure_openai" | "azure-openai" => {
+                Box::new(AzureOpenAIProvider::new(self.client.clone()))
+            }
+            "bedrock" | "aws" | "aws-bedrock" => {
+                Box::new(BedrockProvider::new(self.client.clone()))
+            }
             // Explicitly map known OpenAI-compatible providers for clarity
             "deepseek" | "groq" | "ollama" | "perplexity" | "mistral" | "openrouter"
             | "lmstudio" | "localai" => Box::new(OpenAICompatProvider::new(self.client.clone())),
@@ -46,7 +78,8 @@ impl LLMService {
         api_key: Option<&str>,
         provider: &str,
     ) -> Result<Vec<LLMModel>, AppError> {
-        let provider_impl = self.get_provider(provider);
+        api_key.map_or(Ok(()), ensure_unlocked)?;
+        let provider_impl = self.get_provider(provider, None);
         provider_impl.fetch_models(base_url, api_key).await
     }
 
@@ -60,19 +93,174 @@ impl LLMService {
         app: AppHandle,
         cancellation_rx: Option<tokio::sync::broadcast::Receiver<()>>,
         provider: &str,
+        api_mode: Option<&str>,
     ) -> Result<LLMChatResponse, AppError> {
-        let provider_impl = self.get_provider(provider);
-        provider_impl
-            .chat(
+        let result = self
+            .chat_once(
                 base_url,
                 api_key,
                 request,
-                chat_id,
-                message_id,
-                app,
+                chat_id.clone(),
+                message_id.clone(),
+                app.clone(),
+                cancellation_rx,
+                provider,
+                api_mode,
+            )
+            .await;
+        match result {
+            Err(AppError::LlmUnavailable(error)) => {
+                MessageEmitter::new(app).emit_message_error(chat_id, message_id, error.clone())?;
+                Err(AppError::Llm(error))
+            }
+            result => result,
+        }
+    }
+
+    /// Send a request to the first target, moving on to the next one while they are unavailable
+    ///
+    /// Each target has already been retried by its provider. Nothing is streamed before a
+    /// target accepts the request, so a fallback reply reaches the UI like any other.
+    pub async fn chat_with_fallback(
+        &self,
+        targets: &[LLMTarget],
+        mut request: LLMChatRequest,
+        chat_id: String,
+        message_id: String,
+        app: AppHandle,
+        cancellation_rx: Option<tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<(LLMChatResponse, Option<FallbackUsed>), AppError> {
+        let mut reason: Option<String> = None;
+        for (index, target) in targets.iter().enumerate() {
+            request.model.clone_from(&target.model);
+            let result = self
+                .chat_once(
+                    &target.base_url,
+                    target.api_key.as_deref(),
+                    request.clone(),
+                    chat_id.clone(),
+                    message_id.clone(),
+                    app.clone(),
+                    cancellation_rx
+                        .as_ref()
+                        .map(tokio::sync::broadcast::Receiver::resubscribe),
+                    &target.provider,
+                    target.api_mode.as_deref(),
+                )
+                .await;
+
+            match result {
+                Ok(response) => {
+                    let fallback = reason.map(|reason| FallbackUsed {
+                        connection_id: target.connection_id.clone(),
+                        provider: target.provider.clone(),
+                        model: target.model.clone(),
+                        reason,
+                    });
+                    return Ok((response, fallback));
+                }
+                Err(AppError::LlmUnavailable(error)) if index + 1 < targets.len() => {
+                    tracing::warn!(
+                        provider = %target.provider,
+                        model = %target.model,
+                        error = %error,
+                        "LLM unavailable, trying the next fallback model"
+                    );
+                    crate::lib::sentry_helpers::add_breadcrumb(
+                        "llm",
+                        format!("{} unavailable, falling back", target.model),
+                        sentry::Level::Warning,
+                    );
+                    reason = Some(error);
+                }
+                Err(AppError::LlmUnavailable(error)) => {
+                    MessageEmitter::new(app).emit_message_error(
+                        chat_id,
+                        message_id,
+                        error.clone(),
+                    )?;
+                    return Err(AppError::Llm(error));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(AppError::Validation(
+            "No LLM connection to send to".to_string(),
+        ))
+    }
+
+    /// One target, with structured output validation; unavailability is left to the caller
+    async fn chat_once(
+        &self,
+        base_url: &str,
+        api_key: Option<&str>,
+        request: LLMChatRequest,
+        chat_id: String,
+        message_id: String,
+        app: AppHandle,
+        cancellation_rx: Option<tokio::sync::broadcast::Receiver<()>>,
+        provider: &str,
+        api_mode: Option<&str>,
+    ) -> Result<LLMChatResponse, AppError> {
+        // A key still encrypted while the vault is locked can't be sent, fall back to another connection
+        if let Err(e) = api_key.map_or(Ok(()), ensure_unlocked) {
+            return Err(AppError::LlmUnavailable(e.to_string()));
+        }
+        let provider_impl = self.get_provider(provider, api_mode);
+        let Some(format) = request.response_format.clone() else {
+            return provider_impl
+                .chat(
+                    base_url,
+                    api_key,
+                    request,
+                    chat_id,
+                    message_id,
+                    app,
+                    cancellation_rx,
+                )
+                .await;
+        };
+
+        // Structured output is validated, an invalid reply gets one repair attempt
+        let response = provider_impl
+            .chat(
+                base_url,
+                api_key,
+                request.clone(),
+                chat_id.clone(),
+                message_id.clone(),
+                app.clone(),
+                cancellation_rx
+                    .as_ref()
+                    .map(tokio::sync::broadcast::Receiver::resubscribe),
+            )
+            .await?;
+        let validated = structured::validate_with_repair(response, &format, |messages| {
+            // Not streamed: the repaired reply replaces the invalid one when it completes
+            let mut repair_request = request;
+            repair_request.messages.extend(messages);
+            repair_request.stream = false;
+            provider_impl.chat(
+                base_url,
+                api_key,
+                repair_request,
+                chat_id.clone(),
+                message_id.clone(),
+                app.clone(),
                 cancellation_rx,
             )
-            .await
+        })
+        .await?;
+
+        validated.or_else(|errors| {
+            let error = format!(
+                "Structured output does not match the schema \"{}\": {}",
+                format.name,
+                errors.join("; ")
+            );
+            MessageEmitter::new(app).emit_message_error(chat_id, message_id, error.clone())?;
+            Err(AppError::Llm(error))
+        })
     }
 }
 
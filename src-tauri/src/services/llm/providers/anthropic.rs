@@ -7,6 +7,7 @@ use crate::models::llm_types::{
     AssistantContent, ChatMessage, ContentPart, LLMChatRequest, LLMChatResponse, LLMModel,
     TokenUsage, ToolCall, ToolCallFunction, ToolChoice, UserContent,
 };
+use crate::services::llm::retry;
 use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest::Client;
@@ -40,7 +41,9 @@ enum AnthropicContentBlock {
     #[serde(rename = "tool_result")]
     ToolResult {
         tool_use_id: String,
-        content: String,
+        content: AnthropicMessageContent,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        is_error: bool,
     },
     // Thinking block (beta/extended thinking)
     #[serde(rename = "thinking")]
@@ -102,10 +105,29 @@ enum AnthropicToolChoice {
     Tool { name: String },
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Default)]
 struct AnthropicUsage {
     input_tokens: u32,
     output_tokens: u32,
+    #[serde(default)]
+    cache_creation_input_tokens: u32,
+    #[serde(default)]
+    cache_read_input_tokens: u32,
+}
+
+impl AnthropicUsage {
+    /// `input_tokens` excludes cached tokens, so they are added back into the prompt count
+    fn token_usage(&self) -> TokenUsage {
+        let prompt_tokens =
+            self.input_tokens + self.cache_creation_input_tokens + self.cache_read_input_tokens;
+        TokenUsage {
+            prompt_tokens: Some(prompt_tokens),
+            completion_tokens: Some(self.output_tokens),
+            total_tokens: Some(prompt_tokens + self.output_tokens),
+            cache_read_tokens: Some(self.cache_read_input_tokens),
+            cache_write_tokens: Some(self.cache_creation_input_tokens),
+        }
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -115,11 +137,58 @@ struct AnthropicResponse {
     stop_reason: Option<String>,
 }
 
+/// Mark the stable prefix of the request (system prompt, tools and conversation so far) for
+/// prompt caching, so follow-up turns only pay full price for the newest message.
+/// Anthropic allows up to 4 breakpoints, this uses at most 3.
+fn add_cache_breakpoints(body: &mut Value) {
+    let cache_control = serde_json::json!({ "type": "ephemeral" });
+
+    if let Some(system) = body.get("system").and_then(Value::as_str) {
+        body["system"] = serde_json::json!([{
+            "type": "text",
+            "text": system,
+            "cache_control": cache_control
+        }]);
+    }
+
+    if let Some(tool) = body["tools"].as_array_mut().and_then(|t| t.last_mut()) {
+        tool["cache_control"] = cache_control.clone();
+    }
+
+    let Some(message) = body["messages"].as_array_mut().and_then(|m| m.last_mut()) else {
+        return;
+    };
+    if let Some(text) = message["content"].as_str() {
+        if text.is_empty() {
+            return;
+        }
+        message["content"] = serde_json::json!([{ "type": "text", "text": text }]);
+    }
+    // Thinking blocks and empty text blocks cannot carry a breakpoint
+    if let Some(block) = message["content"].as_array_mut().and_then(|blocks| {
+        blocks.iter_mut().rev().find(|block| {
+            !matches!(
+                block["type"].as_str(),
+                Some("thinking" | "redacted_thinking")
+            ) && block["text"].as_str() != Some("")
+        })
+    }) {
+        block["cache_control"] = cache_control;
+    }
+}
+
 impl AnthropicProvider {
     pub const fn new(client: Arc<Client>) -> Self {
         Self { client }
     }
 
+    /// Split a base64 data URL (data:image/png;base64,...) into mime type and data
+    fn parse_data_url(url: &str) -> Option<(String, String)> {
+        let (meta, data) = url.strip_prefix("data:")?.split_once(',')?;
+        let mime_type = meta.split(';').next().unwrap_or_default();
+        Some((mime_type.to_string(), data.to_string()))
+    }
+
     fn check_model_capabilities(model_id: &str) -> (bool, bool, bool) {
         let clean_id = model_id.split('/').next_back().unwrap_or(model_id);
         let model_lower = clean_id.to_lowercase();
@@ -135,13 +204,15 @@ impl AnthropicProvider {
     async fn handle_streaming(
         &self,
         req_builder: reqwest::RequestBuilder,
-        request_body: AnthropicRequest,
+        request_body: Value,
         chat_id: String,
         message_id: String,
         app: AppHandle,
         mut cancellation_rx: Option<tokio::sync::broadcast::Receiver<()>>,
+        output_tool: Option<String>,
     ) -> Result<LLMChatResponse, AppError> {
-        let response = req_builder.json(&request_body).send().await?;
+        let response =
+            retry::send(req_builder.json(&request_body), cancellation_rx.as_mut()).await?;
         let message_emitter = MessageEmitter::new(app.clone());
         let tool_emitter = ToolEmitter::new(app.clone());
 
@@ -164,8 +235,7 @@ impl AnthropicProvider {
         let mut full_content = String::new();
         let mut full_thinking = String::new();
         let mut buffer = String::new();
-        let mut input_tokens = 0;
-        let mut output_tokens = 0;
+        let mut usage = AnthropicUsage::default();
         let mut finish_reason = None;
 
         // Track current block state
@@ -173,6 +243,7 @@ impl AnthropicProvider {
         let mut current_tool_name = String::new();
         let mut current_tool_input = String::new();
         let mut _current_block_index: Option<u32> = None;
+        let mut in_output_tool = false; // Structured output arrives as the forced tool's input
 
         let mut tool_calls = Vec::new();
 
@@ -217,7 +288,13 @@ impl AnthropicProvider {
                             if let Some(block_type) =
                                 content_block.get("type").and_then(|s| s.as_str())
                             {
-                                if block_type == "tool_use" {
+                                let name = content_block.get("name").and_then(|s| s.as_str());
+                                if block_type == "tool_use"
+                                    && name.is_some()
+                                    && name == output_tool.as_deref()
+                                {
+                                    in_output_tool = true;
+                                } else if block_type == "tool_use" {
                                     current_tool_id = content_block
                                         .get("id")
                                         .and_then(|s| s.as_str())
@@ -252,7 +329,16 @@ impl AnthropicProvider {
                                     if let Some(partial_json) =
                                         delta.get("partial_json").and_then(|s| s.as_str())
                                     {
-                                        current_tool_input.push_str(partial_json);
+                                        if in_output_tool {
+                                            full_content.push_str(partial_json);
+                                            message_emitter.emit_message_chunk(
+                                                chat_id.clone(),
+                                                message_id.clone(),
+                                                partial_json.to_string(),
+                                            )?;
+                                        } else {
+                                            current_tool_input.push_str(partial_json);
+                                        }
                                     }
                                 } else if delta_type == "thinking_delta" {
                                     if let Some(thinking) =
@@ -270,6 +356,7 @@ impl AnthropicProvider {
                         }
                     }
                 } else if event_type == "content_block_stop" {
+                    in_output_tool = false;
                     // If we were processing a tool use block, finalize it
                     if !current_tool_id.is_empty() {
                         // Try to parse input as JSON
@@ -308,23 +395,25 @@ impl AnthropicProvider {
                     }
                 } else if event_type == "message_start" {
                     if let Ok(val) = serde_json::from_str::<Value>(event_data) {
-                        if let Some(usage) = val.get("message").and_then(|m| m.get("usage")) {
-                            if let Some(it) = usage
-                                .get("input_tokens")
-                                .and_then(serde_json::Value::as_u64)
-                            {
-                                input_tokens = it as u32;
-                            }
+                        if let Some(start) = val
+                            .get("message")
+                            .and_then(|m| m.get("usage"))
+                            .and_then(|u| AnthropicUsage::deserialize(u).ok())
+                        {
+                            usage = AnthropicUsage {
+                                output_tokens: usage.output_tokens,
+                                ..start
+                            };
                         }
                     }
                 } else if event_type == "message_delta" {
                     if let Ok(val) = serde_json::from_str::<Value>(event_data) {
-                        if let Some(usage) = val.get("usage") {
-                            if let Some(ot) = usage
+                        if let Some(delta) = val.get("usage") {
+                            if let Some(ot) = delta
                                 .get("output_tokens")
                                 .and_then(serde_json::Value::as_u64)
                             {
-                                output_tokens = ot as u32;
+                                usage.output_tokens = ot as u32;
                             }
                         }
                         if let Some(stop) = val.get("stop_reason").and_then(|s| s.as_str()) {
@@ -335,14 +424,15 @@ impl AnthropicProvider {
             }
         }
 
+        let token_usage = usage.token_usage();
         message_emitter.emit_message_complete(
             chat_id,
             message_id,
             full_content.clone(),
             Some(EventTokenUsage {
-                prompt_tokens: Some(input_tokens),
-                completion_tokens: Some(output_tokens),
-                total_tokens: Some(input_tokens + output_tokens),
+                prompt_tokens: token_usage.prompt_tokens,
+                completion_tokens: token_usage.completion_tokens,
+                total_tokens: token_usage.total_tokens,
             }),
         )?;
 
@@ -354,33 +444,27 @@ impl AnthropicProvider {
             } else {
                 Some(tool_calls)
             },
-            usage: Some(TokenUsage {
-                prompt_tokens: Some(input_tokens),
-                completion_tokens: Some(output_tokens),
-                total_tokens: Some(input_tokens + output_tokens),
-            }),
+            usage: Some(token_usage),
             reasoning: if full_thinking.is_empty() {
                 None
             } else {
                 Some(full_thinking)
             },
             images: None,
+            reasoning_items: None,
         })
     }
 
     async fn handle_non_streaming(
         &self,
         req_builder: reqwest::RequestBuilder,
-        request_body: AnthropicRequest,
+        request_body: Value,
         chat_id: String,
         message_id: String,
         app: AppHandle,
+        output_tool: Option<String>,
     ) -> Result<LLMChatResponse, AppError> {
-        let response = req_builder
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| AppError::Generic(format!("HTTP request failed: {e}")))?;
+        let response = retry::send(req_builder.json(&request_body), None).await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -414,6 +498,11 @@ impl AnthropicProvider {
                 AnthropicContentBlock::Thinking { thinking, .. } => {
                     thinking_str.push_str(&thinking);
                 }
+                AnthropicContentBlock::ToolUse { input, name, .. }
+                    if Some(&name) == output_tool.as_ref() =>
+                {
+                    content_str.push_str(&input.to_string());
+                }
                 AnthropicContentBlock::ToolUse { id, name, input } => {
                     tool_calls.push(ToolCall {
                         id: id.clone(),
@@ -446,17 +535,16 @@ impl AnthropicProvider {
             )?;
         }
 
+        let token_usage = json_response.usage.token_usage();
         let message_emitter = MessageEmitter::new(app.clone());
         message_emitter.emit_message_complete(
             chat_id.clone(),
             message_id.clone(),
             content_str.clone(),
             Some(EventTokenUsage {
-                prompt_tokens: Some(json_response.usage.input_tokens),
-                completion_tokens: Some(json_response.usage.output_tokens),
-                total_tokens: Some(
-                    json_response.usage.input_tokens + json_response.usage.output_tokens,
-                ),
+                prompt_tokens: token_usage.prompt_tokens,
+                completion_tokens: token_usage.completion_tokens,
+                total_tokens: token_usage.total_tokens,
             }),
         )?;
 
@@ -468,19 +556,14 @@ impl AnthropicProvider {
             } else {
                 Some(tool_calls)
             },
-            usage: Some(TokenUsage {
-                prompt_tokens: Some(json_response.usage.input_tokens),
-                completion_tokens: Some(json_response.usage.output_tokens),
-                total_tokens: Some(
-                    json_response.usage.input_tokens + json_response.usage.output_tokens,
-                ),
-            }),
+            usage: Some(token_usage),
             reasoning: if thinking_str.is_empty() {
                 None
             } else {
                 Some(thinking_str)
             },
             images: None,
+            reasoning_items: None,
         })
     }
 }
@@ -534,6 +617,7 @@ impl LLMProvider for AnthropicProvider {
                         supports_tools,
                         supports_thinking,
                         supports_image_generation,
+                        context_length: None,
                     });
                 }
             }
@@ -679,6 +763,7 @@ impl LLMProvider for AnthropicProvider {
                 ChatMessage::Assistant {
                     content,
                     tool_calls,
+                    ..
                 } => {
                     let mut blocks = Vec::new();
                     // Handle AssistantContent
@@ -717,13 +802,63 @@ impl LLMProvider for AnthropicProvider {
                 ChatMessage::Tool {
                     content,
                     tool_call_id,
+                    parts,
+                    is_error,
                 } => {
+                    // Images returned by the tool are sent inside the tool_result
+                    let content = if parts.is_empty() {
+                        AnthropicMessageContent::Text(content)
+                    } else {
+                        let mut blocks = vec![AnthropicContentBlock::Text { text: content }];
+                        for part in parts {
+                            let (media_type, data) = match part {
+                                ContentPart::ImageUrl { image_url } => {
+                                    match Self::parse_data_url(&image_url.url) {
+                                        Some(parsed) => parsed,
+                                        None => continue,
+                                    }
+                                }
+                                ContentPart::FileUrl { file_url } => {
+                                    match Self::parse_data_url(&file_url.url) {
+                                        Some((_, data)) => (file_url.mime_type, data),
+                                        None => continue,
+                                    }
+                                }
+                                ContentPart::InlineData { inline_data } => {
+                                    (inline_data.mime_type, inline_data.data)
+                                }
+                                ContentPart::Text { text } => {
+                                    blocks.push(AnthropicContentBlock::Text { text });
+                                    continue;
+                                }
+                            };
+
+                            if media_type.starts_with("image/") {
+                                blocks.push(AnthropicContentBlock::Image {
+                                    source: AnthropicImageSource {
+                                        r#type: "base64".to_string(),
+                                        media_type,
+                                        data,
+                                    },
+                                });
+                            } else {
+                                blocks.push(AnthropicContentBlock::Text {
+                                    text: format!(
+                                        "[Tool output: {media_type} - Not supported by this model]"
+                                    ),
+                                });
+                            }
+                        }
+                        AnthropicMessageContent::Blocks(blocks)
+                    };
+
                     messages.push(AnthropicMessage {
                         role: "user".to_string(), // Tool results are role "user"
                         content: AnthropicMessageContent::Blocks(vec![
                             AnthropicContentBlock::ToolResult {
                                 tool_use_id: tool_call_id,
                                 content,
+                                is_error,
                             },
                         ]),
                     });
@@ -732,7 +867,7 @@ impl LLMProvider for AnthropicProvider {
         }
 
         // Handle Tools
-        let tools = request.tools.map(|req_tools| {
+        let mut tools: Option<Vec<AnthropicTool>> = request.tools.map(|req_tools| {
             req_tools
                 .into_iter()
                 .map(|t| AnthropicTool {
@@ -746,8 +881,20 @@ impl LLMProvider for AnthropicProvider {
                 .collect()
         });
 
+        // Structured output: a tool taking the schema as input, forced so the reply is its input
+        let output_tool = request.response_format.map(|format| {
+            tools.get_or_insert_with(Vec::new).push(AnthropicTool {
+                name: format.name.clone(),
+                description: Some("Respond with the final answer in this format".to_string()),
+                input_schema: format.schema,
+            });
+            format.name
+        });
+
         // Handle Tool Choice
-        let tool_choice = if let Some(tc) = request.tool_choice {
+        let tool_choice = if let Some(name) = &output_tool {
+            Some(AnthropicToolChoice::Tool { name: name.clone() })
+        } else if let Some(tc) = request.tool_choice {
             match tc {
                 ToolChoice::String(s) if s == "auto" => Some(AnthropicToolChoice::Auto),
                 ToolChoice::String(s) if s == "any" => Some(AnthropicToolChoice::Any),
@@ -760,8 +907,8 @@ impl LLMProvider for AnthropicProvider {
             None
         };
 
-        // Handle Thinking
-        let thinking = if supports_thinking {
+        // Handle Thinking (not allowed together with a forced tool)
+        let thinking = if supports_thinking && output_tool.is_none() {
             // Hardcode 1024 tokens for now or fetch from generic request config if available in future
             Some(AnthropicThinkingConfig {
                 r#type: "enabled".to_string(),
@@ -772,7 +919,7 @@ impl LLMProvider for AnthropicProvider {
         };
 
         // Adjust max_tokens if thinking is enabled to ensure we have room
-        let max_tokens = if supports_thinking && request.max_tokens.unwrap_or(0) < 4096 {
+        let max_tokens = if thinking.is_some() && request.max_tokens.unwrap_or(0) < 4096 {
             8192 // Bump up for thinking models
         } else {
             request.max_tokens.unwrap_or(4096)
@@ -788,20 +935,30 @@ impl LLMProvider for AnthropicProvider {
             tool_choice,
             thinking,
         };
+        let mut request_body = serde_json::to_value(&anthropic_request)?;
+        add_cache_breakpoints(&mut request_body);
 
         if request.stream {
             self.handle_streaming(
                 req_builder,
-                anthropic_request,
+                request_body,
                 chat_id,
                 message_id,
                 app,
                 cancellation_rx,
+                output_tool,
             )
             .await
         } else {
-            self.handle_non_streaming(req_builder, anthropic_request, chat_id, message_id, app)
-                .await
+            self.handle_non_streaming(
+                req_builder,
+                request_body,
+                chat_id,
+                message_id,
+                app,
+                output_tool,
+            )
+            .await
         }
     }
 }
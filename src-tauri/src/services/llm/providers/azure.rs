@@ -0,0 +1,239 @@
+//! Azure OpenAI: requests go to a deployment instead of a model
+//!
+//! The connection's base URL is the resource endpoint, settings ride along as query parameters:
+//! `https://my-resource.openai.azure.com?api-version=2024-10-21&deployments=prod-4o:gpt-4o,mini:gpt-4o-mini`
+//! - `api-version`: data plane API version, defaults to `DEFAULT_API_VERSION`
+//! - `deployments`: `deployment:model` pairs, for resources whose deployments cannot be listed
+//!
+//! Chat completions use the OpenAI format, so streaming, tool calls and usage are handled
+//! by `OpenAICompatProvider`.
+
+use super::{LLMProvider, OpenAICompatProvider, OpenAIProvider};
+use crate::error::AppError;
+use crate::models::llm_types::{LLMChatRequest, LLMChatResponse, LLMModel};
+use async_trait::async_trait;
+use reqwest::{Client, Url};
+use std::sync::Arc;
+use tauri::AppHandle;
+
+pub const DEFAULT_API_VERSION: &str = "2024-10-21";
+/// Last API version that lists deployments, newer ones only list base models
+const DEPLOYMENTS_API_VERSION: &str = "2022-12-01";
+
+/// Resource endpoint and settings parsed from a connection's base URL
+struct AzureEndpoint {
+    endpoint: String,
+    api_version: String,
+    deployments: Vec<(String, String)>, // (deployment, model)
+}
+
+impl AzureEndpoint {
+    fn parse(base_url: &str) -> Result<Self, AppError> {
+        let url = Url::parse(base_url.trim())
+            .map_err(|e| AppError::Validation(format!("Invalid Azure OpenAI endpoint: {e}")))?;
+
+        let mut api_version = None;
+        let mut deployments = Vec::new();
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "api-version" => api_version = Some(value.to_string()),
+                "deployments" => deployments.extend(value.split(',').filter_map(|pair| {
+                    let (deployment, model) = pair.split_once(':')?;
+                    let deployment = deployment.trim();
+                    (!deployment.is_empty())
+                        .then(|| (deployment.to_string(), model.trim().to_string()))
+                })),
+                _ => {}
+            }
+        }
+
+        // The resource endpoint, with or without the /openai path segment
+        let mut endpoint = url.clone();
+        endpoint.set_query(None);
+        endpoint.set_fragment(None);
+        let endpoint = endpoint.as_str().trim_end_matches('/');
+        let endpoint = endpoint.strip_suffix("/openai").unwrap_or(endpoint);
+
+        Ok(Self {
+            endpoint: endpoint.to_string(),
+            api_version: api_version
+                .filter(|v| !v.is_empty())
+                .unwrap_or_else(|| DEFAULT_API_VERSION.to_string()),
+            deployments,
+        })
+    }
+
+    /// Deployment serving a model ID, the ID is a deployment name unless it maps to a model
+    fn deployment_for<'a>(&'a self, model: &'a str) -> &'a str {
+        self.deployments
+            .iter()
+            .find(|(deployment, _)| deployment == model)
+            .or_else(|| self.deployments.iter().find(|(_, m)| m == model))
+            .map_or(model, |(deployment, _)| deployment)
+    }
+
+    fn model_for<'a>(&'a self, deployment: &'a str) -> &'a str {
+        self.deployments
+            .iter()
+            .find(|(d, _)| d == deployment)
+            .map_or(deployment, |(_, model)| model)
+    }
+
+    fn chat_url(&self, deployment: &str) -> String {
+        format!(
+            "{}/openai/deployments/{deployment}/chat/completions?api-version={}",
+            self.endpoint, self.api_version
+        )
+    }
+}
+
+pub struct AzureOpenAIProvider {
+    client: Arc<Client>,
+    compat: OpenAICompatProvider,
+}
+
+impl AzureOpenAIProvider {
+    pub fn new(client: Arc<Client>) -> Self {
+        Self {
+            compat: OpenAICompatProvider::new(client.clone()),
+            client,
+        }
+    }
+
+    fn deployment_model(deployment: &str, model: &str) -> LLMModel {
+        let (supports_tools, supports_thinking, supports_image_generation) =
+            OpenAIProvider::check_model_capabilities(model);
+        LLMModel {
+            id: deployment.to_string(),
+            name: if deployment == model {
+                deployment.to_string()
+            } else {
+                format!("{deployment} ({model})")
+            },
+            created: None,
+            owned_by: Some("azure".to_string()),
+            supports_tools,
+            supports_thinking,
+            supports_image_generation,
+            context_length: None,
+        }
+    }
+
+    /// Deployments of the resource with the model each one serves
+    async fn list_deployments(
+        &self,
+        endpoint: &AzureEndpoint,
+        api_key: Option<&str>,
+    ) -> Result<Vec<(String, String)>, AppError> {
+        let url = format!(
+            "{}/openai/deployments?api-version={DEPLOYMENTS_API_VERSION}",
+            endpoint.endpoint
+        );
+        let mut req_builder = self.client.get(&url);
+        if let Some(key) = api_key {
+            req_builder = req_builder.header("api-key", key);
+        }
+
+        let response = req_builder.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(AppError::Llm(format!(
+                "Azure OpenAI API error ({status}): {error_text}"
+            )));
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        let deployments = json["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|item| item["status"].as_str().is_none_or(|s| s == "succeeded"))
+            .filter_map(|item| {
+                let deployment = item["id"].as_str()?;
+                let model = item["model"].as_str().unwrap_or(deployment);
+                Some((deployment.to_string(), model.to_string()))
+            })
+            .collect();
+        Ok(deployments)
+    }
+}
+
+#[async_trait]
+impl LLMProvider for AzureOpenAIProvider {
+    async fn fetch_models(
+        &self,
+        base_url: &str,
+        api_key: Option<&str>,
+    ) -> Result<Vec<LLMModel>, AppError> {
+        let endpoint = AzureEndpoint::parse(base_url)?;
+
+        // Configured deployments always show, listed ones are added when the resource allows it
+        let mut deployments = endpoint.deployments.clone();
+        match self.list_deployments(&endpoint, api_key).await {
+            Ok(listed) => {
+                for (deployment, model) in listed {
+                    if !deployments.iter().any(|(d, _)| *d == deployment) {
+                        deployments.push((deployment, model));
+                    }
+                }
+            }
+            Err(e) if !deployments.is_empty() => {
+                tracing::warn!(error = %e, "Failed to list Azure OpenAI deployments");
+            }
+            Err(e) => return Err(e),
+        }
+
+        Ok(deployments
+            .iter()
+            .map(|(deployment, model)| Self::deployment_model(deployment, model))
+            .collect())
+    }
+
+    async fn chat(
+        &self,
+        base_url: &str,
+        api_key: Option<&str>,
+        request: LLMChatRequest,
+        chat_id: String,
+        message_id: String,
+        app: AppHandle,
+        cancellation_rx: Option<tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<LLMChatResponse, AppError> {
+        let endpoint = AzureEndpoint::parse(base_url)?;
+        let deployment = endpoint.deployment_for(&request.model);
+
+        let mut req_builder = self.client.post(endpoint.chat_url(deployment));
+        if let Some(key) = api_key {
+            req_builder = req_builder.header("api-key", key);
+        }
+        req_builder = req_builder.header("Content-Type", "application/json");
+
+        // The deployment picks the model, the body names it only for logging on Azure's side
+        let mut request_body = serde_json::to_value(&request)?;
+        request_body["model"] = serde_json::Value::from(endpoint.model_for(deployment));
+        if let Some(format) = &request.response_format {
+            request_body["response_format"] = format.to_openai();
+        }
+
+        if request.stream {
+            self.compat
+                .handle_streaming(
+                    req_builder,
+                    request_body,
+                    chat_id,
+                    message_id,
+                    app,
+                    cancellation_rx,
+                )
+                .await
+        } else {
+            self.compat
+                .handle_non_streaming(req_builder, request_body, chat_id, message_id, app)
+                .await
+        }
+    }
+}
@@ -0,0 +1,1253 @@
+//! AWS Bedrock through the `Converse` and `ConverseStream` APIs
+//!
+//! The connection's base URL is the Bedrock runtime endpoint, settings ride along as query parameters:
+//! `https://bedrock-runtime.us-east-1.amazonaws.com?profile=work`
+//! - `region`: signing region, read from the endpoint host when it is an AWS one
+//! - `profile`: named profile from the shared credentials and config files
+//!
+//! Any other endpoint (e.g. `http://localhost:4010?region=us-east-1`) is used as-is for both the
+//! runtime and the control plane, so a local stub can stand in for Bedrock.
+//!
+//! The API key holds `ACCESS_KEY_ID:SECRET_ACCESS_KEY[:SESSION_TOKEN]` or `profile:NAME`. Without
+//! one, credentials come from the `AWS_*` environment variables, then the selected profile.
+
+use super::LLMProvider;
+use crate::error::AppError;
+use crate::events::{
+    MessageEmitter, TokenUsage as EventTokenUsage, ToolCall as EventToolCall, ToolEmitter,
+};
+use crate::models::llm_types::{
+    AssistantContent, ChatMessage, ContentPart, LLMChatRequest, LLMChatResponse, LLMModel,
+    TokenUsage, ToolCall, ToolCallFunction, ToolChoice, UserContent,
+};
+use crate::services::llm::retry;
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::{Client, Method, RequestBuilder, Url};
+use ring::hmac;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::AppHandle;
+
+const DEFAULT_REGION: &str = "us-east-1";
+/// Bedrock signs both the runtime and the control plane under one service name
+const SIGNING_SERVICE: &str = "bedrock";
+
+struct Credentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+impl Credentials {
+    /// `ACCESS_KEY_ID:SECRET_ACCESS_KEY[:SESSION_TOKEN]`
+    fn parse(key: &str) -> Option<Self> {
+        let mut parts = key.trim().splitn(3, ':');
+        let access_key_id = parts.next()?.trim();
+        let secret_access_key = parts.next()?.trim();
+        if access_key_id.is_empty() || secret_access_key.is_empty() {
+            return None;
+        }
+        Some(Self {
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: secret_access_key.to_string(),
+            session_token: parts
+                .next()
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(String::from),
+        })
+    }
+
+    fn from_env() -> Option<Self> {
+        let access_key_id = std::env::var("AWS_ACCESS_KEY_ID").ok()?;
+        let secret_access_key = std::env::var("AWS_SECRET_ACCESS_KEY").ok()?;
+        Some(Self {
+            access_key_id,
+            secret_access_key,
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+
+    fn from_profile(profile: &HashMap<String, String>) -> Option<Self> {
+        Some(Self {
+            access_key_id: profile.get("aws_access_key_id")?.clone(),
+            secret_access_key: profile.get("aws_secret_access_key")?.clone(),
+            session_token: profile.get("aws_session_token").cloned(),
+        })
+    }
+
+    /// `SigV4` headers for a request, `authorization` included
+    fn sign(&self, method: &Method, url: &Url, region: &str, body: &[u8]) -> Vec<(String, String)> {
+        let payload_hash = hex::encode(Sha256::digest(body));
+        let scope = SigningScope {
+            time: chrono::Utc::now(),
+            region,
+            service: SIGNING_SERVICE,
+        };
+        let headers = vec![("x-amz-content-sha256".to_string(), payload_hash.clone())];
+        self.sign_at(&scope, method, url, &payload_hash, headers)
+    }
+
+    /// Sign with a fixed time and service, `headers` are signed along with `host`,
+    /// `x-amz-date` and the session token
+    fn sign_at(
+        &self,
+        scope: &SigningScope,
+        method: &Method,
+        url: &Url,
+        payload_hash: &str,
+        mut headers: Vec<(String, String)>,
+    ) -> Vec<(String, String)> {
+        let amz_date = scope.time.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = scope.time.format("%Y%m%d").to_string();
+
+        let mut host = url.host_str().unwrap_or_default().to_string();
+        if let Some(port) = url.port() {
+            let _ = write!(host, ":{port}");
+        }
+        headers.push(("host".to_string(), host));
+        headers.push(("x-amz-date".to_string(), amz_date.clone()));
+        if let Some(token) = &self.session_token {
+            headers.push(("x-amz-security-token".to_string(), token.clone()));
+        }
+        headers.sort();
+
+        // Path segments are encoded a second time, every service but S3 expects that
+        let canonical_uri = url
+            .path()
+            .split('/')
+            .map(uri_encode)
+            .collect::<Vec<_>>()
+            .join("/");
+        let mut query: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(k, v)| (uri_encode(&k), uri_encode(&v)))
+            .collect();
+        query.sort();
+        let canonical_query = query
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join("&");
+        let canonical_headers =
+            headers
+                .iter()
+                .fold(String::new(), |mut canonical, (name, value)| {
+                    let _ = writeln!(canonical, "{name}:{}", value.trim());
+                    canonical
+                });
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(";");
+
+        let canonical_request = format!(
+            "{method}\n{canonical_uri}\n{canonical_query}\n{canonical_headers}\n{signed_headers}\n{payload_hash}"
+        );
+        let credential_scope = format!("{date}/{}/{}/aws4_request", scope.region, scope.service);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{credential_scope}\n{}",
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let hmac_sha256 = |key: &[u8], data: &str| {
+            hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data.as_bytes())
+                .as_ref()
+                .to_vec()
+        };
+        let signing_key = [scope.region, scope.service, "aws4_request"].iter().fold(
+            hmac_sha256(format!("AWS4{}", self.secret_access_key).as_bytes(), &date),
+            |key, part| hmac_sha256(&key, part),
+        );
+        let signature = hex::encode(hmac_sha256(&signing_key, &string_to_sign));
+
+        // reqwest sets the host header itself
+        headers.retain(|(name, _)| name != "host");
+        headers.push((
+            "authorization".to_string(),
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{credential_scope}, SignedHeaders={signed_headers}, Signature={signature}",
+                self.access_key_id
+            ),
+        ));
+        headers
+    }
+}
+
+/// Time, region and service a `SigV4` signature is valid for
+struct SigningScope<'a> {
+    time: chrono::DateTime<chrono::Utc>,
+    region: &'a str,
+    service: &'a str,
+}
+
+/// RFC 3986 encoding of everything but unreserved characters, as `SigV4` requires
+fn uri_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+/// Key/value pairs of one section of an AWS shared config or credentials file
+fn read_profile_section(path: Option<PathBuf>, section: &str) -> HashMap<String, String> {
+    let Some(contents) = path.and_then(|p| std::fs::read_to_string(p).ok()) else {
+        return HashMap::new();
+    };
+
+    let mut values = HashMap::new();
+    let mut in_section = false;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_section = name.trim() == section;
+        } else if in_section {
+            if let Some((key, value)) = line.split_once('=') {
+                values.insert(key.trim().to_lowercase(), value.trim().to_string());
+            }
+        }
+    }
+    values
+}
+
+/// Settings of a named profile, the credentials file taking precedence over the config file
+fn load_profile(name: &str) -> HashMap<String, String> {
+    let credentials_file = std::env::var_os("AWS_SHARED_CREDENTIALS_FILE")
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|h| h.join(".aws").join("credentials")));
+    let config_file = std::env::var_os("AWS_CONFIG_FILE")
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|h| h.join(".aws").join("config")));
+
+    let config_section = if name == "default" {
+        name.to_string()
+    } else {
+        format!("profile {name}")
+    };
+    let mut profile = read_profile_section(config_file, &config_section);
+    profile.extend(read_profile_section(credentials_file, name));
+    profile
+}
+
+/// Endpoints, region and credentials resolved from a connection's base URL and API key
+struct BedrockConfig {
+    runtime: String,
+    control: String,
+    region: String,
+    credentials: Credentials,
+}
+
+impl BedrockConfig {
+    fn resolve(base_url: &str, api_key: Option<&str>) -> Result<Self, AppError> {
+        let url = Url::parse(base_url.trim())
+            .map_err(|e| AppError::Validation(format!("Invalid Bedrock endpoint: {e}")))?;
+
+        let mut region = None;
+        let mut profile_name = None;
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "region" => region = Some(value.to_string()),
+                "profile" => profile_name = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        let api_key = api_key.map(str::trim).filter(|k| !k.is_empty());
+        if let Some(name) = api_key.and_then(|k| k.strip_prefix("profile:")) {
+            profile_name = Some(name.trim().to_string());
+        }
+        let explicit_profile = profile_name.is_some();
+        let profile_name = profile_name
+            .filter(|p| !p.is_empty())
+            .or_else(|| std::env::var("AWS_PROFILE").ok())
+            .unwrap_or_else(|| "default".to_string());
+        let profile = load_profile(&profile_name);
+
+        let credentials = match api_key {
+            Some(key) if !key.starts_with("profile:") => Credentials::parse(key).ok_or_else(|| {
+                AppError::Validation(
+                    "Bedrock API key must be ACCESS_KEY_ID:SECRET_ACCESS_KEY[:SESSION_TOKEN] or profile:NAME"
+                        .to_string(),
+                )
+            })?,
+            _ => (!explicit_profile)
+                .then(Credentials::from_env)
+                .flatten()
+                .or_else(|| Credentials::from_profile(&profile))
+                .ok_or_else(|| {
+                    AppError::Validation(format!(
+                        "No AWS credentials found for Bedrock (profile '{profile_name}')"
+                    ))
+                })?,
+        };
+
+        let mut endpoint = url.clone();
+        endpoint.set_query(None);
+        endpoint.set_fragment(None);
+        let runtime = endpoint.as_str().trim_end_matches('/').to_string();
+
+        // bedrock-runtime[-fips].<region>.amazonaws.com: the control plane drops "-runtime"
+        let host = url.host_str().unwrap_or_default();
+        let aws_host = host
+            .strip_prefix("bedrock-runtime.")
+            .or_else(|| host.strip_prefix("bedrock-runtime-fips."))
+            .filter(|rest| rest.contains(".amazonaws.com"));
+        let control = match aws_host {
+            Some(_) => runtime.replacen("bedrock-runtime", "bedrock", 1),
+            None => runtime.clone(),
+        };
+
+        let region = region
+            .filter(|r| !r.is_empty())
+            .or_else(|| aws_host.and_then(|rest| rest.split('.').next().map(String::from)))
+            .or_else(|| std::env::var("AWS_REGION").ok())
+            .or_else(|| std::env::var("AWS_DEFAULT_REGION").ok())
+            .or_else(|| profile.get("region").cloned())
+            .unwrap_or_else(|| DEFAULT_REGION.to_string());
+
+        Ok(Self {
+            runtime,
+            control,
+            region,
+            credentials,
+        })
+    }
+
+    fn url(base: &str, path: &str) -> Result<Url, AppError> {
+        Url::parse(&format!("{base}{path}"))
+            .map_err(|e| AppError::Validation(format!("Invalid Bedrock endpoint: {e}")))
+    }
+
+    fn converse_url(&self, model: &str, stream: bool) -> Result<Url, AppError> {
+        let action = if stream {
+            "converse-stream"
+        } else {
+            "converse"
+        };
+        Self::url(
+            &self.runtime,
+            &format!("/model/{}/{action}", uri_encode(model)),
+        )
+    }
+
+    fn foundation_models_url(&self) -> Result<Url, AppError> {
+        Self::url(&self.control, "/foundation-models?byOutputModality=TEXT")
+    }
+
+    /// Inference profile prefix for cross-region models, e.g. `us.` or `eu.`
+    fn geo_prefix(&self) -> Option<&'static str> {
+        match self.region.split('-').next()? {
+            "us" => Some("us"),
+            "eu" => Some("eu"),
+            "ap" => Some("apac"),
+            _ => None,
+        }
+    }
+}
+
+/// CRC-32 (IEEE) lookup table used by the event-stream framing
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i: u32 = 0;
+    while i < 256 {
+        let mut crc = i;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i as usize] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        CRC32_TABLE[((crc ^ u32::from(byte)) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// One message of the `application/vnd.amazon.eventstream` framing
+struct EventStreamMessage {
+    headers: HashMap<String, String>,
+    payload: Vec<u8>,
+}
+
+impl EventStreamMessage {
+    /// Decode the first message in `buf`, `None` until it has fully arrived
+    ///
+    /// Layout: total length, headers length and prelude CRC (4 bytes each), headers,
+    /// payload, then the CRC of everything before it.
+    fn decode(buf: &[u8]) -> Result<Option<(Self, usize)>, AppError> {
+        let read_u32 =
+            |at: usize| u32::from_be_bytes([buf[at], buf[at + 1], buf[at + 2], buf[at + 3]]);
+        if buf.len() < 12 {
+            return Ok(None);
+        }
+        let total_len = read_u32(0) as usize;
+        let headers_len = read_u32(4) as usize;
+        if read_u32(8) != crc32(&buf[..8]) || total_len < 16 + headers_len {
+            return Err(AppError::Llm("Corrupt Bedrock event stream".to_string()));
+        }
+        if buf.len() < total_len {
+            return Ok(None);
+        }
+        if read_u32(total_len - 4) != crc32(&buf[..total_len - 4]) {
+            return Err(AppError::Llm("Corrupt Bedrock event stream".to_string()));
+        }
+
+        let mut headers = HashMap::new();
+        let raw = &buf[12..12 + headers_len];
+        let mut pos = 0;
+        while pos < raw.len() {
+            let name_len = raw[pos] as usize;
+            let name =
+                String::from_utf8_lossy(raw.get(pos + 1..pos + 1 + name_len).unwrap_or_default());
+            pos += 1 + name_len;
+            let Some(&value_type) = raw.get(pos) else {
+                break;
+            };
+            pos += 1;
+            let value_len = match value_type {
+                0 | 1 => 0, // bool
+                2 => 1,     // byte
+                3 => 2,     // short
+                4 => 4,     // int
+                5 | 8 => 8, // long, timestamp
+                9 => 16,    // uuid
+                6 | 7 => {
+                    // bytes, string: 2 byte length prefix
+                    let Some(len) = raw.get(pos..pos + 2) else {
+                        break;
+                    };
+                    pos += 2;
+                    u16::from_be_bytes([len[0], len[1]]) as usize
+                }
+                _ => break,
+            };
+            if value_type == 7 {
+                if let Some(value) = raw.get(pos..pos + value_len) {
+                    headers.insert(
+                        name.into_owned(),
+                        String::from_utf8_lossy(value).into_owned(),
+                    );
+                }
+            }
+            pos += value_len;
+        }
+
+        let payload = buf[12 + headers_len..total_len - 4].to_vec();
+        Ok(Some((Self { headers, payload }, total_len)))
+    }
+
+    fn header(&self, name: &str) -> &str {
+        self.headers.get(name).map_or("", String::as_str)
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct BedrockUsage {
+    #[serde(default)]
+    input_tokens: u32,
+    #[serde(default)]
+    output_tokens: u32,
+    #[serde(default)]
+    cache_read_input_tokens: u32,
+    #[serde(default)]
+    cache_write_input_tokens: u32,
+}
+
+impl BedrockUsage {
+    /// `inputTokens` excludes cached tokens, so they are added back into the prompt count
+    const fn token_usage(&self) -> TokenUsage {
+        let prompt_tokens =
+            self.input_tokens + self.cache_read_input_tokens + self.cache_write_input_tokens;
+        TokenUsage {
+            prompt_tokens: Some(prompt_tokens),
+            completion_tokens: Some(self.output_tokens),
+            total_tokens: Some(prompt_tokens + self.output_tokens),
+            cache_read_tokens: Some(self.cache_read_input_tokens),
+            cache_write_tokens: Some(self.cache_write_input_tokens),
+        }
+    }
+}
+
+/// Output of a `ConverseStream` response, passed on as it arrives
+enum StreamEvent<'a> {
+    Text(&'a str),
+    Thinking(&'a str),
+    ToolCall(&'a ToolCall),
+}
+
+/// Tool use block being streamed
+#[derive(Default)]
+struct PendingToolUse {
+    id: String,
+    name: String,
+    input: String,
+}
+
+pub struct BedrockProvider {
+    client: Arc<Client>,
+}
+
+impl BedrockProvider {
+    pub const fn new(client: Arc<Client>) -> Self {
+        Self { client }
+    }
+
+    fn signed_request(
+        &self,
+        config: &BedrockConfig,
+        method: Method,
+        url: Url,
+        body: Option<Vec<u8>>,
+    ) -> RequestBuilder {
+        let body = body.unwrap_or_default();
+        let headers = config
+            .credentials
+            .sign(&method, &url, &config.region, &body);
+        let mut req_builder = self.client.request(method, url);
+        for (name, value) in headers {
+            req_builder = req_builder.header(name, value);
+        }
+        if body.is_empty() {
+            req_builder
+        } else {
+            req_builder
+                .header("Content-Type", "application/json")
+                .body(body)
+        }
+    }
+
+    fn check_model_capabilities(model_id: &str) -> (bool, bool, bool) {
+        let model_lower = model_id.to_lowercase();
+
+        // Families that support tool use through the Converse API
+        let supports_tools = (model_lower.contains("anthropic.claude")
+            && !model_lower.contains("claude-v2")
+            && !model_lower.contains("claude-instant"))
+            || [
+                "amazon.nova",
+                "mistral.mistral-large",
+                "mistral.pixtral",
+                "cohere.command-r",
+                "meta.llama3-1",
+                "meta.llama3-2-11b",
+                "meta.llama3-2-90b",
+                "meta.llama3-3",
+                "meta.llama4",
+                "ai21.jamba",
+                "writer.palmyra",
+            ]
+            .iter()
+            .any(|family| model_lower.contains(family));
+        // Models that return reasoning content without extra request fields
+        let supports_thinking = model_lower.contains("deepseek.r1");
+
+        (supports_tools, supports_thinking, false)
+    }
+
+    /// Split a base64 data URL (data:image/png;base64,...) into mime type and data
+    fn parse_data_url(url: &str) -> Option<(String, String)> {
+        let (meta, data) = url.strip_prefix("data:")?.split_once(',')?;
+        let mime_type = meta.split(';').next().unwrap_or_default();
+        Some((mime_type.to_string(), data.to_string()))
+    }
+
+    /// Converse content block for base64 data, a text note when Bedrock cannot take the type
+    fn media_block(mime_type: &str, data: &str, index: usize) -> Value {
+        let image_format = match mime_type {
+            "image/png" => Some("png"),
+            "image/jpeg" | "image/jpg" => Some("jpeg"),
+            "image/gif" => Some("gif"),
+            "image/webp" => Some("webp"),
+            _ => None,
+        };
+        if let Some(format) = image_format {
+            return json!({ "image": { "format": format, "source": { "bytes": data } } });
+        }
+
+        let document_format = match mime_type {
+            "application/pdf" => Some("pdf"),
+            "text/csv" => Some("csv"),
+            "text/html" => Some("html"),
+            "text/plain" => Some("txt"),
+            "text/markdown" => Some("md"),
+            "application/msword" => Some("doc"),
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => {
+                Some("docx")
+            }
+            "application/vnd.ms-excel" => Some("xls"),
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => Some("xlsx"),
+            _ => None,
+        };
+        document_format.map_or_else(
+            || json!({ "text": format!("[File attachment: {mime_type} - Not supported by this model]") }),
+            // Document names must be unique within a message
+            |format| {
+                json!({
+                    "document": {
+                        "format": format,
+                        "name": format!("Attachment {}", index + 1),
+                        "source": { "bytes": data }
+                    }
+                })
+            },
+        )
+    }
+
+    fn part_block(part: ContentPart, index: usize) -> Option<Value> {
+        match part {
+            ContentPart::Text { text } => (!text.is_empty()).then(|| json!({ "text": text })),
+            ContentPart::ImageUrl { image_url } => Self::parse_data_url(&image_url.url)
+                .map(|(mime_type, data)| Self::media_block(&mime_type, &data, index)),
+            ContentPart::FileUrl { file_url } => Self::parse_data_url(&file_url.url)
+                .map(|(_, data)| Self::media_block(&file_url.mime_type, &data, index)),
+            ContentPart::InlineData { inline_data } => Some(Self::media_block(
+                &inline_data.mime_type,
+                &inline_data.data,
+                index,
+            )),
+        }
+    }
+
+    /// Converse requires alternating roles, so consecutive turns of one role are merged
+    fn push_message(messages: &mut Vec<Value>, role: &str, content: Vec<Value>) {
+        if content.is_empty() {
+            return;
+        }
+        if let Some(last) = messages.last_mut() {
+            if last["role"] == role {
+                if let Some(blocks) = last["content"].as_array_mut() {
+                    blocks.extend(content);
+                    return;
+                }
+            }
+        }
+        messages.push(json!({ "role": role, "content": content }));
+    }
+
+    /// Converse request body and the name of the forced structured output tool, if any
+    fn build_body(request: LLMChatRequest) -> (Value, Option<String>) {
+        let mut system = Vec::new();
+        let mut messages = Vec::new();
+
+        for msg in request.messages {
+            match msg {
+                ChatMessage::System { content } => {
+                    if !content.is_empty() {
+                        system.push(json!({ "text": content }));
+                    }
+                }
+                ChatMessage::User { content } => {
+                    let blocks = match content {
+                        UserContent::Text(text) => vec![json!({ "text": text })],
+                        UserContent::Parts(parts) => parts
+                            .into_iter()
+                            .enumerate()
+                            .filter_map(|(i, part)| Self::part_block(part, i))
+                            .collect(),
+                    };
+                    Self::push_message(&mut messages, "user", blocks);
+                }
+                ChatMessage::Assistant {
+                    content,
+                    tool_calls,
+                    ..
+                } => {
+                    let mut blocks = Vec::new();
+                    match content {
+                        AssistantContent::Text(text) => {
+                            if !text.is_empty() {
+                                blocks.push(json!({ "text": text }));
+                            }
+                        }
+                        AssistantContent::Parts(parts) => {
+                            for part in parts {
+                                if let ContentPart::Text { text } = part {
+                                    if !text.is_empty() {
+                                        blocks.push(json!({ "text": text }));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    for tc in tool_calls.unwrap_or_default() {
+                        let input: Value = serde_json::from_str(&tc.function.arguments)
+                            .unwrap_or_else(|_| json!({}));
+                        blocks.push(json!({
+                            "toolUse": {
+                                "toolUseId": tc.id,
+                                "name": tc.function.name,
+                                "input": input
+                            }
+                        }));
+                    }
+                    Self::push_message(&mut messages, "assistant", blocks);
+                }
+                ChatMessage::Tool {
+                    content,
+                    tool_call_id,
+                    parts,
+                    is_error,
+                } => {
+                    // Images returned by the tool are sent inside the toolResult
+                    let mut result = vec![json!({ "text": content })];
+                    result.extend(
+                        parts
+                            .into_iter()
+                            .enumerate()
+                            .filter_map(|(i, part)| Self::part_block(part, i))
+                            .filter(|block| block.get("document").is_none()),
+                    );
+                    let block = json!({
+                        "toolResult": {
+                            "toolUseId": tool_call_id,
+                            "content": result,
+                            "status": if is_error { "error" } else { "success" }
+                        }
+                    });
+                    Self::push_message(&mut messages, "user", vec![block]);
+                }
+            }
+        }
+
+        let mut tools: Vec<Value> = request
+            .tools
+            .unwrap_or_default()
+            .into_iter()
+            .map(|t| {
+                let mut spec = json!({
+                    "name": t.function.name,
+                    "inputSchema": {
+                        "json": t
+                            .function
+                            .parameters
+                            .unwrap_or_else(|| json!({"type": "object", "properties": {}}))
+                    }
+                });
+                if let Some(description) = t.function.description.filter(|d| !d.is_empty()) {
+                    spec["description"] = Value::from(description);
+                }
+                json!({ "toolSpec": spec })
+            })
+            .collect();
+
+        // Structured output: a tool taking the schema as input, forced so the reply is its input
+        let output_tool = request.response_format.map(|format| {
+            tools.push(json!({
+                "toolSpec": {
+                    "name": format.name,
+                    "description": "Respond with the final answer in this format",
+                    "inputSchema": { "json": format.schema }
+                }
+            }));
+            format.name
+        });
+
+        let tool_choice = if let Some(name) = &output_tool {
+            Some(json!({ "tool": { "name": name } }))
+        } else {
+            match request.tool_choice {
+                Some(ToolChoice::String(s)) if s == "auto" => Some(json!({ "auto": {} })),
+                Some(ToolChoice::String(s)) if s == "any" || s == "required" => {
+                    Some(json!({ "any": {} }))
+                }
+                Some(ToolChoice::Object { function, .. }) => {
+                    Some(json!({ "tool": { "name": function.name } }))
+                }
+                _ => None,
+            }
+        };
+
+        let mut inference_config = json!({});
+        if let Some(max_tokens) = request.max_tokens {
+            inference_config["maxTokens"] = Value::from(max_tokens);
+        }
+        if let Some(temperature) = request.temperature {
+            inference_config["temperature"] = Value::from(temperature);
+        }
+
+        let mut body = json!({ "messages": messages, "inferenceConfig": inference_config });
+        if !system.is_empty() {
+            body["system"] = Value::from(system);
+        }
+        if !tools.is_empty() {
+            body["toolConfig"] = json!({ "tools": tools });
+            if let Some(choice) = tool_choice {
+                body["toolConfig"]["toolChoice"] = choice;
+            }
+        }
+
+        (body, output_tool)
+    }
+
+    async fn error_response(
+        response: reqwest::Response,
+        chat_id: String,
+        message_id: String,
+        app: &AppHandle,
+    ) -> AppError {
+        let status = response.status();
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        let error_msg = format!("LLM API error ({status}): {error_text}");
+        let _ = MessageEmitter::new(app.clone()).emit_message_error(
+            chat_id,
+            message_id,
+            error_msg.clone(),
+        );
+        AppError::Llm(error_msg)
+    }
+
+    async fn handle_streaming(
+        &self,
+        req_builder: RequestBuilder,
+        chat_id: String,
+        message_id: String,
+        app: AppHandle,
+        mut cancellation_rx: Option<tokio::sync::broadcast::Receiver<()>>,
+        output_tool: Option<String>,
+    ) -> Result<LLMChatResponse, AppError> {
+        let response = retry::send(req_builder, cancellation_rx.as_mut()).await?;
+        if !response.status().is_success() {
+            return Err(Self::error_response(response, chat_id, message_id, &app).await);
+        }
+
+        let message_emitter = MessageEmitter::new(app.clone());
+        let tool_emitter = ToolEmitter::new(app.clone());
+
+        let result = Self::read_stream(
+            response,
+            output_tool.as_deref(),
+            cancellation_rx.as_mut(),
+            |event| match event {
+                StreamEvent::Text(text) => message_emitter.emit_message_chunk(
+                    chat_id.clone(),
+                    message_id.clone(),
+                    text.to_string(),
+                ),
+                StreamEvent::Thinking(thinking) => message_emitter.emit_thinking_chunk(
+                    chat_id.clone(),
+                    message_id.clone(),
+                    thinking.to_string(),
+                ),
+                StreamEvent::ToolCall(tool_call) => tool_emitter.emit_tool_calls_detected(
+                    chat_id.clone(),
+                    message_id.clone(),
+                    vec![EventToolCall {
+                        id: tool_call.id.clone(),
+                        name: tool_call.function.name.clone(),
+                        arguments: serde_json::from_str(&tool_call.function.arguments)
+                            .unwrap_or_else(|_| json!({})),
+                    }],
+                ),
+            },
+        )
+        .await;
+
+        let response = match result {
+            Ok(response) => response,
+            Err(AppError::Cancelled) => {
+                let _ = message_emitter.emit_message_error(chat_id, message_id, "Cancelled".into());
+                return Err(AppError::Cancelled);
+            }
+            Err(AppError::Llm(error_msg)) => {
+                message_emitter.emit_message_error(chat_id, message_id, error_msg.clone())?;
+                return Err(AppError::Llm(error_msg));
+            }
+            Err(e) => return Err(e),
+        };
+
+        let usage = response.usage.clone().unwrap_or_default();
+        message_emitter.emit_message_complete(
+            chat_id,
+            message_id,
+            response.content.clone(),
+            Some(EventTokenUsage {
+                prompt_tokens: usage.prompt_tokens,
+                completion_tokens: usage.completion_tokens,
+                total_tokens: usage.total_tokens,
+            }),
+        )?;
+        Ok(response)
+    }
+
+    /// Collect a `ConverseStream` response, handing output to `on_event` as it arrives
+    async fn read_stream(
+        response: reqwest::Response,
+        output_tool: Option<&str>,
+        mut cancellation_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        mut on_event: impl FnMut(StreamEvent<'_>) -> Result<(), AppError> + Send,
+    ) -> Result<LLMChatResponse, AppError> {
+        let mut stream = response.bytes_stream();
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut full_content = String::new();
+        let mut full_thinking = String::new();
+        let mut usage = BedrockUsage::default();
+        let mut finish_reason = None;
+        let mut tool_calls = Vec::new();
+        let mut pending_tool: Option<PendingToolUse> = None;
+        let mut in_output_tool = false; // Structured output arrives as the forced tool's input
+
+        while let Some(item) = tokio::select! {
+            next = stream.next() => next,
+             () = async {
+                if let Some(rx) = cancellation_rx.as_mut() {
+                    let _ = rx.recv().await;
+                }
+                futures::future::pending::<()>().await;
+            }, if cancellation_rx.is_some() => {
+                return Err(AppError::Cancelled);
+            }
+        } {
+            let chunk = item.map_err(|e| AppError::Generic(format!("Stream error: {e}")))?;
+            buffer.extend_from_slice(&chunk);
+
+            while let Some((message, consumed)) = EventStreamMessage::decode(&buffer)? {
+                buffer.drain(..consumed);
+                let payload: Value = serde_json::from_slice(&message.payload).unwrap_or_default();
+
+                if message.header(":message-type") != "event" {
+                    return Err(AppError::Llm(format!(
+                        "LLM API error ({}): {}",
+                        message.header(":exception-type"),
+                        payload["message"].as_str().unwrap_or("Unknown error")
+                    )));
+                }
+
+                match message.header(":event-type") {
+                    "contentBlockStart" => {
+                        let tool_use = &payload["start"]["toolUse"];
+                        if let Some(name) = tool_use["name"].as_str() {
+                            if Some(name) == output_tool {
+                                in_output_tool = true;
+                            } else {
+                                pending_tool = Some(PendingToolUse {
+                                    id: tool_use["toolUseId"]
+                                        .as_str()
+                                        .unwrap_or_default()
+                                        .to_string(),
+                                    name: name.to_string(),
+                                    input: String::new(),
+                                });
+                            }
+                        }
+                    }
+                    "contentBlockDelta" => {
+                        let delta = &payload["delta"];
+                        if let Some(text) = delta["text"].as_str() {
+                            full_content.push_str(text);
+                            on_event(StreamEvent::Text(text))?;
+                        } else if let Some(input) = delta["toolUse"]["input"].as_str() {
+                            if in_output_tool {
+                                full_content.push_str(input);
+                                on_event(StreamEvent::Text(input))?;
+                            } else if let Some(tool) = pending_tool.as_mut() {
+                                tool.input.push_str(input);
+                            }
+                        } else if let Some(thinking) = delta["reasoningContent"]["text"].as_str() {
+                            full_thinking.push_str(thinking);
+                            on_event(StreamEvent::Thinking(thinking))?;
+                        }
+                    }
+                    "contentBlockStop" => {
+                        in_output_tool = false;
+                        if let Some(tool) = pending_tool.take() {
+                            let arguments = if tool.input.is_empty() {
+                                json!({})
+                            } else {
+                                serde_json::from_str(&tool.input).unwrap_or_else(|_| json!({}))
+                            };
+                            let tool_call = ToolCall {
+                                id: tool.id,
+                                r#type: "function".to_string(),
+                                function: ToolCallFunction {
+                                    name: tool.name,
+                                    arguments: arguments.to_string(),
+                                },
+                            };
+                            on_event(StreamEvent::ToolCall(&tool_call))?;
+                            tool_calls.push(tool_call);
+                        }
+                    }
+                    "messageStop" => {
+                        finish_reason = payload["stopReason"].as_str().map(String::from);
+                    }
+                    "metadata" => {
+                        if let Ok(u) = BedrockUsage::deserialize(&payload["usage"]) {
+                            usage = u;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(LLMChatResponse {
+            content: full_content,
+            finish_reason,
+            tool_calls: if tool_calls.is_empty() {
+                None
+            } else {
+                Some(tool_calls)
+            },
+            usage: Some(usage.token_usage()),
+            reasoning: if full_thinking.is_empty() {
+                None
+            } else {
+                Some(full_thinking)
+            },
+            images: None,
+            reasoning_items: None,
+        })
+    }
+
+    async fn handle_non_streaming(
+        &self,
+        req_builder: RequestBuilder,
+        chat_id: String,
+        message_id: String,
+        app: AppHandle,
+        output_tool: Option<String>,
+    ) -> Result<LLMChatResponse, AppError> {
+        let response = retry::send(req_builder, None).await?;
+        if !response.status().is_success() {
+            return Err(Self::error_response(response, chat_id, message_id, &app).await);
+        }
+
+        let json_response: Value = response
+            .json()
+            .await
+            .map_err(|e| AppError::Generic(format!("Failed to parse response: {e}")))?;
+
+        let mut content_str = String::new();
+        let mut thinking_str = String::new();
+        let mut tool_calls = Vec::new();
+
+        let blocks = json_response["output"]["message"]["content"].as_array();
+        for block in blocks.into_iter().flatten() {
+            if let Some(text) = block["text"].as_str() {
+                content_str.push_str(text);
+            } else if let Some(thinking) =
+                block["reasoningContent"]["reasoningText"]["text"].as_str()
+            {
+                thinking_str.push_str(thinking);
+            } else if let Some(tool_use) = block.get("toolUse") {
+                let name = tool_use["name"].as_str().unwrap_or_default();
+                if Some(name) == output_tool.as_deref() {
+                    content_str.push_str(&tool_use["input"].to_string());
+                } else {
+                    tool_calls.push(ToolCall {
+                        id: tool_use["toolUseId"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string(),
+                        r#type: "function".to_string(),
+                        function: ToolCallFunction {
+                            name: name.to_string(),
+                            arguments: tool_use["input"].to_string(),
+                        },
+                    });
+                }
+            }
+        }
+
+        if !tool_calls.is_empty() {
+            let event_tool_calls: Vec<EventToolCall> = tool_calls
+                .iter()
+                .map(|tc| EventToolCall {
+                    id: tc.id.clone(),
+                    name: tc.function.name.clone(),
+                    arguments: serde_json::from_str(&tc.function.arguments)
+                        .unwrap_or_else(|_| json!({})),
+                })
+                .collect();
+            ToolEmitter::new(app.clone()).emit_tool_calls_detected(
+                chat_id.clone(),
+                message_id.clone(),
+                event_tool_calls,
+            )?;
+        }
+
+        let token_usage = BedrockUsage::deserialize(&json_response["usage"])
+            .unwrap_or_default()
+            .token_usage();
+        MessageEmitter::new(app).emit_message_complete(
+            chat_id,
+            message_id,
+            content_str.clone(),
+            Some(EventTokenUsage {
+                prompt_tokens: token_usage.prompt_tokens,
+                completion_tokens: token_usage.completion_tokens,
+                total_tokens: token_usage.total_tokens,
+            }),
+        )?;
+
+        Ok(LLMChatResponse {
+            content: content_str,
+            finish_reason: json_response["stopReason"].as_str().map(String::from),
+            tool_calls: if tool_calls.is_empty() {
+                None
+            } else {
+                Some(tool_calls)
+            },
+            usage: Some(token_usage),
+            reasoning: if thinking_str.is_empty() {
+                None
+            } else {
+                Some(thinking_str)
+            },
+            images: None,
+            reasoning_items: None,
+        })
+    }
+}
+
+#[async_trait]
+impl LLMProvider for BedrockProvider {
+    async fn fetch_models(
+        &self,
+        base_url: &str,
+        api_key: Option<&str>,
+    ) -> Result<Vec<LLMModel>, AppError> {
+        let config = BedrockConfig::resolve(base_url, api_key)?;
+        let response = self
+            .signed_request(&config, Method::GET, config.foundation_models_url()?, None)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(AppError::Llm(format!(
+                "Bedrock API error ({status}): {error_text}"
+            )));
+        }
+
+        let json: Value = response.json().await?;
+        let mut models = Vec::new();
+        for item in json["modelSummaries"].as_array().into_iter().flatten() {
+            let Some(id) = item["modelId"].as_str() else {
+                continue;
+            };
+            if item["modelLifecycle"]["status"]
+                .as_str()
+                .is_some_and(|s| s != "ACTIVE")
+            {
+                continue;
+            }
+            let inference_types: Vec<&str> = item["inferenceTypesSupported"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .collect();
+
+            // Newer models are only served through cross-region inference profiles
+            let model_id = if inference_types.is_empty() || inference_types.contains(&"ON_DEMAND") {
+                id.to_string()
+            } else if inference_types.contains(&"INFERENCE_PROFILE") {
+                match config.geo_prefix() {
+                    Some(geo) => format!("{geo}.{id}"),
+                    None => continue,
+                }
+            } else {
+                continue;
+            };
+
+            let (supports_tools, supports_thinking, supports_image_generation) =
+                Self::check_model_capabilities(id);
+            let name = item["modelName"].as_str().unwrap_or(id);
+            models.push(LLMModel {
+                name: item["providerName"]
+                    .as_str()
+                    .map_or_else(|| name.to_string(), |provider| format!("{provider} {name}")),
+                id: model_id,
+                created: None,
+                owned_by: Some("bedrock".to_string()),
+                supports_tools,
+                supports_thinking,
+                supports_image_generation,
+                context_length: None,
+            });
+        }
+
+        Ok(models)
+    }
+
+    async fn chat(
+        &self,
+        base_url: &str,
+        api_key: Option<&str>,
+        request: LLMChatRequest,
+        chat_id: String,
+        message_id: String,
+        app: AppHandle,
+        cancellation_rx: Option<tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<LLMChatResponse, AppError> {
+        let config = BedrockConfig::resolve(base_url, api_key)?;
+        let stream = request.stream;
+        let url = config.converse_url(&request.model, stream)?;
+        let (body, output_tool) = Self::build_body(request);
+        let req_builder =
+            self.signed_request(&config, Method::POST, url, Some(serde_json::to_vec(&body)?));
+
+        if stream {
+            self.handle_streaming(
+                req_builder.header("Accept", "application/vnd.amazon.eventstream"),
+                chat_id,
+                message_id,
+                app,
+                cancellation_rx,
+                output_tool,
+            )
+            .await
+        } else {
+            self.handle_non_streaming(
+                req_builder.header("Accept", "application/json"),
+                chat_id,
+                message_id,
+                app,
+                output_tool,
+            )
+            .await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
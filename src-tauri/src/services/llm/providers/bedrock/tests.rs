@@ -0,0 +1,385 @@
+use super::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+// Credentials and scope of the AWS SigV4 test suite
+const TEST_ACCESS_KEY_ID: &str = "AKIDEXAMPLE";
+const TEST_SECRET_ACCESS_KEY: &str = "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY";
+const TEST_SESSION_TOKEN: &str = "AQoDYXdzEPT//////////wEXAMPLEtc764bNrC9SAPBSM22wDOk4x4HIZ8j4FZTwdQWLWsKWHGBuFqwAeMicRXmxfpSPfIeoIYRqTflfKD8YUuwthAx7mSEI/qkPpKPi/kMcGdQrmGdeehM4IC1NtBmUpp2wUE8phUZampKsburEDy0KPkyQDYwT7WZ0wq5VSXDvp75YU9HFvlRd8Tx6q6fE8YQcHNVXAkiY9q6d+xo0rKwT38xVqr7ZD0u0iPPkUL64lIZbqBAz+scqKmlzm8FDrypNC9Yjc8fPOLn9FX9KSYvKTr4rvx3iSIlTJabIQwj2ICCR/oLxBA==";
+
+fn test_suite_authorization(method: &Method, url: &str, session_token: Option<&str>) -> String {
+    let credentials = Credentials {
+        access_key_id: TEST_ACCESS_KEY_ID.to_string(),
+        secret_access_key: TEST_SECRET_ACCESS_KEY.to_string(),
+        session_token: session_token.map(String::from),
+    };
+    let scope = SigningScope {
+        time: chrono::DateTime::parse_from_rfc3339("2015-08-30T12:36:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc),
+        region: "us-east-1",
+        service: "service",
+    };
+    let payload_hash = hex::encode(Sha256::digest(b""));
+    let headers = credentials.sign_at(
+        &scope,
+        method,
+        &Url::parse(url).unwrap(),
+        &payload_hash,
+        Vec::new(),
+    );
+    headers
+        .into_iter()
+        .find(|(name, _)| name == "authorization")
+        .map(|(_, value)| value)
+        .unwrap()
+}
+
+#[test]
+fn signs_get_vanilla() {
+    assert_eq!(
+        test_suite_authorization(&Method::GET, "https://example.amazonaws.com/", None),
+        "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+    );
+}
+
+#[test]
+fn signs_get_vanilla_query_order_key_case() {
+    assert_eq!(
+        test_suite_authorization(
+            &Method::GET,
+            "https://example.amazonaws.com/?Param2=value2&Param1=value1",
+            None
+        ),
+        "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500"
+    );
+}
+
+#[test]
+fn signs_post_vanilla() {
+    assert_eq!(
+        test_suite_authorization(&Method::POST, "https://example.amazonaws.com/", None),
+        "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=5da7c1a2acd57cee7505fc6676e4e544621c30862966e37dddb68e92efbe5d6b"
+    );
+}
+
+#[test]
+fn signs_post_sts_header_after() {
+    assert_eq!(
+        test_suite_authorization(
+            &Method::POST,
+            "https://example.amazonaws.com/",
+            Some(TEST_SESSION_TOKEN)
+        ),
+        "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date;x-amz-security-token, Signature=85d96828115b5dc0cfc3bd16ad9e210dd772bbebba041836c64533a82be05ead"
+    );
+}
+
+#[test]
+fn crc32_matches_the_ieee_check_value() {
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+}
+
+/// Encode an event-stream message with string headers
+fn frame(headers: &[(&str, &str)], payload: &[u8]) -> Vec<u8> {
+    let mut raw_headers = Vec::new();
+    for (name, value) in headers {
+        raw_headers.push(u8::try_from(name.len()).unwrap());
+        raw_headers.extend_from_slice(name.as_bytes());
+        raw_headers.push(7);
+        raw_headers.extend_from_slice(&u16::try_from(value.len()).unwrap().to_be_bytes());
+        raw_headers.extend_from_slice(value.as_bytes());
+    }
+    let total_len = u32::try_from(16 + raw_headers.len() + payload.len()).unwrap();
+    let headers_len = u32::try_from(raw_headers.len()).unwrap();
+
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&total_len.to_be_bytes());
+    buf.extend_from_slice(&headers_len.to_be_bytes());
+    buf.extend_from_slice(&crc32(&buf).to_be_bytes());
+    buf.extend_from_slice(&raw_headers);
+    buf.extend_from_slice(payload);
+    buf.extend_from_slice(&crc32(&buf).to_be_bytes());
+    buf
+}
+
+fn event(event_type: &str, payload: &Value) -> Vec<u8> {
+    frame(
+        &[
+            (":event-type", event_type),
+            (":content-type", "application/json"),
+            (":message-type", "event"),
+        ],
+        payload.to_string().as_bytes(),
+    )
+}
+
+#[test]
+fn decodes_a_message_and_reports_its_length() {
+    let mut buf = event("messageStart", &json!({ "role": "assistant" }));
+    let first_len = buf.len();
+    buf.extend(event("messageStop", &json!({ "stopReason": "end_turn" })));
+
+    let (message, consumed) = EventStreamMessage::decode(&buf).unwrap().unwrap();
+    assert_eq!(consumed, first_len);
+    assert_eq!(message.header(":event-type"), "messageStart");
+    assert_eq!(message.header(":message-type"), "event");
+    assert_eq!(message.header(":missing"), "");
+    assert_eq!(message.payload, br#"{"role":"assistant"}"#);
+
+    let (message, consumed) = EventStreamMessage::decode(&buf[first_len..])
+        .unwrap()
+        .unwrap();
+    assert_eq!(consumed, buf.len() - first_len);
+    assert_eq!(message.header(":event-type"), "messageStop");
+}
+
+#[test]
+fn waits_for_partial_frames() {
+    let buf = event(
+        "contentBlockDelta",
+        &json!({ "delta": { "text": "Hello" } }),
+    );
+    for len in [0, 4, 11, 12, 20, buf.len() - 1] {
+        assert!(
+            EventStreamMessage::decode(&buf[..len]).unwrap().is_none(),
+            "decoded {len} of {} bytes",
+            buf.len()
+        );
+    }
+    assert!(EventStreamMessage::decode(&buf).unwrap().is_some());
+}
+
+#[test]
+fn rejects_a_prelude_crc_mismatch() {
+    let mut buf = event("messageStart", &json!({ "role": "assistant" }));
+    buf[8] ^= 0xFF;
+    assert!(EventStreamMessage::decode(&buf).is_err());
+    // Detected from the prelude alone, before the rest has arrived
+    assert!(EventStreamMessage::decode(&buf[..12]).is_err());
+}
+
+#[test]
+fn rejects_a_corrupt_prelude() {
+    let mut buf = event("messageStart", &json!({ "role": "assistant" }));
+    buf[3] ^= 0x01;
+    assert!(EventStreamMessage::decode(&buf).is_err());
+}
+
+#[test]
+fn rejects_a_message_crc_mismatch() {
+    let mut buf = event("messageStart", &json!({ "role": "assistant" }));
+    let payload_byte = buf.len() - 6;
+    buf[payload_byte] ^= 0xFF;
+    assert!(EventStreamMessage::decode(&buf).is_err());
+}
+
+/// Serve one HTTP response on a local port, the raw request comes back through the handle
+async fn serve_once(
+    content_type: &'static str,
+    body: Vec<u8>,
+) -> (String, tokio::task::JoinHandle<String>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let read = socket.read(&mut chunk).await.unwrap();
+            if read == 0 {
+                break;
+            }
+            request.extend_from_slice(&chunk[..read]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some(head_end) = text.find("\r\n\r\n") {
+                let content_length = text[..head_end]
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if request.len() >= head_end + 4 + content_length {
+                    break;
+                }
+            }
+        }
+
+        let head = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: {content_type}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+            body.len()
+        );
+        socket.write_all(head.as_bytes()).await.unwrap();
+        socket.write_all(&body).await.unwrap();
+        socket.shutdown().await.unwrap();
+        String::from_utf8_lossy(&request).to_string()
+    });
+
+    (format!("http://{addr}?region=us-west-2"), server)
+}
+
+async fn converse_stream(
+    endpoint: &str,
+    output_tool: Option<&str>,
+) -> (Result<LLMChatResponse, AppError>, Vec<String>) {
+    let provider = BedrockProvider::new(Arc::new(Client::new()));
+    let config = BedrockConfig::resolve(endpoint, Some("AKIDSTUB:stub-secret")).unwrap();
+    let url = config
+        .converse_url("anthropic.claude-3-haiku", true)
+        .unwrap();
+    let body = serde_json::to_vec(&json!({ "messages": [] })).unwrap();
+    let response = provider
+        .signed_request(&config, Method::POST, url, Some(body))
+        .header("Accept", "application/vnd.amazon.eventstream")
+        .send()
+        .await
+        .unwrap();
+
+    let mut events = Vec::new();
+    let result = BedrockProvider::read_stream(response, output_tool, None, |event| {
+        events.push(match event {
+            StreamEvent::Text(text) => format!("text:{text}"),
+            StreamEvent::Thinking(thinking) => format!("thinking:{thinking}"),
+            StreamEvent::ToolCall(call) => {
+                format!("tool:{}:{}", call.function.name, call.function.arguments)
+            }
+        });
+        Ok(())
+    })
+    .await;
+    (result, events)
+}
+
+#[tokio::test]
+async fn streams_text_reasoning_and_tool_calls_from_a_stub_server() {
+    let mut body = Vec::new();
+    for (event_type, payload) in [
+        ("messageStart", json!({ "role": "assistant" })),
+        (
+            "contentBlockDelta",
+            json!({ "contentBlockIndex": 0, "delta": { "reasoningContent": { "text": "Weather?" } } }),
+        ),
+        (
+            "contentBlockDelta",
+            json!({ "contentBlockIndex": 1, "delta": { "text": "Let me " } }),
+        ),
+        (
+            "contentBlockDelta",
+            json!({ "contentBlockIndex": 1, "delta": { "text": "check." } }),
+        ),
+        ("contentBlockStop", json!({ "contentBlockIndex": 1 })),
+        (
+            "contentBlockStart",
+            json!({ "contentBlockIndex": 2, "start": { "toolUse": { "toolUseId": "tooluse_1", "name": "get_weather" } } }),
+        ),
+        (
+            "contentBlockDelta",
+            json!({ "contentBlockIndex": 2, "delta": { "toolUse": { "input": "{\"city\":" } } }),
+        ),
+        (
+            "contentBlockDelta",
+            json!({ "contentBlockIndex": 2, "delta": { "toolUse": { "input": "\"Paris\"}" } } }),
+        ),
+        ("contentBlockStop", json!({ "contentBlockIndex": 2 })),
+        ("messageStop", json!({ "stopReason": "tool_use" })),
+        (
+            "metadata",
+            json!({ "usage": { "inputTokens": 12, "outputTokens": 7, "cacheReadInputTokens": 3 } }),
+        ),
+    ] {
+        body.extend(event(event_type, &payload));
+    }
+
+    let (endpoint, server) = serve_once("application/vnd.amazon.eventstream", body).await;
+    let (result, events) = converse_stream(&endpoint, None).await;
+    let response = result.unwrap();
+
+    assert_eq!(
+        events,
+        [
+            "thinking:Weather?",
+            "text:Let me ",
+            "text:check.",
+            r#"tool:get_weather:{"city":"Paris"}"#,
+        ]
+    );
+    assert_eq!(response.content, "Let me check.");
+    assert_eq!(response.reasoning.as_deref(), Some("Weather?"));
+    assert_eq!(response.finish_reason.as_deref(), Some("tool_use"));
+    let tool_calls = response.tool_calls.unwrap();
+    assert_eq!(tool_calls.len(), 1);
+    assert_eq!(tool_calls[0].id, "tooluse_1");
+    let usage = response.usage.unwrap();
+    assert_eq!(usage.prompt_tokens, Some(15));
+    assert_eq!(usage.completion_tokens, Some(7));
+
+    let request = server.await.unwrap();
+    assert!(request.starts_with("POST /model/anthropic.claude-3-haiku/converse-stream "));
+    let request = request.to_lowercase();
+    assert!(request.contains("authorization: aws4-hmac-sha256 credential=akidstub/"));
+    assert!(request.contains("/us-west-2/bedrock/aws4_request"));
+    assert!(request.contains("x-amz-content-sha256: "));
+}
+
+#[tokio::test]
+async fn streams_structured_output_as_text() {
+    let mut body = Vec::new();
+    for (event_type, payload) in [
+        (
+            "contentBlockStart",
+            json!({ "start": { "toolUse": { "toolUseId": "tooluse_2", "name": "answer" } } }),
+        ),
+        (
+            "contentBlockDelta",
+            json!({ "delta": { "toolUse": { "input": "{\"ok\":true}" } } }),
+        ),
+        ("contentBlockStop", json!({})),
+        ("messageStop", json!({ "stopReason": "tool_use" })),
+    ] {
+        body.extend(event(event_type, &payload));
+    }
+
+    let (endpoint, server) = serve_once("application/vnd.amazon.eventstream", body).await;
+    let (result, events) = converse_stream(&endpoint, Some("answer")).await;
+    let response = result.unwrap();
+
+    assert_eq!(events, [r#"text:{"ok":true}"#]);
+    assert_eq!(response.content, r#"{"ok":true}"#);
+    assert!(response.tool_calls.is_none());
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn returns_stream_exceptions_as_errors() {
+    let mut body = event(
+        "contentBlockDelta",
+        &json!({ "delta": { "text": "Partial" } }),
+    );
+    body.extend(frame(
+        &[
+            (":exception-type", "throttlingException"),
+            (":content-type", "application/json"),
+            (":message-type", "exception"),
+        ],
+        json!({ "message": "Too many requests" })
+            .to_string()
+            .as_bytes(),
+    ));
+
+    let (endpoint, server) = serve_once("application/vnd.amazon.eventstream", body).await;
+    let (result, events) = converse_stream(&endpoint, None).await;
+
+    assert_eq!(events, ["text:Partial"]);
+    match result {
+        Err(AppError::Llm(message)) => {
+            assert_eq!(
+                message,
+                "LLM API error (throttlingException): Too many requests"
+            );
+        }
+        other => panic!("expected a stream error, got {other:?}"),
+    }
+    server.await.unwrap();
+}
@@ -5,11 +5,13 @@ use crate::models::llm_types::{
     AssistantContent, ChatMessage, ContentPart, InlineData, LLMChatRequest, LLMChatResponse,
     LLMModel, TokenUsage, ToolCall, ToolCallFunction, UserContent,
 };
+use crate::services::llm::retry;
 use async_trait::async_trait;
 use base64::Engine as _;
 use futures::StreamExt;
 use reqwest::Client;
 use serde_json::json;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::AppHandle;
 
@@ -307,6 +309,7 @@ impl GoogleProvider {
                 supports_tools: true,
                 supports_thinking: false,
                 supports_image_generation: false,
+                context_length: None,
             },
             LLMModel {
                 id: "gemini-1.5-flash".to_string(),
@@ -316,6 +319,7 @@ impl GoogleProvider {
                 supports_tools: true,
                 supports_thinking: false,
                 supports_image_generation: false,
+                context_length: None,
             },
             LLMModel {
                 id: "gemini-pro".to_string(),
@@ -325,6 +329,7 @@ impl GoogleProvider {
                 supports_tools: true,
                 supports_thinking: false,
                 supports_image_generation: false,
+                context_length: None,
             },
             LLMModel {
                 id: "gemini-2.5-flash-image".to_string(),
@@ -334,6 +339,7 @@ impl GoogleProvider {
                 supports_tools: false,
                 supports_thinking: false,
                 supports_image_generation: true,
+                context_length: None,
             },
             LLMModel {
                 id: "gemini-3-pro-image-preview".to_string(),
@@ -343,6 +349,7 @@ impl GoogleProvider {
                 supports_tools: false,
                 supports_thinking: false,
                 supports_image_generation: true,
+                context_length: None,
             },
         ]
     }
@@ -355,7 +362,7 @@ impl GoogleProvider {
         app: AppHandle,
         mut cancellation_rx: Option<tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<LLMChatResponse, AppError> {
-        let response = req_builder.send().await?;
+        let response = retry::send(req_builder, cancellation_rx.as_mut()).await?;
         let message_emitter = MessageEmitter::new(app.clone());
 
         if !response.status().is_success() {
@@ -542,20 +549,7 @@ impl GoogleProvider {
 
                         // Parse usage
                         if let Some(usage) = json_val.get("usageMetadata") {
-                            final_usage = Some(TokenUsage {
-                                prompt_tokens: usage
-                                    .get("promptTokenCount")
-                                    .and_then(serde_json::Value::as_u64)
-                                    .map(|v| v as u32),
-                                completion_tokens: usage
-                                    .get("candidatesTokenCount")
-                                    .and_then(serde_json::Value::as_u64)
-                                    .map(|v| v as u32),
-                                total_tokens: usage
-                                    .get("totalTokenCount")
-                                    .and_then(serde_json::Value::as_u64)
-                                    .map(|v| v as u32),
-                            });
+                            final_usage = Some(TokenUsage::from_gemini(usage));
                         }
                     }
 
@@ -600,6 +594,7 @@ impl GoogleProvider {
             } else {
                 Some(final_images)
             },
+            reasoning_items: None,
         })
     }
 
@@ -610,10 +605,7 @@ impl GoogleProvider {
         message_id: String,
         app: AppHandle,
     ) -> Result<LLMChatResponse, AppError> {
-        let response = req_builder
-            .send()
-            .await
-            .map_err(|e| AppError::Generic(format!("HTTP request failed: {e}")))?;
+        let response = retry::send(req_builder, None).await?;
 
         let message_emitter = MessageEmitter::new(app.clone());
 
@@ -704,20 +696,7 @@ impl GoogleProvider {
         }
 
         // Parse usage
-        let usage = json.get("usageMetadata").map(|u| TokenUsage {
-            prompt_tokens: u
-                .get("promptTokenCount")
-                .and_then(serde_json::Value::as_u64)
-                .map(|v| v as u32),
-            completion_tokens: u
-                .get("candidatesTokenCount")
-                .and_then(serde_json::Value::as_u64)
-                .map(|v| v as u32),
-            total_tokens: u
-                .get("totalTokenCount")
-                .and_then(serde_json::Value::as_u64)
-                .map(|v| v as u32),
-        });
+        let usage = json.get("usageMetadata").map(TokenUsage::from_gemini);
 
         message_emitter.emit_message_complete(
             chat_id.clone(),
@@ -750,6 +729,7 @@ impl GoogleProvider {
             } else {
                 Some(images)
             },
+            reasoning_items: None,
         })
     }
 }
@@ -803,6 +783,10 @@ impl LLMProvider for GoogleProvider {
                                 // Check model capabilities
                                 let (supports_tools, supports_thinking, supports_image_generation) =
                                     Self::check_model_capabilities(&clean_id);
+                                let context_length = m
+                                    .get("inputTokenLimit")
+                                    .and_then(serde_json::Value::as_u64)
+                                    .and_then(|limit| usize::try_from(limit).ok());
 
                                 Some(LLMModel {
                                     id: clean_id,
@@ -812,6 +796,7 @@ impl LLMProvider for GoogleProvider {
                                     supports_tools,
                                     supports_thinking,
                                     supports_image_generation,
+                                    context_length,
                                 })
                             })
                             .collect();
@@ -860,6 +845,7 @@ impl LLMProvider for GoogleProvider {
         // Map request to Google format
         let mut contents = Vec::new();
         let mut system_instruction = None;
+        let mut tool_call_names: HashMap<String, String> = HashMap::new();
 
         for msg in request.messages {
             match msg {
@@ -1070,6 +1056,44 @@ impl LLMProvider for GoogleProvider {
                         }
                     }
                 }
+                ChatMessage::Assistant {
+                    content,
+                    tool_calls: Some(tool_calls),
+                    ..
+                } if !tool_calls.is_empty() => {
+                    // Tool calls are replayed as functionCall parts, remembered by id so the
+                    // matching tool results can name their function
+                    let mut google_parts = Vec::new();
+                    match content {
+                        AssistantContent::Text(text) if !text.is_empty() => {
+                            google_parts.push(json!({ "text": text }));
+                        }
+                        AssistantContent::Text(_) => {}
+                        AssistantContent::Parts(parts) => {
+                            for part in parts {
+                                if let ContentPart::Text { text } = part {
+                                    google_parts.push(json!({ "text": text }));
+                                }
+                            }
+                        }
+                    }
+                    for tool_call in tool_calls {
+                        let args: serde_json::Value =
+                            serde_json::from_str(&tool_call.function.arguments)
+                                .unwrap_or_else(|_| json!({}));
+                        google_parts.push(json!({
+                            "functionCall": {
+                                "name": tool_call.function.name,
+                                "args": args
+                            }
+                        }));
+                        tool_call_names.insert(tool_call.id, tool_call.function.name);
+                    }
+                    contents.push(json!({
+                        "role": "model",
+                        "parts": google_parts
+                    }));
+                }
                 ChatMessage::Assistant { content, .. } => {
                     match content {
                         AssistantContent::Text(text) => {
@@ -1103,7 +1127,74 @@ impl LLMProvider for GoogleProvider {
                         }
                     }
                 }
-                _ => {} // Tool messages not yet fully supported in this simple implementation
+                ChatMessage::Tool {
+                    content,
+                    tool_call_id,
+                    parts,
+                    is_error,
+                } => {
+                    // Results of calls not replayed above have no function to answer
+                    let Some(name) = tool_call_names.get(&tool_call_id) else {
+                        continue;
+                    };
+
+                    let output: serde_json::Value =
+                        serde_json::from_str(&content).unwrap_or_else(|_| json!(content));
+                    let response = if is_error {
+                        json!({ "error": output })
+                    } else {
+                        json!({ "output": output })
+                    };
+
+                    let mut google_parts = vec![json!({
+                        "functionResponse": {
+                            "name": name,
+                            "response": response
+                        }
+                    })];
+
+                    // Images and audio returned by the tool are sent as inline data
+                    for part in parts {
+                        let (mime_type, url) = match part {
+                            ContentPart::ImageUrl { image_url } => (None, image_url.url),
+                            ContentPart::FileUrl { file_url } => {
+                                (Some(file_url.mime_type), file_url.url)
+                            }
+                            ContentPart::InlineData { inline_data } => {
+                                google_parts.push(json!({
+                                    "inlineData": {
+                                        "mimeType": inline_data.mime_type,
+                                        "data": inline_data.data
+                                    }
+                                }));
+                                continue;
+                            }
+                            ContentPart::Text { .. } => continue,
+                        };
+
+                        let Some((meta, data)) = url.split_once(',') else {
+                            continue;
+                        };
+                        let mime_type = mime_type.unwrap_or_else(|| {
+                            meta.trim_start_matches("data:")
+                                .split(';')
+                                .next()
+                                .unwrap_or_default()
+                                .to_string()
+                        });
+                        google_parts.push(json!({
+                            "inlineData": {
+                                "mimeType": mime_type,
+                                "data": data
+                            }
+                        }));
+                    }
+
+                    contents.push(json!({
+                        "role": "user",
+                        "parts": google_parts
+                    }));
+                }
             }
         }
 
@@ -1147,6 +1238,12 @@ impl LLMProvider for GoogleProvider {
             }
         }
 
+        // Structured output
+        if let Some(format) = request.response_format.as_ref() {
+            gen_config["responseMimeType"] = json!("application/json");
+            gen_config["responseSchema"] = Self::clean_parameters_for_google(&format.schema);
+        }
+
         let mut body = json!({
             "contents": contents,
             "generationConfig": gen_config
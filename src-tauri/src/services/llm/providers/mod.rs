@@ -1,4 +1,6 @@
 pub mod anthropic;
+pub mod azure;
+pub mod bedrock;
 pub mod google;
 pub mod openai;
 pub mod openai_compat;
@@ -9,6 +11,8 @@ use async_trait::async_trait;
 use tauri::AppHandle;
 
 pub use anthropic::AnthropicProvider;
+pub use azure::AzureOpenAIProvider;
+pub use bedrock::BedrockProvider;
 pub use google::GoogleProvider;
 pub use openai::OpenAIProvider;
 pub use openai_compat::OpenAICompatProvider;
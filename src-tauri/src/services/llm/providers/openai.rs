@@ -1,10 +1,19 @@
-use super::LLMProvider;
+//! OpenAI through the Responses API or Chat Completions
+//!
+//! Connections use Chat Completions, handled by `OpenAICompatProvider`, unless their API mode
+//! is set to `responses`. Existing connections have no API mode and keep Chat Completions.
+//!
+//! Responses API requests are stateless (`store: false`): reasoning items come back with their
+//! encrypted content and are passed back with the tool results of the turn that produced them.
+
+use super::{LLMProvider, OpenAICompatProvider};
 use crate::error::AppError;
 use crate::events::{MessageEmitter, TokenUsage as EventTokenUsage, ToolEmitter};
 use crate::models::llm_types::{
-    AssistantContent, ChatMessage, ContentPart, LLMChatRequest, LLMChatResponse, LLMModel,
-    TokenUsage, ToolCall, ToolCallFunction, UserContent,
+    AssistantContent, ChatCompletionTool, ChatMessage, ContentPart, LLMChatRequest,
+    LLMChatResponse, LLMModel, TokenUsage, ToolCall, ToolCallFunction, ToolChoice, UserContent,
 };
+use crate::services::llm::retry;
 use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest::Client;
@@ -12,16 +21,25 @@ use serde_json::json;
 use std::sync::Arc;
 use tauri::AppHandle;
 
+/// `api_mode` of an OpenAI connection that opted into the Responses API
+pub const RESPONSES_API_MODE: &str = "responses";
+
 pub struct OpenAIProvider {
     client: Arc<Client>,
+    compat: OpenAICompatProvider,
+    chat_completions: bool, // Send to Chat Completions instead of the Responses API
 }
 
 impl OpenAIProvider {
-    pub const fn new(client: Arc<Client>) -> Self {
-        Self { client }
+    pub fn new(client: Arc<Client>, api_mode: Option<&str>) -> Self {
+        Self {
+            compat: OpenAICompatProvider::new(client.clone()),
+            client,
+            chat_completions: api_mode != Some(RESPONSES_API_MODE),
+        }
     }
 
-    fn check_model_capabilities(model_id: &str) -> (bool, bool, bool) {
+    pub(super) fn check_model_capabilities(model_id: &str) -> (bool, bool, bool) {
         // Remove provider prefix if exists (e.g., "openai/gpt-4" -> "gpt-4")
         let clean_id = model_id.split('/').next_back().unwrap_or(model_id);
         let model_lower = clean_id.to_lowercase();
@@ -61,10 +79,13 @@ impl OpenAIProvider {
     }
 
     /// Transform messages to the new generalized 'input' format for Responses API
+    ///
+    /// Assistant turns expand into their reasoning items, the message and one `function_call`
+    /// item per tool call, tool results become `function_call_output` items.
     fn transform_messages_to_input(messages: Vec<ChatMessage>) -> Vec<serde_json::Value> {
         messages
             .into_iter()
-            .map(|msg| match msg {
+            .flat_map(|msg| match msg {
                 ChatMessage::User { content } => {
                     let content_arr = match content {
                         UserContent::Text(text) => vec![json!({
@@ -83,11 +104,24 @@ impl OpenAIProvider {
                                     "image_url": image_url.url
                                 })),
                                 ContentPart::FileUrl { file_url } => {
-                                    tracing::warn!(
-                                        mime_type = %file_url.mime_type,
-                                        "FileUrl ignored. Automatic file upload not implemented in logic layer."
-                                    );
-                                    None
+                                    if file_url.mime_type.starts_with("image/") {
+                                        Some(json!({
+                                            "type": "input_image",
+                                            "image_url": file_url.url
+                                        }))
+                                    } else if file_url.mime_type == "application/pdf" {
+                                        Some(json!({
+                                            "type": "input_file",
+                                            "filename": "attachment.pdf",
+                                            "file_data": file_url.url
+                                        }))
+                                    } else {
+                                        tracing::warn!(
+                                            mime_type = %file_url.mime_type,
+                                            "FileUrl ignored. Automatic file upload not implemented in logic layer."
+                                        );
+                                        None
+                                    }
                                 }
                                 ContentPart::InlineData { inline_data } => {
                                     if inline_data.mime_type.starts_with("image/") {
@@ -105,63 +139,99 @@ impl OpenAIProvider {
                             })
                             .collect(),
                     };
-                    json!({
+                    vec![json!({
                         "role": "user",
                         "content": content_arr
-                    })
+                    })]
                 }
-                ChatMessage::Assistant { content, tool_calls } => {
-                    let content_arr = match content {
-                        AssistantContent::Text(text) => {
-                             if text.is_empty() {
-                                 vec![]
-                             } else {
-                                 vec![json!({
-                                     "type": "output_text", // CORRECTED: output_text
-                                     "text": text
-                                 })]
-                             }
-                        },
-                        AssistantContent::Parts(parts) => parts.into_iter().filter_map(|part| {
-                             match part {
-                                 ContentPart::Text { text } => Some(json!({ "type": "output_text", "text": text })), // CORRECTED: output_text
-                                 _ => None
-                             }
-                        }).collect()
+                ChatMessage::Assistant {
+                    content,
+                    tool_calls,
+                    reasoning_items,
+                } => {
+                    let text = match content {
+                        AssistantContent::Text(text) => text,
+                        AssistantContent::Parts(parts) => parts
+                            .into_iter()
+                            .filter_map(|part| match part {
+                                ContentPart::Text { text } => Some(text),
+                                _ => None,
+                            })
+                            .collect(),
                     };
 
-                    let mut obj = json!({
-                        "role": "assistant",
-                        "content": content_arr
-                    });
-
-                    if let Some(tc) = tool_calls {
-                        obj.as_object_mut().unwrap().insert("tool_calls".to_string(), json!(tc));
+                    // Reasoning items must precede the function calls they led to
+                    let mut items = reasoning_items.unwrap_or_default();
+                    if !text.is_empty() {
+                        items.push(json!({
+                            "role": "assistant",
+                            "content": [{ "type": "output_text", "text": text }]
+                        }));
                     }
-                    obj
+                    items.extend(tool_calls.into_iter().flatten().map(|tc| {
+                        json!({
+                            "type": "function_call",
+                            "call_id": tc.id,
+                            "name": tc.function.name,
+                            "arguments": tc.function.arguments
+                        })
+                    }));
+                    items
                 }
-                ChatMessage::System { content } => json!({
+                ChatMessage::System { content } => vec![json!({
                     "role": "system",
                     "content": [{
                         "type": "input_text",
                         "text": content
                     }]
-                }),
+                })],
                 ChatMessage::Tool {
                     tool_call_id,
                     content,
-                } => json!({
-                    "role": "tool",
-                    "tool_call_id": tool_call_id,
-                    "content": [{
-                        "type": "input_text",
-                        "text": content
-                    }]
-                }),
+                    ..
+                } => vec![json!({
+                    "type": "function_call_output",
+                    "call_id": tool_call_id,
+                    "output": content
+                })],
+            })
+            .collect()
+    }
+
+    /// Responses API tools are flat, without the Chat Completions `function` wrapper
+    ///
+    /// They default to strict schemas there, which MCP tool schemas rarely satisfy.
+    fn transform_tools(tools: Vec<ChatCompletionTool>) -> Vec<serde_json::Value> {
+        tools
+            .into_iter()
+            .map(|t| {
+                let mut tool = json!({
+                    "type": "function",
+                    "name": t.function.name,
+                    "parameters": t
+                        .function
+                        .parameters
+                        .unwrap_or_else(|| json!({"type": "object", "properties": {}})),
+                    "strict": false,
+                });
+                if let Some(description) = t.function.description {
+                    tool["description"] = json!(description);
+                }
+                tool
             })
             .collect()
     }
 
+    fn transform_tool_choice(tool_choice: ToolChoice) -> serde_json::Value {
+        match tool_choice {
+            ToolChoice::String(choice) => json!(choice),
+            ToolChoice::Object { function, .. } => json!({
+                "type": "function",
+                "name": function.name
+            }),
+        }
+    }
+
     async fn handle_responses_api(
         &self,
         req_builder: reqwest::RequestBuilder,
@@ -172,7 +242,8 @@ impl OpenAIProvider {
         mut cancellation_rx: Option<tokio::sync::broadcast::Receiver<()>>,
         is_streaming_requested: bool,
     ) -> Result<LLMChatResponse, AppError> {
-        let response = req_builder.json(&request_body).send().await?;
+        let response =
+            retry::send(req_builder.json(&request_body), cancellation_rx.as_mut()).await?;
 
         // Handle error responses before creating emitters
         if !response.status().is_success() {
@@ -201,10 +272,11 @@ impl OpenAIProvider {
 
         // State for aggregation
         let mut full_content = String::new();
+        let mut full_reasoning = String::new();
+        let mut reasoning_items: Vec<serde_json::Value> = Vec::new();
         let mut tool_calls: Vec<ToolCall> = Vec::new();
         let mut finish_reason: Option<String> = None;
         let mut final_usage: Option<TokenUsage> = None;
-        let tool_calls_emitted = false;
 
         while let Some(item) = tokio::select! {
             next_item = stream.next() => next_item,
@@ -243,173 +315,133 @@ impl OpenAIProvider {
                     }
                 }
 
-                if event_data_str == "[DONE]" {
-                    continue;
-                }
-
                 // If no data, skip
-                if event_data_str.is_empty() {
+                if event_data_str.is_empty() || event_data_str == "[DONE]" {
                     continue;
                 }
 
-                match serde_json::from_str::<serde_json::Value>(&event_data_str) {
-                    Ok(data) => {
-                        // Determine event type: check "event:" line first, then "type" field in JSON
-                        let type_from_data = data.get("type").and_then(|s| s.as_str());
-                        let effective_event_type = if event_type.is_empty() {
-                            type_from_data.unwrap_or("unknown")
-                        } else {
-                            event_type.as_str()
-                        };
-
-                        // Log for debugging
-                        tracing::debug!(
-                            event_type = effective_event_type,
-                            data_keys = ?data.as_object().map(|o| o.keys().collect::<Vec<_>>()),
-                            "Received SSE event"
-                        );
-
-                        match effective_event_type {
-                            "response.output_text.delta" | "response.text.delta" => {
-                                // Try multiple fields for content
-                                let content_candidate = data.get("delta").and_then(|d| d.get("text")).and_then(|s| s.as_str())
-                                    .or_else(|| data.get("delta").and_then(|s| s.as_str())) // delta as string
-                                    .or_else(|| data.get("text").and_then(|s| s.as_str()))
-                                    .or_else(|| data.get("value").and_then(|s| s.as_str()))
-                                    .or_else(|| data.get("content").and_then(|s| s.as_str()));
-
-                                if let Some(text) = content_candidate {
-                                    full_content.push_str(text);
-                                    if is_streaming_requested {
-                                        message_emitter.emit_message_chunk(
-                                            chat_id.clone(),
-                                            message_id.clone(),
-                                            text.to_string(),
-                                        )?;
-                                    }
-                                }
-                            }
-                            "response.output_tool_call" | "response.tool_call" => {
-                                // New tool call received
-                                let id = data
-                                    .get("tool_call_id")
-                                    .or_else(|| data.get("id"))
-                                    .and_then(|s| s.as_str())
-                                    .unwrap_or_default()
-                                    .to_string();
-
-                                let name = data
-                                    .get("function")
-                                    .and_then(|f| f.get("name"))
-                                    .and_then(|s| s.as_str())
-                                    .unwrap_or_default()
-                                    .to_string();
-
-                                let args = data
-                                    .get("function")
-                                    .and_then(|f| f.get("arguments"))
-                                    .and_then(|s| s.as_str())
-                                    .unwrap_or_default()
-                                    .to_string();
-
-                                // Only add if we have at least a name or ID
-                                if !id.is_empty() || !name.is_empty() {
-                                    let call = ToolCall {
-                                        id,
-                                        r#type: "function".to_string(),
-                                        function: ToolCallFunction {
-                                            name,
-                                            arguments: args,
-                                        },
-                                    };
-                                    tool_calls.push(call);
-                                }
-                            }
-                            "response.usage" => {
-                                if let Some(u) = data.get("usage") {
-                                    final_usage = u.as_object().map(|obj| TokenUsage {
-                                        prompt_tokens: obj
-                                            .get("prompt_tokens")
-                                            .and_then(serde_json::Value::as_u64)
-                                            .map(|v| v as u32),
-                                        completion_tokens: obj
-                                            .get("completion_tokens")
-                                            .and_then(serde_json::Value::as_u64)
-                                            .map(|v| v as u32),
-                                        total_tokens: obj
-                                            .get("total_tokens")
-                                            .and_then(serde_json::Value::as_u64)
-                                            .map(|v| v as u32),
-                                    });
-                                }
+                let data = match serde_json::from_str::<serde_json::Value>(&event_data_str) {
+                    Ok(data) => data,
+                    Err(e) => {
+                        tracing::error!(error = %e, data = %event_data_str, "Failed to parse SSE data");
+                        continue;
+                    }
+                };
+
+                // Determine event type: check "event:" line first, then "type" field in JSON
+                let effective_event_type = if event_type.is_empty() {
+                    data.get("type")
+                        .and_then(|s| s.as_str())
+                        .unwrap_or("unknown")
+                } else {
+                    event_type.as_str()
+                };
+
+                tracing::debug!(event_type = effective_event_type, "Received SSE event");
+
+                match effective_event_type {
+                    "response.output_text.delta" | "response.refusal.delta" => {
+                        if let Some(text) = data.get("delta").and_then(|s| s.as_str()) {
+                            full_content.push_str(text);
+                            if is_streaming_requested {
+                                message_emitter.emit_message_chunk(
+                                    chat_id.clone(),
+                                    message_id.clone(),
+                                    text.to_string(),
+                                )?;
                             }
-                            "response.completed" | "response.end" => {
-                                if let Some(fr) = data.get("finish_reason").and_then(|v| v.as_str())
-                                {
-                                    finish_reason = Some(fr.to_string());
-                                }
-                                if let Some(u) = data.get("usage") {
-                                    final_usage = u.as_object().map(|obj| TokenUsage {
-                                        prompt_tokens: obj
-                                            .get("prompt_tokens")
-                                            .and_then(serde_json::Value::as_u64)
-                                            .map(|v| v as u32),
-                                        completion_tokens: obj
-                                            .get("completion_tokens")
-                                            .and_then(serde_json::Value::as_u64)
-                                            .map(|v| v as u32),
-                                        total_tokens: obj
-                                            .get("total_tokens")
-                                            .and_then(serde_json::Value::as_u64)
-                                            .map(|v| v as u32),
-                                    });
-                                }
+                        }
+                    }
+                    // Summaries for OpenAI reasoning models, full reasoning text for open ones
+                    "response.reasoning_summary_text.delta" | "response.reasoning_text.delta" => {
+                        if let Some(text) = data.get("delta").and_then(|s| s.as_str()) {
+                            full_reasoning.push_str(text);
+                            if is_streaming_requested {
+                                message_emitter.emit_thinking_chunk(
+                                    chat_id.clone(),
+                                    message_id.clone(),
+                                    text.to_string(),
+                                )?;
                             }
-                            "response.output_text.done" | "response.content_part.done" => {
-                                // IGNORE to avoid duplication
+                        }
+                    }
+                    "response.reasoning_summary_part.added" => {
+                        if !full_reasoning.is_empty() {
+                            full_reasoning.push_str("\n\n");
+                            if is_streaming_requested {
+                                message_emitter.emit_thinking_chunk(
+                                    chat_id.clone(),
+                                    message_id.clone(),
+                                    "\n\n".to_string(),
+                                )?;
                             }
-                            _ => {
-                                // Fallback: Check if 'choices' exists (Legacy format mixed in?)
-                                if let Some(choices) =
-                                    data.get("choices").and_then(|c| c.as_array())
-                                {
-                                    for choice in choices {
-                                        if let Some(content) = choice
-                                            .get("delta")
-                                            .and_then(|d| d.get("content"))
-                                            .and_then(|s| s.as_str())
-                                        {
-                                            full_content.push_str(content);
-                                            if is_streaming_requested {
-                                                message_emitter.emit_message_chunk(
-                                                    chat_id.clone(),
-                                                    message_id.clone(),
-                                                    content.to_string(),
-                                                )?;
-                                            }
-                                        }
-                                        if let Some(reason) =
-                                            choice.get("finish_reason").and_then(|s| s.as_str())
-                                        {
-                                            finish_reason = Some(reason.to_string());
-                                        }
-                                    }
-                                }
-                                // REMOVED generic fallback extracting 'text'/'content' from unknown events.
+                        }
+                    }
+                    // Completed output items carry the full function call and reasoning item
+                    "response.output_item.done" => {
+                        let Some(item) = data.get("item") else {
+                            continue;
+                        };
+                        match item.get("type").and_then(|t| t.as_str()) {
+                            Some("function_call") => {
+                                let field = |name: &str| {
+                                    item.get(name)
+                                        .and_then(|s| s.as_str())
+                                        .unwrap_or_default()
+                                        .to_string()
+                                };
+                                tool_calls.push(ToolCall {
+                                    id: field("call_id"),
+                                    r#type: "function".to_string(),
+                                    function: ToolCallFunction {
+                                        name: field("name"),
+                                        arguments: field("arguments"),
+                                    },
+                                });
                             }
+                            Some("reasoning") => reasoning_items.push(item.clone()),
+                            _ => {}
                         }
                     }
-                    Err(e) => {
-                        if !event_data_str.is_empty() {
-                            tracing::error!(error = %e, data = %event_data_str, "Failed to parse SSE data");
+                    "response.completed" | "response.incomplete" => {
+                        let response = data.get("response").unwrap_or(&data);
+                        finish_reason = Some(
+                            match response
+                                .pointer("/incomplete_details/reason")
+                                .and_then(|r| r.as_str())
+                            {
+                                Some("max_output_tokens") => "length".to_string(),
+                                Some(reason) => reason.to_string(),
+                                None if !tool_calls.is_empty() => "tool_calls".to_string(),
+                                None => "stop".to_string(),
+                            },
+                        );
+                        if let Some(u) = response.get("usage") {
+                            final_usage = Some(TokenUsage::from_openai(u));
                         }
                     }
+                    "response.failed" | "error" => {
+                        let error = data.pointer("/response/error").unwrap_or(&data);
+                        let error_msg = format!(
+                            "OpenAI Responses API error: {}",
+                            error
+                                .get("message")
+                                .and_then(|m| m.as_str())
+                                .unwrap_or("Unknown error")
+                        );
+                        message_emitter.emit_message_error(
+                            chat_id.clone(),
+                            message_id.clone(),
+                            error_msg.clone(),
+                        )?;
+                        return Err(AppError::Llm(error_msg));
+                    }
+                    _ => {}
                 }
             }
         }
 
-        // Final tool emission
-        if !tool_calls.is_empty() && !tool_calls_emitted {
+        if !tool_calls.is_empty() {
             let event_tool_calls: Vec<crate::events::ToolCall> = tool_calls
                 .iter()
                 .map(|tc| crate::events::ToolCall {
@@ -447,8 +479,17 @@ impl OpenAIProvider {
                 Some(tool_calls)
             },
             usage: final_usage,
-            reasoning: None,
+            reasoning: if full_reasoning.is_empty() {
+                None
+            } else {
+                Some(full_reasoning)
+            },
             images: None,
+            reasoning_items: if reasoning_items.is_empty() {
+                None
+            } else {
+                Some(reasoning_items)
+            },
         })
     }
 }
@@ -506,6 +547,7 @@ impl LLMProvider for OpenAIProvider {
                 supports_tools,
                 supports_thinking,
                 supports_image_generation,
+                context_length: None,
             })
         };
 
@@ -532,6 +574,21 @@ impl LLMProvider for OpenAIProvider {
         app: AppHandle,
         cancellation_rx: Option<tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<LLMChatResponse, AppError> {
+        if self.chat_completions {
+            return self
+                .compat
+                .chat(
+                    base_url,
+                    api_key,
+                    request,
+                    chat_id,
+                    message_id,
+                    app,
+                    cancellation_rx,
+                )
+                .await;
+        }
+
         let url = format!("{}/responses", base_url.trim_end_matches('/'));
 
         let mut req_builder = self.client.post(&url);
@@ -542,6 +599,8 @@ impl LLMProvider for OpenAIProvider {
 
         req_builder = req_builder.header("Content-Type", "application/json");
 
+        let (_, supports_thinking, _) = Self::check_model_capabilities(&request.model);
+
         // Transform to new input format
         let input_messages = Self::transform_messages_to_input(request.messages);
 
@@ -552,19 +611,41 @@ impl LLMProvider for OpenAIProvider {
             "stream": true, // Always stream for event-driven handling
         });
 
-        if let Some(temp) = request.temperature {
-            request_body
-                .as_object_mut()
-                .unwrap()
-                .insert("temperature".to_string(), json!(temp));
+        if supports_thinking {
+            // Nothing is stored server-side, so reasoning items come back encrypted for the next turn
+            request_body["store"] = json!(false);
+            request_body["include"] = json!(["reasoning.encrypted_content"]);
+            request_body["reasoning"] = json!({ "summary": "auto" });
+            if let Some(effort) = request.reasoning_effort {
+                request_body["reasoning"]["effort"] = json!(effort);
+            }
+        } else if let Some(temp) = request.temperature {
+            // Reasoning models reject sampling parameters
+            request_body["temperature"] = json!(temp);
+        }
+
+        if let Some(max_tokens) = request.max_tokens {
+            request_body["max_output_tokens"] = json!(max_tokens);
         }
 
         // Add tools if present
         if let Some(tools) = request.tools {
-            request_body
-                .as_object_mut()
-                .unwrap()
-                .insert("tools".to_string(), json!(tools));
+            request_body["tools"] = json!(Self::transform_tools(tools));
+        }
+        if let Some(tool_choice) = request.tool_choice {
+            request_body["tool_choice"] = Self::transform_tool_choice(tool_choice);
+        }
+
+        // Structured output: Responses API puts the schema under text.format
+        if let Some(format) = request.response_format {
+            request_body["text"] = json!({
+                "format": {
+                    "type": "json_schema",
+                    "name": format.name,
+                    "schema": format.schema,
+                    "strict": format.strict,
+                }
+            });
         }
 
         self.handle_responses_api(
@@ -4,6 +4,7 @@ use crate::events::{MessageEmitter, TokenUsage as EventTokenUsage, ToolEmitter};
 use crate::models::llm_types::{
     LLMChatRequest, LLMChatResponse, LLMModel, SSEChunk, TokenUsage, ToolCall, ToolCallFunction,
 };
+use crate::services::llm::retry;
 use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest::Client;
@@ -40,7 +41,7 @@ impl OpenAICompatProvider {
         (supports_tools, supports_thinking, false)
     }
 
-    async fn handle_streaming(
+    pub(super) async fn handle_streaming(
         &self,
         req_builder: reqwest::RequestBuilder,
         request_body: serde_json::Value,
@@ -49,7 +50,8 @@ impl OpenAICompatProvider {
         app: AppHandle,
         mut cancellation_rx: Option<tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<LLMChatResponse, AppError> {
-        let response = req_builder.json(&request_body).send().await?;
+        let response =
+            retry::send(req_builder.json(&request_body), cancellation_rx.as_mut()).await?;
 
         let message_emitter = MessageEmitter::new(app.clone());
         let tool_emitter = ToolEmitter::new(app.clone());
@@ -118,8 +120,8 @@ impl OpenAICompatProvider {
                             tracing::debug!(?sse_chunk, "Parsed SSE chunk");
 
                             // Check for usage
-                            if let Some(usage) = sse_chunk.usage {
-                                final_usage = Some(usage);
+                            if let Some(usage) = sse_chunk.usage.filter(|u| !u.is_null()) {
+                                final_usage = Some(TokenUsage::from_openai(&usage));
                             }
                             if let Some(choices) = sse_chunk.choices {
                                 for choice in choices {
@@ -283,10 +285,11 @@ impl OpenAICompatProvider {
                 Some(full_reasoning)
             },
             images: None,
+            reasoning_items: None,
         })
     }
 
-    async fn handle_non_streaming(
+    pub(super) async fn handle_non_streaming(
         &self,
         req_builder: reqwest::RequestBuilder,
         request_body: serde_json::Value,
@@ -294,11 +297,7 @@ impl OpenAICompatProvider {
         message_id: String,
         app: AppHandle,
     ) -> Result<LLMChatResponse, AppError> {
-        let response = req_builder
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| AppError::Generic(format!("HTTP request failed: {e}")))?;
+        let response = retry::send(req_builder.json(&request_body), None).await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -409,20 +408,7 @@ impl OpenAICompatProvider {
         }
 
         // Parse usage
-        let usage = json_response.get("usage").map(|u| TokenUsage {
-            prompt_tokens: u
-                .get("prompt_tokens")
-                .and_then(serde_json::Value::as_u64)
-                .map(|v| v as u32),
-            completion_tokens: u
-                .get("completion_tokens")
-                .and_then(serde_json::Value::as_u64)
-                .map(|v| v as u32),
-            total_tokens: u
-                .get("total_tokens")
-                .and_then(serde_json::Value::as_u64)
-                .map(|v| v as u32),
-        });
+        let usage = json_response.get("usage").map(TokenUsage::from_openai);
 
         // Emit complete event
         message_emitter.emit_message_complete(
@@ -443,6 +429,7 @@ impl OpenAICompatProvider {
             usage,
             reasoning: None,
             images: None,
+            reasoning_items: None,
         })
     }
 }
@@ -523,6 +510,11 @@ impl LLMProvider for OpenAICompatProvider {
                 // Check model capabilities
                 let (supports_tools, supports_thinking, supports_image_generation) =
                     Self::check_model_capabilities(&id);
+                // OpenRouter and Together say context_length, Groq context_window, vLLM max_model_len
+                let context_length = ["context_length", "context_window", "max_model_len"]
+                    .iter()
+                    .find_map(|key| item.get(*key).and_then(serde_json::Value::as_u64))
+                    .and_then(|limit| usize::try_from(limit).ok());
 
                 Some(LLMModel {
                     id,
@@ -536,6 +528,7 @@ impl LLMProvider for OpenAICompatProvider {
                     supports_tools,
                     supports_thinking,
                     supports_image_generation,
+                    context_length,
                 })
             } else {
                 None
@@ -596,7 +589,10 @@ impl LLMProvider for OpenAICompatProvider {
 
         req_builder = req_builder.header("Content-Type", "application/json");
 
-        let request_body = serde_json::to_value(&request)?;
+        let mut request_body = serde_json::to_value(&request)?;
+        if let Some(format) = &request.response_format {
+            request_body["response_format"] = format.to_openai();
+        }
 
         if request.stream {
             self.handle_streaming(
@@ -0,0 +1,106 @@
+//! Retries for provider requests that fail because of rate limits or outages
+//!
+//! Only sending the request is retried: once a success status arrives the reply is streamed
+//! to the UI, so a failure after that point cannot be replayed. Requests that are still
+//! rate limited or failing after the last retry end with `AppError::LlmUnavailable`, which
+//! lets `LLMService` move on to the workspace's fallback chain.
+
+use crate::error::AppError;
+use reqwest::header::HeaderMap;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use std::time::Duration;
+
+/// Retries after the first attempt
+const MAX_RETRIES: u32 = 3;
+/// Delay before the first retry, doubled for every following one
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Longest `Retry-After` waited for, a provider asking for more is treated as unavailable
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Rate limits and server errors, including Anthropic's 529 overloaded
+pub fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn backoff(retry: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(retry))
+        .min(MAX_BACKOFF)
+}
+
+/// Delay requested by the provider: `retry-after-ms`, or `Retry-After` in seconds or as an HTTP date
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    if let Some(ms) = header("retry-after-ms").and_then(|v| v.trim().parse::<f64>().ok()) {
+        return Some(Duration::from_secs_f64(ms.max(0.0) / 1000.0));
+    }
+    let value = header("retry-after")?.trim();
+    if let Ok(seconds) = value.parse::<f64>() {
+        return Some(Duration::from_secs_f64(seconds.max(0.0)));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let seconds = (date.timestamp() - chrono::Utc::now().timestamp()).max(0);
+    Some(Duration::from_secs(seconds.unsigned_abs()))
+}
+
+/// Send a provider request, retrying rate limits, server errors and connection failures
+///
+/// Other error statuses are returned as responses for the provider to report.
+pub async fn send(
+    request: RequestBuilder,
+    mut cancellation_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+) -> Result<Response, AppError> {
+    let mut retry = 0;
+    loop {
+        // Bodies that cannot be cloned (streams) are sent once
+        let Some(attempt) = request.try_clone() else {
+            return Ok(request.send().await?);
+        };
+
+        let delay = match attempt.send().await {
+            Ok(response) if is_retryable(response.status()) => {
+                let status = response.status();
+                let requested = retry_after(response.headers());
+                if retry >= MAX_RETRIES || requested.is_some_and(|d| d > MAX_RETRY_AFTER) {
+                    let error_text = response
+                        .text()
+                        .await
+                        .unwrap_or_else(|_| "Unknown error".to_string());
+                    return Err(AppError::LlmUnavailable(format!(
+                        "LLM API error ({status}): {error_text}"
+                    )));
+                }
+                tracing::warn!(
+                    status = %status,
+                    retry = retry + 1,
+                    "LLM provider request failed, retrying"
+                );
+                requested.unwrap_or_else(|| backoff(retry))
+            }
+            Ok(response) => return Ok(response),
+            Err(e) if e.is_connect() || e.is_timeout() => {
+                if retry >= MAX_RETRIES {
+                    return Err(AppError::LlmUnavailable(format!(
+                        "HTTP request failed: {e}"
+                    )));
+                }
+                tracing::warn!(error = %e, retry = retry + 1, "LLM provider unreachable, retrying");
+                backoff(retry)
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        match cancellation_rx.as_deref_mut() {
+            Some(rx) => {
+                tokio::select! {
+                    () = tokio::time::sleep(delay) => {}
+                    _ = rx.recv() => return Err(AppError::Cancelled),
+                }
+            }
+            None => tokio::time::sleep(delay).await,
+        }
+        retry += 1;
+    }
+}
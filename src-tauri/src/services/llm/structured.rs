@@ -0,0 +1,321 @@
+//! Structured output: validate a model reply against the requested JSON Schema
+//!
+//! Providers constrain the output natively where they can, but the schema support differs
+//! between them (and OpenAI-compatible servers may ignore it), so replies are always checked.
+//! The validator covers the JSON Schema keywords structured output schemas use in practice.
+
+use crate::error::AppError;
+use crate::models::llm_types::{
+    AssistantContent, ChatMessage, LLMChatResponse, ResponseFormat, TokenUsage, UserContent,
+};
+use serde_json::Value;
+use std::future::Future;
+
+#[cfg(test)]
+mod tests;
+
+/// Errors reported back to the model in a repair request
+const MAX_REPORTED_ERRORS: usize = 10;
+
+/// Parse a reply as JSON, tolerating a Markdown code fence around it
+pub fn parse_output(content: &str) -> Result<Value, String> {
+    let trimmed = content.trim();
+    let unfenced = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map_or(trimmed, str::trim);
+
+    serde_json::from_str(unfenced).map_err(|e| format!("The reply is not valid JSON: {e}"))
+}
+
+/// Check a reply against the format, returning the parsed value or every problem found
+pub fn check(content: &str, format: &ResponseFormat) -> Result<Value, Vec<String>> {
+    let value = parse_output(content).map_err(|e| vec![e])?;
+    let mut errors = Vec::new();
+    validate(&value, &format.schema, &format.schema, "", &mut errors);
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Follow-up turn asking the model to fix a reply that failed validation
+pub fn repair_messages(content: &str, errors: &[String]) -> Vec<ChatMessage> {
+    let mut problems = errors
+        .iter()
+        .take(MAX_REPORTED_ERRORS)
+        .map(|e| format!("- {e}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if errors.len() > MAX_REPORTED_ERRORS {
+        problems.push_str(&format!(
+            "\n- ...and {} more",
+            errors.len() - MAX_REPORTED_ERRORS
+        ));
+    }
+
+    vec![
+        ChatMessage::Assistant {
+            content: AssistantContent::Text(content.to_string()),
+            tool_calls: None,
+            reasoning_items: None,
+        },
+        ChatMessage::User {
+            content: UserContent::Text(format!(
+                "Your reply does not match the required JSON Schema:\n{problems}\n\n\
+                 Reply again with only the corrected JSON, without any other text."
+            )),
+        },
+    ]
+}
+
+/// Validate a reply, sending one repair turn through `repair` when it does not match.
+/// A reply with tool calls is returned as is, since the final answer comes later.
+/// Returns the valid reply with its JSON as content, or the errors left after the repair.
+pub async fn validate_with_repair<F, Fut>(
+    response: LLMChatResponse,
+    format: &ResponseFormat,
+    repair: F,
+) -> Result<Result<LLMChatResponse, Vec<String>>, AppError>
+where
+    F: FnOnce(Vec<ChatMessage>) -> Fut,
+    Fut: Future<Output = Result<LLMChatResponse, AppError>>,
+{
+    if response
+        .tool_calls
+        .as_ref()
+        .is_some_and(|calls| !calls.is_empty())
+    {
+        return Ok(Ok(response));
+    }
+
+    let errors = match check(&response.content, format) {
+        Ok(value) => return Ok(Ok(with_content(response, &value))),
+        Err(errors) => errors,
+    };
+    tracing::warn!(
+        schema = %format.name,
+        errors = ?errors,
+        "Structured output does not match the schema, asking for a repair"
+    );
+
+    let repaired = repair(repair_messages(&response.content, &errors)).await?;
+    let repaired = LLMChatResponse {
+        usage: add_usage(response.usage, repaired.usage.clone()),
+        ..repaired
+    };
+    Ok(check(&repaired.content, format).map(|value| with_content(repaired, &value)))
+}
+
+/// Valid structured output as compact JSON, without the code fences some models add
+fn with_content(mut response: LLMChatResponse, value: &Value) -> LLMChatResponse {
+    response.content = value.to_string();
+    response
+}
+
+fn add_usage(first: Option<TokenUsage>, second: Option<TokenUsage>) -> Option<TokenUsage> {
+    let sum = |a: Option<u32>, b: Option<u32>| match (a, b) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+    };
+    match (first, second) {
+        (Some(a), Some(b)) => Some(TokenUsage {
+            prompt_tokens: sum(a.prompt_tokens, b.prompt_tokens),
+            completion_tokens: sum(a.completion_tokens, b.completion_tokens),
+            total_tokens: sum(a.total_tokens, b.total_tokens),
+            cache_read_tokens: sum(a.cache_read_tokens, b.cache_read_tokens),
+            cache_write_tokens: sum(a.cache_write_tokens, b.cache_write_tokens),
+        }),
+        (a, b) => a.or(b),
+    }
+}
+
+fn resolve<'a>(schema: &'a Value, root: &'a Value) -> &'a Value {
+    let Some(reference) = schema["$ref"].as_str() else {
+        return schema;
+    };
+    // Only local references (#/$defs/name, #/definitions/name) are supported
+    reference
+        .strip_prefix('#')
+        .and_then(|pointer| root.pointer(pointer))
+        .unwrap_or(schema)
+}
+
+fn type_matches(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn location(path: &str) -> &str {
+    if path.is_empty() {
+        "the reply"
+    } else {
+        path
+    }
+}
+
+fn validate(value: &Value, schema: &Value, root: &Value, path: &str, errors: &mut Vec<String>) {
+    let schema = resolve(schema, root);
+    let at = location(path);
+
+    match &schema["type"] {
+        Value::String(expected) if !type_matches(value, expected) => {
+            errors.push(format!("{at} must be of type {expected}"));
+            return;
+        }
+        Value::Array(types)
+            if !types
+                .iter()
+                .filter_map(Value::as_str)
+                .any(|t| type_matches(value, t)) =>
+        {
+            errors.push(format!("{at} must be one of the types {}", schema["type"]));
+            return;
+        }
+        _ => {}
+    }
+
+    if let Some(options) = schema["enum"].as_array() {
+        if !options.contains(value) {
+            errors.push(format!("{at} must be one of {}", schema["enum"]));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            errors.push(format!("{at} must be {expected}"));
+        }
+    }
+
+    if let Some(all) = schema["allOf"].as_array() {
+        for sub in all {
+            validate(value, sub, root, path, errors);
+        }
+    }
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(options) = schema[keyword].as_array() {
+            let matching = options
+                .iter()
+                .filter(|sub| {
+                    let mut sub_errors = Vec::new();
+                    validate(value, sub, root, path, &mut sub_errors);
+                    sub_errors.is_empty()
+                })
+                .count();
+            if matching == 0 || (keyword == "oneOf" && matching > 1) {
+                errors.push(format!(
+                    "{at} must match {} of the allowed schemas",
+                    if keyword == "oneOf" {
+                        "exactly one"
+                    } else {
+                        "one"
+                    }
+                ));
+            }
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            if let Some(required) = schema["required"].as_array() {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !object.contains_key(key) {
+                        errors.push(format!("{at} is missing the required property \"{key}\""));
+                    }
+                }
+            }
+            let properties = schema["properties"].as_object();
+            for (key, item) in object {
+                let item_path = format!("{path}/{key}");
+                match properties.and_then(|p| p.get(key)) {
+                    Some(sub) => validate(item, sub, root, &item_path, errors),
+                    None => match &schema["additionalProperties"] {
+                        Value::Bool(false) => {
+                            errors.push(format!("{at} has an unexpected property \"{key}\""));
+                        }
+                        sub @ Value::Object(_) => validate(item, sub, root, &item_path, errors),
+                        _ => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(min) = schema["minItems"].as_u64() {
+                if (items.len() as u64) < min {
+                    errors.push(format!("{at} must have at least {min} items"));
+                }
+            }
+            if let Some(max) = schema["maxItems"].as_u64() {
+                if items.len() as u64 > max {
+                    errors.push(format!("{at} must have at most {max} items"));
+                }
+            }
+            if schema.get("items").is_some_and(Value::is_object) {
+                for (index, item) in items.iter().enumerate() {
+                    validate(
+                        item,
+                        &schema["items"],
+                        root,
+                        &format!("{path}/{index}"),
+                        errors,
+                    );
+                }
+            }
+        }
+        Value::String(text) => {
+            let length = text.chars().count() as u64;
+            if schema["minLength"].as_u64().is_some_and(|min| length < min) {
+                errors.push(format!("{at} is shorter than {}", schema["minLength"]));
+            }
+            if schema["maxLength"].as_u64().is_some_and(|max| length > max) {
+                errors.push(format!("{at} is longer than {}", schema["maxLength"]));
+            }
+            if let Some(pattern) = schema["pattern"].as_str() {
+                if let Ok(regex) = regex::Regex::new(pattern) {
+                    if !regex.is_match(text) {
+                        errors.push(format!("{at} does not match the pattern {pattern}"));
+                    }
+                }
+            }
+        }
+        Value::Number(number) => {
+            let n = number.as_f64().unwrap_or_default();
+            if schema["minimum"].as_f64().is_some_and(|min| n < min) {
+                errors.push(format!("{at} must be at least {}", schema["minimum"]));
+            }
+            if schema["maximum"].as_f64().is_some_and(|max| n > max) {
+                errors.push(format!("{at} must be at most {}", schema["maximum"]));
+            }
+            if schema["exclusiveMinimum"]
+                .as_f64()
+                .is_some_and(|min| n <= min)
+            {
+                errors.push(format!(
+                    "{at} must be greater than {}",
+                    schema["exclusiveMinimum"]
+                ));
+            }
+            if schema["exclusiveMaximum"]
+                .as_f64()
+                .is_some_and(|max| n >= max)
+            {
+                errors.push(format!(
+                    "{at} must be less than {}",
+                    schema["exclusiveMaximum"]
+                ));
+            }
+        }
+        _ => {}
+    }
+}
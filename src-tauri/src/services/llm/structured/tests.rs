@@ -0,0 +1,194 @@
+use super::*;
+use serde_json::json;
+use std::cell::Cell;
+
+fn format(schema: Value) -> ResponseFormat {
+    ResponseFormat {
+        name: "answer".to_string(),
+        schema,
+        strict: true,
+    }
+}
+
+fn reply(content: &str, total_tokens: u32) -> LLMChatResponse {
+    LLMChatResponse {
+        content: content.to_string(),
+        finish_reason: None,
+        tool_calls: None,
+        usage: Some(TokenUsage {
+            total_tokens: Some(total_tokens),
+            ..TokenUsage::default()
+        }),
+        reasoning: None,
+        images: None,
+        reasoning_items: None,
+    }
+}
+
+fn person_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "name": { "type": "string" },
+            "role": { "enum": ["admin", "member"] },
+            "address": {
+                "type": "object",
+                "properties": {
+                    "city": { "type": "string" },
+                    "zip": { "type": "integer" }
+                },
+                "required": ["city"]
+            },
+            "tags": { "type": "array", "items": { "type": "string" } }
+        },
+        "required": ["name", "role"],
+        "additionalProperties": false
+    })
+}
+
+#[test]
+fn accepts_a_fenced_reply_matching_the_schema() {
+    let content = "```json\n{\"name\": \"Ada\", \"role\": \"admin\", \"tags\": [\"x\"]}\n```";
+    let value = check(content, &format(person_schema())).unwrap();
+    assert_eq!(value["name"], "Ada");
+}
+
+#[test]
+fn reports_missing_required_properties() {
+    let errors = check(r#"{"address": {"zip": 1}}"#, &format(person_schema())).unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            "the reply is missing the required property \"name\"",
+            "the reply is missing the required property \"role\"",
+            "/address is missing the required property \"city\"",
+        ]
+    );
+}
+
+#[test]
+fn reports_type_errors_in_nested_values() {
+    let content = r#"{"name": "Ada", "role": "member", "address": {"city": 7, "zip": 1.5}, "tags": ["a", 2]}"#;
+    let errors = check(content, &format(person_schema())).unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            "/address/city must be of type string",
+            "/address/zip must be of type integer",
+            "/tags/1 must be of type string",
+        ]
+    );
+}
+
+#[test]
+fn reports_values_outside_an_enum_and_unexpected_properties() {
+    let content = r#"{"name": "Ada", "role": "owner", "age": 36}"#;
+    let mut errors = check(content, &format(person_schema())).unwrap_err();
+    errors.sort();
+    assert_eq!(
+        errors,
+        vec![
+            "/role must be one of [\"admin\",\"member\"]",
+            "the reply has an unexpected property \"age\"",
+        ]
+    );
+}
+
+#[test]
+fn resolves_local_references() {
+    let schema = json!({
+        "type": "array",
+        "items": { "$ref": "#/$defs/step" },
+        "$defs": { "step": { "type": "string", "minLength": 2 } }
+    });
+    let errors = check(r#"["ok", "x"]"#, &format(schema)).unwrap_err();
+    assert_eq!(errors, vec!["/1 is shorter than 2"]);
+}
+
+#[test]
+fn reports_invalid_json() {
+    let errors = check("Sure! Here it is.", &format(person_schema())).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("The reply is not valid JSON"));
+}
+
+#[tokio::test]
+async fn returns_a_valid_reply_without_repairing() {
+    let repairs = Cell::new(0);
+    let validated = validate_with_repair(
+        reply(r#"{ "name": "Ada", "role": "admin" }"#, 10),
+        &format(person_schema()),
+        |_| async {
+            repairs.set(repairs.get() + 1);
+            Ok(reply("{}", 0))
+        },
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(repairs.get(), 0);
+    assert_eq!(validated.content, r#"{"name":"Ada","role":"admin"}"#);
+}
+
+#[tokio::test]
+async fn repairs_an_invalid_reply_once() {
+    let repairs = Cell::new(0);
+    let validated = validate_with_repair(
+        reply(r#"{"name": "Ada"}"#, 10),
+        &format(person_schema()),
+        |messages| {
+            repairs.set(repairs.get() + 1);
+            let ChatMessage::User {
+                content: UserContent::Text(request),
+            } = &messages[1]
+            else {
+                panic!("the repair turn must end with a user message");
+            };
+            assert!(request.contains("missing the required property \"role\""));
+            async { Ok(reply(r#"{"name": "Ada", "role": "member"}"#, 5)) }
+        },
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(repairs.get(), 1);
+    assert_eq!(validated.content, r#"{"name":"Ada","role":"member"}"#);
+    assert_eq!(validated.usage.unwrap().total_tokens, Some(15));
+}
+
+#[tokio::test]
+async fn gives_up_when_the_repaired_reply_is_still_invalid() {
+    let repairs = Cell::new(0);
+    let errors = validate_with_repair(reply("not json", 10), &format(person_schema()), |_| {
+        repairs.set(repairs.get() + 1);
+        async { Ok(reply(r#"{"name": 1, "role": "admin"}"#, 5)) }
+    })
+    .await
+    .unwrap()
+    .unwrap_err();
+
+    assert_eq!(repairs.get(), 1);
+    assert_eq!(errors, vec!["/name must be of type string"]);
+}
+
+#[test]
+fn stores_the_format_next_to_existing_metadata() {
+    let format = format(json!({ "type": "object" }));
+    let metadata = format
+        .to_metadata(Some(r#"{"flow": {"nodes": []}}"#))
+        .unwrap();
+    let metadata: Value = serde_json::from_str(&metadata).unwrap();
+
+    assert_eq!(metadata["flow"], json!({ "nodes": [] }));
+    let stored = ResponseFormat::from_metadata(Some(&metadata.to_string())).unwrap();
+    assert_eq!(stored.name, "answer");
+    assert!(format.validate().is_ok());
+    assert!(ResponseFormat {
+        schema: json!(true),
+        ..format
+    }
+    .validate()
+    .is_err());
+}
@@ -6,16 +6,31 @@ use crate::features::chat::input_settings::{
     ChatInputSettingsRepository, ChatInputSettingsService, SqliteChatInputSettingsRepository,
 };
 use crate::features::chat::{ChatRepository, ChatService, SqliteChatRepository};
+use crate::features::export::{ExportRepository, ExportService, SqliteExportRepository};
+use crate::features::import::{ImportRepository, ImportService, SqliteImportRepository};
 use crate::features::llm_connection::{
     LLMConnectionRepository, LLMConnectionService, SqliteLLMConnectionRepository,
 };
 use crate::features::mcp_connection::{
+    oauth::{MCPOAuthRepository, MCPOAuthService, SqliteMCPOAuthRepository},
     MCPConnectionRepository, MCPConnectionService, SqliteMCPConnectionRepository,
 };
 use crate::features::message::{MessageRepository, MessageService, SqliteMessageRepository};
 use crate::features::prompt::{PromptRepository, PromptService, SqlitePromptRepository};
+use crate::features::search::{SearchRepository, SearchService, SqliteSearchRepository};
+use crate::features::secrets::{SecretsRepository, SecretsService, SqliteSecretsRepository};
 
-use crate::features::tool::{mcp_refresh::MCPToolRefreshService, service::ToolService};
+use crate::features::tool::{
+    call_context::ToolCallContexts, client_pool::MCPClientPool, elicitation::MCPElicitationService,
+    mcp_refresh::MCPToolRefreshService, sampling::MCPSamplingService, service::ToolService,
+};
+use crate::features::tool_audit::{
+    SqliteToolAuditRepository, ToolAuditRepository, ToolAuditService,
+};
+use crate::features::tool_permission::PermissionScope;
+use crate::features::tool_permission::{
+    SqliteToolPermissionRepository, ToolPermissionRepository, ToolPermissionService,
+};
 use crate::features::usage::{SqliteUsageRepository, UsageRepository, UsageService};
 use crate::features::workspace::{
     management::{SqliteWorkspaceRepository, WorkspaceRepository, WorkspaceService},
@@ -36,6 +51,7 @@ use tokio::sync::oneshot;
 pub struct PermissionDecision {
     pub approved: bool,
     pub allowed_tool_ids: Vec<String>,
+    pub remember: Option<PermissionScope>, // Always allow the approved tools from now on
 }
 
 pub struct AppState {
@@ -50,11 +66,22 @@ pub struct AppState {
     pub chat_input_settings_service: Arc<ChatInputSettingsService>,
     pub llm_connection_service: Arc<LLMConnectionService>,
     pub mcp_connection_service: Arc<MCPConnectionService>,
+    pub mcp_oauth_service: Arc<MCPOAuthService>,
     pub usage_service: Arc<UsageService>,
     #[allow(dead_code)]
     pub tool_service: Arc<ToolService>,
+    pub mcp_client_pool: Arc<MCPClientPool>,
+    pub tool_call_contexts: Arc<ToolCallContexts>,
+    pub sampling_service: Arc<MCPSamplingService>,
+    pub elicitation_service: Arc<MCPElicitationService>,
     pub app_settings_service: Arc<AppSettingsService>,
     pub prompt_service: Arc<PromptService>,
+    pub secrets_service: Arc<SecretsService>,
+    pub search_service: Arc<SearchService>,
+    pub export_service: Arc<ExportService>,
+    pub import_service: Arc<ImportService>,
+    pub tool_permission_service: Arc<ToolPermissionService>,
+    pub tool_audit_service: Arc<ToolAuditService>,
 
     // Tool permission state: message_id -> oneshot sender for approval response
     pub pending_tool_permissions: Arc<Mutex<HashMap<String, oneshot::Sender<PermissionDecision>>>>,
@@ -76,6 +103,14 @@ impl AppState {
             }
         }
 
+        // Load the secrets vault key and encrypt any plaintext secrets left from older versions
+        let secrets_repo: Arc<dyn SecretsRepository> =
+            Arc::new(SqliteSecretsRepository::new(app.clone()));
+        let secrets_service = Arc::new(SecretsService::new(secrets_repo));
+        if let Err(e) = secrets_service.initialize() {
+            tracing::error!(error = %e, "Failed to initialize secrets vault");
+        }
+
         // Create repositories
         let workspace_repo: Arc<dyn WorkspaceRepository> =
             Arc::new(SqliteWorkspaceRepository::new(app.clone()));
@@ -93,6 +128,16 @@ impl AppState {
             Arc::new(SqliteUsageRepository::new(app.clone()));
         let chat_input_settings_repo: Arc<dyn ChatInputSettingsRepository> =
             Arc::new(SqliteChatInputSettingsRepository::new(app.clone()));
+        let search_repo: Arc<dyn SearchRepository> =
+            Arc::new(SqliteSearchRepository::new(app.clone()));
+        let export_repo: Arc<dyn ExportRepository> =
+            Arc::new(SqliteExportRepository::new(app.clone()));
+        let import_repo: Arc<dyn ImportRepository> =
+            Arc::new(SqliteImportRepository::new(app.clone()));
+        let tool_permission_repo: Arc<dyn ToolPermissionRepository> =
+            Arc::new(SqliteToolPermissionRepository::new(app.clone()));
+        let tool_audit_repo: Arc<dyn ToolAuditRepository> =
+            Arc::new(SqliteToolAuditRepository::new(app.clone()));
 
         // Initialize Agent Manager first as it's needed by ChatService
         let agent_manager = Arc::new(crate::features::agent::manager::AgentManager::new(
@@ -117,26 +162,43 @@ impl AppState {
             workspace_settings_service.clone(),
         ));
 
-        let llm_connection_repo: Arc<dyn LLMConnectionRepository> =
-            Arc::new(SqliteLLMConnectionRepository::new(app.clone()));
+        let llm_connection_repo: Arc<dyn LLMConnectionRepository> = Arc::new(
+            SqliteLLMConnectionRepository::new(app.clone(), secrets_service.clone()),
+        );
         let llm_connection_service = Arc::new(LLMConnectionService::new(llm_connection_repo));
 
         let llm_service = Arc::new(LLMService::new());
         let usage_service = Arc::new(UsageService::new(usage_repo));
-        let mcp_connection_repo: Arc<dyn MCPConnectionRepository> =
-            Arc::new(SqliteMCPConnectionRepository::new(app.clone()));
+        let mcp_connection_repo: Arc<dyn MCPConnectionRepository> = Arc::new(
+            SqliteMCPConnectionRepository::new(app.clone(), secrets_service.clone()),
+        );
         let mcp_connection_service =
             Arc::new(MCPConnectionService::new(mcp_connection_repo.clone()));
+        let mcp_oauth_repo: Arc<dyn MCPOAuthRepository> = Arc::new(SqliteMCPOAuthRepository::new(
+            app.clone(),
+            secrets_service.clone(),
+        ));
+        let mcp_oauth_service = Arc::new(MCPOAuthService::new(mcp_oauth_repo));
+        let mcp_client_pool = Arc::new(MCPClientPool::new((*app).clone()));
         let tool_service = Arc::new(ToolService::new(
-            (*app).clone(),
             mcp_connection_service.clone(),
             workspace_settings_service.clone(),
+            mcp_client_pool.clone(),
         ));
+        let tool_call_contexts = Arc::new(ToolCallContexts::new());
+        let sampling_service = Arc::new(MCPSamplingService::new(
+            llm_service.clone(),
+            llm_connection_service.clone(),
+            workspace_settings_service.clone(),
+            usage_service.clone(),
+            tool_call_contexts.clone(),
+        ));
+        let elicitation_service = Arc::new(MCPElicitationService::new(tool_call_contexts.clone()));
         let chat_service = Arc::new(ChatService::new(
             chat_repo,
             llm_service,
             message_service.clone(),
-            workspace_settings_service,
+            workspace_settings_service.clone(),
             llm_connection_service.clone(),
             tool_service.clone(),
             usage_service.clone(),
@@ -144,6 +206,11 @@ impl AppState {
         ));
         let app_settings_service = Arc::new(AppSettingsService::new(app_settings_repo));
         let prompt_service = Arc::new(PromptService::new(prompt_repo));
+        let search_service = Arc::new(SearchService::new(search_repo));
+        let export_service = Arc::new(ExportService::new(export_repo, message_service.clone()));
+        let import_service = Arc::new(ImportService::new(import_repo, workspace_settings_service));
+        let tool_permission_service = Arc::new(ToolPermissionService::new(tool_permission_repo));
+        let tool_audit_service = Arc::new(ToolAuditService::new(tool_audit_repo));
         let chat_input_settings_service =
             Arc::new(ChatInputSettingsService::new(chat_input_settings_repo));
 
@@ -156,6 +223,9 @@ impl AppState {
         // Start background refresh job (runs every 5 minutes)
         mcp_tool_refresh_service.start_background_refresh();
 
+        // Start pooled MCP client maintenance (idle shutdown, crash restarts)
+        mcp_client_pool.clone().start_maintenance();
+
         Ok(Self {
             db_state,
             workspace_feature,
@@ -164,10 +234,21 @@ impl AppState {
             chat_input_settings_service,
             llm_connection_service,
             mcp_connection_service,
+            mcp_oauth_service,
             usage_service,
             tool_service,
+            mcp_client_pool,
+            tool_call_contexts,
+            sampling_service,
+            elicitation_service,
             app_settings_service,
             prompt_service,
+            secrets_service,
+            search_service,
+            export_service,
+            import_service,
+            tool_permission_service,
+            tool_audit_service,
             pending_tool_permissions: Arc::new(Mutex::new(HashMap::new())),
             agent_manager,
         })
@@ -15,6 +15,8 @@ import i18n from '@/i18n/config';
 import { useAutoUpdate } from '@/features/updater/hooks/useAutoUpdate';
 import { UpdateModal } from '@/features/updater/ui/UpdateModal';
 import { FirstRunSetup } from '@/features/ui/setup/FirstRunSetup';
+import { SamplingRequestDialog } from '@/features/tools/ui/SamplingRequestDialog';
+import { ElicitationRequestDialog } from '@/features/tools/ui/ElicitationRequestDialog';
 
 function AppContent() {
   const dispatch = useAppDispatch();
@@ -122,6 +124,8 @@ function AppContent() {
         downloadProgress={downloadProgress}
       />
       <FirstRunSetup open={!loading && !setupCompleted} />
+      <SamplingRequestDialog />
+      <ElicitationRequestDialog />
     </>
   );
 }
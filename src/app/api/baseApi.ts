@@ -55,6 +55,7 @@ export const baseApi = createApi({
     'MCPConnection',
     'Agent',
     'ChatInputSettings',
+    'Vault',
   ],
   endpoints: () => ({}),
 });
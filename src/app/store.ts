@@ -14,6 +14,7 @@ import {
 import uiReducer from '@/features/ui/state/uiSlice';
 import notificationReducer from '@/features/notifications/state/notificationSlice';
 import toolPermissionReducer from '@/features/tools/state/toolPermissionSlice';
+import serverRequestReducer from '@/features/tools/state/serverRequestSlice';
 import { sentryMiddleware } from './sentryMiddleware';
 import { loggingMiddleware } from './loggingMiddleware';
 
@@ -33,6 +34,7 @@ export const store = configureStore({
     notifications: notificationReducer,
     chatSearch: chatSearchReducer,
     toolPermission: toolPermissionReducer,
+    serverRequest: serverRequestReducer,
   },
   middleware: (getDefaultMiddleware) =>
     getDefaultMiddleware().concat(
@@ -22,9 +22,19 @@ export const TauriCommands = {
   DELETE_ALL_CHATS_BY_WORKSPACE: 'delete_all_chats_by_workspace',
   SEND_MESSAGE: 'send_message',
   EDIT_AND_RESEND_MESSAGE: 'edit_and_resend_message',
+  SEND_COMPARE_MESSAGE: 'send_compare_message',
+  REGENERATE_MESSAGE: 'regenerate_message',
   RESPOND_TOOL_PERMISSION: 'respond_tool_permission',
   GENERATE_CHAT_TITLE: 'generate_chat_title',
 
+  // Tool Permission commands
+  GET_TOOL_PERMISSION_GRANTS: 'get_tool_permission_grants',
+  REVOKE_TOOL_PERMISSION_GRANT: 'revoke_tool_permission_grant',
+
+  // Tool Audit commands
+  GET_TOOL_AUDIT_LOG: 'get_tool_audit_log',
+  EXPORT_TOOL_AUDIT_LOG: 'export_tool_audit_log',
+
   // Chat Input Settings commands
   GET_CHAT_INPUT_SETTINGS: 'get_chat_input_settings',
   SAVE_CHAT_INPUT_SETTINGS: 'save_chat_input_settings',
@@ -36,6 +46,17 @@ export const TauriCommands = {
   DELETE_MESSAGE: 'delete_message',
   DELETE_MESSAGES_AFTER: 'delete_messages_after',
   CANCEL_MESSAGE: 'cancel_message',
+  GET_MESSAGE_BRANCHES: 'get_message_branches',
+  SWITCH_MESSAGE_BRANCH: 'switch_message_branch',
+
+  // Search commands
+  SEARCH_HISTORY: 'search_history',
+
+  // Export commands
+  EXPORT_CHAT: 'export_chat',
+  EXPORT_WORKSPACE: 'export_workspace',
+  EXPORT_ALL_CHATS: 'export_all_chats',
+  IMPORT_HISTORY: 'import_history',
 
   // Workspace Settings commands
   SAVE_WORKSPACE_SETTINGS: 'save_workspace_settings',
@@ -54,17 +75,27 @@ export const TauriCommands = {
   UPDATE_MCP_SERVER_CONNECTION: 'update_mcp_server_connection',
   DELETE_MCP_SERVER_CONNECTION: 'delete_mcp_server_connection',
   UPDATE_MCP_SERVER_STATUS: 'update_mcp_server_status',
+  AUTHORIZE_MCP_CONNECTION: 'authorize_mcp_connection',
+  GET_MCP_OAUTH_STATUS: 'get_mcp_oauth_status',
+  REVOKE_MCP_AUTHORIZATION: 'revoke_mcp_authorization',
 
   // App Settings commands
   SAVE_APP_SETTING: 'save_app_setting',
   GET_APP_SETTING: 'get_app_setting',
   GET_ALL_APP_SETTINGS: 'get_all_app_settings',
 
+  // Secrets vault commands
+  GET_VAULT_STATUS: 'get_vault_status',
+  UNLOCK_VAULT: 'unlock_vault',
+  SET_VAULT_PASSPHRASE: 'set_vault_passphrase',
+
   // Prompt commands
   CREATE_PROMPT: 'create_prompt',
   GET_PROMPTS: 'get_prompts',
   UPDATE_PROMPT: 'update_prompt',
   DELETE_PROMPT: 'delete_prompt',
+  GET_MCP_PROMPTS: 'get_mcp_prompts',
+  GET_MCP_PROMPT_TEMPLATE: 'get_mcp_prompt_template',
 
   // Hub commands
   FETCH_HUB_PROMPTS: 'fetch_hub_prompts',
@@ -82,6 +113,11 @@ export const TauriCommands = {
   GET_MCP_CLIENT: 'get_mcp_client',
   CALL_MCP_TOOL: 'call_mcp_tool',
   DISCONNECT_MCP_CLIENT: 'disconnect_mcp_client',
+  LIST_MCP_RESOURCES: 'list_mcp_resources',
+  LIST_MCP_RESOURCE_TEMPLATES: 'list_mcp_resource_templates',
+  READ_MCP_RESOURCE: 'read_mcp_resource',
+  RESPOND_SAMPLING_REQUEST: 'respond_sampling_request',
+  RESPOND_ELICITATION_REQUEST: 'respond_elicitation_request',
 
   // Python commands
   GET_PYTHON_RUNTIMES_STATUS: 'get_python_runtimes_status',
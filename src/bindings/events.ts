@@ -24,6 +24,8 @@ export const TauriEvents = {
   TOOL_EXECUTION_COMPLETED: 'tool-execution-completed',
   TOOL_EXECUTION_ERROR: 'tool-execution-error',
   TOOL_PERMISSION_REQUEST: 'tool-permission-request',
+  SAMPLING_REQUEST: 'sampling-request',
+  ELICITATION_REQUEST: 'elicitation-request',
 
   // Agent events
   AGENT_LOOP_ITERATION: 'agent-loop-iteration',
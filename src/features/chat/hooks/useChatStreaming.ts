@@ -15,6 +15,12 @@ import {
   updateChatLastMessage,
 } from '@/features/chat/state/chatsSlice';
 import { addPermissionRequest } from '@/features/tools/state/toolPermissionSlice';
+import {
+  addElicitationRequest,
+  addSamplingRequest,
+  type ElicitationSchema,
+  type SamplingMessage,
+} from '@/features/tools/state/serverRequestSlice';
 import { useTranslation } from 'react-i18next';
 import { messagesApi } from '@/features/chat/state/messagesApi';
 import { extractCodeBlocks } from '@/features/chat/lib/code-block-extractor';
@@ -113,6 +119,28 @@ interface ToolPermissionRequestEvent {
     name: string;
     arguments: unknown;
   }>;
+  timeout_secs: number;
+}
+
+interface SamplingRequestEvent {
+  chat_id: string;
+  message_id: string;
+  tool_call_id: string;
+  request_id: string;
+  server_name: string;
+  system_prompt: string | null;
+  messages: SamplingMessage[];
+  max_tokens: number;
+}
+
+interface ElicitationRequestEvent {
+  chat_id: string;
+  message_id: string;
+  tool_call_id: string;
+  request_id: string;
+  server_name: string;
+  message: string;
+  requested_schema: ElicitationSchema;
 }
 
 interface MessageMetadataUpdatedEvent {
@@ -436,10 +464,46 @@ export function useChatStreaming() {
               messageId: payload.message_id,
               toolCalls: payload.tool_calls,
               timestamp: Date.now(),
+              timeoutSecs: payload.timeout_secs,
             })
           );
         }
       );
+    const unlistenSamplingRequest = listenToEvent<SamplingRequestEvent>(
+      TauriEvents.SAMPLING_REQUEST,
+      (payload) => {
+        dispatch(
+          addSamplingRequest({
+            requestId: payload.request_id,
+            chatId: payload.chat_id,
+            messageId: payload.message_id,
+            toolCallId: payload.tool_call_id,
+            serverName: payload.server_name,
+            systemPrompt: payload.system_prompt,
+            messages: payload.messages,
+            maxTokens: payload.max_tokens,
+            timestamp: Date.now(),
+          })
+        );
+      }
+    );
+    const unlistenElicitationRequest = listenToEvent<ElicitationRequestEvent>(
+      TauriEvents.ELICITATION_REQUEST,
+      (payload) => {
+        dispatch(
+          addElicitationRequest({
+            requestId: payload.request_id,
+            chatId: payload.chat_id,
+            messageId: payload.message_id,
+            toolCallId: payload.tool_call_id,
+            serverName: payload.server_name,
+            message: payload.message,
+            requestedSchema: payload.requested_schema,
+            timestamp: Date.now(),
+          })
+        );
+      }
+    );
 
     const unlistenChatUpdated = listenToEvent<ChatUpdatedEvent>(
       TauriEvents.CHAT_UPDATED,
@@ -468,6 +532,8 @@ export function useChatStreaming() {
       unlistenToolExecutionError.then((fn) => fn());
       unlistenAgentLoopIteration.then((fn) => fn());
       unlistenToolPermissionRequest.then((fn) => fn());
+      unlistenSamplingRequest.then((fn) => fn());
+      unlistenElicitationRequest.then((fn) => fn());
       unlistenMetadataUpdated.then((fn) => fn());
       unlistenChatUpdated.then((fn) => fn());
     };
@@ -1,6 +1,7 @@
 import { ActionCreatorWithPayload, createAsyncThunk } from '@reduxjs/toolkit';
 import { invokeCommand, TauriCommands } from '@/lib/tauri';
 import type { RootState } from '@/app/store';
+import type { ResponseFormat } from '@/features/chat/types';
 import { validateAndExtractState } from '../helpers/sendMessage/stateValidation';
 
 /**
@@ -27,11 +28,13 @@ export function createSendMessageThunkNew(_actions: {
         content,
         files,
         metadata,
+        responseFormat,
       }: {
         chatId: string;
         content: string;
         files?: string[];
         metadata?: string;
+        responseFormat?: ResponseFormat;
       },
       { getState, dispatch }
     ) => {
@@ -70,6 +73,7 @@ export function createSendMessageThunkNew(_actions: {
           reasoningEffort:
             isThinkingEnabled && supportsThinking ? reasoningEffort : undefined,
           llmConnectionId: context.llmConnection.id,
+          responseFormat,
         }
       );
 
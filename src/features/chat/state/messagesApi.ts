@@ -1,6 +1,6 @@
 import { baseApi } from '@/app/api/baseApi';
 import { TauriCommands } from '@/bindings/commands';
-import type { Message } from '../types';
+import type { Message, MessageBranch, ResponseFormat } from '../types';
 import { extractCodeBlocks } from '@/features/chat/lib/code-block-extractor';
 
 interface DbMessage {
@@ -15,6 +15,12 @@ interface DbMessage {
   metadata: string | null;
 }
 
+interface DbMessageBranch {
+  parent_message_id: string | null;
+  message_ids: string[];
+  active_message_id: string;
+}
+
 export const messagesApi = baseApi.injectEndpoints({
   endpoints: (builder) => ({
     getMessages: builder.query<Message[], string>({
@@ -60,6 +66,33 @@ export const messagesApi = baseApi.injectEndpoints({
         { type: 'Message', id: `LIST_${chatId}` },
       ],
     }),
+    getMessageBranches: builder.query<MessageBranch[], string>({
+      query: (chatId) => ({
+        command: TauriCommands.GET_MESSAGE_BRANCHES,
+        args: { chatId },
+      }),
+      transformResponse: (response: DbMessageBranch[]) =>
+        response.map((branch) => ({
+          parentMessageId: branch.parent_message_id,
+          messageIds: branch.message_ids,
+          activeMessageId: branch.active_message_id,
+        })),
+      providesTags: (_result, _error, chatId) => [
+        { type: 'Message', id: `LIST_${chatId}` },
+      ],
+    }),
+    switchMessageBranch: builder.mutation<
+      void,
+      { chatId: string; messageId: string }
+    >({
+      query: (body) => ({
+        command: TauriCommands.SWITCH_MESSAGE_BRANCH,
+        args: body,
+      }),
+      invalidatesTags: (_result, _error, { chatId }) => [
+        { type: 'Message', id: `LIST_${chatId}` },
+      ],
+    }),
     sendMessage: builder.mutation<
       { assistant_message_id: string },
       {
@@ -67,6 +100,7 @@ export const messagesApi = baseApi.injectEndpoints({
         content: string;
         selectedModel?: string;
         reasoningEffort?: string;
+        responseFormat?: ResponseFormat;
       }
     >({
       query: (body) => ({
@@ -81,6 +115,7 @@ export const messagesApi = baseApi.injectEndpoints({
       {
         id: string;
         chatId: string;
+        parentMessageId: string | null;
         role: string;
         content: string;
         timestamp: number;
@@ -116,6 +151,8 @@ export const messagesApi = baseApi.injectEndpoints({
 
 export const {
   useGetMessagesQuery,
+  useGetMessageBranchesQuery,
+  useSwitchMessageBranchMutation,
   useSendMessageMutation,
   useCreateMessageMutation,
   useUpdateMessageMutation,
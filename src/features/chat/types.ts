@@ -36,6 +36,20 @@ export interface TokenUsage {
   responseTimeMs_?: number;
 }
 
+// Structured output: the reply must be JSON matching `schema`
+export interface ResponseFormat {
+  name: string;
+  schema: Record<string, unknown>;
+  strict?: boolean;
+}
+
+// A fork on the active path: sibling messages sharing the same parent
+export interface MessageBranch {
+  parentMessageId: string | null;
+  messageIds: string[]; // Oldest first
+  activeMessageId: string;
+}
+
 export interface CodeBlock {
   id: string;
   content: string;
@@ -0,0 +1,70 @@
+import { describe, it, expect, vi } from 'vitest';
+import { render, screen } from '@testing-library/react';
+import userEvent from '@testing-library/user-event';
+import { BranchSwitcher } from './BranchSwitcher';
+import type { MessageBranch } from '../../types';
+
+vi.mock('lucide-react', () => ({
+  ChevronLeft: () => <div data-testid="chevron-left" />,
+  ChevronRight: () => <div data-testid="chevron-right" />,
+}));
+
+const branch: MessageBranch = {
+  parentMessageId: 'user-1',
+  messageIds: ['reply-1', 'reply-2', 'reply-3'],
+  activeMessageId: 'reply-2',
+};
+
+const t = (key: string) => key;
+
+describe('BranchSwitcher', () => {
+  it('shows the position of the active sibling', () => {
+    render(<BranchSwitcher branch={branch} onSwitch={vi.fn()} t={t} />);
+
+    expect(screen.getByText('2/3')).toBeInTheDocument();
+  });
+
+  it('labels a compared reply with its model', () => {
+    render(
+      <BranchSwitcher
+        branch={branch}
+        onSwitch={vi.fn()}
+        label="gpt-4o"
+        t={t}
+      />
+    );
+
+    expect(screen.getByText('2/3')).toBeInTheDocument();
+    expect(screen.getByText('gpt-4o')).toBeInTheDocument();
+  });
+
+  it('switches to the previous and next sibling', async () => {
+    const user = userEvent.setup();
+    const onSwitch = vi.fn();
+    render(<BranchSwitcher branch={branch} onSwitch={onSwitch} t={t} />);
+
+    await user.click(screen.getByLabelText('previousBranch'));
+    await user.click(screen.getByLabelText('nextBranch'));
+
+    expect(onSwitch).toHaveBeenNthCalledWith(1, 'reply-1');
+    expect(onSwitch).toHaveBeenNthCalledWith(2, 'reply-3');
+  });
+
+  it('disables the buttons at the ends and while disabled', () => {
+    const { rerender } = render(
+      <BranchSwitcher
+        branch={{ ...branch, activeMessageId: 'reply-1' }}
+        onSwitch={vi.fn()}
+        t={t}
+      />
+    );
+    expect(screen.getByLabelText('previousBranch')).toBeDisabled();
+    expect(screen.getByLabelText('nextBranch')).toBeEnabled();
+
+    rerender(
+      <BranchSwitcher branch={branch} onSwitch={vi.fn()} disabled t={t} />
+    );
+    expect(screen.getByLabelText('previousBranch')).toBeDisabled();
+    expect(screen.getByLabelText('nextBranch')).toBeDisabled();
+  });
+});
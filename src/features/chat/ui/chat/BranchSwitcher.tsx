@@ -0,0 +1,60 @@
+import { ChevronLeft, ChevronRight } from 'lucide-react';
+import type { MessageBranch } from '../../types';
+
+interface BranchSwitcherProps {
+  branch: MessageBranch;
+  onSwitch: (messageId: string) => void;
+  disabled?: boolean;
+  /** Shown next to the position, the model of a compared reply */
+  label?: string;
+  t: (key: string) => string;
+}
+
+/**
+ * Steps between sibling messages (edits, regenerations, compared models).
+ * The sibling on screen is the one the conversation continues from.
+ */
+export function BranchSwitcher({
+  branch,
+  onSwitch,
+  disabled = false,
+  label,
+  t,
+}: BranchSwitcherProps) {
+  const index = branch.messageIds.indexOf(branch.activeMessageId);
+  const previous = branch.messageIds[index - 1];
+  const next = branch.messageIds[index + 1];
+
+  return (
+    <div className="flex items-center gap-0.5 text-xs text-muted-foreground select-none">
+      <button
+        type="button"
+        className="p-0.5 rounded hover:bg-black/10 dark:hover:bg-white/10 disabled:opacity-40 disabled:pointer-events-none"
+        onClick={() => previous && onSwitch(previous)}
+        disabled={disabled || !previous}
+        title={t('previousBranch')}
+        aria-label={t('previousBranch')}
+      >
+        <ChevronLeft className="h-3.5 w-3.5" />
+      </button>
+      <span className="tabular-nums">
+        {index + 1}/{branch.messageIds.length}
+      </span>
+      {label && (
+        <span className="px-1 truncate max-w-[12rem]" title={label}>
+          {label}
+        </span>
+      )}
+      <button
+        type="button"
+        className="p-0.5 rounded hover:bg-black/10 dark:hover:bg-white/10 disabled:opacity-40 disabled:pointer-events-none"
+        onClick={() => next && onSwitch(next)}
+        disabled={disabled || !next}
+        title={t('nextBranch')}
+        aria-label={t('nextBranch')}
+      >
+        <ChevronRight className="h-3.5 w-3.5" />
+      </button>
+    </div>
+  );
+}
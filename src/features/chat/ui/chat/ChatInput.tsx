@@ -45,7 +45,10 @@ import { showError } from '@/features/notifications/state/notificationSlice';
 import { isVisionModel } from '@/features/llm/lib/model-utils';
 import { useChatInput } from '../../hooks/useChatInput';
 import { useMessages } from '../../hooks/useMessages';
-import { useSlashCommand } from '@/hooks/useSlashCommand';
+import {
+  useSlashCommand,
+  type SlashCommandPrompt,
+} from '@/hooks/useSlashCommand';
 import { useAgentMention } from '@/features/chat/hooks/useAgentMention';
 import { useComponentPerformance } from '@/hooks/useComponentPerformance';
 import { SlashCommandDropdown } from '@/ui/molecules/SlashCommandDropdown';
@@ -56,13 +59,23 @@ import { PromptPanel } from './PromptPanel';
 import { AgentMentionChips } from './AgentBadgeOverlay';
 import { AttachedFileItem } from './AttachedFileItem';
 import { FlowAttachment } from './FlowAttachment';
+import {
+  ResourceChips,
+  ResourcePicker,
+  type AttachedResource,
+} from './ResourcePicker';
 import { FLOW_NODES } from '@/constants/flow-nodes';
 
 import {
   parsePromptVariables,
   renderPrompt,
 } from '@/features/settings/lib/prompt-utils';
-import type { Prompt, InstalledAgent } from '@/app/types';
+import type { InstalledAgent } from '@/app/types';
+import type {
+  MCPPromptEntry,
+  ParsedPromptTemplate,
+} from '@/features/prompt/types';
+import { invokeCommand, TauriCommands } from '@/lib/tauri';
 import { useAppSettings } from '@/hooks/useAppSettings';
 import { logger } from '@/lib/logger';
 
@@ -108,7 +121,8 @@ export function ChatInput({
 
   // State for variable input dialog
   const [variableDialogOpen, setVariableDialogOpen] = useState(false);
-  const [selectedPrompt, setSelectedPrompt] = useState<Prompt | null>(null);
+  const [selectedPrompt, setSelectedPrompt] =
+    useState<SlashCommandPrompt | null>(null);
   const [promptVariables, setPromptVariables] = useState<
     Record<string, string>
   >({});
@@ -123,6 +137,9 @@ export function ChatInput({
   const [selectedAgentIds, setSelectedAgentIds] = useState<string[]>([]);
 
   const [flowDialogOpen, setFlowDialogOpen] = useState(false);
+  const [attachedResources, setAttachedResources] = useState<
+    AttachedResource[]
+  >([]);
 
   // Use chat input hook
   const {
@@ -208,9 +225,33 @@ export function ChatInput({
     }, 0);
   };
 
+  // Render an MCP prompt through its server with the collected arguments
+  const insertMcpPrompt = async (
+    entry: MCPPromptEntry,
+    args: Record<string, string>
+  ) => {
+    try {
+      const template = await invokeCommand<ParsedPromptTemplate>(
+        TauriCommands.GET_MCP_PROMPT_TEMPLATE,
+        {
+          connectionId: entry.connection_id,
+          name: entry.name,
+          arguments: args,
+        }
+      );
+      insertPromptContent(template.content, template.title);
+    } catch (error) {
+      logger.error('Error rendering MCP prompt:', error);
+      dispatch(showError(t('failedToLoadMcpPrompt', { ns: 'chat' })));
+    }
+  };
+
+  const getPromptVariableNames = (prompt: SlashCommandPrompt) =>
+    prompt.mcp ? prompt.mcp.variables : parsePromptVariables(prompt.content);
+
   // Handle prompt selection
-  const handleSelectPrompt = (prompt: Prompt) => {
-    const variableNames = parsePromptVariables(prompt.content);
+  const handleSelectPrompt = (prompt: SlashCommandPrompt) => {
+    const variableNames = getPromptVariableNames(prompt);
 
     if (variableNames.length > 0) {
       const initialVariables: Record<string, string> = {};
@@ -225,13 +266,18 @@ export function ChatInput({
       slashCommand.close();
       // Use setTimeout to ensure close() state is set before input changes
       setTimeout(() => {
-        insertPromptContent(prompt.content, prompt.name);
+        if (prompt.mcp) {
+          void insertMcpPrompt(prompt.mcp, {});
+        } else {
+          insertPromptContent(prompt.content, prompt.name);
+        }
       }, 0);
     }
   };
 
   const slashCommand = useSlashCommand({
     input,
+    includeMcpPrompts: true,
     onSelectPrompt: handleSelectPrompt,
   });
 
@@ -263,6 +309,13 @@ export function ChatInput({
 
   const handleVariableDialogSubmit = () => {
     if (!selectedPrompt) return;
+    if (selectedPrompt.mcp) {
+      void insertMcpPrompt(selectedPrompt.mcp, promptVariables);
+      setVariableDialogOpen(false);
+      setSelectedPrompt(null);
+      setPromptVariables({});
+      return;
+    }
     const renderedContent = renderPrompt(
       selectedPrompt.content,
       promptVariables
@@ -309,7 +362,12 @@ export function ChatInput({
     }
 
     // Only proceed if we have something to send, attached files, or flow
-    if (combinedInput.trim() || attachedFiles.length > 0 || attachedFlow) {
+    if (
+      combinedInput.trim() ||
+      attachedFiles.length > 0 ||
+      attachedFlow ||
+      attachedResources.length > 0
+    ) {
       // Process attached files
       let images: string[] = [];
       if (attachedFiles.length > 0) {
@@ -331,20 +389,30 @@ export function ChatInput({
         }
       }
 
-      // If we have a flow attachment, we need to add it to metadata
-      let metadata: string | undefined;
+      // Flow and MCP resource attachments travel in the message metadata
+      const metadataFields: Record<string, unknown> = {};
       if (attachedFlow) {
-        metadata = JSON.stringify({
+        Object.assign(metadataFields, {
           type: 'flow_attachment',
           flow: attachedFlow,
           timestamp: Date.now(),
         });
       }
+      if (attachedResources.length > 0) {
+        metadataFields.resources = attachedResources.map(
+          ({ connection_id, uri }) => ({ connection_id, uri })
+        );
+      }
+      const metadata =
+        Object.keys(metadataFields).length > 0
+          ? JSON.stringify(metadataFields)
+          : undefined;
 
       // Clear states
       setInsertedPrompt(null);
       setSelectedAgentIds([]);
       setFlow(null); // Changed from setAttachedFlow(null)
+      setAttachedResources([]);
       handleFileUpload([]); // Clear attached files
 
       // Send the combined content directly
@@ -763,6 +831,16 @@ export function ChatInput({
               </div>
             )}
 
+            {/* Attached MCP Resources */}
+            <ResourceChips
+              resources={attachedResources}
+              onRemove={(resource) =>
+                setAttachedResources(
+                  attachedResources.filter((item) => item !== resource)
+                )
+              }
+            />
+
             {/* Inserted Prompt Panel */}
             {insertedPrompt && (
               <PromptPanel
@@ -856,6 +934,14 @@ export function ChatInput({
                   <Paperclip className="size-4" />
                 </Button>
 
+                {/* MCP Resources Button - Only shown with a connected server */}
+                <ResourcePicker
+                  connections={mcpConnections}
+                  attached={attachedResources}
+                  onChange={setAttachedResources}
+                  disabled={disabled}
+                />
+
                 {/* Flow Button - Only show if experimental feature is enabled */}
                 {enableWorkflowEditor && (
                   <Button
@@ -1180,11 +1266,14 @@ export function ChatInput({
         open={variableDialogOpen}
         title={selectedPrompt?.name || ''}
         variableNames={
-          selectedPrompt ? parsePromptVariables(selectedPrompt.content) : []
+          selectedPrompt ? getPromptVariableNames(selectedPrompt) : []
         }
+        optionalVariableNames={selectedPrompt?.mcp?.variables.filter(
+          (name) => !selectedPrompt.mcp?.required_variables.includes(name)
+        )}
         variables={promptVariables}
         renderedPreview={
-          selectedPrompt
+          selectedPrompt && !selectedPrompt.mcp
             ? renderPrompt(selectedPrompt.content, promptVariables)
             : undefined
         }
@@ -4,14 +4,21 @@ import { useStickToBottom } from 'use-stick-to-bottom';
 import { invokeCommand, TauriCommands } from '@/lib/tauri';
 import { useAppSettings } from '@/hooks/useAppSettings';
 import { useAppSelector, useAppDispatch } from '@/app/hooks';
-import { removePermissionRequest } from '@/features/tools/state/toolPermissionSlice';
+import {
+  removePermissionRequest,
+  type PermissionScope,
+} from '@/features/tools/state/toolPermissionSlice';
 import { showError } from '@/features/notifications/state/notificationSlice';
 import { setAgentChatHistoryDrawerOpen } from '@/features/ui/state/uiSlice';
 import { MessageList } from './MessageList';
 import { useComponentPerformance } from '@/hooks/useComponentPerformance';
 import type { Message } from '../../types';
 import { ScrollArea } from '@/ui/atoms/scroll-area';
-import { messagesApi } from '../../state/messagesApi';
+import {
+  messagesApi,
+  useGetMessageBranchesQuery,
+  useSwitchMessageBranchMutation,
+} from '../../state/messagesApi';
 import { logger } from '@/lib/logger';
 
 interface ChatMessagesProps {
@@ -42,19 +49,41 @@ export function ChatMessages({
   const pendingRequests = useAppSelector(
     (state) => state.toolPermission.pendingRequests
   );
+  const { data: branches } = useGetMessageBranchesQuery(selectedChatId ?? '', {
+    skip: !selectedChatId,
+  });
+  const [switchMessageBranch] = useSwitchMessageBranchMutation();
+
+  const handleSwitchBranch = useCallback(
+    async (messageId: string) => {
+      if (!selectedChatId) return;
+      try {
+        await switchMessageBranch({
+          chatId: selectedChatId,
+          messageId,
+        }).unwrap();
+      } catch (error) {
+        logger.error('Failed to switch message branch:', error);
+        dispatch(showError(t('failedToSwitchBranch')));
+      }
+    },
+    [dispatch, selectedChatId, switchMessageBranch, t]
+  );
 
   const handlePermissionRespond = useCallback(
     async (
       messageId: string,
       toolId: string,
       toolName: string,
-      approved: boolean
+      approved: boolean,
+      remember?: PermissionScope
     ) => {
       try {
         await invokeCommand(TauriCommands.RESPOND_TOOL_PERMISSION, {
           messageId,
           approved,
           allowedToolIds: approved ? [toolId] : [],
+          remember: approved ? (remember ?? null) : null,
         });
 
         if (!approved && selectedChatId) {
@@ -66,6 +95,7 @@ export function ChatMessages({
           await invokeCommand(TauriCommands.CREATE_MESSAGE, {
             id,
             chatId: selectedChatId,
+            parentMessageId: messages[messages.length - 1]?.id ?? null,
             role: 'assistant',
             content,
             timestamp,
@@ -86,7 +116,7 @@ export function ChatMessages({
         logger.error('Failed to respond to tool permission:', error);
       }
     },
-    [dispatch, selectedChatId]
+    [dispatch, messages, selectedChatId]
   );
 
   // Countdown for pending permissions
@@ -101,13 +131,12 @@ export function ChatMessages({
   useEffect(() => {
     const timer = setInterval(() => {
       const now = Date.now();
-      const TIMEOUT_MS = 60 * 1000; // 60s
-
       if (pendingRequests) {
         const newTimeLeft: Record<string, number> = {};
 
         Object.values(pendingRequests).forEach((req) => {
           if (req.timestamp) {
+            const TIMEOUT_MS = (req.timeoutSecs ?? 60) * 1000;
             const elapsed = now - req.timestamp;
             const remaining = Math.max(0, TIMEOUT_MS - elapsed);
             newTimeLeft[req.messageId] = Math.ceil(remaining / 1000);
@@ -204,6 +233,10 @@ export function ChatMessages({
         onViewAgentDetails={handleViewAgentDetails}
         onCancelToolExecution={onCancelToolExecution}
         onEditingMessageIdChange={onEditMessage}
+        branches={branches}
+        onSwitchBranch={
+          streamingMessageId || isLoading ? undefined : handleSwitchBranch
+        }
         permissionTimeLeft={permissionTimeLeft}
         showUsage={showUsage}
         t={t}
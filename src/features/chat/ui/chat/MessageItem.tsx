@@ -21,7 +21,8 @@ import { useComponentPerformance } from '@/hooks/useComponentPerformance';
 import { useAppDispatch } from '@/app/hooks';
 import { setImagePreviewOpen } from '@/features/ui/state/uiSlice';
 import { FLOW_NODES } from '@/constants/flow-nodes';
-import type { Message } from '../../types';
+import type { Message, MessageBranch } from '../../types';
+import { BranchSwitcher } from './BranchSwitcher';
 
 export interface MessageItemProps {
   message: Message;
@@ -34,6 +35,8 @@ export interface MessageItemProps {
   onCopy: (content: string, messageId: string) => void;
   onEdit: (messageId: string) => void;
   onViewAgentDetails?: (sessionId: string, agentId: string) => void;
+  branch?: MessageBranch; // Siblings of this message, when it has any
+  onSwitchBranch?: (messageId: string) => void; // Unset while a reply streams
   t: (key: string) => string;
 }
 
@@ -49,6 +52,8 @@ export const MessageItem = memo(
     onCopy,
     onEdit,
     onViewAgentDetails,
+    branch,
+    onSwitchBranch,
     t,
   }: MessageItemProps) {
     // Track render performance
@@ -100,6 +105,7 @@ export const MessageItem = memo(
     // Check for Agent Card metadata
     let agentCardData = null;
     let flowData = null;
+    let compareModel: string | undefined;
     if (message.metadata) {
       try {
         const parsed = JSON.parse(message.metadata);
@@ -107,6 +113,8 @@ export const MessageItem = memo(
           agentCardData = parsed;
         } else if (parsed && parsed.type === 'flow_attachment') {
           flowData = parsed.flow;
+        } else if (parsed && parsed.compare) {
+          compareModel = parsed.compare.model;
         }
       } catch (_) {
         // Ignore JSON parse errors
@@ -439,6 +447,19 @@ export const MessageItem = memo(
               </button>
             </div>
 
+            {/* Sibling switcher for edited, regenerated and compared messages */}
+            {branch && (
+              <div className="mt-1 pl-2">
+                <BranchSwitcher
+                  branch={branch}
+                  onSwitch={(messageId) => onSwitchBranch?.(messageId)}
+                  disabled={!onSwitchBranch}
+                  label={compareModel}
+                  t={t}
+                />
+              </div>
+            )}
+
             {/* Footer: Only show token usage if showUsage is enabled */}
             {showUsage &&
               message.role === 'assistant' &&
@@ -489,7 +510,9 @@ export const MessageItem = memo(
       prevProps.markdownEnabled === nextProps.markdownEnabled &&
       prevProps.isCopied === nextProps.isCopied &&
       prevProps.isStreaming === nextProps.isStreaming &&
-      prevProps.isLastMessage === nextProps.isLastMessage
+      prevProps.isLastMessage === nextProps.isLastMessage &&
+      prevProps.branch === nextProps.branch &&
+      prevProps.onSwitchBranch === nextProps.onSwitchBranch
     );
   }
 );
@@ -1,6 +1,9 @@
 import { useMemo, useState, useCallback, forwardRef, Fragment } from 'react';
-import type { Message } from '../../types';
-import type { PermissionRequest } from '@/features/tools/state/toolPermissionSlice';
+import type { Message, MessageBranch } from '../../types';
+import type {
+  PermissionRequest,
+  PermissionScope,
+} from '@/features/tools/state/toolPermissionSlice';
 import { ToolCallItem } from './ToolCallItem';
 import { ThinkingItem } from './ThinkingItem';
 import { MessageItem } from './MessageItem';
@@ -48,10 +51,13 @@ interface MessageListProps {
     messageId: string,
     toolId: string,
     toolName: string,
-    approved: boolean
+    approved: boolean,
+    remember?: PermissionScope
   ) => void | Promise<void>;
   onViewAgentDetails?: (sessionId: string, agentId: string) => void;
   onCancelToolExecution?: () => void;
+  branches?: MessageBranch[]; // Forks on the active path, for the sibling switcher
+  onSwitchBranch?: (messageId: string) => void;
 
   // Other
   showUsage?: boolean;
@@ -81,6 +87,8 @@ export const MessageList = forwardRef<HTMLDivElement, MessageListProps>(
       onPermissionRespond,
       onViewAgentDetails,
       onCancelToolExecution,
+      branches,
+      onSwitchBranch,
 
       showUsage = false,
       t,
@@ -95,6 +103,14 @@ export const MessageList = forwardRef<HTMLDivElement, MessageListProps>(
       threshold: 100,
     });
 
+    const branchByMessageId = useMemo(
+      () =>
+        new Map(
+          (branches ?? []).map((branch) => [branch.activeMessageId, branch])
+        ),
+      [branches]
+    );
+
     // Internal state management (if not controlled from parent)
     const [internalMarkdownEnabled, setInternalMarkdownEnabled] = useState<
       Record<string, boolean>
@@ -259,6 +275,8 @@ export const MessageList = forwardRef<HTMLDivElement, MessageListProps>(
                     enableStreaming && streamingMessageId === message.id
                   }
                   isLastMessage={message.id === lastRenderableMessageId}
+                  branch={branchByMessageId.get(message.id)}
+                  onSwitchBranch={onSwitchBranch}
                   t={t}
                 />
               )}
@@ -286,12 +304,13 @@ export const MessageList = forwardRef<HTMLDivElement, MessageListProps>(
                       t={t}
                       onRespond={
                         onPermissionRespond
-                          ? (allow: boolean) =>
+                          ? (allow: boolean, remember?: PermissionScope) =>
                               onPermissionRespond(
                                 message.id,
                                 tc.id,
                                 tc.name,
-                                allow
+                                allow,
+                                remember
                               )
                           : undefined
                       }
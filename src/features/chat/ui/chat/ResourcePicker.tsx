@@ -0,0 +1,178 @@
+import { useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Database, X } from 'lucide-react';
+import { Button } from '@/ui/atoms/button/button';
+import {
+  DropdownMenu,
+  DropdownMenuCheckboxItem,
+  DropdownMenuContent,
+  DropdownMenuLabel,
+  DropdownMenuTrigger,
+} from '@/ui/atoms/dropdown-menu';
+import type {
+  MCPResource,
+  MCPResourceRef,
+  MCPServerConnection,
+} from '@/features/mcp/types';
+import { invokeCommand, TauriCommands } from '@/lib/tauri';
+import { logger } from '@/lib/logger';
+
+export interface AttachedResource extends MCPResourceRef {
+  name: string;
+}
+
+interface ConnectionResources {
+  connection: MCPServerConnection;
+  resources: MCPResource[];
+}
+
+interface ResourcePickerProps {
+  connections: MCPServerConnection[];
+  attached: AttachedResource[];
+  onChange: (resources: AttachedResource[]) => void;
+  disabled?: boolean;
+}
+
+const isSameResource = (a: MCPResourceRef, b: MCPResourceRef) =>
+  a.connection_id === b.connection_id && a.uri === b.uri;
+
+/** Lists the resources of the connected MCP servers so they can be attached to the next message */
+export function ResourcePicker({
+  connections,
+  attached,
+  onChange,
+  disabled,
+}: ResourcePickerProps) {
+  const { t } = useTranslation('chat');
+  const [groups, setGroups] = useState<ConnectionResources[]>([]);
+  const [loading, setLoading] = useState(false);
+
+  const connected = connections.filter((conn) => conn.status === 'connected');
+
+  const loadResources = async () => {
+    setLoading(true);
+    const loaded = await Promise.all(
+      connected.map(async (connection) => {
+        try {
+          const resources = await invokeCommand<MCPResource[]>(
+            TauriCommands.LIST_MCP_RESOURCES,
+            { connectionId: connection.id }
+          );
+          return { connection, resources };
+        } catch (error) {
+          logger.error('Failed to list MCP resources', error);
+          return { connection, resources: [] };
+        }
+      })
+    );
+    setGroups(loaded.filter((group) => group.resources.length > 0));
+    setLoading(false);
+  };
+
+  const toggle = (connection: MCPServerConnection, resource: MCPResource) => {
+    const ref = { connection_id: connection.id, uri: resource.uri };
+    onChange(
+      attached.some((item) => isSameResource(item, ref))
+        ? attached.filter((item) => !isSameResource(item, ref))
+        : [...attached, { ...ref, name: resource.name }]
+    );
+  };
+
+  if (connected.length === 0) {
+    return null;
+  }
+
+  return (
+    <DropdownMenu
+      onOpenChange={(open) => {
+        if (open) {
+          void loadResources();
+        }
+      }}
+    >
+      <DropdownMenuTrigger asChild>
+        <Button
+          type="button"
+          variant="ghost"
+          size="icon"
+          disabled={disabled}
+          className="h-7 w-7 text-muted-foreground hover:text-foreground border-0 shadow-none disabled:opacity-50 disabled:cursor-not-allowed"
+          aria-label={t('attachResource')}
+          title={t('attachResource')}
+        >
+          <Database className="size-4" />
+        </Button>
+      </DropdownMenuTrigger>
+      <DropdownMenuContent align="start" className="w-72 max-h-80">
+        {loading && (
+          <DropdownMenuLabel className="font-normal text-muted-foreground">
+            {t('loadingResources')}
+          </DropdownMenuLabel>
+        )}
+        {!loading && groups.length === 0 && (
+          <DropdownMenuLabel className="font-normal text-muted-foreground">
+            {t('noResources')}
+          </DropdownMenuLabel>
+        )}
+        {!loading &&
+          groups.map(({ connection, resources }) => (
+            <div key={connection.id}>
+              <DropdownMenuLabel>{connection.name}</DropdownMenuLabel>
+              {resources.map((resource) => (
+                <DropdownMenuCheckboxItem
+                  key={resource.uri}
+                  checked={attached.some((item) =>
+                    isSameResource(item, {
+                      connection_id: connection.id,
+                      uri: resource.uri,
+                    })
+                  )}
+                  onSelect={(event) => event.preventDefault()}
+                  onCheckedChange={() => toggle(connection, resource)}
+                  title={resource.description ?? resource.uri}
+                >
+                  <span className="truncate">{resource.name}</span>
+                </DropdownMenuCheckboxItem>
+              ))}
+            </div>
+          ))}
+      </DropdownMenuContent>
+    </DropdownMenu>
+  );
+}
+
+interface ResourceChipsProps {
+  resources: AttachedResource[];
+  onRemove: (resource: AttachedResource) => void;
+}
+
+export function ResourceChips({ resources, onRemove }: ResourceChipsProps) {
+  const { t } = useTranslation('chat');
+
+  if (resources.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className="flex flex-wrap gap-2 p-2 pt-0">
+      {resources.map((resource) => (
+        <div
+          key={`${resource.connection_id}:${resource.uri}`}
+          className="flex items-center gap-1.5 rounded-md border border-primary/30 bg-primary/5 px-2 py-1 text-xs"
+          title={resource.uri}
+        >
+          <Database className="size-3.5 text-primary shrink-0" />
+          <span className="max-w-48 truncate">{resource.name}</span>
+          <button
+            type="button"
+            onClick={() => onRemove(resource)}
+            className="text-muted-foreground hover:text-foreground"
+            aria-label={t('removeResource')}
+          >
+            <X className="size-3.5" />
+          </button>
+        </div>
+      ))}
+    </div>
+  );
+}
@@ -6,6 +6,8 @@ import {
   AlertCircle,
   Loader2,
   Check,
+  CheckCheck,
+  ShieldCheck,
   X,
   StopCircle,
 } from 'lucide-react';
@@ -13,6 +15,7 @@ import { cn } from '@/lib/utils';
 import { Button } from '@/ui/atoms/button/button';
 import { logger } from '@/lib/logger';
 import type { Message } from '../../types';
+import type { PermissionScope } from '@/features/tools/state/toolPermissionSlice';
 
 export interface ToolCallData {
   id: string;
@@ -29,7 +32,7 @@ export interface ToolCallItemProps {
   isExpanded: boolean;
   onToggle: () => void;
   t: (key: string) => string;
-  onRespond?: (allow: boolean) => void;
+  onRespond?: (allow: boolean, remember?: PermissionScope) => void;
   onCancel?: () => void;
   timeLeft?: number; // Countdown in seconds for pending permission
 }
@@ -72,9 +75,13 @@ export const ToolCallItem = memo(
     }, [onToggle]);
 
     const handleRespond = useCallback(
-      (e: MouseEvent<HTMLButtonElement>, allow: boolean) => {
+      (
+        e: MouseEvent<HTMLButtonElement>,
+        allow: boolean,
+        remember?: PermissionScope
+      ) => {
         e.stopPropagation();
-        onRespond?.(allow);
+        onRespond?.(allow, remember);
       },
       [onRespond]
     );
@@ -188,6 +195,28 @@ export const ToolCallItem = memo(
                   >
                     <Check className="h-4 w-4" />
                   </Button>
+                  <Button
+                    size="icon"
+                    variant="ghost"
+                    className="h-6 w-6 text-success hover:bg-success/10"
+                    onClick={(e: MouseEvent<HTMLButtonElement>) =>
+                      handleRespond(e, true, 'chat')
+                    }
+                    title="Always allow in this chat"
+                  >
+                    <CheckCheck className="h-4 w-4" />
+                  </Button>
+                  <Button
+                    size="icon"
+                    variant="ghost"
+                    className="h-6 w-6 text-success hover:bg-success/10"
+                    onClick={(e: MouseEvent<HTMLButtonElement>) =>
+                      handleRespond(e, true, 'workspace')
+                    }
+                    title="Always allow in this workspace"
+                  >
+                    <ShieldCheck className="h-4 w-4" />
+                  </Button>
                   <Button
                     size="icon"
                     variant="ghost"
@@ -22,6 +22,7 @@ describe('llmConnectionsApi', () => {
         api_key: 'test-key',
         models_json: JSON.stringify([{ id: 'gemini-pro', name: 'Gemini Pro' }]),
         default_model: null,
+        api_mode: null,
         enabled: true,
         created_at: 123456789,
         updated_at: 123456789,
@@ -49,6 +50,7 @@ describe('llmConnectionsApi', () => {
         api_key: 'test-key',
         models_json: null,
         default_model: null,
+        api_mode: null,
         enabled: true,
         created_at: 123456789,
         updated_at: 123456789,
@@ -58,6 +60,32 @@ describe('llmConnectionsApi', () => {
       expect(result.models).toBeUndefined();
     });
 
+    it('should only map the responses api mode', () => {
+      const mockDbConnection = {
+        id: '1',
+        name: 'Test LLM',
+        base_url: 'https://api.test.com',
+        provider: 'openai',
+        api_key: 'test-key',
+        models_json: null,
+        default_model: null,
+        api_mode: 'responses',
+        enabled: true,
+        created_at: 123456789,
+        updated_at: 123456789,
+      };
+
+      expect(dbToFrontendLLMConnection(mockDbConnection).apiMode).toBe(
+        'responses'
+      );
+      expect(
+        dbToFrontendLLMConnection({
+          ...mockDbConnection,
+          api_mode: 'chat_completions',
+        }).apiMode
+      ).toBeUndefined();
+    });
+
     it('should default to openai for unknown providers', () => {
       const mockDbConnection = {
         id: '1',
@@ -67,6 +95,7 @@ describe('llmConnectionsApi', () => {
         api_key: 'test-key',
         models_json: null,
         default_model: null,
+        api_mode: null,
         enabled: true,
         created_at: 123456789,
         updated_at: 123456789,
@@ -85,6 +114,7 @@ describe('llmConnectionsApi', () => {
         api_key: 'test-key',
         models_json: null,
         default_model: null,
+        api_mode: null,
         enabled: true,
         created_at: 123456789,
         updated_at: 123456789,
@@ -97,6 +127,7 @@ describe('llmConnectionsApi', () => {
         api_key: 'test-key',
         models_json: null,
         default_model: null,
+        api_mode: null,
         enabled: true,
         created_at: 123456789,
         updated_at: 123456789,
@@ -12,6 +12,7 @@ interface DbLLMConnection {
   api_key: string;
   models_json: string | null;
   default_model: string | null;
+  api_mode: string | null;
   enabled: boolean;
   created_at: number;
   updated_at: number;
@@ -43,6 +44,8 @@ export function dbToFrontendLLMConnection(
     dbConn.provider === 'deepinfra' ||
     dbConn.provider === 'google' ||
     dbConn.provider === 'anthropic' ||
+    dbConn.provider === 'azure' ||
+    dbConn.provider === 'bedrock' ||
     dbConn.provider === 'deepseek'
       ? (dbConn.provider as LLMConnection['provider'])
       : 'openai';
@@ -53,6 +56,7 @@ export function dbToFrontendLLMConnection(
     baseUrl: dbConn.base_url,
     provider,
     apiKey: dbConn.api_key,
+    apiMode: dbConn.api_mode === 'responses' ? 'responses' : undefined,
     models,
     enabled: dbConn.enabled,
   };
@@ -96,6 +100,7 @@ export const llmConnectionsApi = baseApi.injectEndpoints({
             apiKey: connection.apiKey,
             modelsJson,
             defaultModel: null,
+            apiMode: connection.apiMode ?? null,
           },
         };
       },
@@ -120,6 +125,7 @@ export const llmConnectionsApi = baseApi.injectEndpoints({
             apiKey: connection.apiKey ?? null,
             modelsJson: modelsJson ?? null,
             defaultModel: null,
+            apiMode: connection.apiMode ?? null,
             enabled: connection.enabled ?? null,
           },
         };
@@ -143,6 +149,7 @@ export const llmConnectionsApi = baseApi.injectEndpoints({
           apiKey: null,
           modelsJson: null,
           defaultModel: null,
+          apiMode: null,
           enabled,
         },
       }),
@@ -6,6 +6,7 @@ export interface LLMModel {
   supportsTools: boolean;
   supportsThinking: boolean;
   supportsImageGeneration: boolean;
+  contextLength?: number;
 }
 
 export interface LLMConnection {
@@ -24,8 +25,11 @@ export interface LLMConnection {
     | 'deepinfra'
     | 'google'
     | 'anthropic'
+    | 'azure'
+    | 'bedrock'
     | 'deepseek';
   apiKey: string;
+  apiMode?: 'responses' | 'chat_completions'; // OpenAI only, Chat Completions by default
   models?: LLMModel[];
   enabled: boolean;
 }
@@ -45,6 +45,7 @@ import {
   showSuccess,
 } from '@/features/notifications/state/notificationSlice';
 import { logger } from '@/lib/logger';
+import { isLockedSecret } from '@/features/settings/lib/secret-utils';
 
 export function LLMConnections() {
   const { t } = useTranslation('settings');
@@ -118,6 +119,7 @@ export function LLMConnections() {
             baseUrl: connection.baseUrl,
             provider: connection.provider,
             apiKey: connection.apiKey,
+            apiMode: connection.apiMode,
             models: connection.models,
           },
         }).unwrap();
@@ -393,9 +395,14 @@ const DEFAULT_URLS: Record<string, string> = {
   deepinfra: 'https://api.deepinfra.com/v1/openai',
   google: 'https://generativelanguage.googleapis.com/v1beta',
   anthropic: 'https://api.anthropic.com',
+  // Settings ride along as query parameters, see the Azure OpenAI provider
+  azure: 'https://YOUR-RESOURCE.openai.azure.com?api-version=2024-10-21',
+  bedrock: 'https://bedrock-runtime.us-east-1.amazonaws.com',
   deepseek: 'https://api.deepseek.com',
 };
 
+type ApiMode = NonNullable<LLMConnection['apiMode']>;
+
 const PROVIDER_OPTIONS: { value: LLMConnection['provider']; label: string }[] =
   [
     { value: 'openai', label: 'OpenAI' },
@@ -409,6 +416,8 @@ const PROVIDER_OPTIONS: { value: LLMConnection['provider']; label: string }[] =
     { value: 'together', label: 'Together AI' },
     { value: 'deepinfra', label: 'DeepInfra' },
     { value: 'anthropic', label: 'Anthropic Claude' },
+    { value: 'azure', label: 'Azure OpenAI' },
+    { value: 'bedrock', label: 'AWS Bedrock' },
     { value: 'deepseek', label: 'DeepSeek' },
   ];
 
@@ -430,6 +439,9 @@ export function LLMConnectionForm({
     connection?.baseUrl || DEFAULT_URLS[connection?.provider || 'openai'] || ''
   );
   const [apiKey, setApiKey] = useState(connection?.apiKey || '');
+  const [apiMode, setApiMode] = useState<ApiMode>(
+    connection?.apiMode ?? 'chat_completions'
+  );
   // Filter models even when loading from existing connection
   const [models, setModels] = useState<LLMModel[]>(
     connection?.models
@@ -540,6 +552,7 @@ export function LLMConnectionForm({
         baseUrl: baseUrl.trim(),
         provider,
         apiKey,
+        apiMode: provider === 'openai' ? apiMode : undefined,
         models: modelsToSave,
         enabled: connection?.enabled ?? true, // Keep existing enabled state or default to true for new connections
       });
@@ -607,6 +620,30 @@ export function LLMConnectionForm({
                 required
               />
             </div>
+            {provider === 'openai' && (
+              <div className="space-y-2 w-full">
+                <Label htmlFor="apiMode">{t('apiMode')}</Label>
+                <Select
+                  value={apiMode}
+                  onValueChange={(value: ApiMode) => setApiMode(value)}
+                >
+                  <SelectTrigger id="apiMode" className="w-full">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value="chat_completions">
+                      {t('apiModeChatCompletions')}
+                    </SelectItem>
+                    <SelectItem value="responses">
+                      {t('apiModeResponses')}
+                    </SelectItem>
+                  </SelectContent>
+                </Select>
+                <p className="text-xs text-muted-foreground">
+                  {t('apiModeDescription')}
+                </p>
+              </div>
+            )}
             <div className="space-y-2 w-full">
               <Label htmlFor="apiKey">{t('apiKey')}</Label>
               <Input
@@ -620,8 +657,14 @@ export function LLMConnectionForm({
                     : t('enterApiKey')
                 }
                 className="w-full"
+                disabled={isLockedSecret(apiKey)}
                 data-tour="llm-api-key-input"
               />
+              {isLockedSecret(apiKey) && (
+                <p className="text-xs text-muted-foreground">
+                  {t('lockedSecret')}
+                </p>
+              )}
             </div>
 
             {/* Connection Status */}
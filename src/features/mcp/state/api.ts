@@ -129,10 +129,10 @@ export const mcpConnectionsApi = baseApi.injectEndpoints({
       queryFn: async (params) => {
         const { id, url, type, headers, env_vars, runtime_path } = params;
         try {
-          // Connect and fetch tools
-          const mcpTools = await invokeCommand<
-            Array<{ name: string; description?: string; input_schema?: string }>
-          >(TauriCommands.CONNECT_MCP_SERVER_AND_FETCH_TOOLS, {
+          // Connect and fetch tools, resources and prompts
+          // The backend caches them and marks the server connected
+          await invokeCommand(TauriCommands.CONNECT_MCP_SERVER_AND_FETCH_TOOLS, {
+            connectionId: id,
             url,
             type,
             headers: headers || null,
@@ -140,30 +140,6 @@ export const mcpConnectionsApi = baseApi.injectEndpoints({
             runtimePath: runtime_path || null,
           });
 
-          const tools = mcpTools.map((tool) => {
-            let inputSchema: object | undefined;
-            if (tool.input_schema) {
-              try {
-                inputSchema = JSON.parse(tool.input_schema);
-              } catch (_) {
-                // ignore
-              }
-            }
-            return {
-              name: tool.name,
-              description: tool.description,
-              inputSchema,
-            };
-          });
-
-          const toolsJson = JSON.stringify(tools);
-          await invokeCommand(TauriCommands.UPDATE_MCP_SERVER_STATUS, {
-            id,
-            status: 'connected',
-            toolsJson,
-            errorMessage: null,
-          });
-
           return { data: undefined };
         } catch (error: unknown) {
           const errorMsg =
@@ -187,7 +187,11 @@ describe('mcpConnectionsSlice', () => {
         },
       ];
 
-      (invokeCommand as ReturnType<typeof vi.fn>).mockResolvedValue(mockTools);
+      (invokeCommand as ReturnType<typeof vi.fn>).mockResolvedValue({
+        tools: mockTools,
+        resources: { resources: [], templates: [] },
+        prompts: [],
+      });
 
       const dispatch = vi.fn();
       const thunk = connectMCPConnection({
@@ -199,6 +203,14 @@ describe('mcpConnectionsSlice', () => {
 
       const state = mcpConnectionsReducer(stateWithConnection, result);
 
+      expect(invokeCommand).toHaveBeenCalledWith(
+        'connect_mcp_server_and_fetch_tools',
+        expect.objectContaining({ connectionId: '1' })
+      );
+      expect(invokeCommand).not.toHaveBeenCalledWith(
+        'update_mcp_server_status',
+        expect.anything()
+      );
       expect(state.mcpConnections[0].status).toBe('connected');
       expect(state.mcpConnections[0].tools).toBeDefined();
       expect(state.mcpConnections[0].tools).toHaveLength(1);
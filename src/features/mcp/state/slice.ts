@@ -137,16 +137,22 @@ export const connectMCPConnection = createAsyncThunk(
 
     try {
       // Connect and fetch tools immediately - no delay
-      const mcpTools = await invokeCommand<
-        Array<{ name: string; description?: string; input_schema?: string }>
-      >(TauriCommands.CONNECT_MCP_SERVER_AND_FETCH_TOOLS, {
+      // The backend caches the tools, resources and prompts and marks the server connected
+      const catalog = await invokeCommand<{
+        tools: Array<{
+          name: string;
+          description?: string;
+          input_schema?: string;
+        }>;
+      }>(TauriCommands.CONNECT_MCP_SERVER_AND_FETCH_TOOLS, {
+        connectionId: id,
         url,
         type,
         headers: headers || null,
         runtimePath: runtime_path || null,
       });
 
-      tools = mcpTools.map((tool) => {
+      tools = catalog.tools.map((tool) => {
         let inputSchema: MCPToolType['inputSchema'];
         if (tool.input_schema) {
           try {
@@ -162,14 +168,6 @@ export const connectMCPConnection = createAsyncThunk(
         };
       });
 
-      // Update status to connected and save tools, clear error
-      const toolsJson = JSON.stringify(tools);
-      await invokeCommand(TauriCommands.UPDATE_MCP_SERVER_STATUS, {
-        id,
-        status: 'connected',
-        toolsJson,
-        errorMessage: null,
-      });
       status = 'connected';
       errorMessage = undefined;
     } catch (error: unknown) {
@@ -33,6 +33,19 @@ export interface MCPServerConnection {
   errorMessage?: string;
 }
 
+export interface MCPResource {
+  uri: string;
+  name: string;
+  description?: string;
+  mime_type?: string;
+}
+
+/** A resource attached to a message, stored under `resources` in its metadata */
+export interface MCPResourceRef {
+  connection_id: string;
+  uri: string;
+}
+
 export interface HubMCPServer {
   id: string;
   name: string;
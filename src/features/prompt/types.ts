@@ -12,3 +12,14 @@ export interface ParsedPromptTemplate {
   content: string;
   variables: string[];
 }
+
+export interface MCPPromptEntry {
+  id: string;
+  connection_id: string;
+  connection_name: string;
+  name: string;
+  title?: string | null;
+  description?: string | null;
+  variables: string[];
+  required_variables: string[];
+}
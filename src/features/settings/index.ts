@@ -8,3 +8,4 @@ export * from './ui/HeadersEditor';
 export * from './ui/PromptManagement';
 export * from './ui/SettingsScreen';
 export * from './ui/SettingsLayout';
+export * from './ui/VaultSettings';
@@ -1 +1,2 @@
 export * from './prompt-utils';
+export * from './secret-utils';
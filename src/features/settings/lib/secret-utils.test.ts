@@ -0,0 +1,21 @@
+import { describe, it, expect } from 'vitest';
+import { isLockedSecret } from './secret-utils';
+
+describe('secret-utils', () => {
+  describe('isLockedSecret', () => {
+    it('should detect values still encrypted by the vault', () => {
+      expect(isLockedSecret('enc:v1:AAAA')).toBe(true);
+    });
+
+    it('should treat decrypted values as unlocked', () => {
+      expect(isLockedSecret('sk-test')).toBe(false);
+      expect(isLockedSecret('{"Authorization":"Bearer x"}')).toBe(false);
+    });
+
+    it('should handle empty values', () => {
+      expect(isLockedSecret('')).toBe(false);
+      expect(isLockedSecret(null)).toBe(false);
+      expect(isLockedSecret(undefined)).toBe(false);
+    });
+  });
+});
@@ -0,0 +1,17 @@
+/**
+ * Utility functions for secrets stored in the vault
+ */
+
+// Prefix of values encrypted by the secrets vault (see SecretsService on the backend)
+const ENCRYPTED_PREFIX = 'enc:v1:';
+
+/**
+ * Whether a secret field is still encrypted because the vault is locked
+ * Locked values must be sent back unchanged so the stored secret is kept
+ *
+ * @param value - The secret as returned by the backend
+ * @returns True if the value could not be decrypted
+ */
+export function isLockedSecret(value: string | null | undefined): boolean {
+  return !!value && value.startsWith(ENCRYPTED_PREFIX);
+}
@@ -1,6 +1,12 @@
 import { baseApi } from '@/app/api/baseApi';
 import { TauriCommands } from '@/bindings/commands';
 
+export interface VaultStatus {
+  initialized: boolean;
+  unlocked: boolean;
+  key_source: 'keyring' | 'passphrase' | null;
+}
+
 export const appSettingsApi = baseApi.injectEndpoints({
   endpoints: (builder) => ({
     getAppSetting: builder.query<string | null, string>({
@@ -15,8 +21,32 @@ export const appSettingsApi = baseApi.injectEndpoints({
         args: { key, value },
       }),
     }),
+    getVaultStatus: builder.query<VaultStatus, void>({
+      query: () => ({ command: TauriCommands.GET_VAULT_STATUS }),
+      providesTags: ['Vault'],
+    }),
+    // Connections are re-read so their secrets show up decrypted
+    unlockVault: builder.mutation<VaultStatus, string>({
+      query: (passphrase) => ({
+        command: TauriCommands.UNLOCK_VAULT,
+        args: { passphrase },
+      }),
+      invalidatesTags: ['Vault', 'LLMConnection', 'MCPConnection'],
+    }),
+    setVaultPassphrase: builder.mutation<VaultStatus, string>({
+      query: (passphrase) => ({
+        command: TauriCommands.SET_VAULT_PASSPHRASE,
+        args: { passphrase },
+      }),
+      invalidatesTags: ['Vault', 'LLMConnection', 'MCPConnection'],
+    }),
   }),
 });
 
-export const { useGetAppSettingQuery, useSaveAppSettingMutation } =
-  appSettingsApi;
+export const {
+  useGetAppSettingQuery,
+  useSaveAppSettingMutation,
+  useGetVaultStatusQuery,
+  useUnlockVaultMutation,
+  useSetVaultPassphraseMutation,
+} = appSettingsApi;
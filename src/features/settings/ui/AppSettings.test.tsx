@@ -34,6 +34,10 @@ vi.mock('@/hooks/useAppSettings', () => ({
   }),
 }));
 
+vi.mock('./VaultSettings', () => ({
+  VaultSettings: () => <div data-testid="vault-settings" />,
+}));
+
 vi.mock('@/i18n/config', () => ({
   default: {
     changeLanguage: vi.fn(),
@@ -17,6 +17,7 @@ import { Button } from '@/ui/atoms/button/button';
 import { Separator } from '@/ui/atoms/separator';
 import { useAppSettings } from '@/hooks/useAppSettings';
 import i18n from '@/i18n/config';
+import { VaultSettings } from './VaultSettings';
 
 type Theme =
   | 'light'
@@ -124,6 +125,11 @@ export function AppSettings() {
 
       <Separator />
 
+      {/* Secrets vault */}
+      <VaultSettings />
+
+      <Separator />
+
       {/* Experiments Collapsible */}
       <Collapsible className="space-y-2">
         <CollapsibleTrigger asChild>
@@ -107,4 +107,12 @@ describe('HeadersEditor', () => {
       JSON.stringify({ Authorization: 'Bearer token' })
     );
   });
+
+  it('keeps headers that are still encrypted while the vault is locked', () => {
+    render(<HeadersEditor value="enc:v1:AAAA" onChange={mockOnChange} />);
+
+    expect(screen.getByText('lockedSecret')).toBeInTheDocument();
+    expect(screen.queryByTestId('plus-icon')).not.toBeInTheDocument();
+    expect(mockOnChange).not.toHaveBeenCalled();
+  });
 });
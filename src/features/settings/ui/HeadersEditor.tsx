@@ -4,6 +4,7 @@ import { useTranslation } from 'react-i18next';
 import { Button } from '@/ui/atoms/button/button';
 import { Input } from '@/ui/atoms/input';
 import { Label } from '@/ui/atoms/label';
+import { isLockedSecret } from '../lib/secret-utils';
 
 interface Header {
   key: string;
@@ -84,6 +85,18 @@ export function HeadersEditor({
     updateHeaders(newHeaders);
   };
 
+  // Values still encrypted while the vault is locked are kept as they are
+  if (isLockedSecret(value)) {
+    return (
+      <div className="space-y-2">
+        <Label className="text-xs font-medium text-muted-foreground uppercase tracking-wider">
+          {label || t('headersOptional')}
+        </Label>
+        <p className="text-xs text-muted-foreground">{t('lockedSecret')}</p>
+      </div>
+    );
+  }
+
   return (
     <div className="space-y-2">
       <div className="flex items-center justify-between">
@@ -14,12 +14,14 @@ vi.mock('lucide-react', () => ({
   Plus: () => <div data-testid="plus-icon" />,
   Trash2: () => <div data-testid="trash-icon" />,
   FileText: () => <div data-testid="file-icon" />,
+  Plug: () => <div data-testid="plug-icon" />,
 }));
 
 vi.mock('@/lib/tauri', () => ({
   invokeCommand: vi.fn(),
   TauriCommands: {
     GET_PROMPTS: 'get_prompts',
+    GET_MCP_PROMPTS: 'get_mcp_prompts',
     CREATE_PROMPT: 'create_prompt',
     UPDATE_PROMPT: 'update_prompt',
     DELETE_PROMPT: 'delete_prompt',
@@ -162,6 +164,37 @@ describe('PromptManagement', () => {
     });
   });
 
+  it('lists prompts of connected MCP servers', async () => {
+    (invokeCommand as Mock).mockImplementation((command: string) => {
+      if (command === TauriCommands.GET_PROMPTS) {
+        return Promise.resolve(mockPrompts);
+      }
+      if (command === TauriCommands.GET_MCP_PROMPTS) {
+        return Promise.resolve([
+          {
+            id: 'mcp:conn-1:review',
+            connection_id: 'conn-1',
+            connection_name: 'GitHub',
+            name: 'review',
+            title: 'Review pull request',
+            description: 'Review the changes of a pull request',
+            variables: ['pr'],
+            required_variables: ['pr'],
+          },
+        ]);
+      }
+      return Promise.resolve();
+    });
+
+    render(<PromptManagement />);
+
+    await waitFor(() => {
+      expect(screen.getByText('mcpPrompts')).toBeInTheDocument();
+      expect(screen.getByText('Review pull request')).toBeInTheDocument();
+      expect(screen.getByText('GitHub')).toBeInTheDocument();
+    });
+  });
+
   it('shows empty state when no prompts', async () => {
     (invokeCommand as Mock).mockImplementation((command: string) => {
       if (command === TauriCommands.GET_PROMPTS) {
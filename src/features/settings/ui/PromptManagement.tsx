@@ -1,5 +1,5 @@
 import React, { useState, useEffect } from 'react';
-import { Plus, Trash2, FileText } from 'lucide-react';
+import { Plus, Trash2, FileText, Plug } from 'lucide-react';
 import { useTranslation } from 'react-i18next';
 import { Button } from '@/ui/atoms/button/button';
 import { EmptyState } from '@/ui/atoms/empty-state';
@@ -23,6 +23,7 @@ import {
   showSuccess,
 } from '@/features/notifications/state/notificationSlice';
 import { logger } from '@/lib/logger';
+import type { MCPPromptEntry } from '@/features/prompt/types';
 
 interface Prompt {
   id: string;
@@ -36,6 +37,7 @@ export function PromptManagement() {
   const { t } = useTranslation('settings');
   const dispatch = useAppDispatch();
   const [prompts, setPrompts] = useState<Prompt[]>([]);
+  const [mcpPrompts, setMcpPrompts] = useState<MCPPromptEntry[]>([]);
   const [editingPrompt, setEditingPrompt] = useState<Prompt | null>(null);
   const [dialogOpen, setDialogOpen] = useState(false);
   const [deleteDialogOpen, setDeleteDialogOpen] = useState(false);
@@ -49,6 +51,15 @@ export function PromptManagement() {
       setPrompts(data);
     } catch (error) {
       logger.error('Error loading prompts:', error);
+    }
+    // Prompts of connected MCP servers are listed read-only below the local ones
+    try {
+      const mcpData = await invokeCommand<MCPPromptEntry[]>(
+        TauriCommands.GET_MCP_PROMPTS
+      );
+      setMcpPrompts(mcpData ?? []);
+    } catch (error) {
+      logger.error('Error loading MCP prompts:', error);
     } finally {
       setLoading(false);
     }
@@ -172,6 +183,43 @@ export function PromptManagement() {
         </ScrollArea>
       )}
 
+      {!loading && mcpPrompts.length > 0 && (
+        <div className="space-y-3">
+          <div>
+            <h3 className="text-sm font-medium">{t('mcpPrompts')}</h3>
+            <p className="text-xs text-muted-foreground">
+              {t('mcpPromptsDescription')}
+            </p>
+          </div>
+          <div className="grid grid-cols-1 md:grid-cols-3 gap-3">
+            {mcpPrompts.map((prompt) => (
+              <div key={prompt.id} className="rounded-lg border bg-card p-4">
+                <div className="space-y-3">
+                  <div className="flex items-center gap-3">
+                    <div className="flex items-center justify-center size-10 rounded-lg bg-muted">
+                      <Plug className="size-5 text-muted-foreground" />
+                    </div>
+                    <div className="flex-1 min-w-0">
+                      <div className="font-normal truncate">
+                        {prompt.title || prompt.name}
+                      </div>
+                      <div className="text-xs text-muted-foreground truncate">
+                        {prompt.connection_name}
+                      </div>
+                    </div>
+                  </div>
+                  {prompt.description && (
+                    <p className="text-xs text-muted-foreground line-clamp-3">
+                      {prompt.description}
+                    </p>
+                  )}
+                </div>
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
+
       <PromptDialog
         open={dialogOpen}
         onOpenChange={setDialogOpen}
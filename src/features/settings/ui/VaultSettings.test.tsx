@@ -0,0 +1,96 @@
+import { describe, it, expect, vi, beforeEach } from 'vitest';
+import { render, screen, fireEvent, waitFor } from '@testing-library/react';
+import { VaultSettings } from './VaultSettings';
+
+vi.mock('react-i18next', () => ({
+  useTranslation: () => ({
+    t: (key: string) => key,
+  }),
+}));
+
+const mockDispatch = vi.fn();
+vi.mock('@/app/hooks', () => ({
+  useAppDispatch: () => mockDispatch,
+}));
+
+const mockStatus = vi.fn();
+const mockUnlock = vi.fn();
+const mockSetPassphrase = vi.fn();
+
+vi.mock('../state/api', () => ({
+  useGetVaultStatusQuery: () => ({ data: mockStatus() }),
+  useUnlockVaultMutation: () => [
+    (passphrase: string) => ({ unwrap: () => mockUnlock(passphrase) }),
+    { isLoading: false },
+  ],
+  useSetVaultPassphraseMutation: () => [
+    (passphrase: string) => ({ unwrap: () => mockSetPassphrase(passphrase) }),
+    { isLoading: false },
+  ],
+}));
+
+describe('VaultSettings', () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    mockUnlock.mockResolvedValue({});
+    mockSetPassphrase.mockResolvedValue({});
+  });
+
+  it('unlocks a locked passphrase vault', async () => {
+    mockStatus.mockReturnValue({
+      initialized: true,
+      unlocked: false,
+      key_source: 'passphrase',
+    });
+    render(<VaultSettings />);
+
+    expect(screen.getByText('vaultLocked')).toBeInTheDocument();
+    fireEvent.change(screen.getByPlaceholderText('vaultPassphrase'), {
+      target: { value: 'correct horse' },
+    });
+    fireEvent.click(screen.getByText('unlockVault'));
+
+    await waitFor(() => {
+      expect(mockUnlock).toHaveBeenCalledWith('correct horse');
+    });
+  });
+
+  it('offers to set a passphrase when the keyring is unavailable', async () => {
+    mockStatus.mockReturnValue({
+      initialized: false,
+      unlocked: false,
+      key_source: null,
+    });
+    render(<VaultSettings />);
+
+    expect(
+      screen.getByText('vaultUninitializedDescription')
+    ).toBeInTheDocument();
+    const submit = screen.getByText('setVaultPassphrase');
+    fireEvent.change(screen.getByPlaceholderText('vaultPassphrase'), {
+      target: { value: 'short' },
+    });
+    expect(submit).toBeDisabled();
+
+    fireEvent.change(screen.getByPlaceholderText('vaultPassphrase'), {
+      target: { value: 'long enough' },
+    });
+    fireEvent.click(submit);
+
+    await waitFor(() => {
+      expect(mockSetPassphrase).toHaveBeenCalledWith('long enough');
+    });
+  });
+
+  it('offers to change the passphrase of an unlocked vault', () => {
+    mockStatus.mockReturnValue({
+      initialized: true,
+      unlocked: true,
+      key_source: 'passphrase',
+    });
+    render(<VaultSettings />);
+
+    expect(screen.getByText('vaultUnlocked')).toBeInTheDocument();
+    expect(screen.getByText('changeVaultPassphrase')).toBeInTheDocument();
+  });
+});
@@ -0,0 +1,143 @@
+import { useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { KeyRound, Lock, LockOpen } from 'lucide-react';
+import { Button } from '@/ui/atoms/button/button';
+import { Input } from '@/ui/atoms/input';
+import { Badge } from '@/ui/atoms/badge';
+import { useAppDispatch } from '@/app/hooks';
+import {
+  showError,
+  showSuccess,
+} from '@/features/notifications/state/notificationSlice';
+import { logger } from '@/lib/logger';
+import {
+  useGetVaultStatusQuery,
+  useSetVaultPassphraseMutation,
+  useUnlockVaultMutation,
+} from '../state/api';
+
+const MIN_PASSPHRASE_LEN = 8;
+
+export function VaultSettings() {
+  const { t } = useTranslation('settings');
+  const dispatch = useAppDispatch();
+  const { data: status } = useGetVaultStatusQuery();
+  const [unlockVault, { isLoading: isUnlocking }] = useUnlockVaultMutation();
+  const [setVaultPassphrase, { isLoading: isSaving }] =
+    useSetVaultPassphraseMutation();
+  const [unlockPassphrase, setUnlockPassphrase] = useState('');
+  const [newPassphrase, setNewPassphrase] = useState('');
+
+  if (!status) {
+    return null;
+  }
+
+  const isLocked = status.initialized && !status.unlocked;
+  const description = !status.initialized
+    ? t('vaultUninitializedDescription')
+    : status.key_source === 'keyring'
+      ? t('vaultKeyringDescription')
+      : t('vaultPassphraseDescription');
+
+  const handleUnlock = async (e: React.FormEvent) => {
+    e.preventDefault();
+    try {
+      await unlockVault(unlockPassphrase).unwrap();
+      setUnlockPassphrase('');
+      dispatch(showSuccess(t('vaultUnlockedSuccess')));
+    } catch (error) {
+      logger.error('Error unlocking vault:', error);
+      dispatch(
+        showError(
+          t('cannotUnlockVault'),
+          (error as { message?: string }).message
+        )
+      );
+    }
+  };
+
+  const handleSetPassphrase = async (e: React.FormEvent) => {
+    e.preventDefault();
+    try {
+      await setVaultPassphrase(newPassphrase).unwrap();
+      setNewPassphrase('');
+      dispatch(showSuccess(t('vaultPassphraseSaved')));
+    } catch (error) {
+      logger.error('Error setting vault passphrase:', error);
+      dispatch(
+        showError(
+          t('cannotSetVaultPassphrase'),
+          (error as { message?: string }).message
+        )
+      );
+    }
+  };
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center gap-2">
+        <KeyRound className="size-4 text-muted-foreground shrink-0" />
+        <h3 className="font-medium text-sm leading-none my-0">
+          {t('secretsVault')}
+        </h3>
+        {status.initialized && (
+          <Badge variant={isLocked ? 'destructive' : 'secondary'}>
+            {isLocked ? (
+              <Lock className="mr-1 size-3" />
+            ) : (
+              <LockOpen className="mr-1 size-3" />
+            )}
+            {isLocked ? t('vaultLocked') : t('vaultUnlocked')}
+          </Badge>
+        )}
+      </div>
+      <p className="text-xs text-muted-foreground">{description}</p>
+
+      {isLocked ? (
+        <form onSubmit={handleUnlock} className="space-y-2">
+          <p className="text-xs text-muted-foreground">
+            {t('vaultLockedDescription')}
+          </p>
+          <div className="flex gap-2">
+            <Input
+              id="vault-unlock-passphrase"
+              type="password"
+              value={unlockPassphrase}
+              onChange={(e) => setUnlockPassphrase(e.target.value)}
+              placeholder={t('vaultPassphrase')}
+              autoComplete="current-password"
+            />
+            <Button type="submit" disabled={!unlockPassphrase || isUnlocking}>
+              {t('unlockVault')}
+            </Button>
+          </div>
+        </form>
+      ) : (
+        <form onSubmit={handleSetPassphrase} className="space-y-2">
+          <div className="flex gap-2">
+            <Input
+              id="vault-new-passphrase"
+              type="password"
+              value={newPassphrase}
+              onChange={(e) => setNewPassphrase(e.target.value)}
+              placeholder={t('vaultPassphrase')}
+              autoComplete="new-password"
+            />
+            <Button
+              type="submit"
+              variant="outline"
+              disabled={newPassphrase.length < MIN_PASSPHRASE_LEN || isSaving}
+            >
+              {status.key_source === 'passphrase'
+                ? t('changeVaultPassphrase')
+                : t('setVaultPassphrase')}
+            </Button>
+          </div>
+          <p className="text-xs text-muted-foreground">
+            {t('vaultPassphraseHint')}
+          </p>
+        </form>
+      )}
+    </div>
+  );
+}
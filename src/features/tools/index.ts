@@ -1,2 +1,6 @@
 export { default as toolPermissionReducer } from './state/toolPermissionSlice';
 export * from './state/toolPermissionSlice';
+export { default as serverRequestReducer } from './state/serverRequestSlice';
+export * from './state/serverRequestSlice';
+export * from './ui/SamplingRequestDialog';
+export * from './ui/ElicitationRequestDialog';
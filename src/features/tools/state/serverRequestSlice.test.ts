@@ -0,0 +1,90 @@
+import { describe, it, expect } from 'vitest';
+import serverRequestReducer, {
+  addSamplingRequest,
+  removeSamplingRequest,
+  addElicitationRequest,
+  removeElicitationRequest,
+  type ElicitationRequest,
+  type SamplingRequest,
+} from './serverRequestSlice';
+
+describe('serverRequestSlice', () => {
+  const initialState = {
+    samplingRequests: [],
+    elicitationRequests: [],
+  };
+
+  const samplingRequest = (requestId: string): SamplingRequest => ({
+    requestId,
+    chatId: 'chat-123',
+    messageId: 'msg-456',
+    toolCallId: 'tool-1',
+    serverName: 'docs',
+    systemPrompt: null,
+    messages: [{ role: 'user', content: { type: 'text', text: 'Summarize' } }],
+    maxTokens: 256,
+    timestamp: Date.now(),
+  });
+
+  it('should return the initial state', () => {
+    expect(serverRequestReducer(undefined, { type: 'unknown' })).toEqual(
+      initialState
+    );
+  });
+
+  it('should queue sampling requests in arrival order', () => {
+    let state = serverRequestReducer(
+      initialState,
+      addSamplingRequest(samplingRequest('req-1'))
+    );
+    state = serverRequestReducer(
+      state,
+      addSamplingRequest(samplingRequest('req-2'))
+    );
+
+    expect(state.samplingRequests.map((r) => r.requestId)).toEqual([
+      'req-1',
+      'req-2',
+    ]);
+  });
+
+  it('should remove a sampling request by id', () => {
+    let state = serverRequestReducer(
+      initialState,
+      addSamplingRequest(samplingRequest('req-1'))
+    );
+    state = serverRequestReducer(
+      state,
+      addSamplingRequest(samplingRequest('req-2'))
+    );
+    state = serverRequestReducer(state, removeSamplingRequest('req-1'));
+
+    expect(state.samplingRequests.map((r) => r.requestId)).toEqual(['req-2']);
+  });
+
+  it('should queue and remove elicitation requests', () => {
+    const elicitationRequest: ElicitationRequest = {
+      requestId: 'req-3',
+      chatId: 'chat-123',
+      messageId: 'msg-456',
+      toolCallId: 'tool-1',
+      serverName: 'github',
+      message: 'Which repository?',
+      requestedSchema: {
+        type: 'object',
+        properties: { repo: { type: 'string' } },
+        required: ['repo'],
+      },
+      timestamp: Date.now(),
+    };
+
+    let state = serverRequestReducer(
+      initialState,
+      addElicitationRequest(elicitationRequest)
+    );
+    expect(state.elicitationRequests).toEqual([elicitationRequest]);
+
+    state = serverRequestReducer(state, removeElicitationRequest('req-3'));
+    expect(state.elicitationRequests).toEqual([]);
+  });
+});
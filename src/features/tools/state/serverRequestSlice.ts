@@ -0,0 +1,102 @@
+import { createSlice, PayloadAction } from '@reduxjs/toolkit';
+
+// Message a server wants completed, as sent in `sampling/createMessage`
+export interface SamplingMessage {
+  role: 'user' | 'assistant';
+  content: {
+    type: 'text' | 'image' | 'audio';
+    text?: string;
+    mimeType?: string;
+  };
+}
+
+// A server asking for an LLM completion while one of its tools runs
+export interface SamplingRequest {
+  requestId: string;
+  chatId: string;
+  messageId: string;
+  toolCallId: string;
+  serverName: string;
+  systemPrompt: string | null;
+  messages: SamplingMessage[];
+  maxTokens: number;
+  timestamp: number;
+}
+
+// One field of an elicitation form (MCP allows only flat primitive properties)
+export interface ElicitationField {
+  type: 'string' | 'number' | 'integer' | 'boolean';
+  title?: string;
+  description?: string;
+  enum?: string[];
+  enumNames?: string[];
+  format?: 'email' | 'uri' | 'date' | 'date-time';
+  minLength?: number;
+  maxLength?: number;
+  minimum?: number;
+  maximum?: number;
+  default?: boolean;
+}
+
+export interface ElicitationSchema {
+  type: 'object';
+  properties: Record<string, ElicitationField>;
+  required?: string[];
+}
+
+// A server asking the user for input while one of its tools runs
+export interface ElicitationRequest {
+  requestId: string;
+  chatId: string;
+  messageId: string;
+  toolCallId: string;
+  serverName: string;
+  message: string;
+  requestedSchema: ElicitationSchema;
+  timestamp: number;
+}
+
+interface ServerRequestState {
+  samplingRequests: SamplingRequest[]; // Oldest first, shown one at a time
+  elicitationRequests: ElicitationRequest[];
+}
+
+const initialState: ServerRequestState = {
+  samplingRequests: [],
+  elicitationRequests: [],
+};
+
+const serverRequestSlice = createSlice({
+  name: 'serverRequest',
+  initialState,
+  reducers: {
+    addSamplingRequest: (state, action: PayloadAction<SamplingRequest>) => {
+      state.samplingRequests.push(action.payload);
+    },
+    removeSamplingRequest: (state, action: PayloadAction<string>) => {
+      state.samplingRequests = state.samplingRequests.filter(
+        (request) => request.requestId !== action.payload
+      );
+    },
+    addElicitationRequest: (
+      state,
+      action: PayloadAction<ElicitationRequest>
+    ) => {
+      state.elicitationRequests.push(action.payload);
+    },
+    removeElicitationRequest: (state, action: PayloadAction<string>) => {
+      state.elicitationRequests = state.elicitationRequests.filter(
+        (request) => request.requestId !== action.payload
+      );
+    },
+  },
+});
+
+export const {
+  addSamplingRequest,
+  removeSamplingRequest,
+  addElicitationRequest,
+  removeElicitationRequest,
+} = serverRequestSlice.actions;
+
+export default serverRequestSlice.reducer;
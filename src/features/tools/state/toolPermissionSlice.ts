@@ -11,8 +11,12 @@ export interface PermissionRequest {
   messageId: string;
   toolCalls: PendingToolCall[];
   timestamp: number;
+  timeoutSecs?: number; // How long the backend waits for a decision
 }
 
+// Where an "always allow" applies
+export type PermissionScope = 'chat' | 'workspace';
+
 interface ToolPermissionState {
   pendingRequests: Record<string, PermissionRequest>; // Key by messageId
 }
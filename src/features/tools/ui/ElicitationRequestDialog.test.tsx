@@ -0,0 +1,80 @@
+import { describe, it, expect } from 'vitest';
+import { buildElicitationContent } from './ElicitationRequestDialog';
+import type { ElicitationSchema } from '../state/serverRequestSlice';
+
+describe('buildElicitationContent', () => {
+  const schema: ElicitationSchema = {
+    type: 'object',
+    properties: {
+      name: { type: 'string', minLength: 2 },
+      age: { type: 'integer', minimum: 0, maximum: 150 },
+      score: { type: 'number' },
+      plan: { type: 'string', enum: ['free', 'pro'] },
+      subscribe: { type: 'boolean', default: true },
+    },
+    required: ['name', 'plan'],
+  };
+
+  it('converts valid input to typed content', () => {
+    const result = buildElicitationContent(schema, {
+      name: ' Ada ',
+      age: '36',
+      score: '4.5',
+      plan: 'pro',
+      subscribe: false,
+    });
+
+    expect(result).toEqual({
+      content: {
+        name: 'Ada',
+        age: 36,
+        score: 4.5,
+        plan: 'pro',
+        subscribe: false,
+      },
+    });
+  });
+
+  it('leaves out empty optional fields', () => {
+    const result = buildElicitationContent(schema, {
+      name: 'Ada',
+      age: '',
+      score: '',
+      plan: 'free',
+      subscribe: true,
+    });
+
+    expect(result).toEqual({
+      content: { name: 'Ada', plan: 'free', subscribe: true },
+    });
+  });
+
+  it('reports missing and invalid fields', () => {
+    const result = buildElicitationContent(schema, {
+      name: 'A',
+      age: '3.5',
+      score: 'abc',
+      plan: '',
+      subscribe: false,
+    });
+
+    expect(result).toEqual({
+      errors: {
+        name: 'minLength',
+        age: 'integer',
+        score: 'number',
+        plan: 'required',
+      },
+    });
+  });
+
+  it('checks number bounds', () => {
+    const result = buildElicitationContent(schema, {
+      name: 'Ada',
+      age: '200',
+      plan: 'pro',
+    });
+
+    expect(result).toEqual({ errors: { age: 'maximum' } });
+  });
+});
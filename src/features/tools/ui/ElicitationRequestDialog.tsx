@@ -0,0 +1,313 @@
+import { useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { MessageSquareText } from 'lucide-react';
+import {
+  Dialog,
+  DialogBody,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/ui/atoms/dialog/component';
+import { Button } from '@/ui/atoms/button/button';
+import { Input } from '@/ui/atoms/input';
+import { Label } from '@/ui/atoms/label';
+import { Switch } from '@/ui/atoms/switch';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/ui/atoms/select';
+import { useAppDispatch, useAppSelector } from '@/app/hooks';
+import { invokeCommand, TauriCommands } from '@/lib/tauri';
+import { showError } from '@/features/notifications/state/notificationSlice';
+import { logger } from '@/lib/logger';
+import {
+  removeElicitationRequest,
+  type ElicitationField,
+  type ElicitationSchema,
+} from '../state/serverRequestSlice';
+
+// Matches the backend timeout, after which the request is cancelled
+const ELICITATION_TIMEOUT_MS = 60_000;
+
+type FieldValue = string | boolean;
+
+const ERROR_KEYS: Record<string, string> = {
+  required: 'elicitationErrorRequired',
+  number: 'elicitationErrorNumber',
+  integer: 'elicitationErrorInteger',
+  minimum: 'elicitationErrorMinimum',
+  maximum: 'elicitationErrorMaximum',
+  minLength: 'elicitationErrorMinLength',
+  maxLength: 'elicitationErrorMaxLength',
+};
+
+const INPUT_TYPES: Record<string, string> = {
+  email: 'email',
+  uri: 'url',
+  date: 'date',
+  'date-time': 'datetime-local',
+};
+
+function initialValues(schema: ElicitationSchema): Record<string, FieldValue> {
+  return Object.fromEntries(
+    Object.entries(schema.properties).map(([name, field]) => [
+      name,
+      field.type === 'boolean' ? (field.default ?? false) : '',
+    ])
+  );
+}
+
+/**
+ * Validate the form and convert it to the content sent back to the server
+ * Returns the first problem per field instead when the input is invalid
+ */
+export function buildElicitationContent(
+  schema: ElicitationSchema,
+  values: Record<string, FieldValue>
+):
+  | { content: Record<string, unknown> }
+  | { errors: Record<string, string> } {
+  const content: Record<string, unknown> = {};
+  const errors: Record<string, string> = {};
+  const required = new Set(schema.required ?? []);
+
+  for (const [name, field] of Object.entries(schema.properties)) {
+    const value = values[name];
+    if (field.type === 'boolean') {
+      content[name] = value === true;
+      continue;
+    }
+
+    const text = typeof value === 'string' ? value.trim() : '';
+    if (!text) {
+      if (required.has(name)) {
+        errors[name] = 'required';
+      }
+      continue;
+    }
+
+    if (field.type === 'number' || field.type === 'integer') {
+      const number = Number(text);
+      if (
+        Number.isNaN(number) ||
+        (field.type === 'integer' && !Number.isInteger(number))
+      ) {
+        errors[name] = field.type === 'integer' ? 'integer' : 'number';
+      } else if (field.minimum !== undefined && number < field.minimum) {
+        errors[name] = 'minimum';
+      } else if (field.maximum !== undefined && number > field.maximum) {
+        errors[name] = 'maximum';
+      } else {
+        content[name] = number;
+      }
+      continue;
+    }
+
+    if (field.minLength !== undefined && text.length < field.minLength) {
+      errors[name] = 'minLength';
+    } else if (field.maxLength !== undefined && text.length > field.maxLength) {
+      errors[name] = 'maxLength';
+    } else {
+      content[name] = text;
+    }
+  }
+
+  return Object.keys(errors).length > 0 ? { errors } : { content };
+}
+
+interface FieldInputProps {
+  id: string;
+  field: ElicitationField;
+  value: FieldValue;
+  onChange: (value: FieldValue) => void;
+}
+
+function FieldInput({ id, field, value, onChange }: FieldInputProps) {
+  if (field.type === 'boolean') {
+    return (
+      <Switch id={id} checked={value === true} onCheckedChange={onChange} />
+    );
+  }
+
+  if (field.enum) {
+    return (
+      <Select value={value as string} onValueChange={onChange}>
+        <SelectTrigger id={id} className="w-full">
+          <SelectValue />
+        </SelectTrigger>
+        <SelectContent>
+          {field.enum.map((option, index) => (
+            <SelectItem key={option} value={option}>
+              {field.enumNames?.[index] ?? option}
+            </SelectItem>
+          ))}
+        </SelectContent>
+      </Select>
+    );
+  }
+
+  return (
+    <Input
+      id={id}
+      type={
+        field.type === 'string'
+          ? (INPUT_TYPES[field.format ?? ''] ?? 'text')
+          : 'number'
+      }
+      step={field.type === 'integer' ? 1 : 'any'}
+      value={value as string}
+      onChange={(e) => onChange(e.target.value)}
+    />
+  );
+}
+
+/**
+ * Shows forms MCP servers send through `elicitation/create`
+ * Declining sends no data, closing the dialog cancels the request
+ */
+export function ElicitationRequestDialog() {
+  const { t } = useTranslation('chat');
+  const dispatch = useAppDispatch();
+  const request = useAppSelector(
+    (state) => state.serverRequest.elicitationRequests[0]
+  );
+  const [values, setValues] = useState<Record<string, FieldValue>>({});
+  const [errors, setErrors] = useState<Record<string, string>>({});
+  const [isResponding, setIsResponding] = useState(false);
+
+  useEffect(() => {
+    if (!request) {
+      return;
+    }
+    setValues(initialValues(request.requestedSchema));
+    setErrors({});
+
+    // Drop requests the backend has already cancelled
+    const remaining = request.timestamp + ELICITATION_TIMEOUT_MS - Date.now();
+    const timer = setTimeout(
+      () => dispatch(removeElicitationRequest(request.requestId)),
+      Math.max(remaining, 0)
+    );
+    return () => clearTimeout(timer);
+  }, [dispatch, request]);
+
+  if (!request) {
+    return null;
+  }
+
+  const respond = async (
+    action: 'accept' | 'decline' | 'cancel',
+    content: Record<string, unknown> | null = null
+  ) => {
+    setIsResponding(true);
+    try {
+      await invokeCommand(TauriCommands.RESPOND_ELICITATION_REQUEST, {
+        requestId: request.requestId,
+        action,
+        content,
+      });
+    } catch (error) {
+      // Usually the request already timed out on the backend
+      logger.error('Error responding to elicitation request:', error);
+      dispatch(showError(t('elicitationRequestExpired')));
+    } finally {
+      dispatch(removeElicitationRequest(request.requestId));
+      setIsResponding(false);
+    }
+  };
+
+  const handleSubmit = (e: React.FormEvent) => {
+    e.preventDefault();
+    const result = buildElicitationContent(request.requestedSchema, values);
+    if ('errors' in result) {
+      setErrors(result.errors);
+      return;
+    }
+    respond('accept', result.content);
+  };
+
+  const required = new Set(request.requestedSchema.required ?? []);
+
+  return (
+    <Dialog
+      open
+      onOpenChange={(open) => {
+        if (!open && !isResponding) {
+          respond('cancel');
+        }
+      }}
+    >
+      <DialogContent className="sm:max-w-[520px]">
+        <form onSubmit={handleSubmit}>
+          <DialogHeader>
+            <DialogTitle className="flex items-center gap-2">
+              <MessageSquareText className="size-4 text-muted-foreground" />
+              {t('elicitationRequestTitle', { server: request.serverName })}
+            </DialogTitle>
+            <DialogDescription className="whitespace-pre-wrap">
+              {request.message}
+            </DialogDescription>
+          </DialogHeader>
+          <DialogBody>
+            <div className="space-y-4">
+              {Object.entries(request.requestedSchema.properties).map(
+                ([name, field]) => {
+                  const id = `elicitation-${name}`;
+                  return (
+                    <div key={name} className="space-y-2">
+                      <Label htmlFor={id}>
+                        {field.title ?? name}
+                        {required.has(name) && (
+                          <span className="text-destructive">*</span>
+                        )}
+                      </Label>
+                      <FieldInput
+                        id={id}
+                        field={field}
+                        value={values[name] ?? ''}
+                        onChange={(value) =>
+                          setValues((prev) => ({ ...prev, [name]: value }))
+                        }
+                      />
+                      {field.description && (
+                        <p className="text-xs text-muted-foreground">
+                          {field.description}
+                        </p>
+                      )}
+                      {errors[name] && (
+                        <p className="text-xs text-destructive">
+                          {t(ERROR_KEYS[errors[name]], {
+                            min: field.minimum ?? field.minLength,
+                            max: field.maximum ?? field.maxLength,
+                          })}
+                        </p>
+                      )}
+                    </div>
+                  );
+                }
+              )}
+            </div>
+          </DialogBody>
+          <DialogFooter>
+            <Button
+              type="button"
+              variant="outline"
+              onClick={() => respond('decline')}
+              disabled={isResponding}
+            >
+              {t('elicitationDecline')}
+            </Button>
+            <Button type="submit" disabled={isResponding}>
+              {t('elicitationSubmit')}
+            </Button>
+          </DialogFooter>
+        </form>
+      </DialogContent>
+    </Dialog>
+  );
+}
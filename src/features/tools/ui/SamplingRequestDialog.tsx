@@ -0,0 +1,144 @@
+import { useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Sparkles } from 'lucide-react';
+import {
+  Dialog,
+  DialogBody,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/ui/atoms/dialog/component';
+import { Button } from '@/ui/atoms/button/button';
+import { ScrollArea } from '@/ui/atoms/scroll-area';
+import { useAppDispatch, useAppSelector } from '@/app/hooks';
+import { invokeCommand, TauriCommands } from '@/lib/tauri';
+import { showError } from '@/features/notifications/state/notificationSlice';
+import { logger } from '@/lib/logger';
+import {
+  removeSamplingRequest,
+  type SamplingMessage,
+} from '../state/serverRequestSlice';
+
+// Matches the backend approval timeout
+const SAMPLING_TIMEOUT_MS = 60_000;
+
+function messageText(message: SamplingMessage): string {
+  return message.content.type === 'text'
+    ? (message.content.text ?? '')
+    : `[${message.content.mimeType ?? message.content.type}]`;
+}
+
+/**
+ * Asks the user to approve completions requested by MCP servers
+ * The backend gives up on a request after 60 seconds
+ */
+export function SamplingRequestDialog() {
+  const { t } = useTranslation('chat');
+  const dispatch = useAppDispatch();
+  const request = useAppSelector(
+    (state) => state.serverRequest.samplingRequests[0]
+  );
+  const [isResponding, setIsResponding] = useState(false);
+
+  // Drop requests the backend has already given up on
+  useEffect(() => {
+    if (!request) {
+      return;
+    }
+    const remaining = request.timestamp + SAMPLING_TIMEOUT_MS - Date.now();
+    const timer = setTimeout(
+      () => dispatch(removeSamplingRequest(request.requestId)),
+      Math.max(remaining, 0)
+    );
+    return () => clearTimeout(timer);
+  }, [dispatch, request]);
+
+  if (!request) {
+    return null;
+  }
+
+  const respond = async (approved: boolean) => {
+    setIsResponding(true);
+    try {
+      await invokeCommand(TauriCommands.RESPOND_SAMPLING_REQUEST, {
+        requestId: request.requestId,
+        approved,
+      });
+    } catch (error) {
+      // Usually the request already timed out on the backend
+      logger.error('Error responding to sampling request:', error);
+      dispatch(showError(t('samplingRequestExpired')));
+    } finally {
+      dispatch(removeSamplingRequest(request.requestId));
+      setIsResponding(false);
+    }
+  };
+
+  return (
+    <Dialog
+      open
+      onOpenChange={(open) => {
+        if (!open && !isResponding) {
+          respond(false);
+        }
+      }}
+    >
+      <DialogContent className="sm:max-w-[600px]">
+        <DialogHeader>
+          <DialogTitle className="flex items-center gap-2">
+            <Sparkles className="size-4 text-muted-foreground" />
+            {t('samplingRequestTitle')}
+          </DialogTitle>
+          <DialogDescription>
+            {t('samplingRequestDescription', {
+              server: request.serverName,
+              maxTokens: request.maxTokens,
+            })}
+          </DialogDescription>
+        </DialogHeader>
+        <DialogBody>
+          <ScrollArea className="max-h-[50vh]">
+            <div className="space-y-3">
+              {request.systemPrompt && (
+                <div className="space-y-1">
+                  <p className="text-xs font-medium text-muted-foreground uppercase tracking-wider">
+                    {t('samplingSystemPrompt')}
+                  </p>
+                  <p className="text-sm whitespace-pre-wrap rounded-md border bg-muted/30 p-2">
+                    {request.systemPrompt}
+                  </p>
+                </div>
+              )}
+              {request.messages.map((message, index) => (
+                <div key={index} className="space-y-1">
+                  <p className="text-xs font-medium text-muted-foreground uppercase tracking-wider">
+                    {message.role === 'user'
+                      ? t('samplingRoleUser')
+                      : t('samplingRoleAssistant')}
+                  </p>
+                  <p className="text-sm whitespace-pre-wrap rounded-md border bg-muted/30 p-2">
+                    {messageText(message)}
+                  </p>
+                </div>
+              ))}
+            </div>
+          </ScrollArea>
+        </DialogBody>
+        <DialogFooter>
+          <Button
+            variant="outline"
+            onClick={() => respond(false)}
+            disabled={isResponding}
+          >
+            {t('samplingDeny')}
+          </Button>
+          <Button onClick={() => respond(true)} disabled={isResponding}>
+            {t('samplingApprove')}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
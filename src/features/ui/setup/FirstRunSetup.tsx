@@ -58,6 +58,8 @@ const DEFAULT_URLS: Record<string, string> = {
   deepinfra: 'https://api.deepinfra.com/v1/openai',
   google: 'https://generativelanguage.googleapis.com/v1beta',
   anthropic: 'https://api.anthropic.com',
+  azure: 'https://YOUR-RESOURCE.openai.azure.com?api-version=2024-10-21',
+  bedrock: 'https://bedrock-runtime.us-east-1.amazonaws.com',
   deepseek: 'https://api.deepseek.com',
 };
 
@@ -73,6 +75,8 @@ const PROVIDER_OPTIONS = [
   { value: 'together', label: 'Together AI' },
   { value: 'deepinfra', label: 'DeepInfra' },
   { value: 'anthropic', label: 'Anthropic Claude' },
+  { value: 'azure', label: 'Azure OpenAI' },
+  { value: 'bedrock', label: 'AWS Bedrock' },
   { value: 'deepseek', label: 'DeepSeek' },
 ];
 
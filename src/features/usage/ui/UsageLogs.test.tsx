@@ -23,6 +23,8 @@ describe('UsageLogs', () => {
       input_tokens: 100,
       output_tokens: 50,
       total_tokens: 150,
+      cache_read_tokens: 0,
+      cache_write_tokens: 0,
       latency_ms: 500,
       cost: 0.0045,
       status: 'success',
@@ -40,6 +42,8 @@ describe('UsageLogs', () => {
       input_tokens: 200,
       output_tokens: 80,
       total_tokens: 280,
+      cache_read_tokens: 0,
+      cache_write_tokens: 0,
       latency_ms: 800,
       cost: 0.0084,
       status: 'error',
@@ -16,6 +16,8 @@ describe('UsageOverview', () => {
     total_cost: 1.234567,
     total_input_tokens: 1000,
     total_output_tokens: 500,
+    total_cache_read_tokens: 0,
+    total_cache_write_tokens: 0,
     total_requests: 50,
     average_latency: 120.5,
   };
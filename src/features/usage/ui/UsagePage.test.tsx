@@ -36,6 +36,8 @@ describe('UsagePage', () => {
         total_cost: 0,
         total_input_tokens: 0,
         total_output_tokens: 0,
+        total_cache_read_tokens: 0,
+        total_cache_write_tokens: 0,
         total_requests: 0,
         average_latency: 0,
       },
@@ -1,7 +1,13 @@
 import { baseApi } from '@/app/api/baseApi';
 import { TauriCommands } from '@/bindings/commands';
 import { logger } from '@/lib/logger';
-import type { WorkspaceSettings } from '../types';
+import type {
+  ContextConfig,
+  FallbackModel,
+  ToolExecutionConfig,
+  ToolPermissionEntry,
+  WorkspaceSettings,
+} from '../types';
 
 interface DbWorkspaceSettings {
   workspace_id: string;
@@ -12,6 +18,9 @@ interface DbWorkspaceSettings {
   default_model: string | null;
   tool_permission_config: string | null;
   max_agent_iterations: number | null;
+  context_config: string | null;
+  tool_execution_config: string | null;
+  fallback_models: string | null;
   created_at: number;
   updated_at: number;
 }
@@ -58,7 +67,7 @@ export const workspaceSettingsApi = baseApi.injectEndpoints({
             : undefined;
 
         let toolPermissionConfig:
-          | Record<string, 'require' | 'auto'>
+          | Record<string, ToolPermissionEntry>
           | undefined;
         if (dbSettings.tool_permission_config) {
           try {
@@ -73,6 +82,42 @@ export const workspaceSettingsApi = baseApi.injectEndpoints({
           }
         }
 
+        let contextConfig: ContextConfig | undefined;
+        if (dbSettings.context_config) {
+          try {
+            contextConfig = JSON.parse(dbSettings.context_config);
+          } catch (e) {
+            logger.error(
+              'Error parsing contextConfig in workspace settings API:',
+              e
+            );
+          }
+        }
+
+        let toolExecutionConfig: ToolExecutionConfig | undefined;
+        if (dbSettings.tool_execution_config) {
+          try {
+            toolExecutionConfig = JSON.parse(dbSettings.tool_execution_config);
+          } catch (e) {
+            logger.error(
+              'Error parsing toolExecutionConfig in workspace settings API:',
+              e
+            );
+          }
+        }
+
+        let fallbackModels: FallbackModel[] | undefined;
+        if (dbSettings.fallback_models) {
+          try {
+            fallbackModels = JSON.parse(dbSettings.fallback_models);
+          } catch (e) {
+            logger.error(
+              'Error parsing fallbackModels in workspace settings API:',
+              e
+            );
+          }
+        }
+
         const settings: WorkspaceSettings = {
           id: dbSettings.workspace_id,
           name: workspaceName,
@@ -83,6 +128,9 @@ export const workspaceSettingsApi = baseApi.injectEndpoints({
           defaultModel: dbSettings.default_model || undefined,
           toolPermissionConfig,
           maxAgentIterations: dbSettings.max_agent_iterations || undefined,
+          contextConfig,
+          toolExecutionConfig,
+          fallbackModels,
         };
 
         return { data: settings };
@@ -121,6 +169,15 @@ export const workspaceSettingsApi = baseApi.injectEndpoints({
             defaultModel: settings.defaultModel || null,
             toolPermissionConfig: toolPermissionConfigJson,
             maxAgentIterations: settings.maxAgentIterations || null,
+            contextConfig: settings.contextConfig
+              ? JSON.stringify(settings.contextConfig)
+              : null,
+            toolExecutionConfig: settings.toolExecutionConfig
+              ? JSON.stringify(settings.toolExecutionConfig)
+              : null,
+            fallbackModels: settings.fallbackModels?.length
+              ? JSON.stringify(settings.fallbackModels)
+              : null,
           },
         });
 
@@ -1,7 +1,7 @@
 import { createSlice, createAsyncThunk, PayloadAction } from '@reduxjs/toolkit';
 import { invokeCommand, TauriCommands } from '@/lib/tauri';
 import { logger } from '@/lib/logger';
-import type { WorkspaceSettings } from '../types';
+import type { ToolPermissionEntry, WorkspaceSettings } from '../types';
 
 // Types matching Rust structs
 interface DbWorkspaceSettings {
@@ -65,7 +65,9 @@ export const fetchWorkspaceSettings = createAsyncThunk(
         : undefined;
 
     // Parse tool_permission_config JSON
-    let toolPermissionConfig: Record<string, 'require' | 'auto'> | undefined;
+    let toolPermissionConfig:
+      | Record<string, ToolPermissionEntry>
+      | undefined;
     if (dbSettings.tool_permission_config) {
       try {
         toolPermissionConfig = JSON.parse(dbSettings.tool_permission_config);
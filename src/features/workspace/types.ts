@@ -11,6 +11,62 @@ export interface WorkspaceSettings {
   llmConnectionId?: string;
   streamEnabled?: boolean;
   defaultModel?: string; // Default model ID for this workspace
-  toolPermissionConfig?: Record<string, 'require' | 'auto'>; // Per-tool permission configuration
+  toolPermissionConfig?: Record<string, ToolPermissionEntry>; // Per-tool permission configuration
   maxAgentIterations?: number;
+  contextConfig?: ContextConfig;
+  toolExecutionConfig?: ToolExecutionConfig;
+  fallbackModels?: FallbackModel[]; // Tried in order when the chat model is unavailable
+}
+
+// A connection and model the chat falls back to on rate limits and outages
+export interface FallbackModel {
+  connection_id: string;
+  model: string;
+}
+
+// How chat history is fitted into the model's context window
+export type ContextStrategy = 'truncate' | 'summarize' | 'drop_tool_outputs';
+
+export interface ContextConfig {
+  strategy?: ContextStrategy;
+  summary_model?: string; // Defaults to the chat model
+  summary_connection_id?: string; // Defaults to the chat connection
+  max_context_tokens?: number; // Overrides the built-in limit for the model
+}
+
+// How the tool calls of one model turn are executed
+export interface ToolExecutionConfig {
+  max_concurrent_calls?: number; // 1 runs the calls one after another
+  max_concurrent_per_server?: number; // Defaults to max_concurrent_calls
+  server_limits?: Record<string, number>; // Per MCP connection ID
+  default_policy?: ToolPolicy;
+  connection_policies?: Record<string, ToolPolicy>; // Per MCP connection ID
+  tool_policies?: Record<string, ToolPolicy>; // Per tool name, over its connection's policy
+  circuit_breaker_threshold?: number; // Consecutive failures that mark a server degraded, 0 disables
+  circuit_breaker_cooldown_secs?: number;
+}
+
+export type ToolErrorKind = 'timeout' | 'transport' | 'server' | 'tool';
+
+// Timeout and retries of a tool call, unset fields fall back to the less specific policy
+export interface ToolPolicy {
+  timeout_secs?: number;
+  max_retries?: number;
+  retry_backoff_ms?: number; // Doubles with every retry
+  retry_on?: ToolErrorKind[];
+}
+
+export type PermissionMode = 'auto' | 'require' | 'deny';
+
+// A mode, or rules over the tool's arguments (first matching rule wins)
+export type ToolPermissionEntry =
+  | PermissionMode
+  | { default?: PermissionMode; rules?: ArgumentRule[] };
+
+export interface ArgumentRule {
+  action: PermissionMode;
+  argument?: string; // Argument name or JSON pointer, all arguments when unset
+  prefix?: string;
+  pattern?: string; // Regex the value must match
+  not_pattern?: string; // Regex the value must not match
 }
@@ -44,7 +44,12 @@ import {
   parsePromptVariables,
   renderPrompt,
 } from '@/features/settings/lib/prompt-utils';
-import type { Workspace, WorkspaceSettings } from '../types';
+import type {
+  PermissionMode,
+  ToolPermissionEntry,
+  Workspace,
+  WorkspaceSettings,
+} from '../types';
 import type { LLMConnection, MCPServerConnection, Prompt } from '@/app/types';
 
 interface WorkspaceSettingsFormProps {
@@ -105,7 +110,7 @@ export function WorkspaceSettingsForm({
   );
 
   const [toolPermissionConfig, setToolPermissionConfig] = useState<
-    Record<string, 'require' | 'auto'>
+    Record<string, ToolPermissionEntry>
   >(initialSettings?.toolPermissionConfig || {});
   const [showAllMcpConnections, setShowAllMcpConnections] =
     useState<boolean>(false);
@@ -154,6 +159,9 @@ export function WorkspaceSettingsForm({
             ? toolPermissionConfig
             : undefined,
         maxAgentIterations,
+        contextConfig: initialSettings?.contextConfig,
+        toolExecutionConfig: initialSettings?.toolExecutionConfig,
+        fallbackModels: initialSettings?.fallbackModels,
       };
       await onSave(newSettings);
       onOpenChange(false);
@@ -786,7 +794,10 @@ export function WorkspaceSettingsForm({
                                                   </span>
                                                   <Select
                                                     value={
-                                                      configValue || 'auto'
+                                                      typeof configValue ===
+                                                      'object'
+                                                        ? 'rules'
+                                                        : configValue || 'auto'
                                                     }
                                                     onValueChange={(value) => {
                                                       setToolPermissionConfig(
@@ -803,9 +814,8 @@ export function WorkspaceSettingsForm({
                                                           } else {
                                                             newConfig[
                                                               tool.name
-                                                            ] = value as
-                                                              | 'require'
-                                                              | 'auto';
+                                                            ] =
+                                                              value as PermissionMode;
                                                           }
                                                           return newConfig;
                                                         }
@@ -827,6 +837,23 @@ export function WorkspaceSettingsForm({
                                                           ns: 'settings',
                                                         })}
                                                       </SelectItem>
+                                                      <SelectItem value="deny">
+                                                        {t('permissionDeny', {
+                                                          ns: 'settings',
+                                                        })}
+                                                      </SelectItem>
+                                                      {typeof configValue ===
+                                                        'object' && (
+                                                        <SelectItem
+                                                          value="rules"
+                                                          disabled
+                                                        >
+                                                          {t(
+                                                            'permissionRules',
+                                                            { ns: 'settings' }
+                                                          )}
+                                                        </SelectItem>
+                                                      )}
                                                     </SelectContent>
                                                   </Select>
                                                 </div>
@@ -7,6 +7,7 @@ vi.mock('@/lib/tauri', () => ({
   invokeCommand: vi.fn(),
   TauriCommands: {
     GET_PROMPTS: 'get_prompts',
+    GET_MCP_PROMPTS: 'get_mcp_prompts',
   },
 }));
 
@@ -61,4 +62,32 @@ describe('useSlashCommand', () => {
 
     expect(result.current.isActive).toBe(false);
   });
+
+  it('should list MCP prompts after local prompts when enabled', async () => {
+    const mcpEntry = {
+      id: 'mcp:conn-1:review',
+      connection_id: 'conn-1',
+      connection_name: 'GitHub',
+      name: 'review',
+      title: 'Review',
+      description: 'Review a pull request',
+      variables: ['pr'],
+      required_variables: ['pr'],
+    };
+    (invokeCommand as any).mockImplementation((command: string) =>
+      Promise.resolve(command === 'get_mcp_prompts' ? [mcpEntry] : mockPrompts)
+    );
+
+    const { result } = renderHook(() =>
+      useSlashCommand({ input: '/', includeMcpPrompts: true })
+    );
+
+    await waitFor(() => {
+      expect(result.current.filteredPrompts).toHaveLength(3);
+    });
+    expect(result.current.filteredPrompts[2].mcp).toEqual(mcpEntry);
+    expect(result.current.filteredPrompts[2].content).toBe(
+      'Review a pull request'
+    );
+  });
 });
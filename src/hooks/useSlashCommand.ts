@@ -1,28 +1,57 @@
 import { useState, useEffect, useMemo, useCallback, useRef } from 'react';
 import { invokeCommand, TauriCommands } from '@/lib/tauri';
 import type { Prompt } from '@/app/types';
+import type { MCPPromptEntry } from '@/features/prompt/types';
 import { logger } from '@/lib/logger';
 
+// MCP prompts are listed next to local prompts and keep their entry,
+// so the caller can collect arguments and render them through the server
+export type SlashCommandPrompt = Prompt & { mcp?: MCPPromptEntry };
+
 interface UseSlashCommandOptions {
   input: string;
-  onSelectPrompt?: (prompt: Prompt) => void;
+  includeMcpPrompts?: boolean;
+  onSelectPrompt?: (prompt: SlashCommandPrompt) => void;
 }
 
 interface UseSlashCommandReturn {
   isActive: boolean;
   query: string;
   selectedIndex: number;
-  filteredPrompts: Prompt[];
+  filteredPrompts: SlashCommandPrompt[];
   handleKeyDown: (e: React.KeyboardEvent) => boolean; // Returns true if handled
-  handleSelect: (prompt: Prompt) => void;
+  handleSelect: (prompt: SlashCommandPrompt) => void;
   close: () => void;
 }
 
+const toSlashCommandPrompt = (entry: MCPPromptEntry): SlashCommandPrompt => ({
+  id: entry.id,
+  name: entry.name,
+  content: entry.description || entry.title || '',
+  created_at: 0,
+  updated_at: 0,
+  mcp: entry,
+});
+
+async function loadMcpPrompts(): Promise<SlashCommandPrompt[]> {
+  try {
+    const entries = await invokeCommand<MCPPromptEntry[]>(
+      TauriCommands.GET_MCP_PROMPTS
+    );
+    return entries.map(toSlashCommandPrompt);
+  } catch (error) {
+    // A failing MCP server should not hide the local prompts
+    logger.error('Error loading MCP prompts:', error);
+    return [];
+  }
+}
+
 export function useSlashCommand({
   input,
+  includeMcpPrompts = false,
   onSelectPrompt,
 }: UseSlashCommandOptions): UseSlashCommandReturn {
-  const [prompts, setPrompts] = useState<Prompt[]>([]);
+  const [prompts, setPrompts] = useState<SlashCommandPrompt[]>([]);
   const [selectedIndex, setSelectedIndex] = useState(0);
   const [, setIsLoading] = useState(false);
   const [forceClosed, setForceClosed] = useState(false);
@@ -39,14 +68,15 @@ export function useSlashCommand({
     setIsLoading(true);
     try {
       const data = await invokeCommand<Prompt[]>(TauriCommands.GET_PROMPTS);
-      setPrompts(data);
+      const mcpPrompts = includeMcpPrompts ? await loadMcpPrompts() : [];
+      setPrompts([...data, ...mcpPrompts]);
       hasLoadedPromptsRef.current = true;
     } catch (error) {
       logger.error('Error loading prompts:', error);
     } finally {
       setIsLoading(false);
     }
-  }, [prompts.length]);
+  }, [prompts.length, includeMcpPrompts]);
 
   // Load prompts on mount - only once
   useEffect(() => {
@@ -143,7 +173,7 @@ export function useSlashCommand({
 
   // Handle prompt selection
   const handleSelect = useCallback(
-    (prompt: Prompt) => {
+    (prompt: SlashCommandPrompt) => {
       onSelectPrompt?.(prompt);
     },
     [onSelectPrompt]
@@ -8,6 +8,8 @@ export interface UsageStat {
   input_tokens: number;
   output_tokens: number;
   total_tokens: number;
+  cache_read_tokens: number;
+  cache_write_tokens: number;
   latency_ms: number;
   cost: number;
   timestamp: number;
@@ -27,6 +29,8 @@ export interface UsageFilter {
 export interface UsageSummary {
   total_input_tokens: number;
   total_output_tokens: number;
+  total_cache_read_tokens: number;
+  total_cache_write_tokens: number;
   total_cost: number;
   total_requests: number;
   average_latency: number;
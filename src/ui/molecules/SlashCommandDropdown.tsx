@@ -3,12 +3,12 @@ import { Sparkles } from 'lucide-react';
 import { cn } from '@/lib/utils';
 import { ScrollArea } from '@/ui/atoms/scroll-area';
 import { parsePromptVariables } from '@/features/settings/lib/prompt-utils';
-import type { Prompt } from '@/app/types';
+import type { SlashCommandPrompt } from '@/hooks/useSlashCommand';
 
 interface SlashCommandDropdownProps {
-  prompts: Prompt[];
+  prompts: SlashCommandPrompt[];
   selectedIndex: number;
-  onSelect: (prompt: Prompt) => void;
+  onSelect: (prompt: SlashCommandPrompt) => void;
   position?: { top: number; left: number };
   direction?: 'up' | 'down';
 }
@@ -40,7 +40,7 @@ export function SlashCommandDropdown({
     return null;
   }
 
-  const handleClick = (prompt: Prompt) => {
+  const handleClick = (prompt: SlashCommandPrompt) => {
     onSelect(prompt);
   };
 
@@ -59,8 +59,9 @@ export function SlashCommandDropdown({
       <ScrollArea className="max-h-[200px]">
         <div className="p-1" ref={scrollAreaRef}>
           {prompts.map((prompt, index) => {
-            const hasVariables =
-              parsePromptVariables(prompt.content).length > 0;
+            const hasVariables = prompt.mcp
+              ? prompt.mcp.variables.length > 0
+              : parsePromptVariables(prompt.content).length > 0;
             const isSelected = index === selectedIndex;
 
             return (
@@ -84,6 +85,11 @@ export function SlashCommandDropdown({
                     {hasVariables && (
                       <Sparkles className="size-3 shrink-0 text-muted-foreground" />
                     )}
+                    {prompt.mcp && (
+                      <span className="ml-auto shrink-0 rounded bg-muted px-1.5 py-0.5 text-[10px] text-muted-foreground">
+                        {prompt.mcp.connection_name}
+                      </span>
+                    )}
                   </div>
                   {prompt.content && (
                     <div className="text-xs text-muted-foreground line-clamp-2 mt-1">
@@ -17,6 +17,7 @@ interface VariableInputDialogProps {
   open: boolean;
   title: string;
   variableNames: string[];
+  optionalVariableNames?: string[];
   variables: Record<string, string>;
   renderedPreview?: string;
   onClose: () => void;
@@ -28,6 +29,7 @@ export function VariableInputDialog({
   open,
   title,
   variableNames,
+  optionalVariableNames = [],
   variables,
   renderedPreview,
   onClose,
@@ -37,9 +39,11 @@ export function VariableInputDialog({
   const { t } = useTranslation(['chat', 'common']);
   const [showPreview, setShowPreview] = useState(false);
 
-  // Validate all variables are filled
+  // Validate all variables are filled, except the optional ones
   const allFilled = variableNames.every(
-    (name) => variables[name] && variables[name].trim() !== ''
+    (name) =>
+      optionalVariableNames.includes(name) ||
+      (variables[name] && variables[name].trim() !== '')
   );
 
   const handleSubmit = (e: React.FormEvent) => {
@@ -73,6 +77,11 @@ export function VariableInputDialog({
                   <div key={name} className="space-y-2">
                     <Label htmlFor={`var-${name}`}>
                       {name.replace(/_/g, ' ')}
+                      {optionalVariableNames.includes(name) && (
+                        <span className="text-xs font-normal text-muted-foreground">
+                          {t('optional', { ns: 'common' })}
+                        </span>
+                      )}
                     </Label>
                     <Input
                       id={`var-${name}`}